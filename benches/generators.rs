@@ -0,0 +1,73 @@
+//! Generation and conversion throughput. Run with `cargo bench`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use strum::IntoEnumIterator;
+
+use terminal_palette::color::ColorBlock;
+use terminal_palette::convert::{hsl_to_rgb, hsv_to_rgb, parse_hex, rgb_to_hsl, rgb_to_hsv};
+use terminal_palette::generators::{self, ColorTheories};
+
+/// Block counts from the default palette up to the largest supported one
+const BLOCK_COUNTS: [usize; 4] = [5, 9, 16, 32];
+
+fn palette(count: usize, locked: usize) -> Vec<ColorBlock> {
+    (0..count)
+        .map(|i| {
+            let mut block = ColorBlock::new(i + 1, (i * 37 % 360) as f32, 0.6, 0.7);
+            block.locked = i < locked;
+            block
+        })
+        .collect()
+}
+
+fn bench_generators(c: &mut Criterion) {
+    for theory in ColorTheories::iter() {
+        let mut group = c.benchmark_group(format!("generate/{theory:?}"));
+
+        for count in BLOCK_COUNTS {
+            group.bench_with_input(BenchmarkId::new("unlocked", count), &count, |b, &count| {
+                let mut blocks = palette(count, 0);
+                b.iter(|| generators::generate(theory, black_box(&mut blocks)));
+            });
+            group.bench_with_input(
+                BenchmarkId::new("two_locked", count),
+                &count,
+                |b, &count| {
+                    let mut blocks = palette(count, 2);
+                    b.iter(|| generators::generate(theory, black_box(&mut blocks)));
+                },
+            );
+        }
+
+        group.finish();
+    }
+}
+
+fn bench_conversions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convert");
+
+    group.bench_function("parse_hex", |b| b.iter(|| parse_hex(black_box("#e07a5f"))));
+    group.bench_function("rgb_to_hsv", |b| {
+        b.iter(|| rgb_to_hsv(black_box(224), black_box(122), black_box(95)))
+    });
+    group.bench_function("hsv_to_rgb", |b| {
+        b.iter(|| hsv_to_rgb(black_box(12.6), black_box(0.58), black_box(0.88)))
+    });
+    group.bench_function("rgb_to_hsl", |b| {
+        b.iter(|| rgb_to_hsl(black_box(224), black_box(122), black_box(95)))
+    });
+    group.bench_function("hsl_to_rgb", |b| {
+        b.iter(|| hsl_to_rgb(black_box(12.6), black_box(0.67), black_box(0.63)))
+    });
+    group.bench_function("block_hex", |b| {
+        let block = ColorBlock::new(1, 12.6, 0.58, 0.88);
+        b.iter(|| black_box(&block).get_hex())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_generators, bench_conversions);
+criterion_main!(benches);
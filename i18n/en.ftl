@@ -0,0 +1,356 @@
+# English UI strings, also the fallback for every other locale.
+# Translations go in $XDG_CONFIG_HOME/terminal-palette/locales/<locale>.ftl and may
+# leave out any message; missing ones are shown in English.
+
+## Status bar hints
+
+hint-quit = Quit
+hint-move = Move
+hint-jump = Row ends / top and bottom
+hint-add = Add
+hint-delete = Delete
+hint-theory = Theory
+hint-edit = Edit
+hint-lock = Lock
+hint-undo = Undo
+hint-redo = Redo
+hint-freeze = Lock hue / lightness
+hint-label = Label
+hint-note = Note
+hint-copy = Copy
+hint-save = Save
+hint-save-project = Save project
+hint-open = Open
+hint-editor = Edit in $EDITOR
+hint-recent = Recent
+hint-star = Star
+hint-favorites = Favorites
+hint-repeat = Repeat
+hint-command = Command
+hint-complete = Complete
+hint-run = Run
+hint-skip = Skip
+hint-next = Next
+hint-settings = Settings
+hint-chrome = Theme UI
+hint-appearance = Light/dark
+hint-illuminant = Lighting
+hint-night-light = Night light
+hint-change = Change
+hint-record = Record macro
+hint-play-macro = Play macro
+hint-generate = Generate
+hint-close = Close
+hint-first = First
+hint-last = Last
+hint-apply = Apply
+hint-cancel = Cancel
+hint-clear = Clear
+hint-load-insert = Load / Insert
+hint-search = Search
+hint-tags = Tags
+hint-restore = Restore
+hint-export = Export
+hint-load = Load
+hint-more = … more (?)
+hint-slideshow = Slideshow
+hint-watch-clipboard = Watch clipboard
+hint-explore = Explore
+hint-ansi = ANSI
+hint-variants = Brights
+hint-preview = Charts
+hint-proportions = Proportions
+hint-balance = Balance
+hint-gradients = Gradients
+hint-space = Space
+hint-rename = Rename
+hint-copy-css = Copy CSS
+hint-code = Code
+hint-art = Art
+hint-step = Step
+hint-role = Role
+hint-check = Check
+hint-fill-roles = Fill roles
+hint-select = Select
+hint-pairs = Pairs
+hint-insert = Insert
+hint-collections = Named colors
+hint-collection = Collection
+hint-color-history = History
+hint-blend = Blend
+hint-compare = Compare
+hint-mix = Mix
+hint-blend-modes = Blend modes
+hint-top-block = Top block
+hint-states = UI states
+hint-scale = Scale
+hint-jitter = Jitter
+hint-strict = Strict
+hint-fit-gamut = Fit gamut
+hint-weight = Weight
+hint-toggle-insert = Toggle / Insert
+hint-steps = Steps
+
+## Popup titles and fields
+
+title-select-theory = Select Theory
+title-edit-color = Edit Color
+title-block-label = Block Label
+title-block-note = Block Note
+title-clipboard = From the clipboard
+clipboard-offer = Put it in block { $block }?
+title-save-palette = Save Palette
+title-open-palette = Open Palette
+title-recent = Recent
+title-favorites = Favorites
+title-color-history = Color history
+title-blend = Blend { $from } → { $to }
+blend-name = { $from } → { $to } { $percent }%
+title-collection = { $name } ({ $index }/{ $count })
+title-bar-chart = Bar chart
+title-line-chart = Line chart
+title-heatmap = Heatmap
+title-proportions = In proportion
+usage-dominant = Dominant
+usage-secondary = Secondary
+usage-accent = Accent
+title-balance = Temperature balance
+balance-summary = Warm { $warm }%  Cool { $cool }%  Neutral { $neutral }%
+balance-leans-warm = Leans warm, the reds, oranges and yellows carry it
+balance-leans-cool = Leans cool, the greens, blues and violets carry it
+balance-even = Balanced between warm and cool hues
+balance-no-hues = Only neutrals, no hue to lean either way
+title-gradients = Gradients
+title-gradient-name = Gradient Name
+gradient-details = { $space }, { $stops } stops
+gradients-empty = No gradients yet, [a] makes one through the blocks in order
+gradient-default-name = Gradient { $number }
+gradient-space-oklab = OKLab
+gradient-space-oklch = OKLCH
+gradient-space-srgb = sRGB
+gradient-space-linear-rgb = linear RGB
+title-before = Before the last generation
+title-mix = Mix blocks
+title-blend-modes = Block { $top } over block { $base }
+title-code-preview = Code preview
+title-art-preview = Art preview
+title-role = Role
+title-diagnostics = Diagnostics
+title-pairings = Pairs with { $hex }
+title-command = Command
+title-setup = Setup { $step }/{ $steps }: { $question }
+setup-theory = Default theory
+setup-blocks = Blocks per palette
+setup-copy-format = Copy colors as
+setup-recolor = Recolor the terminal in the slideshow
+setup-block-count = { $count } blocks
+setup-recolor-off = No, leave the terminal's colors alone
+setup-recolor-on = Yes, set its 16 ANSI colors to each palette
+title-settings = Settings
+setting-theory = Default theory
+setting-blocks = Blocks per palette
+setting-copy-format = Copy colors as
+setting-jitter = Jitter
+setting-strict = Strict mode
+setting-monochrome-scale = Monochrome lightness steps
+setting-animate-transitions = Animate transitions
+setting-generate-on-startup = Generate on startup
+setting-generate-on-theory-change = Generate on theory change
+setting-slideshow-seconds = Slideshow interval
+setting-slideshow-apply-to-terminal = Recolor the terminal in the slideshow
+setting-ansi-dim-variants = Dim ANSI variants
+setting-theme-chrome = Theme the app with the palette's roles
+setting-autosave = Save the project on quit
+setting-weight-imports = Size pasted colors by use
+setting-announce = Announce applied palettes
+setting-on = on
+setting-off = off
+setting-seconds = { $seconds }s
+theory-plugin = { $name } (plugin)
+theory-scaled = { $theory } ({ $scale })
+theory-jitter = Jitter: { $level }
+theory-strict = Strict: exact harmonies
+jitter-none = none
+jitter-low = low
+jitter-medium = medium
+jitter-high = high
+theory-explore = Explore ±{ $step }
+theory-ansi = ANSI { $theory }
+field-color = Enter color: { $value }
+field-label = Label: { $value }
+field-note = Note: { $value }
+field-name = Name: { $value }
+field-tags = Tags: { $value }
+field-search = Search: { $value }
+field-hue = Hue: { $value }
+color-history-help = Type a hue (210) or range (200-240)
+library-help = [/] search  [t] tags
+color-overview = Overview:
+
+## Color blocks
+
+block-locked = LOCKED
+block-unlocked = UNLOCKED
+block-frozen-hue = HUE LOCKED
+block-frozen-lightness = LIGHTNESS LOCKED
+status-seed = seed { $seed }
+status-block = Block { $index }/{ $count }
+status-recording = ● REC
+status-lighting = ☀ { $light }
+status-night-light = ☾ { $kelvin } K
+illuminant-d65 = daylight (D65)
+illuminant-d50 = print viewing light (D50)
+illuminant-a = warm indoor light (A)
+illuminant-f2 = office fluorescent light (F2)
+illuminant-d75 = overcast daylight (D75)
+
+## Roles
+
+role-none = No role
+role-held-by = (block { $block })
+role-background = Background
+role-surface = Surface
+role-text = Text
+role-primary = Primary
+role-secondary = Secondary
+role-error = Error
+role-warning = Warning
+role-success = Success
+diagnostic-low-contrast = Text on { $role } has contrast { $ratio }:1, below { $min }:1
+diagnostic-unexpected-hue = { $role } color at hue { $hue }° is outside the usual range for it
+diagnostic-surface-darker = Surface is darker than the dark background
+diagnostic-surface-lighter = Surface is lighter than the light background
+
+## Mixing
+
+mix-average = Weighted average
+mix-additive = Added as light
+mix-multiply = Multiplied as ink
+mix-block = Block { $block }
+
+## Blend modes
+
+blend-mode-multiply = Multiply
+blend-mode-screen = Screen
+blend-mode-overlay = Overlay
+blend-mode-soft-light = Soft light
+
+## Pairings
+
+pairing-block = block { $block }
+pairing-adjusted = block { $block }, adjusted
+
+## Notifications
+
+msg-copied = Copied { $hex }
+msg-no-roles = No roles assigned yet, press [r] to give the selected block one
+msg-roles-ok = Roles pass every check
+msg-no-pairings = Nothing reaches AA contrast against { $hex }, even adjusted
+msg-scheme-on = ANSI scheme: 16 colors plus foreground, background and cursor
+msg-scheme-off = Left the ANSI scheme designer, blocks can be added and deleted again
+msg-not-scheme = Only ANSI schemes have bright variants, press [A] to design one
+msg-variants-derived = Recolored { $count } variant slots from the normal colors
+msg-scheme-fixed = An ANSI scheme has a fixed set of slots, press [A] to leave the designer
+msg-roles-filled = Filled in { $roles }
+msg-roles-complete = Every role is assigned already
+msg-roles-left-out = No free unlocked block left for { $roles }, add blocks or unlock some
+msg-copy-failed = Couldn't copy { $hex }: { $error }
+msg-invalid-color = Invalid color: { $error }
+msg-task-progress = { $label }... { $done }/{ $total }
+msg-task-failed = { $label } failed: { $error }
+task-export-library = Exporting library
+task-download-picture = Downloading the picture
+task-run-hooks = Running hooks
+msg-appearance-unset = Name a light and a dark palette in the [schedule] of the config first
+msg-appearance-switched = Switched to the { $appearance } palette { $name }
+msg-appearance-failed = Couldn't open the { $appearance } palette { $name }: { $error }
+msg-schedule-invalid = The schedule in the config is invalid: { $error }
+msg-schedule-failed = Couldn't apply the scheduled palette { $name }: { $error }
+task-recolor-terminals = Recoloring other terminals
+msg-terminals-recolored = Other terminals recolored: { $count }
+msg-hooks-ran = Hooks finished, { $count } ran
+msg-exporting-library = Exporting library...
+msg-library-exported = Exported { $count } palettes to { $path }
+msg-plugin-failed = Plugin "{ $name }" failed: { $error }
+msg-swatch-book-failed = Could not load swatch book { $path }: { $error }
+msg-plugin-load-failed = Could not load plugin { $path }: { $error }
+msg-new-project = New project { $path }
+msg-project-saved = Saved project { $path }
+msg-project-save-failed = Could not save project: { $error }
+msg-loaded = Loaded "{ $name }"
+msg-no-recent = No recently used palettes yet
+msg-missing-file = { $path } no longer exists
+msg-open-failed = Could not open { $path }: { $error }
+msg-palette-saved = Saved to { $path }
+msg-save-failed = Save failed: { $error }
+msg-library-empty = No saved palettes yet, press [s] to save one
+msg-library-read-failed = Could not read library: { $error }
+msg-restored = Restored previous version of "{ $name }"
+msg-restore-failed = Could not restore "{ $name }": { $error }
+msg-star-failed = Could not star palette: { $error }
+msg-color-starred = Starred { $hex }
+msg-color-unstarred = Unstarred { $hex }
+msg-favorites-save-failed = Could not save favorites: { $error }
+msg-no-color-history = No colors yet, locked and hand-edited colors are kept here
+msg-blend-first = Blending from "{ $name }", press [b] on the palette to blend into
+msg-blend-loaded = Loaded the { $percent }% blend, it is unsaved
+msg-nothing-generated = Nothing generated yet, press [Space] first
+msg-undone = Undone, { $left } more to undo
+msg-redone = Redone, { $left } more to redo
+msg-nothing-to-undo = Nothing to undo
+msg-nothing-to-redo = Nothing to redo
+msg-states-derived = Added hover, active, focus and disabled variants of { $name }
+msg-out-of-gamut = Outside sRGB, clipped to { $hex }; [G] fits it keeping the hue
+msg-in-gamut = Nothing to fit, the color is inside sRGB
+msg-gamut-fitted = Fitted into sRGB as { $hex }
+msg-no-room = Not enough room for { $count } more blocks (max { $max })
+msg-no-favorites = No favorites yet, star a color with [f] or a palette with [*]
+msg-palette-full = Palette is full (max { $max } blocks)
+msg-color-inserted = Inserted { $hex } as a locked block
+msg-tagged = Tagged "{ $name }"
+msg-tags-save-failed = Could not save tags: { $error }
+msg-slideshow-started = Slideshow: a new palette every { $seconds }s, any key pauses
+msg-slideshow-paused = Slideshow paused, [p] resumes
+msg-editor-applied = Read { $count } colors back from the editor
+msg-editor-unchanged = No colors changed in the editor
+msg-editor-bad-line = Line { $line } isn't a color, nothing changed: { $error }
+msg-editor-count = The editor left { $count } colors, a palette takes { $min } to { $max }
+msg-editor-failed = Couldn't edit the palette: { $error }
+msg-clipboard-watch-on = Watching the clipboard, colors copied anywhere are offered for the selected block
+msg-clipboard-watch-off = Stopped watching the clipboard
+msg-clipboard-unreadable = Can't read the { $backend } clipboard: { $error }
+msg-recolor-failed = Could not recolor the terminal: { $error }
+msg-explore-on = Explore: [Space] nudges the palette by up to ±{ $step }, [+]/[-] change the step
+msg-explore-off = Back to generating with the theory
+msg-chrome-on = The app wears the palette's background, surface, text and primary roles
+msg-chrome-off = Back to the app's own colors
+msg-illuminant = Showing the palette under { $light }, copies and exports keep the true colors
+msg-illuminant-off = Back to daylight, the palette shows its true colors
+msg-night-light = Showing the palette through a night-light filter at { $kelvin } K
+msg-night-light-off = Night-light filter off
+msg-gradient-space = { $name } blends in { $space }
+msg-gradient-deleted = Deleted { $name }
+msg-gradient-copied = Copied the CSS of { $name }
+msg-gradient-copy-failed = Couldn't copy the CSS of { $name }: { $error }
+msg-colors-imported = Took { $count } of the { $found } colors in the pasted text, { $hex } covers { $percent }%
+msg-frame-extracted = Took { $count } colors from the frame, { $hex } covers { $percent }%
+msg-extract-too-few = The frame has only { $count } distinct colors, a palette takes at least 3
+msg-downloading-picture = Downloading { $url }...
+msg-extract-failed = Couldn't take colors from the picture: { $error }
+msg-gradient-split = Split the gradient into { $count } blocks, paste it into the gradients popup [t] to keep it whole
+msg-invalid-gradient = Can't read the gradient: { $error }
+msg-block-width = Block { $block } takes { $percent }% of its row
+msg-explore-step = Explore step ±{ $step }
+msg-macro-recording = Recording a macro, [Q] stops
+msg-macro-recorded = Recorded { $count } actions, [@] replays them
+msg-no-macro = No macro recorded yet, [Q] starts recording
+msg-command-empty = Type a command, e.g. theory triad
+msg-command-unknown = Unknown command: { $command }
+msg-command-bad-argument = Can't use { $argument } here
+msg-command-usage = Usage: { $usage }
+msg-theory-set = Theory: { $theory }
+msg-blocks-set = { $count } blocks
+msg-exported = Exported to { $path }
+msg-export-failed = Export failed: { $error }
+msg-setup-done = Settings saved to { $path }
+msg-config-save-failed = Could not save the settings: { $error }
@@ -0,0 +1,687 @@
+//! Everything the user can ask the app to do, decoupled from the keys that trigger it.
+//!
+//! Key events are decoded into an [`Action`] by [`decode_key`] and then applied by
+//! `App::update`, so actions can also be scripted, remapped or replayed without a terminal.
+//! Each page's [`Binding`] table is the single source for both decoding and the status bar
+//! hints, so the hints can't drift from the keys that actually work.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::{CurrentPage, LibraryInput};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Quit,
+
+    // Main view
+    SelectPrevious,
+    SelectNext,
+    SelectRowUp,
+    SelectRowDown,
+    /// Select the first block of the selected block's row
+    SelectRowStart,
+    SelectRowEnd,
+    /// Select in the top row, keeping to the column
+    SelectTopRow,
+    SelectBottomRow,
+    AddBlock,
+    DeleteBlock,
+    RepeatLast,
+    /// Start or stop recording a macro
+    ToggleRecording,
+    /// Replay the last recorded macro
+    PlayMacro,
+    Generate,
+    /// Go back to the palette before the last change
+    Undo,
+    /// Go forward to the palette last undone
+    Redo,
+    ToggleLock,
+    /// Lock only the hue or only the lightness of the selected block
+    CycleFreeze,
+    /// Toggle the lock of the n-th (1-based) block in the selected block's row
+    ToggleLockInRow(usize),
+    /// Give the selected block a larger share of its row
+    WidenBlock,
+    NarrowBlock,
+    CopyHex,
+    ToggleFavoriteColor,
+    SaveProject,
+    /// Assign and color every role no block holds yet
+    FillRoles,
+    /// Start the slideshow; any key pauses it again
+    StartSlideshow,
+    /// Start or stop offering colors copied to the clipboard for the selected block
+    ToggleClipboardWatch,
+    /// Edit the palette as a list of hex codes in `$EDITOR`
+    OpenEditor,
+    /// Enter or leave the ANSI scheme designer
+    ToggleAnsiScheme,
+    /// Recompute the bright (and dim) slots of an ANSI scheme from its normal colors
+    DeriveVariants,
+    /// Show the palette from before the last generation above the current one
+    ToggleCompare,
+    /// Switch generation between the theory and small random walks from the current palette
+    ToggleExplore,
+    IncreaseExploreStep,
+    DecreaseExploreStep,
+    /// Show the next page of status bar hints
+    MoreHints,
+    /// Color the app's own borders, backgrounds and text with the palette's roles
+    ToggleChrome,
+    /// Switch to the other of the light and dark palettes in `[schedule]`
+    ToggleAppearance,
+    /// Show the palette under the next illuminant, back to daylight after the last
+    CycleIlluminant,
+    /// Show the palette through the next night-light strength, then without one
+    CycleNightLight,
+
+    // Opening pages
+    OpenTheorySelector,
+    OpenEditColor,
+    OpenEditLabel,
+    OpenEditNote,
+    OpenSavePalette,
+    OpenLibrary,
+    OpenRecent,
+    OpenFavorites,
+    OpenChartPreview,
+    OpenProportionPreview,
+    OpenCodePreview,
+    OpenArtPreview,
+    OpenBalance,
+    OpenGradients,
+    OpenRolePicker,
+    OpenDiagnostics,
+    OpenPairings,
+    OpenCollections,
+    OpenColorHistory,
+    OpenMix,
+    OpenBlendModes,
+    OpenCommandLine,
+    OpenSettings,
+    /// Complete the command line with its first suggestion
+    Complete,
+    DeriveStates,
+    FitGamut,
+
+    // Theory selector
+    /// Switch the lightness steps the Monochrome theory follows
+    CycleLightnessScale,
+    /// Switch how far generated colors stray from the exact harmony
+    CycleJitter,
+    /// Generate exact harmonies, ignoring the jitter level
+    ToggleStrict,
+
+    // Settings page
+    PreviousSettingValue,
+    NextSettingValue,
+
+    // Blend modes popup
+    PreviousTopBlock,
+    NextTopBlock,
+
+    // Mix popup
+    IncreaseWeight,
+    DecreaseWeight,
+
+    // Collections popup
+    PreviousCollection,
+    NextCollection,
+
+    // Shared by popups
+    /// Close the popup, or leave the text field being edited
+    Back,
+    /// Apply the popup: pick the highlighted entry or submit the typed text
+    Confirm,
+    ListFirst,
+    ListLast,
+    ListPrevious,
+    ListNext,
+    Input(char),
+    Backspace,
+    ClearInput,
+
+    // Library popup
+    StartSearch,
+    EditTags,
+    TogglePaletteStar,
+    RestorePalette,
+    ExportLibrary,
+    /// Pick the highlighted palette as one end of a blend; the second pick opens the blend
+    BlendPalette,
+
+    // Blend popup
+    MoreBlendSteps,
+    FewerBlendSteps,
+
+    // Gradients popup
+    /// Make a gradient through the blocks in order and name it
+    AddGradient,
+    RenameGradient,
+    DeleteGradient,
+    /// Blend the highlighted gradient in the next interpolation space
+    CycleGradientSpace,
+}
+
+/// Keys triggering `action` on a page, and the hint advertising them
+#[derive(Debug, Clone)]
+pub struct Binding {
+    /// Keys with modifiers; `NONE` accepts any modifiers not claimed by an earlier binding
+    pub keys: &'static [(KeyCode, KeyModifiers)],
+    pub action: Action,
+    /// Fluent id of the hint label; consecutive bindings with the same id share one hint,
+    /// and an empty id keeps the binding out of the hints
+    pub hint: &'static str,
+}
+
+impl Binding {
+    fn matches(&self, key: &KeyEvent) -> bool {
+        self.keys.iter().any(|(code, modifiers)| {
+            *code == key.code && (modifiers.is_empty() || key.modifiers.contains(*modifiers))
+        })
+    }
+}
+
+const fn key(code: KeyCode) -> (KeyCode, KeyModifiers) {
+    (code, KeyModifiers::NONE)
+}
+
+const fn ch(c: char) -> (KeyCode, KeyModifiers) {
+    key(KeyCode::Char(c))
+}
+
+const fn ctrl(c: char) -> (KeyCode, KeyModifiers) {
+    (KeyCode::Char(c), KeyModifiers::CONTROL)
+}
+
+const fn ctrl_shift(code: KeyCode) -> (KeyCode, KeyModifiers) {
+    (code, KeyModifiers::CONTROL.union(KeyModifiers::SHIFT))
+}
+
+const fn bind(
+    keys: &'static [(KeyCode, KeyModifiers)],
+    action: Action,
+    hint: &'static str,
+) -> Binding {
+    Binding { keys, action, hint }
+}
+
+/// Most important first: narrow terminals only fit the first hints
+const MAIN: &[Binding] = &[
+    bind(&[ch('q')], Action::Quit, "hint-quit"),
+    bind(&[ch(' ')], Action::Generate, "hint-generate"),
+    // Ahead of the plain arrows, which take any modifiers; too rare to earn a hint
+    bind(&[ctrl_shift(KeyCode::Right)], Action::WidenBlock, ""),
+    bind(&[ctrl_shift(KeyCode::Left)], Action::NarrowBlock, ""),
+    bind(&[key(KeyCode::Left)], Action::SelectPrevious, "hint-move"),
+    bind(&[key(KeyCode::Right)], Action::SelectNext, "hint-move"),
+    bind(&[key(KeyCode::Up)], Action::SelectRowUp, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::SelectRowDown, "hint-move"),
+    bind(&[ch('l')], Action::ToggleLock, "hint-lock"),
+    // Ahead of `z` and `y`, which take any modifiers
+    bind(&[ctrl('z')], Action::Undo, "hint-undo"),
+    bind(&[ctrl('y')], Action::Redo, "hint-redo"),
+    bind(&[ch('z')], Action::OpenEditColor, "hint-edit"),
+    bind(&[ch('c')], Action::CopyHex, "hint-copy"),
+    bind(&[ch('x')], Action::OpenTheorySelector, "hint-theory"),
+    bind(&[ch(':')], Action::OpenCommandLine, "hint-command"),
+    bind(&[ch('a')], Action::AddBlock, "hint-add"),
+    bind(&[ch('d')], Action::DeleteBlock, "hint-delete"),
+    bind(&[ctrl('n')], Action::OpenEditNote, "hint-note"),
+    bind(&[ch('n')], Action::OpenEditLabel, "hint-label"),
+    bind(&[ctrl('s')], Action::SaveProject, "hint-save-project"),
+    bind(&[ch('s')], Action::OpenSavePalette, "hint-save"),
+    bind(&[ch('o')], Action::OpenLibrary, "hint-open"),
+    bind(&[ch('E')], Action::OpenEditor, "hint-editor"),
+    bind(&[ctrl('r')], Action::OpenRecent, "hint-recent"),
+    bind(&[ch('r')], Action::OpenRolePicker, "hint-role"),
+    bind(&[ch('R')], Action::FillRoles, "hint-fill-roles"),
+    bind(&[ch('i')], Action::OpenDiagnostics, "hint-check"),
+    bind(&[ch('g')], Action::OpenPairings, "hint-pairs"),
+    bind(&[ch('m')], Action::OpenMix, "hint-mix"),
+    bind(&[ch('M')], Action::OpenBlendModes, "hint-blend-modes"),
+    bind(&[ch('u')], Action::DeriveStates, "hint-states"),
+    bind(&[ch('G')], Action::FitGamut, "hint-fit-gamut"),
+    bind(&[ch('k')], Action::CycleFreeze, "hint-freeze"),
+    bind(&[ch('f')], Action::ToggleFavoriteColor, "hint-star"),
+    bind(&[ch('F')], Action::OpenFavorites, "hint-favorites"),
+    bind(&[ch('N')], Action::OpenCollections, "hint-collections"),
+    bind(&[ch('H')], Action::OpenColorHistory, "hint-color-history"),
+    bind(&[ch('.')], Action::RepeatLast, "hint-repeat"),
+    bind(&[ch('Q')], Action::ToggleRecording, "hint-record"),
+    bind(&[ch('@')], Action::PlayMacro, "hint-play-macro"),
+    bind(&[ch('w')], Action::ToggleCompare, "hint-compare"),
+    bind(&[ch('v')], Action::OpenChartPreview, "hint-preview"),
+    bind(
+        &[ch('P')],
+        Action::OpenProportionPreview,
+        "hint-proportions",
+    ),
+    bind(&[ch('y')], Action::OpenCodePreview, "hint-code"),
+    bind(&[ch('I')], Action::OpenArtPreview, "hint-art"),
+    bind(&[ch('b')], Action::OpenBalance, "hint-balance"),
+    bind(&[ch('t')], Action::OpenGradients, "hint-gradients"),
+    bind(&[ch('A')], Action::ToggleAnsiScheme, "hint-ansi"),
+    bind(&[ch('B')], Action::DeriveVariants, "hint-variants"),
+    bind(&[ch('e')], Action::ToggleExplore, "hint-explore"),
+    bind(&[ch('+')], Action::IncreaseExploreStep, "hint-step"),
+    bind(&[ch('-')], Action::DecreaseExploreStep, "hint-step"),
+    bind(&[ch('p')], Action::StartSlideshow, "hint-slideshow"),
+    bind(
+        &[ch('V')],
+        Action::ToggleClipboardWatch,
+        "hint-watch-clipboard",
+    ),
+    bind(&[ch('T')], Action::ToggleChrome, "hint-chrome"),
+    bind(&[ch('D')], Action::ToggleAppearance, "hint-appearance"),
+    bind(&[ch('W')], Action::CycleIlluminant, "hint-illuminant"),
+    bind(&[ch('L')], Action::CycleNightLight, "hint-night-light"),
+    bind(&[ch(',')], Action::OpenSettings, "hint-settings"),
+    bind(&[key(KeyCode::Home)], Action::SelectRowStart, "hint-jump"),
+    bind(&[key(KeyCode::End)], Action::SelectRowEnd, "hint-jump"),
+    bind(&[key(KeyCode::PageUp)], Action::SelectTopRow, "hint-jump"),
+    bind(
+        &[key(KeyCode::PageDown)],
+        Action::SelectBottomRow,
+        "hint-jump",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const THEORY_SELECTOR: &[Binding] = &[
+    bind(
+        &[ch('x'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Left)], Action::ListFirst, "hint-first"),
+    bind(&[key(KeyCode::Right)], Action::ListLast, "hint-last"),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[ch('s')], Action::CycleLightnessScale, "hint-scale"),
+    bind(&[ch('j')], Action::CycleJitter, "hint-jitter"),
+    bind(&[ch('t')], Action::ToggleStrict, "hint-strict"),
+    bind(
+        &[key(KeyCode::Enter), ch(' ')],
+        Action::Confirm,
+        "hint-apply",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const CHART_PREVIEW: &[Binding] = &[
+    bind(
+        &[ch('v'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const BALANCE: &[Binding] = &[
+    bind(
+        &[ch('b'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const GRADIENTS: &[Binding] = &[
+    bind(
+        &[ch('t'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[ch('a')], Action::AddGradient, "hint-add"),
+    bind(&[ch('s')], Action::CycleGradientSpace, "hint-space"),
+    bind(&[ch('n')], Action::RenameGradient, "hint-rename"),
+    bind(&[ch('d')], Action::DeleteGradient, "hint-delete"),
+    bind(
+        &[key(KeyCode::Enter), ch('c')],
+        Action::Confirm,
+        "hint-copy-css",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const PROPORTION_PREVIEW: &[Binding] = &[
+    bind(
+        &[ch('P'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const CODE_PREVIEW: &[Binding] = &[
+    bind(
+        &[ch('y'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const ART_PREVIEW: &[Binding] = &[
+    bind(
+        &[ch('I'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const ROLE_PICKER: &[Binding] = &[
+    bind(
+        &[ch('r'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-apply"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const DIAGNOSTICS: &[Binding] = &[
+    bind(
+        &[ch('i'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-select"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const PAIRINGS: &[Binding] = &[
+    bind(
+        &[ch('g'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-insert"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const COLLECTIONS: &[Binding] = &[
+    bind(
+        &[ch('N'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(
+        &[key(KeyCode::Left)],
+        Action::PreviousCollection,
+        "hint-collection",
+    ),
+    bind(
+        &[key(KeyCode::Right)],
+        Action::NextCollection,
+        "hint-collection",
+    ),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-insert"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const BLEND_MODES: &[Binding] = &[
+    bind(
+        &[ch('M'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(
+        &[key(KeyCode::Left)],
+        Action::PreviousTopBlock,
+        "hint-top-block",
+    ),
+    bind(
+        &[key(KeyCode::Right)],
+        Action::NextTopBlock,
+        "hint-top-block",
+    ),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-insert"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const MIX: &[Binding] = &[
+    bind(
+        &[ch('m'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[ch('+')], Action::IncreaseWeight, "hint-weight"),
+    bind(&[ch('-')], Action::DecreaseWeight, "hint-weight"),
+    bind(
+        &[key(KeyCode::Enter), ch(' ')],
+        Action::Confirm,
+        "hint-toggle-insert",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const BLEND: &[Binding] = &[
+    bind(&[ch('q'), key(KeyCode::Esc)], Action::Back, "hint-close"),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[ch('+')], Action::MoreBlendSteps, "hint-steps"),
+    bind(&[ch('-')], Action::FewerBlendSteps, "hint-steps"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-load"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+/// Typed characters become [`Action::Input`] on top of these, for the hue search
+const COLOR_HISTORY: &[Binding] = &[
+    bind(&[key(KeyCode::Esc)], Action::Back, "hint-close"),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[key(KeyCode::Backspace)], Action::Backspace, "hint-delete"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-insert"),
+];
+
+/// Typed characters become [`Action::Input`] on top of these
+const EDIT_COLOR: &[Binding] = &[
+    bind(&[key(KeyCode::Esc)], Action::Back, "hint-cancel"),
+    // doesnt work gonna look later
+    bind(
+        &[(KeyCode::Backspace, KeyModifiers::CONTROL)],
+        Action::ClearInput,
+        "hint-clear",
+    ),
+    bind(&[key(KeyCode::Backspace)], Action::Backspace, "hint-delete"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-apply"),
+];
+
+const EDIT_LABEL: &[Binding] = &[
+    bind(&[key(KeyCode::Esc)], Action::Back, "hint-cancel"),
+    bind(&[key(KeyCode::Backspace)], Action::Backspace, "hint-delete"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-apply"),
+];
+
+const CLIPBOARD_OFFER: &[Binding] = &[
+    bind(&[key(KeyCode::Esc), ch('n')], Action::Back, "hint-skip"),
+    bind(
+        &[key(KeyCode::Enter), ch('y')],
+        Action::Confirm,
+        "hint-insert",
+    ),
+];
+
+const SETUP: &[Binding] = &[
+    bind(&[key(KeyCode::Esc)], Action::Back, "hint-skip"),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(
+        &[key(KeyCode::Enter), ch(' ')],
+        Action::Confirm,
+        "hint-next",
+    ),
+];
+
+const SETTINGS: &[Binding] = &[
+    bind(
+        &[ch(','), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(
+        &[key(KeyCode::Left)],
+        Action::PreviousSettingValue,
+        "hint-change",
+    ),
+    bind(
+        &[key(KeyCode::Right), key(KeyCode::Enter), ch(' ')],
+        Action::NextSettingValue,
+        "hint-change",
+    ),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const COMMAND_LINE: &[Binding] = &[
+    bind(&[key(KeyCode::Esc)], Action::Back, "hint-cancel"),
+    bind(&[key(KeyCode::Tab)], Action::Complete, "hint-complete"),
+    bind(&[key(KeyCode::Backspace)], Action::Backspace, "hint-delete"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-run"),
+];
+
+const SAVE_PALETTE: &[Binding] = &[
+    bind(&[key(KeyCode::Esc)], Action::Back, "hint-cancel"),
+    bind(&[key(KeyCode::Backspace)], Action::Backspace, "hint-delete"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-save"),
+];
+
+const RECENT_SWITCHER: &[Binding] = &[
+    bind(&[ch('q'), key(KeyCode::Esc)], Action::Back, "hint-close"),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(
+        &[key(KeyCode::Down), ctrl('r')],
+        Action::ListNext,
+        "hint-move",
+    ),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-open"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const FAVORITES: &[Binding] = &[
+    bind(
+        &[ch('F'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-load-insert"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+const OPEN_PALETTE: &[Binding] = &[
+    bind(
+        &[ch('o'), ch('q'), key(KeyCode::Esc)],
+        Action::Back,
+        "hint-close",
+    ),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-load"),
+    bind(&[ch('/')], Action::StartSearch, "hint-search"),
+    bind(&[ch('t')], Action::EditTags, "hint-tags"),
+    bind(&[ch('*')], Action::TogglePaletteStar, "hint-star"),
+    bind(&[ch('R')], Action::RestorePalette, "hint-restore"),
+    bind(&[ch('b')], Action::BlendPalette, "hint-blend"),
+    bind(&[ch('E')], Action::ExportLibrary, "hint-export"),
+    bind(&[ch('?')], Action::MoreHints, ""),
+];
+
+/// Search or tags field of the library popup; typed characters become [`Action::Input`]
+const LIBRARY_INPUT: &[Binding] = &[
+    bind(&[key(KeyCode::Esc)], Action::Back, "hint-cancel"),
+    bind(&[key(KeyCode::Up)], Action::ListPrevious, "hint-move"),
+    bind(&[key(KeyCode::Down)], Action::ListNext, "hint-move"),
+    bind(&[key(KeyCode::Backspace)], Action::Backspace, "hint-delete"),
+    bind(&[key(KeyCode::Enter)], Action::Confirm, "hint-apply"),
+];
+
+/// The keymap of `page`, in the order hints are shown.
+/// `input` tells whether a text field of the library popup has focus.
+pub fn bindings(page: CurrentPage, input: LibraryInput) -> &'static [Binding] {
+    match page {
+        CurrentPage::Main => MAIN,
+        CurrentPage::TheorySelector => THEORY_SELECTOR,
+        CurrentPage::EditColor => EDIT_COLOR,
+        CurrentPage::EditLabel | CurrentPage::EditNote | CurrentPage::NameGradient => EDIT_LABEL,
+        CurrentPage::ClipboardOffer => CLIPBOARD_OFFER,
+        CurrentPage::SavePalette => SAVE_PALETTE,
+        CurrentPage::RecentSwitcher => RECENT_SWITCHER,
+        CurrentPage::ChartPreview => CHART_PREVIEW,
+        CurrentPage::ProportionPreview => PROPORTION_PREVIEW,
+        CurrentPage::Balance => BALANCE,
+        CurrentPage::Gradients => GRADIENTS,
+        CurrentPage::CodePreview => CODE_PREVIEW,
+        CurrentPage::ArtPreview => ART_PREVIEW,
+        CurrentPage::RolePicker => ROLE_PICKER,
+        CurrentPage::Diagnostics => DIAGNOSTICS,
+        CurrentPage::Pairings => PAIRINGS,
+        CurrentPage::Favorites => FAVORITES,
+        CurrentPage::Collections => COLLECTIONS,
+        CurrentPage::ColorHistory => COLOR_HISTORY,
+        CurrentPage::Blend => BLEND,
+        CurrentPage::Mix => MIX,
+        CurrentPage::BlendModes => BLEND_MODES,
+        CurrentPage::CommandLine => COMMAND_LINE,
+        CurrentPage::Setup => SETUP,
+        CurrentPage::Settings => SETTINGS,
+        CurrentPage::OpenPalette if input != LibraryInput::None => LIBRARY_INPUT,
+        CurrentPage::OpenPalette => OPEN_PALETTE,
+    }
+}
+
+/// Whether typed characters on `page` go into a text field
+fn takes_text(page: CurrentPage, input: LibraryInput) -> bool {
+    match page {
+        CurrentPage::EditColor
+        | CurrentPage::EditLabel
+        | CurrentPage::EditNote
+        | CurrentPage::NameGradient
+        | CurrentPage::SavePalette
+        | CurrentPage::CommandLine
+        | CurrentPage::ColorHistory => true,
+        CurrentPage::OpenPalette => input != LibraryInput::None,
+        _ => false,
+    }
+}
+
+/// The action `key` stands for on `page`, if any.
+/// `input` tells whether a text field of the library popup has focus.
+pub fn decode_key(page: CurrentPage, input: LibraryInput, key: KeyEvent) -> Option<Action> {
+    if let Some(binding) = bindings(page, input)
+        .iter()
+        .find(|binding| binding.matches(&key))
+    {
+        return Some(binding.action.clone());
+    }
+
+    match (key.code, key.modifiers) {
+        (KeyCode::Char(c), _) if takes_text(page, input) => Some(Action::Input(c)),
+        (KeyCode::Char(c), KeyModifiers::ALT)
+            if page == CurrentPage::Main && ('1'..='9').contains(&c) =>
+        {
+            Some(Action::ToggleLockInRow(c.to_digit(10).unwrap() as usize))
+        }
+        _ => None,
+    }
+}
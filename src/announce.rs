@@ -0,0 +1,38 @@
+//! The palette last set as the terminal's colors, in a well-known file for status bars,
+//! widgets and compositors to follow.
+//!
+//! With `announce` on, every applied palette is written to [`state_path`] as the JSON
+//! hooks get (see [`crate::hooks`]). The file is replaced in one step, so a watcher such
+//! as `inotifywait -e moved_to` never reads half of it.
+
+use std::{env, fs, io, path::PathBuf};
+
+use terminal_palette::export::ExportPalette;
+
+use crate::hooks::{self, Event};
+
+pub fn state_dir() -> PathBuf {
+    let base = match env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var_os("HOME").unwrap_or_default();
+            PathBuf::from(home).join(".local").join("state")
+        }
+    };
+
+    base.join("terminal-palette")
+}
+
+pub fn state_path() -> PathBuf {
+    state_dir().join("palette.json")
+}
+
+/// Write `palette` to [`state_path`] as just applied
+pub fn announce(palette: &ExportPalette) -> io::Result<()> {
+    let path = state_path();
+    fs::create_dir_all(state_dir())?;
+    let partial = path.with_extension("json.partial");
+    let document = hooks::palette_json(&Event::Apply, palette);
+    fs::write(&partial, serde_json::to_string_pretty(&document)?)?;
+    fs::rename(&partial, &path)
+}
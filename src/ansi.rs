@@ -0,0 +1,279 @@
+//! Terminal color schemes: the 16 ANSI colors plus foreground, background and cursor.
+//!
+//! A scheme is an ordinary palette of [`SLOT_COUNT`] blocks labeled with [`SLOTS`], so it
+//! saves, locks and exports like any other. [`generate`] takes the six chromatic hues from
+//! a color theory, keeps each near the hue terminals promise for its slot, and derives the
+//! bright variants and the special colors from the normal ones.
+//!
+//! [`from_colors`] builds a scheme around the colors of a picture instead, for theming a
+//! desktop after its wallpaper.
+//!
+//! Schemes may carry 8 extra dim slots after the special ones, for terminals with a
+//! separate dim palette. [`derive_variants`] fills bright and dim slots from the normal
+//! colors with the same OKLab lightness and chroma offsets for every hue.
+
+use palette::{FromColor, Oklch, Srgb};
+use rand::RngCore;
+
+use crate::color::ColorBlock;
+use crate::convert;
+use crate::generators::ColorTheories;
+
+pub const SLOT_COUNT: usize = 19;
+
+/// Slot labels in scheme order: normal colors 0-7, bright 8-15, then the special colors
+pub const SLOTS: [&str; SLOT_COUNT] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright black",
+    "bright red",
+    "bright green",
+    "bright yellow",
+    "bright blue",
+    "bright magenta",
+    "bright cyan",
+    "bright white",
+    "foreground",
+    "background",
+    "cursor",
+];
+
+/// Labels of the optional dim slots, which follow [`SLOTS`]
+pub const DIM_SLOTS: [&str; 8] = [
+    "dim black",
+    "dim red",
+    "dim green",
+    "dim yellow",
+    "dim blue",
+    "dim magenta",
+    "dim cyan",
+    "dim white",
+];
+
+pub const BRIGHT_OFFSET: usize = 8;
+pub const FOREGROUND: usize = 16;
+pub const BACKGROUND: usize = 17;
+pub const CURSOR: usize = 18;
+pub const DIM_OFFSET: usize = SLOT_COUNT;
+
+/// OKLab lightness a bright variant gains over its normal color, and a dim one loses
+const BRIGHT_LIGHTNESS: f32 = 0.08;
+const DIM_LIGHTNESS: f32 = 0.12;
+/// Chroma factors of the variants: brights pop a little more, dims fade
+const BRIGHT_CHROMA: f32 = 1.05;
+const DIM_CHROMA: f32 = 0.75;
+/// Bright black is the usual gray for comments and hints, so it has to be readable
+const MIN_BRIGHT_BLACK_LIGHTNESS: f32 = 0.45;
+
+/// Hue (degrees) each chromatic normal slot, red to cyan, stands for
+const SLOT_HUES: [f32; 6] = [0.0, 120.0, 60.0, 240.0, 300.0, 180.0];
+/// How far a theory may pull a slot from its hue before red stops reading as red
+const MAX_HUE_SHIFT: f32 = 20.0;
+
+/// A scheme of black blocks, ready for [`generate`]; `dim` adds the dim slots
+pub fn new_scheme(dim: bool) -> Vec<ColorBlock> {
+    let dim_slots: &[&str] = if dim { &DIM_SLOTS } else { &[] };
+    SLOTS
+        .iter()
+        .chain(dim_slots)
+        .enumerate()
+        .map(|(i, slot)| {
+            let mut block = ColorBlock::new(i + 1, 0.0, 0.0, 0.0);
+            block.label = slot.to_string();
+            block
+        })
+        .collect()
+}
+
+/// Whether `color_blocks` is laid out as a scheme, with or without dim slots
+pub fn is_scheme(color_blocks: &[ColorBlock]) -> bool {
+    [SLOT_COUNT, SLOT_COUNT + DIM_SLOTS.len()].contains(&color_blocks.len())
+        && color_blocks
+            .iter()
+            .zip(SLOTS.iter().chain(&DIM_SLOTS))
+            .all(|(block, slot)| block.label == *slot)
+}
+
+/// Whether the scheme has the dim slots
+pub fn has_dim_slots(color_blocks: &[ColorBlock]) -> bool {
+    color_blocks.len() > SLOT_COUNT
+}
+
+/// Recolor the unlocked bright (and dim) slots of a scheme from the 8 normal colors.
+/// Returns how many slots changed color.
+pub fn derive_variants(color_blocks: &mut [ColorBlock]) -> usize {
+    if !is_scheme(color_blocks) {
+        return 0;
+    }
+
+    let mut changed = 0;
+    for normal in 0..BRIGHT_OFFSET {
+        let base = oklch(&color_blocks[normal]);
+
+        let mut bright = Oklch::new(
+            (base.l + BRIGHT_LIGHTNESS).min(0.98),
+            base.chroma * BRIGHT_CHROMA,
+            base.hue,
+        );
+        if normal == 0 {
+            bright.l = bright.l.max(MIN_BRIGHT_BLACK_LIGHTNESS);
+        }
+        let mut targets = vec![(normal + BRIGHT_OFFSET, bright)];
+        if has_dim_slots(color_blocks) {
+            let dim = Oklch::new(
+                (base.l - DIM_LIGHTNESS).max(0.05),
+                base.chroma * DIM_CHROMA,
+                base.hue,
+            );
+            targets.push((normal + DIM_OFFSET, dim));
+        }
+
+        for (slot, color) in targets {
+            let block = &mut color_blocks[slot];
+            if block.locked {
+                continue;
+            }
+            let before = block.get_hex();
+            block.set_oklch(color);
+            if block.get_hex() != before {
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+fn oklch(block: &ColorBlock) -> Oklch {
+    let (r, g, b) = block.get_rgb_values();
+    Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>())
+}
+
+/// Recolor the unlocked slots of a scheme from `theory`. Locked chromatic slots are locked
+/// in the theory's palette too, so the harmony builds around them.
+pub fn generate(theory: ColorTheories, color_blocks: &mut [ColorBlock], rng: &mut dyn RngCore) {
+    if !is_scheme(color_blocks) {
+        return;
+    }
+
+    let mut harmony: Vec<ColorBlock> = color_blocks[1..=SLOT_HUES.len()].to_vec();
+    theory.generator().generate(&mut harmony, rng);
+    let base_hue = harmony[0].hsv.hue.into_positive_degrees();
+
+    // Each unlocked slot takes the closest hue of the harmony nobody took yet; locked slots
+    // keep their own
+    let mut unused: Vec<(f32, f32, f32)> = harmony
+        .iter()
+        .filter(|block| !block.locked)
+        .map(|block| {
+            (
+                block.hsv.hue.into_positive_degrees(),
+                block.hsv.saturation,
+                block.hsv.value,
+            )
+        })
+        .collect();
+    for (slot, &slot_hue) in SLOT_HUES.iter().enumerate() {
+        let block = &mut color_blocks[slot + 1];
+        if block.locked {
+            continue;
+        }
+        let nearest = (0..unused.len())
+            .min_by(|&a, &b| {
+                hue_offset(slot_hue, unused[a].0)
+                    .abs()
+                    .total_cmp(&hue_offset(slot_hue, unused[b].0).abs())
+            })
+            .expect("one harmony color per unlocked slot");
+        let (hue, sat, val) = unused.swap_remove(nearest);
+        set_slot(block, slot_hue, hue, sat, val);
+    }
+
+    // Neutrals lean towards the theory's base hue
+    set_unlocked(&mut color_blocks[0], base_hue, 0.15, 0.16);
+    set_unlocked(&mut color_blocks[7], base_hue, 0.05, 0.82);
+    finish(color_blocks);
+}
+
+/// A scheme around `colors`, dominant first as [`crate::extract::palette`] lists them:
+/// each chromatic slot takes the color closest to its hue, pulled no further than red
+/// still reads as red, black and white the darkest and lightest colors' tint. Slots no
+/// color is near keep their textbook hue. `dim` adds the dim slots.
+///
+/// ```
+/// use terminal_palette::ansi::{self, BACKGROUND};
+///
+/// // A dusky blue wallpaper with an orange sunset
+/// let scheme = ansi::from_colors(&[(28, 36, 64), (230, 120, 50), (90, 110, 170)], false);
+/// assert!(ansi::is_scheme(&scheme));
+/// let (hue, _, value) = scheme[BACKGROUND].get_hsv_values();
+/// assert!((200.0..260.0).contains(&hue) && value < 0.2, "{hue} {value}");
+/// ```
+pub fn from_colors(colors: &[(u8, u8, u8)], dim: bool) -> Vec<ColorBlock> {
+    let mut color_blocks = new_scheme(dim);
+    let hsv: Vec<(f32, f32, f32)> = colors
+        .iter()
+        .map(|&(r, g, b)| convert::rgb_to_hsv(r, g, b))
+        .collect();
+
+    // Grays have no hue worth keeping
+    let chromatic: Vec<_> = hsv.iter().filter(|(_, sat, _)| *sat >= 0.2).collect();
+    for (slot, &slot_hue) in SLOT_HUES.iter().enumerate() {
+        let nearest = chromatic.iter().min_by(|a, b| {
+            hue_offset(slot_hue, a.0)
+                .abs()
+                .total_cmp(&hue_offset(slot_hue, b.0).abs())
+        });
+        let &(hue, sat, val) = nearest.copied().unwrap_or(&(slot_hue, 0.5, 0.75));
+        set_slot(&mut color_blocks[slot + 1], slot_hue, hue, sat, val);
+    }
+
+    let by_value = |a: &&(f32, f32, f32), b: &&(f32, f32, f32)| a.2.total_cmp(&b.2);
+    if let (Some(&(dark_hue, dark_sat, _)), Some(&(light_hue, light_sat, _))) =
+        (hsv.iter().min_by(by_value), hsv.iter().max_by(by_value))
+    {
+        set_unlocked(&mut color_blocks[0], dark_hue, dark_sat.min(0.3), 0.16);
+        set_unlocked(&mut color_blocks[7], light_hue, light_sat.min(0.1), 0.82);
+    }
+    finish(&mut color_blocks);
+    color_blocks
+}
+
+/// Give a chromatic slot standing for `slot_hue` a color near `hue`, saturated and light
+/// enough to read as that slot
+fn set_slot(block: &mut ColorBlock, slot_hue: f32, hue: f32, sat: f32, val: f32) {
+    let hue = slot_hue + hue_offset(slot_hue, hue).clamp(-MAX_HUE_SHIFT, MAX_HUE_SHIFT);
+    block.change_color(
+        hue.rem_euclid(360.0),
+        sat.clamp(0.35, 0.85),
+        val.clamp(0.6, 0.9),
+    );
+}
+
+/// Derive the variants and the special slots from the normal colors
+fn finish(color_blocks: &mut [ColorBlock]) {
+    derive_variants(color_blocks);
+
+    let (hue, sat, _) = color_blocks[7].get_hsv_values();
+    set_unlocked(&mut color_blocks[FOREGROUND], hue, sat, 0.9);
+    let (hue, sat, _) = color_blocks[0].get_hsv_values();
+    set_unlocked(&mut color_blocks[BACKGROUND], hue, sat, 0.11);
+    let (hue, sat, val) = color_blocks[FOREGROUND].get_hsv_values();
+    set_unlocked(&mut color_blocks[CURSOR], hue, sat, val);
+}
+
+/// Signed shortest distance in degrees from hue `from` to hue `to`
+fn hue_offset(from: f32, to: f32) -> f32 {
+    (to - from + 180.0).rem_euclid(360.0) - 180.0
+}
+
+fn set_unlocked(block: &mut ColorBlock, hue: f32, sat: f32, val: f32) {
+    if !block.locked {
+        block.change_color(hue.rem_euclid(360.0), sat, val);
+    }
+}
@@ -1,90 +1,645 @@
-use std::io;
-
-use palette::Hsv;
-use rand::Rng;
+use std::{
+    borrow::Cow,
+    fs, io, iter,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use chrono::Local;
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+use palette::Oklch;
 use ratatui::{
-    DefaultTerminal, Frame,
+    Frame, Terminal,
+    backend::Backend,
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Stylize},
-    text::Line,
-    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Widget},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Widget, Wrap,
+    },
 };
 
+use rand::{SeedableRng, rngs::StdRng};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
-
-use arboard::Clipboard;
-
+use tracing::{debug, info, warn};
+
+use terminal_palette::ansi;
+use terminal_palette::balance;
+use terminal_palette::blend::{self, BlendMode};
+use terminal_palette::collections::COLLECTIONS;
+use terminal_palette::color::{ColorBlock, Freeze, hex2rgb, rgb2hsv};
+use terminal_palette::convert::{self, ColorFormat};
+use terminal_palette::export::{self, ExportPalette, Format};
+use terminal_palette::extract;
+use terminal_palette::generators::{self, ColorTheories, Jitter, LightnessScale};
+use terminal_palette::gradient::{CssError, Gradient, Space};
+use terminal_palette::illuminant::{self, Illuminant};
+use terminal_palette::library::{self, Favorites, RecentEntry, SavedColor, SavedPalette};
+use terminal_palette::night_light;
+use terminal_palette::pairing::{self, Suggestion};
+use terminal_palette::plugins::{self, Plugin};
+use terminal_palette::project::{self, Project, ProjectBlock, ProjectMetadata};
+use terminal_palette::roles::{self, Role, Violation};
+use terminal_palette::states::{self, State};
+use terminal_palette::swatches::{self, SwatchBook};
+
+use crate::action::{Action, decode_key};
+use crate::announce;
+use crate::appearance::{self, Appearance};
+use crate::clipboard::{self, ClipboardProvider};
+use crate::command::{self, Command};
+use crate::config::{self, Config};
+use crate::editor;
+use crate::graphics::{self, Protocol, Raster};
+use crate::hooks;
+use crate::ipc;
+use crate::osc;
+use crate::tasks::{TASK_POLL_INTERVAL, TaskMessage, TaskOutput, TaskRunner};
+use crate::transition::{FRAME_INTERVAL, Transition};
 use crate::widgets::{
-    content::{hex2rgb, rgb2hsv},
+    art::ArtPreview,
+    balance::BalancePane,
+    chrome::Chrome,
+    code::CodePreview,
+    content::{Grid, MainContent, PaletteStrip, grid_columns, role_name},
+    preview::{ChartPreview, ProportionPreview},
     status_bar::StatusBar,
 };
-use crate::{
-    margin,
-    widgets::content::{ColorBlock, MainContent},
-};
+use crate::{i18n, margin, t};
+
+/// Most intermediate palettes the blend popup shows between its two ends
+pub const MAX_BLEND_STEPS: usize = 9;
+
+/// Largest weight a block can have in the mix popup
+pub const MAX_MIX_WEIGHT: u8 = 9;
+
+/// Palette size bounds; 32 covers ANSI/Base24-style schemes and data-viz sets
+pub const MIN_BLOCKS: usize = 3;
+pub const MAX_BLOCKS: usize = 32;
 
-pub const HEX_CHARS: [char; 22] = [
-    'a', 'b', 'c', 'd', 'e', 'f', 'A', 'B', 'C', 'D', 'E', 'F', '0', '1', '2', '3', '4', '5', '6',
-    '7', '8', '9',
-];
+/// Widest a block can get relative to the others in its row
+pub const MAX_BLOCK_WIDTH: u16 = 12;
+
+/// Explore mode step bounds, changed by `EXPLORE_STEP_INCREMENT` with `+`/`-`
+pub const EXPLORE_STEP_DEFAULT: u8 = 8;
+pub const EXPLORE_STEP_MAX: u8 = 45;
+pub const EXPLORE_STEP_INCREMENT: u8 = 2;
+
+/// Where `E` in the library popup writes its bundle
+pub const LIBRARY_EXPORT_FILE: &str = "terminal-palette-library.json";
+
+/// How many earlier palettes a session (and its project file) remembers
+pub const HISTORY_LIMIT: usize = 50;
+/// How many changes `Ctrl+z` can take back
+pub const UNDO_LIMIT: usize = 100;
+
+/// How often `--daemon` checks whether the schedule's day or night has begun
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the clipboard watch looks for a newly copied color
+pub const CLIPBOARD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Longest clipboard text read as a color; anything longer was copied for another reason
+const CLIPBOARD_COLOR_MAX_LEN: usize = 64;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum CurrentPage {
     Main,
     TheorySelector,
     EditColor,
+    SavePalette,
+    OpenPalette,
+    Favorites,
+    EditLabel,
+    EditNote,
+    ClipboardOffer,
+    RecentSwitcher,
+    ChartPreview,
+    ProportionPreview,
+    CodePreview,
+    ArtPreview,
+    Balance,
+    Gradients,
+    NameGradient,
+    RolePicker,
+    Diagnostics,
+    Pairings,
+    Collections,
+    ColorHistory,
+    Blend,
+    Mix,
+    BlendModes,
+    CommandLine,
+    Setup,
+    Settings,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, EnumIter)]
-pub enum ColorTheories {
-    Analogous,
-    Complementary,
-    Triad,
-    Tetrad,
-    Hexad,
-    Monochrome,
-    Shadows,
-    Lights,
-    Neutrals,
+/// Questions of the first-run setup, in order
+#[derive(Debug, PartialEq, Copy, Clone, EnumIter)]
+pub enum SetupStep {
+    Theory,
+    Blocks,
+    CopyFormat,
+    Recolor,
 }
 
-pub struct App {
-    pub counter: i8,
+impl SetupStep {
+    fn key(self) -> &'static str {
+        match self {
+            SetupStep::Theory => "theory",
+            SetupStep::Blocks => "blocks",
+            SetupStep::CopyFormat => "copy-format",
+            SetupStep::Recolor => "recolor",
+        }
+    }
+}
+
+/// Palette sizes the setup offers
+const SETUP_BLOCK_COUNTS: [usize; 7] = [3, 4, 5, 6, 8, 12, 16];
+
+/// Rows of the settings page, each a key of the config file
+#[derive(Debug, PartialEq, Copy, Clone, EnumIter)]
+pub enum Setting {
+    Theory,
+    Blocks,
+    CopyFormat,
+    Jitter,
+    Strict,
+    MonochromeScale,
+    AnimateTransitions,
+    GenerateOnStartup,
+    GenerateOnTheoryChange,
+    SlideshowSeconds,
+    SlideshowApplyToTerminal,
+    AnsiDimVariants,
+    ThemeChrome,
+    Autosave,
+    WeightImports,
+    Announce,
+}
+
+impl Setting {
+    fn key(self) -> &'static str {
+        match self {
+            Setting::Theory => "theory",
+            Setting::Blocks => "blocks",
+            Setting::CopyFormat => "copy-format",
+            Setting::Jitter => "jitter",
+            Setting::Strict => "strict",
+            Setting::MonochromeScale => "monochrome-scale",
+            Setting::AnimateTransitions => "animate-transitions",
+            Setting::GenerateOnStartup => "generate-on-startup",
+            Setting::GenerateOnTheoryChange => "generate-on-theory-change",
+            Setting::SlideshowSeconds => "slideshow-seconds",
+            Setting::SlideshowApplyToTerminal => "slideshow-apply-to-terminal",
+            Setting::AnsiDimVariants => "ansi-dim-variants",
+            Setting::ThemeChrome => "theme-chrome",
+            Setting::Autosave => "autosave",
+            Setting::WeightImports => "weight-imports",
+            Setting::Announce => "announce",
+        }
+    }
+}
+
+/// The option after (or before) `current` in `options`, wrapping around
+fn cycle<T: Copy + PartialEq>(options: impl Iterator<Item = T>, current: T, forward: bool) -> T {
+    let options: Vec<T> = options.collect();
+    let idx = options.iter().position(|&o| o == current).unwrap_or(0);
+    let next = if forward {
+        (idx + 1) % options.len()
+    } else {
+        (idx + options.len() - 1) % options.len()
+    };
+    options[next]
+}
+
+/// Which text field of the library popup currently receives typed characters
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum LibraryInput {
+    None,
+    Search,
+    Tags,
+}
+
+/// A row of the favorites page
+#[derive(Debug, PartialEq, Clone)]
+pub enum FavoriteEntry {
+    Palette(SavedPalette),
+    Color(String),
+}
+
+/// Palette-mutating commands that can be re-applied with `.`
+#[derive(Debug, PartialEq, Clone)]
+pub enum RepeatableAction {
+    Generate,
+    AddBlock,
+    DeleteBlock,
+    SetHex(String),
+}
 
-    pub clipboard: Clipboard,
+/// The palette at one point of the session, for undo and redo
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub color_blocks: Vec<ColorBlock>,
+    pub selected_block_id: usize,
+    pub theory: ColorTheories,
+    pub ansi_scheme: bool,
+}
+
+impl Snapshot {
+    /// Whether the palette itself differs, leaving aside which block is selected
+    fn differs_from(&self, other: &Snapshot) -> bool {
+        self.color_blocks != other.color_blocks
+            || self.theory != other.theory
+            || self.ansi_scheme != other.ansi_scheme
+    }
+}
+
+pub struct App {
+    pub clipboard: Box<dyn ClipboardProvider>,
 
     pub theory_selector_state: ListState,
     pub current_page: CurrentPage,
     pub current_color_theory: ColorTheories,
 
     pub title: &'static str,
-    pub color_block_count: usize,
 
-    pub color_blocks: [Option<ColorBlock>; 9],
+    pub color_blocks: Vec<ColorBlock>,
     pub selected_block_id: usize,
+    /// Grid column that moving between rows aims for, kept across rows too short to reach
+    /// it; see [`App::select_row`]
+    pub selected_column: usize,
 
-    pub status_bar_msg: &'static str,
+    pub status_bar_msg: String,
 
     pub edit_color_field: String,
+    pub edit_label_field: String,
+    pub edit_note_field: String,
+
+    pub project_path: Option<PathBuf>,
+    pub project_metadata: ProjectMetadata,
+    pub history: Vec<Vec<String>>,
+    /// Palettes from before each change, newest last
+    pub undo_stack: Vec<Snapshot>,
+    /// Palettes undone since the last change, newest last
+    pub redo_stack: Vec<Snapshot>,
+    /// The palette as of the last [`App::checkpoint`], what the next change is measured
+    /// against
+    pub undo_base: Snapshot,
+
+    pub palette_name: String,
+    pub save_name_field: String,
+    /// Text typed after `:`
+    pub command_field: String,
+
+    /// Current question of the first-run setup
+    pub setup_step: SetupStep,
+    pub setup_state: ListState,
+    pub settings_state: ListState,
+    pub saved_palettes: Vec<SavedPalette>,
+    pub open_palette_state: ListState,
+    pub library_filtered: Vec<usize>,
+    pub library_input: LibraryInput,
+    pub library_query: String,
+    pub tags_field: String,
+
+    pub favorites: Favorites,
+    pub favorite_entries: Vec<FavoriteEntry>,
+    pub favorites_state: ListState,
+
+    /// Weight of every block in the mix popup, 0 for blocks left out
+    pub mix_weights: Vec<u8>,
+    /// Blocks first, then one row per [`MixResult`]
+    pub mix_state: ListState,
+
+    /// Lightness steps the Monochrome theory follows
+    pub monochrome_scale: LightnessScale,
+    /// How far built-in theories stray at random from their exact harmony
+    pub jitter: Jitter,
+    /// Generate without any jitter, whatever `jitter` is set to
+    pub strict: bool,
+    /// Color the app's own chrome with the palette's roles
+    pub theme_chrome: bool,
+
+    /// Block layered over the selected one in the blend modes popup
+    pub blend_top: usize,
+    /// One row per [`BlendMode`]
+    pub blend_mode_state: ListState,
+
+    /// Palette picked first with `b` in the library, waiting for the one to blend into
+    pub blend_from: Option<SavedPalette>,
+    /// The two ends of the open blend popup
+    pub blend_ends: Option<(SavedPalette, SavedPalette)>,
+    /// Intermediate palettes between the ends
+    pub blend_steps: usize,
+    pub blend_state: ListState,
+
+    /// Every color locked or edited by hand, most recent first, see [`library::remember_color`]
+    pub color_history: Vec<String>,
+    /// Hue or hue range the color history is narrowed to
+    pub color_history_query: String,
+    pub color_history_filtered: Vec<usize>,
+    pub color_history_state: ListState,
+
+    /// Index into [`COLLECTIONS`] of the collection being browsed
+    pub collection: usize,
+    pub collection_state: ListState,
+
+    pub recent_entries: Vec<RecentEntry>,
+    pub recent_state: ListState,
+
+    /// "No role" first, then every [`Role`] in declaration order
+    pub role_picker_state: ListState,
+    /// Rules the assigned roles broke when the diagnostics panel was opened
+    pub violations: Vec<Violation>,
+    pub diagnostics_state: ListState,
+    /// Pairings for the selected block, computed when the popup opens
+    pub pairings: Vec<Suggestion>,
+    pub pairings_state: ListState,
+    /// Named gradients of the project, see [`Gradient`]
+    pub gradients: Vec<Gradient>,
+    pub gradients_state: ListState,
+    pub gradient_name_field: String,
+
+    pub last_action: Option<RepeatableAction>,
+
+    /// Actions of the macro being recorded, `None` when not recording
+    pub recording: Option<Vec<Action>>,
+    /// Last recorded macro, replayed with `@`
+    pub macro_actions: Vec<Action>,
+
+    pub tasks: TaskRunner,
+    /// Socket other programs drive the app through, see [`ipc`]
+    pub ipc: Option<ipc::Server>,
+
+    pub plugins: Vec<Plugin>,
+    /// Plugin theory used instead of `current_color_theory` when set
+    pub active_plugin: Option<usize>,
+    /// Seed of the last built-in generation; `None` until then, after a plugin generated
+    /// or once another palette is loaded
+    pub seed: Option<u64>,
+    /// Page of status bar hints cycled with `?`; back to the first on every page change
+    pub hint_page: usize,
+
+    pub config: Config,
+    /// Loaded from `config.swatch_book`, to show each block's nearest swatch
+    pub swatch_book: Option<SwatchBook>,
+    /// Fade running after the last generation, if any
+    pub transition: Option<Transition>,
+    /// The palette is a terminal scheme: fixed slots, generated with [`ansi::generate`]
+    pub ansi_scheme: bool,
+    /// Palette as it was before the last generation
+    pub previous_palette: Option<Vec<ColorBlock>>,
+    /// Show [`previous_palette`](Self::previous_palette) stacked above the current palette
+    pub compare: bool,
+    /// Light the palette is shown under; only what's drawn changes, never the blocks
+    pub illuminant: Illuminant,
+    /// Color temperature (kelvin) of the night-light filter the palette is shown through
+    pub night_light: Option<u32>,
+    /// Generating walks from the current palette instead of applying the theory
+    pub explore: bool,
+    /// Largest hue (degrees) and saturation/value (percentage points) change per walk
+    pub explore_step: u8,
+    /// When the slideshow generates next; `None` while it's paused
+    pub slideshow: Option<Instant>,
+    /// Which of the schedule's light and dark palettes was opened last with `D` or at
+    /// startup
+    pub appearance: Option<Appearance>,
+    /// When `--daemon` looks at the schedule next, and whether it last applied the day's
+    /// palette
+    pub schedule: (Option<Instant>, Option<bool>),
+    /// The palette is to be opened in `$EDITOR`, which needs the terminal [`App::run`] holds
+    pub editor_requested: bool,
+    /// When watching the clipboard for colors, when to look at it next
+    pub clipboard_watch: Option<Instant>,
+    /// Clipboard text last seen while watching, so each copy is offered once
+    pub clipboard_seen: String,
+    /// Color found in the clipboard, offered for the selected block
+    pub clipboard_offer: Option<String>,
+    /// Whether the slideshow or an `apply` request recolored the terminal, which is
+    /// undone on exit
+    pub terminal_recolored: bool,
+    /// Protocol the blocks' colors are drawn as images with on the main view, `None` for
+    /// colored cells
+    pub graphics: Option<Protocol>,
+    /// Swatches the last frame drawn wants as images
+    pub rasters: Vec<Raster>,
+    /// Swatches on the screen as images now
+    pub rasters_shown: Vec<Raster>,
 
     pub exit: bool,
 }
 
 impl App {
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        // Opening a file or the system's palette at startup isn't for undoing
+        self.undo_base = self.snapshot();
         while !self.exit {
+            self.poll_tasks();
+            self.answer_requests(Duration::ZERO);
+            self.tick_slideshow();
+            self.tick_clipboard_watch();
+            // Catches what background tasks, the slideshow and `$EDITOR` changed
+            self.checkpoint();
+            if self.transition.as_ref().is_some_and(Transition::is_done) {
+                self.transition = None;
+            }
             terminal.draw(|frame| self.draw(frame))?;
+            self.draw_rasters(terminal)?;
             self.handle_events()?;
+
+            if self.editor_requested {
+                self.editor_requested = false;
+                self.edit_in_editor();
+                // The editor drew over the screen the terminal thinks is still there
+                terminal.clear()?;
+                terminal.hide_cursor()?;
+                self.rasters_shown.clear();
+            }
+        }
+
+        self.restore_terminals()
+    }
+
+    /// Draw the swatches the last frame wants as images, unless they are there already
+    fn draw_rasters<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        let Some(protocol) = self.graphics else {
+            return Ok(());
+        };
+        if self.rasters == self.rasters_shown {
+            return Ok(());
+        }
+        // Sixels stay on cells the next frame leaves as they were, so moved or dropped
+        // swatches take drawing the whole screen again
+        let moved = self.rasters.len() != self.rasters_shown.len()
+            || iter::zip(&self.rasters, &self.rasters_shown).any(|(a, b)| a.area != b.area);
+        if protocol == Protocol::Sixel && moved && !self.rasters_shown.is_empty() {
+            terminal.clear()?;
+            terminal.draw(|frame| self.draw(frame))?;
         }
+        graphics::draw(protocol, &self.rasters)?;
+        self.rasters_shown = self.rasters.clone();
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut Frame) {
+    /// `--daemon`: answer requests on the socket without any UI until one asks to quit
+    pub fn serve(&mut self) -> io::Result<()> {
+        while !self.exit {
+            self.poll_tasks();
+            self.tick_slideshow();
+            self.tick_schedule();
+            self.answer_requests(TASK_POLL_INTERVAL);
+        }
+
+        self.restore_terminals()
+    }
+
+    /// Apply the schedule's light palette when the day begins and its dark one when the
+    /// night does, or right away on the first look
+    fn tick_schedule(&mut self) {
+        let (due, applied) = self.schedule;
+        let Some((light, dark)) = self.config.schedule.palettes() else {
+            return;
+        };
+        if due.is_some_and(|due| Instant::now() < due) {
+            return;
+        }
+        self.schedule.0 = Some(Instant::now() + SCHEDULE_INTERVAL);
+
+        let day = match self.config.schedule.is_day(&Local::now()) {
+            Ok(day) => day,
+            Err(e) => {
+                self.report_error(t!("msg-schedule-invalid", error = e.to_string()));
+                return;
+            }
+        };
+        if applied == Some(day) {
+            return;
+        }
+        let name = if day { light } else { dark }.to_string();
+        let switched = library::load_palette(&library::palette_path(&name)).and_then(|palette| {
+            self.apply_saved_palette(&palette)?;
+            self.apply_to_terminal()
+        });
+        match switched {
+            Ok(()) => {
+                self.schedule.1 = Some(day);
+                info!(name, day, "applied the scheduled palette");
+            }
+            Err(e) => self.report_error(t!(
+                "msg-schedule-failed",
+                name = name,
+                error = e.to_string()
+            )),
+        }
+    }
+
+    /// Open the light or dark palette of the schedule
+    pub fn switch_appearance(&mut self, appearance: Appearance) {
+        let Some((light, dark)) = self.config.schedule.palettes() else {
+            self.status_bar_msg = t!("msg-appearance-unset");
+            return;
+        };
+        let name = match appearance {
+            Appearance::Light => light,
+            Appearance::Dark => dark,
+        }
+        .to_string();
+        let loaded = library::load_palette(&library::palette_path(&name))
+            .and_then(|palette| self.apply_saved_palette(&palette));
+        match loaded {
+            Ok(()) => {
+                self.appearance = Some(appearance);
+                self.status_bar_msg = t!(
+                    "msg-appearance-switched",
+                    appearance = appearance.key(),
+                    name = name
+                );
+            }
+            Err(e) => self.report_error(t!(
+                "msg-appearance-failed",
+                appearance = appearance.key(),
+                name = name,
+                error = e.to_string()
+            )),
+        }
+    }
+
+    /// Give the terminals the app recolored their own palette back
+    fn restore_terminals(&self) -> io::Result<()> {
+        if !self.terminal_recolored {
+            return Ok(());
+        }
+        // Terminals closed in the meantime are no reason to fail on the way out
+        let _ = osc::reset_broadcast(&self.config.apply_targets);
+        osc::reset_palette()
+    }
+
+    /// Answer the requests waiting on the socket, waiting up to `timeout` for one
+    fn answer_requests(&mut self, timeout: Duration) {
+        let Some(server) = &self.ipc else {
+            return;
+        };
+        for request in server.requests(timeout) {
+            let reply = self.handle_request(&request.line);
+            self.checkpoint();
+            request.reply(reply);
+        }
+    }
+
+    /// The reply to one request line, see [`ipc`]
+    pub fn handle_request(&mut self, line: &str) -> String {
+        info!(line, "request");
+        self.status_bar_msg.clear();
+        let line = line.trim();
+        let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+        let result = match word {
+            "get" => Ok(()),
+            "palette" => {
+                let colors = convert::scan_colors(rest);
+                match colors.len() {
+                    count if (MIN_BLOCKS..=MAX_BLOCKS).contains(&count) => {
+                        let hexes: Vec<String> = colors
+                            .iter()
+                            .map(|&(r, g, b)| format!("{}\n", convert::rgb_to_hex(r, g, b)))
+                            .collect();
+                        self.apply_color_list(&hexes.concat());
+                        // Reply with the colors rather than the editor's message
+                        self.status_bar_msg.clear();
+                        Ok(())
+                    }
+                    count => Err(format!(
+                        "a palette takes {MIN_BLOCKS} to {MAX_BLOCKS} colors, got {count}"
+                    )),
+                }
+            }
+            "apply" => self.apply_to_terminal().map_err(|e| e.to_string()),
+            _ => command::parse(line).map(|command| self.run_command(command)),
+        };
+
+        match result {
+            Ok(()) if self.status_bar_msg.is_empty() => {
+                format!("ok {}", self.palette_hexes().join(" "))
+            }
+            Ok(()) => format!("ok {}", self.status_bar_msg),
+            Err(e) => format!("error {e}"),
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
         frame.render_widget(&*self, frame.area());
+        self.rasters = if self.raster() {
+            let (main_area, _) = split_footer(frame.area());
+            let (_, blocks_area) = self.main_view_areas(main_area);
+            let blocks = self.shown_blocks();
+            MainContent::new(&blocks, self.selected_block_id)
+                .raster(true)
+                .rasters(blocks_area)
+        } else {
+            Vec::new()
+        };
 
         let popup_area = Rect {
             x: frame.area().width / 3,
@@ -97,13 +652,17 @@ impl App {
             // SETTINGS POPUP
 
             let popup_list_items: Vec<ListItem> = ColorTheories::iter()
-                .map(|t| ListItem::new(format!("{:?}", t)))
+                .map(|t| ListItem::new(self.builtin_theory_name(t)))
+                .chain(self.theory_plugins().map(|(_, plugin)| {
+                    ListItem::new(t!("theory-plugin", name = plugin.name.clone()))
+                }))
                 .collect();
 
             let popup_list = List::new(popup_list_items)
                 .block(
                     Block::default()
-                        .title(" Select Theory ")
+                        .title(format!(" {} ", t!("title-select-theory")))
+                        .title_bottom(format!(" {} ", self.jitter_name()))
                         .borders(Borders::ALL)
                         .border_type(BorderType::Plain),
                 )
@@ -118,1137 +677,3106 @@ impl App {
                 .split(popup_area);
 
             let block = Block::default()
-                .title(" Edit Color ")
+                .title(format!(" {} ", t!("title-edit-color")))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Plain);
 
             frame.render_widget(block, popup_area);
 
-            let (r, g, b) = hex2rgb(&self.edit_color_field);
-
-            let par = Paragraph::new(format!(" Enter HEX: {}", &self.edit_color_field));
-            let overview = Paragraph::new(Line::from("Overview:").add_modifier(Modifier::REVERSED))
-                .block(Block::new().bg(Color::Rgb(r, g, b)));
+            let par = Paragraph::new(format!(
+                " {}",
+                t!("field-color", value = self.edit_color_field.as_str())
+            ));
+            let overview = match convert::parse_color(&self.edit_color_field) {
+                Ok((r, g, b)) => Paragraph::new(
+                    Line::from(t!("color-overview")).add_modifier(Modifier::REVERSED),
+                )
+                .block(Block::new().bg(Color::Rgb(r, g, b))),
+                // Don't preview partial input as if it were a (black) color
+                Err(e) => Paragraph::new(Line::from(format!(" {e}")).fg(Color::DarkGray)),
+            };
 
             frame.render_widget(Clear, popup_area.inner(margin!(1, 1)));
             frame.render_widget(par, layout[0].inner(margin!(1, 1)));
             frame.render_widget(overview, layout[1].inner(margin!(1, 1)));
-        }
-    }
-
-    fn handle_events(&mut self) -> io::Result<()> {
-        match event::read()? {
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                self.handle_key_event(key_event)
-            }
-            _ => {}
-        };
-        Ok(())
-    }
+        } else if self.current_page == CurrentPage::EditLabel {
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-block-label")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
 
-    fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match self.current_page {
-            CurrentPage::Main => match (key_event.code, key_event.modifiers) {
-                (KeyCode::Char('q'), _) => self.exit(),
-                (KeyCode::Left, _) => self.decrement_counter(),
-                (KeyCode::Right, _) => self.increment_counter(),
+            let par = Paragraph::new(format!(
+                " {}",
+                t!("field-label", value = self.edit_label_field.as_str())
+            ))
+            .block(block);
 
-                (KeyCode::Char('a'), _) if self.color_block_count < 9 => self.add_block(),
-                (KeyCode::Char('d'), _) if self.color_block_count > 3 => self.del_block(),
+            frame.render_widget(Clear, popup_area);
+            frame.render_widget(par, popup_area);
+        } else if self.current_page == CurrentPage::EditNote {
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-block-note")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
 
-                (KeyCode::Char('x'), _) => {
-                    self.theory_selector_state.select_first();
-                    self.current_page = CurrentPage::TheorySelector
-                }
+            let par = Paragraph::new(format!(
+                " {}",
+                t!("field-note", value = self.edit_note_field.as_str())
+            ))
+            .wrap(Wrap { trim: false })
+            .block(block);
+
+            // Notes are sentences, give them the width of the wide popups
+            let wide_area = Rect {
+                x: frame.area().width / 6,
+                width: frame.area().width * 2 / 3,
+                ..popup_area
+            };
+            frame.render_widget(Clear, wide_area);
+            frame.render_widget(par, wide_area);
+        } else if self.current_page == CurrentPage::ClipboardOffer {
+            let hex = self.clipboard_offer.clone().unwrap_or_default();
+            let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-clipboard")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
 
-                (KeyCode::Char('z'), _) => {
-                    self.current_page = CurrentPage::EditColor;
-                }
+            let par = Paragraph::new(vec![
+                Line::from(vec![
+                    Span::raw(" "),
+                    Span::raw("    ").bg(Color::Rgb(r, g, b)),
+                    Span::raw(format!(" {hex}")),
+                ]),
+                Line::from(""),
+                Line::from(format!(
+                    " {}",
+                    t!("clipboard-offer", block = self.selected_block_id + 1)
+                )),
+            ])
+            .block(block);
 
-                (KeyCode::Char('l'), _) => {
-                    if let Some(array_idx) =
-                        self.get_array_index_for_logical_position(self.selected_block_id)
-                    {
-                        if let Some(block) = self.color_blocks[array_idx].as_mut() {
-                            block.locked = !block.locked;
-                        }
-                    }
-                }
+            frame.render_widget(Clear, popup_area);
+            frame.render_widget(par, popup_area);
+        } else if self.current_page == CurrentPage::SavePalette {
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-save-palette")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
 
-                (KeyCode::Char('c'), _) => {
-                    if let Some(array_idx) =
-                        self.get_array_index_for_logical_position(self.selected_block_id)
-                    {
-                        if let Some(block) = self.color_blocks[array_idx].as_ref() {
-                            self.clipboard.set_text(block.get_hex()).unwrap();
-                        }
-                    }
-                }
+            let par = Paragraph::new(format!(
+                " {}",
+                t!("field-name", value = self.save_name_field.as_str())
+            ))
+            .block(block);
 
-                (KeyCode::Char(c), KeyModifiers::ALT) if ('1'..='9').contains(&c) => {
-                    let num = c.to_digit(10).unwrap() as usize;
-                    self.toggle_lock(num);
-                }
+            frame.render_widget(Clear, popup_area);
+            frame.render_widget(par, popup_area);
+        } else if self.current_page == CurrentPage::OpenPalette {
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-open-palette")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
 
-                (KeyCode::Char(' '), _) => match self.current_color_theory {
-                    ColorTheories::Analogous => self.generate_analogous(),
-                    ColorTheories::Complementary => self.generate_complementary(),
-                    ColorTheories::Triad => self.generate_triad(),
-                    ColorTheories::Tetrad => self.generate_tetrad(),
-                    ColorTheories::Hexad => self.generate_hexad(),
-                    ColorTheories::Monochrome => self.generate_monochrome(),
-                    ColorTheories::Shadows => self.generate_shades(false),
-                    ColorTheories::Lights => self.generate_shades(true),
-                    ColorTheories::Neutrals => self.generate_neutrals(),
-                },
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Length(1), Constraint::Fill(1)])
+                .split(block.inner(popup_area));
 
-                _ => {}
-            },
-            CurrentPage::TheorySelector => match (key_event.code, key_event.modifiers) {
-                (KeyCode::Char('x'), _) | (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => {
-                    self.current_page = CurrentPage::Main
+            let input_line = match self.library_input {
+                LibraryInput::Tags => {
+                    format!(" {}", t!("field-tags", value = self.tags_field.as_str()))
                 }
-
-                (KeyCode::Left, _) => self.theory_selector_state.select_first(),
-                (KeyCode::Right, _) => self.theory_selector_state.select_last(),
-                (KeyCode::Up, _) => self.theory_selector_state.select_previous(),
-                (KeyCode::Down, _) => self.theory_selector_state.select_next(),
-
-                (KeyCode::Enter, _) | (KeyCode::Char(' '), _) => {
-                    if let Some(selected) = self.theory_selector_state.selected() {
-                        let theories: Vec<ColorTheories> = ColorTheories::iter().collect();
-                        self.current_color_theory = theories[selected];
-                        self.current_page = CurrentPage::Main;
-                    }
+                LibraryInput::Search => format!(
+                    " {}",
+                    t!("field-search", value = self.library_query.as_str())
+                ),
+                LibraryInput::None if self.library_query.is_empty() => {
+                    format!(" {}", t!("library-help"))
                 }
+                LibraryInput::None => format!(
+                    " {}",
+                    t!("field-search", value = self.library_query.as_str())
+                ),
+            };
 
-                _ => {}
-            },
-
-            CurrentPage::EditColor => match (key_event.code, key_event.modifiers) {
-                (KeyCode::Char('z'), _) | (KeyCode::Char('q'), _) => {
-                    self.current_page = CurrentPage::Main
-                }
+            let popup_list_items: Vec<ListItem> = self
+                .library_filtered
+                .iter()
+                .map(|idx| {
+                    let palette = &self.saved_palettes[*idx];
+                    let star = if palette.starred { "★ " } else { "" };
+                    let mut spans = vec![Span::raw(format!("{star}{} ", palette.name))];
+                    for color in palette.colors.iter() {
+                        let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+                        spans.push(Span::raw("  ").bg(Color::Rgb(r, g, b)));
+                    }
+                    if !palette.tags.is_empty() {
+                        spans.push(Span::raw(format!(" {}", palette.tags.join(", "))).dim());
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
 
-                (KeyCode::Char(c), _)
-                    if HEX_CHARS.contains(&c) && self.edit_color_field.len() < 6 =>
-                {
-                    self.edit_color_field.push(c);
-                }
+            let popup_list = List::new(popup_list_items).highlight_symbol(">");
 
-                // doesnt work gonna look later
-                (KeyCode::Backspace, KeyModifiers::CONTROL) => {
-                    self.edit_color_field = String::new();
-                }
+            frame.render_widget(Clear, popup_area);
+            frame.render_widget(block, popup_area);
+            frame.render_widget(Paragraph::new(input_line), layout[0]);
+            frame.render_stateful_widget(popup_list, layout[1], &mut self.open_palette_state);
+        } else if self.current_page == CurrentPage::RecentSwitcher {
+            let popup_list_items: Vec<ListItem> = self
+                .recent_entries
+                .iter()
+                .map(|entry| {
+                    let mut spans = vec![Span::raw(format!("{} ", entry.name))];
+                    for hex in entry.colors.iter() {
+                        let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+                        spans.push(Span::raw("  ").bg(Color::Rgb(r, g, b)));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
 
-                (KeyCode::Backspace, _) => {
-                    self.edit_color_field.pop();
-                }
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t!("title-recent")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
 
-                (KeyCode::Enter, _) => {
-                    if let Some(array_idx) =
-                        self.get_array_index_for_logical_position(self.selected_block_id)
+            frame.render_widget(Clear, popup_area);
+            frame.render_stateful_widget(popup_list, popup_area, &mut self.recent_state);
+        } else if self.current_page == CurrentPage::RolePicker {
+            // Roles held by another block show which one, since picking them moves them here
+            let popup_list_items: Vec<ListItem> = iter::once(ListItem::new(t!("role-none")))
+                .chain(Role::iter().map(|role| {
+                    let mut spans = vec![Span::raw(role_name(role))];
+                    if let Some(holder) = self
+                        .color_blocks
+                        .iter()
+                        .position(|block| block.role == Some(role))
+                        .filter(|&idx| idx != self.selected_block_id)
                     {
-                        if let Some(block) = self.color_blocks[array_idx].as_mut() {
-                            let (r, g, b) = hex2rgb(&self.edit_color_field);
-                            let (h, s, v) = rgb2hsv(r, g, b);
-                            block.hsv = Hsv::new(h, s, v);
-                            self.edit_color_field = String::new();
-                        }
+                        spans.push(
+                            Span::raw(format!("  {}", t!("role-held-by", block = holder + 1)))
+                                .dim(),
+                        );
                     }
-                }
-
-                _ => {}
-            },
-        }
-    }
+                    ListItem::new(Line::from(spans))
+                }))
+                .collect();
 
-    fn get_locked_blocks(&mut self) -> Vec<Option<ColorBlock>> {
-        self.color_blocks
-            .iter()
-            .filter(|block| block.is_some())
-            .filter(|block| block.unwrap().locked)
-            .cloned()
-            .collect()
-    }
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t!("title-role")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
 
-    fn generate_tetrad(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let rand_rate = 4; // Minimal randomness for cleaner tetrad relationships
+            frame.render_widget(Clear, popup_area);
+            frame.render_stateful_widget(popup_list, popup_area, &mut self.role_picker_state);
+        } else if self.current_page == CurrentPage::Diagnostics {
+            let popup_list_items: Vec<ListItem> = self
+                .violations
+                .iter()
+                .map(|violation| ListItem::new(violation_message(violation)))
+                .collect();
 
-        let mut base_sat: f32 = 0.68;
-        let mut base_val: f32 = 0.63;
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t!("title-diagnostics")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-            base_sat = ColorBlock::get_avg_saturation(&locked_blocks);
-            base_val = ColorBlock::get_avg_value(&locked_blocks);
-        } else {
-            // Generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-                base_sat = color_block.hsv.saturation;
-                base_val = color_block.hsv.value;
-            }
-        }
+            // Twice the usual width, the messages are whole sentences
+            let wide_area = Rect {
+                x: frame.area().width / 6,
+                width: frame.area().width * 2 / 3,
+                ..popup_area
+            };
+            frame.render_widget(Clear, wide_area);
+            frame.render_stateful_widget(popup_list, wide_area, &mut self.diagnostics_state);
+        } else if self.current_page == CurrentPage::Pairings {
+            // A text sample on the selected color shows the pairing the way it'd be used
+            let base = self.selected_block().map_or(Color::Reset, |block| {
+                let (r, g, b) = block.get_rgb_values();
+                Color::Rgb(r, g, b)
+            });
+            let popup_list_items: Vec<ListItem> = self
+                .pairings
+                .iter()
+                .map(|suggestion| {
+                    let (r, g, b) = suggestion.color.get_rgb_values();
+                    let level = if suggestion.ratio >= pairing::AAA {
+                        "AAA"
+                    } else {
+                        "AA"
+                    };
+                    let source = if suggestion.adjusted {
+                        t!("pairing-adjusted", block = suggestion.source + 1)
+                    } else {
+                        t!("pairing-block", block = suggestion.source + 1)
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::raw(" Aa ").fg(Color::Rgb(r, g, b)).bg(base),
+                        Span::raw(format!(
+                            " {} {:>5.2}:1 {level:<3} ",
+                            suggestion.color.get_hex(),
+                            suggestion.ratio
+                        )),
+                        Span::raw(source).dim(),
+                    ]))
+                })
+                .collect();
 
-        // Collect all existing blocks to calculate logical positions
-        let mut block_info: Vec<(usize, bool)> = Vec::new();
-        for (i, block) in self.color_blocks.iter().enumerate() {
-            if let Some(_block) = block {
-                block_info.push((i, _block.locked));
-            }
-        }
+            let hex = self.selected_block().map(|block| block.get_hex());
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(
+                            " {} ",
+                            t!("title-pairings", hex = hex.unwrap_or_default())
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
 
-        if block_info.is_empty() {
-            return;
-        }
+            let wide_area = Rect {
+                x: frame.area().width / 6,
+                width: frame.area().width * 2 / 3,
+                ..popup_area
+            };
+            frame.render_widget(Clear, wide_area);
+            frame.render_stateful_widget(popup_list, wide_area, &mut self.pairings_state);
+        } else if self.current_page == CurrentPage::BlendModes {
+            let swatch = |hex: &str| {
+                let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+                Span::raw("    ").bg(Color::Rgb(r, g, b))
+            };
+            let base = self.color_blocks[self.selected_block_id].get_hex();
+            let top = self.color_blocks[self.blend_top].get_hex();
+            let popup_list_items: Vec<ListItem> = BlendMode::iter()
+                .map(|mode| {
+                    let result = mode.apply(&base, &top);
+                    let name = i18n::translate(&format!("blend-mode-{}", mode.key()), None);
+                    ListItem::new(Line::from(vec![
+                        Span::raw(format!("{name:<12}")),
+                        swatch(&base),
+                        swatch(&top),
+                        Span::raw(" = "),
+                        swatch(&result),
+                        Span::raw(format!(" {result}")),
+                    ]))
+                })
+                .collect();
 
-        // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
-        let mut logical_positions: Vec<(usize, usize, bool)> = Vec::new();
-        for (logical_pos, (array_pos, is_locked)) in block_info.iter().enumerate() {
-            logical_positions.push((*array_pos, logical_pos, *is_locked));
-        }
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(
+                            " {} ",
+                            t!(
+                                "title-blend-modes",
+                                top = self.blend_top + 1,
+                                base = self.selected_block_id + 1
+                            )
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
 
-        let total_blocks = block_info.len();
+            frame.render_widget(Clear, popup_area);
+            frame.render_stateful_widget(popup_list, popup_area, &mut self.blend_mode_state);
+        } else if self.current_page == CurrentPage::Setup {
+            let area = Rect {
+                y: frame.area().height / 5,
+                height: frame.area().height * 3 / 5,
+                ..popup_area
+            };
+            let steps: Vec<SetupStep> = SetupStep::iter().collect();
+            let number = steps
+                .iter()
+                .position(|&s| s == self.setup_step)
+                .unwrap_or(0)
+                + 1;
+            let question = i18n::translate(&format!("setup-{}", self.setup_step.key()), None);
+            let popup_list_items: Vec<ListItem> = self
+                .setup_options(self.setup_step)
+                .into_iter()
+                .map(ListItem::new)
+                .collect();
 
-        // Determine how many base colors we have (4 for tetrad)
-        let base_colors = 4;
-        let colors_per_group = (total_blocks + base_colors - 1) / base_colors; // Round up division
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(
+                            " {} ",
+                            t!(
+                                "title-setup",
+                                step = number,
+                                steps = steps.len(),
+                                question = question
+                            )
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
 
-        for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
-            if *is_locked {
-                continue; // Skip locked blocks
-            }
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(popup_list, area, &mut self.setup_state);
+        } else if self.current_page == CurrentPage::Settings {
+            let area = Rect {
+                x: frame.area().width / 6,
+                y: frame.area().height / 5,
+                width: frame.area().width * 2 / 3,
+                height: frame.area().height * 3 / 5,
+            };
+            let labels: Vec<String> = Setting::iter()
+                .map(|setting| i18n::translate(&format!("setting-{}", setting.key()), None))
+                .collect();
+            let width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+            let popup_list_items: Vec<ListItem> = Setting::iter()
+                .zip(labels)
+                .map(|(setting, label)| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(format!("{label:<width$}  ")),
+                        Span::raw(self.setting_value(setting)).bold(),
+                    ]))
+                })
+                .collect();
 
-            if let Some(color_block) = self.color_blocks[*array_pos].as_mut() {
-                let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t!("title-settings")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
 
-                // Determine which base color group (0, 1, 2, or 3 for tetrad)
-                let color_group = *logical_pos % base_colors;
-                let variation_index = *logical_pos / base_colors;
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(popup_list, area, &mut self.settings_state);
+        } else if self.current_page == CurrentPage::Balance {
+            let area = Rect {
+                x: frame.area().width / 6,
+                y: frame.area().height / 5,
+                width: frame.area().width * 2 / 3,
+                height: frame.area().height * 3 / 5,
+            };
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-balance")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
+            let balance = balance::analyze(&self.color_blocks);
 
-                // Calculate base hue for this group
-                let group_base_hue = match color_group {
-                    0 => base_hue,
-                    1 => (base_hue + 90.0) % 360.0,
-                    2 => (base_hue + 180.0) % 360.0,
-                    3 => (base_hue + 270.0) % 360.0,
-                    _ => unreachable!(),
-                };
+            frame.render_widget(Clear, area);
+            frame.render_widget(BalancePane::new(&balance), block.inner(area));
+            frame.render_widget(block, area);
+        } else if self.current_page == CurrentPage::Gradients
+            || self.current_page == CurrentPage::NameGradient
+        {
+            let area = Rect {
+                x: frame.area().width / 6,
+                y: frame.area().height / 5,
+                width: frame.area().width * 2 / 3,
+                height: frame.area().height * 3 / 5,
+            };
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-gradients")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
+            // Each gradient as a bar across the popup under its name, minus the highlight
+            let bar_width = usize::from(block.inner(area).width.saturating_sub(2));
+            let popup_list_items: Vec<ListItem> = self
+                .gradients
+                .iter()
+                .map(|gradient| {
+                    // Left half blocks give each cell two samples of the gradient
+                    let samples = bar_width * 2;
+                    let sample = |idx: usize| {
+                        let position = idx as f32 / samples.saturating_sub(1).max(1) as f32;
+                        let (r, g, b) = hex2rgb(&gradient.sample(position));
+                        Color::Rgb(r, g, b)
+                    };
+                    let bar: Vec<Span> = (0..bar_width)
+                        .map(|x| Span::raw("▌").fg(sample(x * 2)).bg(sample(x * 2 + 1)))
+                        .collect();
+                    ListItem::new(vec![
+                        Line::from(vec![
+                            Span::raw(format!(" {} ", gradient.name)).bold(),
+                            Span::raw(t!(
+                                "gradient-details",
+                                space = space_name(gradient.space),
+                                stops = gradient.stops.len()
+                            ))
+                            .dim(),
+                        ]),
+                        Line::from(bar),
+                        Line::default(),
+                    ])
+                })
+                .collect();
+
+            frame.render_widget(Clear, area);
+            if self.gradients.is_empty() {
+                let empty = Paragraph::new(format!(" {}", t!("gradients-empty")))
+                    .wrap(Wrap { trim: false })
+                    .block(block);
+                frame.render_widget(empty, area);
+            } else {
+                let popup_list = List::new(popup_list_items)
+                    .block(block)
+                    .highlight_symbol(">");
+                frame.render_stateful_widget(popup_list, area, &mut self.gradients_state);
+            }
+
+            if self.current_page == CurrentPage::NameGradient {
+                let block = Block::default()
+                    .title(format!(" {} ", t!("title-gradient-name")))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Plain);
+                let par = Paragraph::new(format!(
+                    " {}",
+                    t!("field-name", value = self.gradient_name_field.as_str())
+                ))
+                .block(block);
+
+                frame.render_widget(Clear, popup_area);
+                frame.render_widget(par, popup_area);
+            }
+        } else if self.current_page == CurrentPage::CommandLine {
+            let area = Rect {
+                x: frame.area().width / 6,
+                y: frame.area().height / 5,
+                width: frame.area().width * 2 / 3,
+                height: frame.area().height * 3 / 5,
+            };
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-command")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Length(2), Constraint::Fill(1)])
+                .split(block.inner(area));
+
+            let field = Paragraph::new(format!(" :{}", self.command_field));
+            let suggestions: Vec<ListItem> = command::suggestions(&self.command_field)
+                .into_iter()
+                .map(|suggestion| ListItem::new(format!("  {suggestion}")))
+                .collect();
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(block, area);
+            frame.render_widget(field, layout[0]);
+            frame.render_widget(List::new(suggestions).fg(Color::DarkGray), layout[1]);
+        } else if self.current_page == CurrentPage::Mix {
+            let swatch = |hex: &str| {
+                let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+                Span::raw("    ").bg(Color::Rgb(r, g, b))
+            };
+            let blocks = self
+                .color_blocks
+                .iter()
+                .zip(&self.mix_weights)
+                .enumerate()
+                .map(|(idx, (block, &weight))| {
+                    let mark = match weight {
+                        0 => String::from("[ ]   "),
+                        _ => format!("[x] ×{weight}"),
+                    };
+                    let hex = block.get_hex();
+                    let mut spans = vec![Span::raw(format!("{mark} ")), swatch(&hex)];
+                    spans.push(Span::raw(format!(" {hex} ")));
+                    spans.push(Span::raw(t!("mix-block", block = idx + 1)).dim());
+                    ListItem::new(Line::from(spans))
+                });
+            let results = self.mix_results().into_iter().map(|(name, hex)| {
+                let mut spans =
+                    vec![Span::raw(format!("= {:<18} ", i18n::translate(name, None))).bold()];
+                if let Some(hex) = hex {
+                    spans.push(swatch(&hex));
+                    spans.push(Span::raw(format!(" {hex}")));
+                }
+                ListItem::new(Line::from(spans))
+            });
+            let popup_list_items: Vec<ListItem> = blocks.chain(results).collect();
+
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t!("title-mix")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
+
+            // Tall enough for a full palette and the results below it
+            let tall_area = Rect {
+                y: frame.area().height / 5,
+                height: frame.area().height * 3 / 5,
+                ..popup_area
+            };
+            frame.render_widget(Clear, tall_area);
+            frame.render_stateful_widget(popup_list, tall_area, &mut self.mix_state);
+        } else if self.current_page == CurrentPage::Blend {
+            let (from, to) = self
+                .blend_ends
+                .as_ref()
+                .map(|(from, to)| (from.name.clone(), to.name.clone()))
+                .unwrap_or_default();
+            let ratios = self.blend_ratios();
+            let popup_list_items: Vec<ListItem> = ratios
+                .iter()
+                .enumerate()
+                .map(|(row, &ratio)| {
+                    let label = match row {
+                        0 => from.clone(),
+                        _ if row == ratios.len() - 1 => to.clone(),
+                        _ => format!("{:.0}%", ratio * 100.0),
+                    };
+                    let mut spans = vec![Span::raw(format!("{label:<12} "))];
+                    for hex in self.blended_hexes(ratio) {
+                        let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+                        spans.push(Span::raw("  ").bg(Color::Rgb(r, g, b)));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
+
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t!("title-blend", from = from, to = to)))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
+
+            // Room for a full row of 32 swatches
+            let wide_area = Rect {
+                x: frame.area().width / 6,
+                width: frame.area().width * 2 / 3,
+                ..popup_area
+            };
+            frame.render_widget(Clear, wide_area);
+            frame.render_stateful_widget(popup_list, wide_area, &mut self.blend_state);
+        } else if self.current_page == CurrentPage::ColorHistory {
+            let block = Block::default()
+                .title(format!(" {} ", t!("title-color-history")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain);
+
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Length(1), Constraint::Fill(1)])
+                .split(block.inner(popup_area));
+
+            let input_line = if self.color_history_query.is_empty() {
+                format!(" {}", t!("color-history-help"))
+            } else {
+                format!(
+                    " {}",
+                    t!("field-hue", value = self.color_history_query.as_str())
+                )
+            };
+
+            let popup_list_items: Vec<ListItem> = self
+                .color_history_filtered
+                .iter()
+                .map(|&idx| {
+                    let hex = &self.color_history[idx];
+                    let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+                    let (hue, _, _) = rgb2hsv(r, g, b);
+                    ListItem::new(Line::from(vec![
+                        Span::raw("    ").bg(Color::Rgb(r, g, b)),
+                        Span::raw(format!(" {hex} ")),
+                        Span::raw(format!("{hue:.0}°")).dim(),
+                    ]))
+                })
+                .collect();
+            let popup_list = List::new(popup_list_items).highlight_symbol(">");
+
+            frame.render_widget(Clear, popup_area);
+            frame.render_widget(block, popup_area);
+            frame.render_widget(Paragraph::new(input_line), layout[0]);
+            frame.render_stateful_widget(popup_list, layout[1], &mut self.color_history_state);
+        } else if self.current_page == CurrentPage::Collections {
+            let collection = &COLLECTIONS[self.collection];
+            let popup_list_items: Vec<ListItem> = (0..collection.colors.len())
+                .filter_map(|idx| {
+                    let hex = collection.hex(idx)?;
+                    let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+                    Some(ListItem::new(Line::from(vec![
+                        Span::raw("    ").bg(Color::Rgb(r, g, b)),
+                        Span::raw(format!(" {hex} {}", collection.colors[idx].0)),
+                    ])))
+                })
+                .collect();
+
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(
+                            " {} ",
+                            t!(
+                                "title-collection",
+                                name = collection.name,
+                                index = self.collection + 1,
+                                count = COLLECTIONS.len()
+                            )
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
+
+            frame.render_widget(Clear, popup_area);
+            frame.render_stateful_widget(popup_list, popup_area, &mut self.collection_state);
+        } else if self.current_page == CurrentPage::Favorites {
+            let popup_list_items: Vec<ListItem> = self
+                .favorite_entries
+                .iter()
+                .map(|entry| match entry {
+                    FavoriteEntry::Palette(palette) => {
+                        let mut spans = vec![Span::raw(format!("★ {} ", palette.name))];
+                        for color in palette.colors.iter() {
+                            let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+                            spans.push(Span::raw("  ").bg(Color::Rgb(r, g, b)));
+                        }
+                        ListItem::new(Line::from(spans))
+                    }
+                    FavoriteEntry::Color(hex) => {
+                        let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+                        ListItem::new(Line::from(vec![
+                            Span::raw("    ").bg(Color::Rgb(r, g, b)),
+                            Span::raw(format!(" {hex}")),
+                        ]))
+                    }
+                })
+                .collect();
+
+            let popup_list = List::new(popup_list_items)
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t!("title-favorites")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Plain),
+                )
+                .highlight_symbol(">");
+
+            frame.render_widget(Clear, popup_area);
+            frame.render_stateful_widget(popup_list, popup_area, &mut self.favorites_state);
+        }
+
+        if self.theme_chrome {
+            Chrome::from_roles(&self.color_blocks).apply(frame.buffer_mut());
+        }
+    }
+
+    fn handle_events(&mut self) -> io::Result<()> {
+        // Don't block on input while a transition plays, the slideshow waits for its next
+        // palette, the clipboard watch for its next look or a background task may still
+        // report progress
+        let timeout = [
+            self.transition.as_ref().map(|_| FRAME_INTERVAL),
+            self.slideshow
+                .map(|due| due.saturating_duration_since(Instant::now())),
+            self.clipboard_watch
+                .map(|due| due.saturating_duration_since(Instant::now())),
+            self.tasks.is_busy().then_some(TASK_POLL_INTERVAL),
+            self.ipc.as_ref().map(|_| TASK_POLL_INTERVAL),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        if let Some(timeout) = timeout
+            && !event::poll(timeout)?
+        {
+            return Ok(());
+        }
+
+        match event::read()? {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                self.handle_key_event(key_event)
+            }
+            Event::Paste(text) => self.handle_paste(&text),
+            _ => {}
+        };
+        Ok(())
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        self.status_bar_msg.clear();
+
+        // Any key only pauses the slideshow, so a stray press can't edit the palette
+        if self.slideshow.take().is_some() {
+            self.status_bar_msg = t!("msg-slideshow-paused");
+            return;
+        }
+
+        let page = (self.current_page, self.library_input);
+        if let Some(action) = decode_key(self.current_page, self.library_input, key_event) {
+            self.update(action);
+        }
+        if page != (self.current_page, self.library_input) {
+            self.hint_page = 0;
+        }
+        self.checkpoint();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            color_blocks: self.color_blocks.clone(),
+            selected_block_id: self.selected_block_id,
+            theory: self.current_color_theory,
+            ansi_scheme: self.ansi_scheme,
+        }
+    }
 
-                // Create variations within each color group
-                let variation_factor = if colors_per_group > 1 {
-                    (variation_index as f32) / (colors_per_group - 1) as f32 // 0.0 to 1.0
+    /// Make whatever changed the palette since the last call undoable. Called after every
+    /// key, paste and request and once per frame, so each of them undoes as one step.
+    pub fn checkpoint(&mut self) {
+        let current = self.snapshot();
+        if !current.differs_from(&self.undo_base) {
+            // Keep the selection current, it comes back with the palette on undo
+            self.undo_base = current;
+            return;
+        }
+
+        self.undo_stack
+            .push(std::mem::replace(&mut self.undo_base, current));
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    /// Go back to the palette before the last change, or forward again to the one last
+    /// undone when `redo` is set
+    fn undo(&mut self, redo: bool) {
+        let current = self.snapshot();
+        let (from, to) = if redo {
+            (&mut self.redo_stack, &mut self.undo_stack)
+        } else {
+            (&mut self.undo_stack, &mut self.redo_stack)
+        };
+        let Some(snapshot) = from.pop() else {
+            self.status_bar_msg = if redo {
+                t!("msg-nothing-to-redo")
+            } else {
+                t!("msg-nothing-to-undo")
+            };
+            return;
+        };
+        to.push(current);
+
+        if self.config.animate_transitions {
+            self.transition = Some(Transition::new(&self.color_blocks));
+        }
+        self.color_blocks = snapshot.color_blocks.clone();
+        self.selected_block_id = snapshot.selected_block_id;
+        self.current_color_theory = snapshot.theory;
+        self.ansi_scheme = snapshot.ansi_scheme;
+        self.undo_base = snapshot;
+        self.status_bar_msg = if redo {
+            t!("msg-redone", left = self.redo_stack.len())
+        } else {
+            t!("msg-undone", left = self.undo_stack.len())
+        };
+    }
+
+    /// Pasting on the main view sets the selected block's color, or the blocks to the stops
+    /// of a CSS gradient; the gradients popup keeps a gradient whole. Text fields take the
+    /// text.
+    pub fn handle_paste(&mut self, text: &str) {
+        self.paste(text);
+        self.checkpoint();
+    }
+
+    fn paste(&mut self, text: &str) {
+        self.status_bar_msg.clear();
+
+        let page = self.current_page;
+        if matches!(page, CurrentPage::Main | CurrentPage::Gradients) {
+            match Gradient::from_css("", text) {
+                Ok(gradient) if page == CurrentPage::Main => return self.split_gradient(&gradient),
+                Ok(gradient) => return self.add_gradient(gradient),
+                Err(CssError::NotAGradient) => {}
+                Err(e) => {
+                    self.status_bar_msg = t!("msg-invalid-gradient", error = e.to_string());
+                    return;
+                }
+            }
+        }
+
+        if self.current_page != CurrentPage::Main {
+            for c in text.chars().filter(|c| !c.is_control()) {
+                self.update(Action::Input(c));
+            }
+            return;
+        }
+
+        match convert::parse_color(text) {
+            Ok((r, g, b)) => {
+                let hex = convert::rgb_to_hex(r, g, b);
+                remember_color(&hex);
+                self.run_repeatable(RepeatableAction::SetHex(hex));
+            }
+            Err(e) => match convert::count_colors(text) {
+                counted if counted.len() >= MIN_BLOCKS => self.import_colors(&counted),
+                _ => self.status_bar_msg = t!("msg-invalid-color", error = e.to_string()),
+            },
+        }
+    }
+
+    /// Apply `action` to the current page
+    pub fn update(&mut self, action: Action) {
+        debug!(?action, page = ?self.current_page, "action");
+
+        if let Some(recording) = &mut self.recording
+            && !matches!(
+                action,
+                Action::ToggleRecording | Action::PlayMacro | Action::Quit
+            )
+        {
+            recording.push(action.clone());
+        }
+
+        match action {
+            Action::Quit => {
+                if self.config.autosave && self.project_path.is_some() {
+                    self.save_project();
+                }
+                self.exit()
+            }
+            Action::ToggleRecording => self.toggle_recording(),
+            Action::PlayMacro => self.play_macro(),
+            Action::StartSlideshow => self.start_slideshow(),
+            Action::ToggleClipboardWatch => self.toggle_clipboard_watch(),
+            Action::OpenEditor => self.editor_requested = true,
+            Action::ToggleAnsiScheme => self.toggle_ansi_scheme(),
+            Action::DeriveVariants => self.derive_variants(),
+            Action::DeriveStates => self.derive_states(),
+            Action::FitGamut => self.fit_gamut(),
+            Action::ToggleCompare => {
+                if self.previous_palette.is_some() {
+                    self.compare = !self.compare;
+                } else {
+                    self.status_bar_msg = t!("msg-nothing-generated");
+                }
+            }
+            Action::ToggleExplore => {
+                self.explore = !self.explore;
+                self.status_bar_msg = if self.explore {
+                    t!("msg-explore-on", step = self.explore_step)
+                } else {
+                    t!("msg-explore-off")
+                };
+            }
+            Action::IncreaseExploreStep | Action::DecreaseExploreStep => {
+                self.explore_step = if action == Action::IncreaseExploreStep {
+                    (self.explore_step + EXPLORE_STEP_INCREMENT).min(EXPLORE_STEP_MAX)
                 } else {
-                    0.5
+                    self.explore_step
+                        .saturating_sub(EXPLORE_STEP_INCREMENT)
+                        .max(1)
+                };
+                self.status_bar_msg = t!("msg-explore-step", step = self.explore_step);
+            }
+            Action::MoreHints => self.hint_page = self.hint_page.wrapping_add(1),
+            Action::SelectPrevious => self.select_previous(),
+            Action::SelectNext => self.select_next(),
+            Action::SelectRowUp => self.move_selection_row(false),
+            Action::SelectRowDown => self.move_selection_row(true),
+            Action::SelectRowStart | Action::SelectRowEnd => {
+                let grid = Grid::new(self.color_blocks.len());
+                let (row, _) = grid.cell(self.selected_block_id);
+                let column = match action {
+                    Action::SelectRowStart => 0,
+                    _ => grid.columns,
                 };
+                if let Some(idx) = grid.index(row, column) {
+                    self.selected_block_id = idx;
+                    self.selected_column = grid.cell(idx).1;
+                }
+            }
+            Action::SelectTopRow => self.select_row(0),
+            Action::SelectBottomRow => {
+                let rows = Grid::new(self.color_blocks.len()).rows();
+                self.select_row(rows.saturating_sub(1));
+            }
+
+            Action::AddBlock | Action::DeleteBlock if self.ansi_scheme => {
+                self.status_bar_msg = t!("msg-scheme-fixed")
+            }
+            Action::AddBlock => {
+                if self.color_blocks.len() < MAX_BLOCKS {
+                    self.run_repeatable(RepeatableAction::AddBlock)
+                }
+            }
+            Action::DeleteBlock => {
+                if self.color_blocks.len() > MIN_BLOCKS {
+                    self.run_repeatable(RepeatableAction::DeleteBlock)
+                }
+            }
+
+            Action::RepeatLast => self.repeat_last_action(),
+            Action::Undo => self.undo(false),
+            Action::Redo => self.undo(true),
+            Action::Generate => self.run_repeatable(RepeatableAction::Generate),
+
+            Action::ToggleLock => self.toggle_lock(self.selected_block_id),
+            Action::CycleFreeze => self.cycle_freeze(),
+
+            Action::ToggleLockInRow(num) => {
+                if let Some(position) = self.position_in_row(num) {
+                    self.toggle_lock(position);
+                }
+            }
 
-                let new_hue = (group_base_hue + randomness) % 360.0;
+            Action::CopyHex => {
+                let format = self.config.copy_format;
+                if let Some(hex) = self.selected_block().map(|block| {
+                    let (r, g, b) = block.get_rgb_values();
+                    format.format(r, g, b)
+                }) {
+                    self.status_bar_msg = match self.clipboard.set_text(&hex) {
+                        Ok(()) => t!("msg-copied", hex = hex.as_str()),
+                        Err(e) => logged_error(t!(
+                            "msg-copy-failed",
+                            hex = hex.as_str(),
+                            error = e.to_string()
+                        )),
+                    };
+                }
+            }
+
+            Action::ToggleFavoriteColor => self.toggle_selected_favorite(),
+            Action::SaveProject => self.save_project(),
+            Action::FillRoles => self.fill_roles(),
+
+            Action::OpenTheorySelector => {
+                self.theory_selector_state.select_first();
+                self.current_page = CurrentPage::TheorySelector
+            }
 
-                // Vary saturation and value to create distinct variations within each group
-                let sat_variation_range = if !locked_blocks.is_empty() {
-                    0.12 // Moderate variation when locked color exists
+            Action::OpenEditColor => self.current_page = CurrentPage::EditColor,
+
+            Action::OpenEditLabel => {
+                if let Some(block) = self.selected_block() {
+                    self.edit_label_field = block.label.clone();
+                    self.current_page = CurrentPage::EditLabel;
+                }
+            }
+
+            Action::OpenEditNote => {
+                if let Some(block) = self.selected_block() {
+                    self.edit_note_field = block.note.clone();
+                    self.current_page = CurrentPage::EditNote;
+                }
+            }
+
+            Action::OpenSavePalette => {
+                self.save_name_field = self.palette_name.clone();
+                self.current_page = CurrentPage::SavePalette;
+            }
+
+            Action::OpenLibrary => self.open_palette_dialog(),
+            Action::OpenRecent => self.open_recent_switcher(),
+            Action::OpenFavorites => self.open_favorites(),
+            Action::OpenColorHistory => self.open_color_history(),
+            Action::OpenMix => {
+                self.mix_weights = vec![0; self.color_blocks.len()];
+                if let Some(weight) = self.mix_weights.get_mut(self.selected_block_id) {
+                    *weight = 1;
+                }
+                self.mix_state.select(Some(self.selected_block_id));
+                self.current_page = CurrentPage::Mix;
+            }
+            Action::CycleLightnessScale => {
+                let scales: Vec<LightnessScale> = LightnessScale::iter().collect();
+                let current = scales.iter().position(|&s| s == self.monochrome_scale);
+                self.monochrome_scale = scales[current.map_or(0, |idx| (idx + 1) % scales.len())];
+            }
+            Action::CycleJitter => {
+                let levels: Vec<Jitter> = Jitter::iter().collect();
+                let current = levels.iter().position(|&j| j == self.jitter);
+                self.jitter = levels[current.map_or(0, |idx| (idx + 1) % levels.len())];
+            }
+            Action::ToggleStrict => self.strict = !self.strict,
+            Action::ToggleAppearance => {
+                let current = self
+                    .appearance
+                    .or_else(appearance::detect)
+                    .unwrap_or(Appearance::Dark);
+                self.switch_appearance(current.toggled());
+            }
+            Action::ToggleChrome => {
+                self.theme_chrome = !self.theme_chrome;
+                self.status_bar_msg = if !self.theme_chrome {
+                    t!("msg-chrome-off")
+                } else if self.color_blocks.iter().all(|b| b.role.is_none()) {
+                    t!("msg-no-roles")
                 } else {
-                    0.16 // More variation when no locked color
+                    t!("msg-chrome-on")
+                };
+            }
+            Action::CycleIlluminant => {
+                self.illuminant = cycle(Illuminant::iter(), self.illuminant, true);
+                self.status_bar_msg = match self.illuminant {
+                    Illuminant::D65 => t!("msg-illuminant-off"),
+                    illuminant => t!("msg-illuminant", light = illuminant_name(illuminant)),
+                };
+            }
+            Action::CycleNightLight => {
+                let strengths = std::iter::once(None).chain(night_light::TEMPERATURES.map(Some));
+                self.night_light = cycle(strengths, self.night_light, true);
+                self.status_bar_msg = match self.night_light {
+                    Some(kelvin) => t!("msg-night-light", kelvin = kelvin),
+                    None => t!("msg-night-light-off"),
                 };
-                let val_variation_range = if !locked_blocks.is_empty() {
-                    0.15 // Moderate variation when locked color exists
+            }
+            Action::WidenBlock | Action::NarrowBlock => {
+                self.resize_block(action == Action::WidenBlock)
+            }
+            Action::OpenSettings => {
+                self.settings_state.select_first();
+                self.current_page = CurrentPage::Settings;
+            }
+            Action::PreviousSettingValue => self.change_setting(false),
+            Action::NextSettingValue => self.change_setting(true),
+            Action::OpenCommandLine => {
+                self.command_field.clear();
+                self.current_page = CurrentPage::CommandLine;
+            }
+            Action::Complete => {
+                if let Some(suggestion) = command::suggestions(&self.command_field).first() {
+                    self.command_field = format!("{suggestion} ");
+                }
+            }
+            Action::OpenBlendModes => {
+                self.blend_top = (self.selected_block_id + 1) % self.color_blocks.len();
+                self.blend_mode_state.select(Some(0));
+                self.current_page = CurrentPage::BlendModes;
+            }
+            Action::PreviousTopBlock | Action::NextTopBlock => {
+                // Step over the selected block, it is the base
+                let count = self.color_blocks.len();
+                let step = if action == Action::NextTopBlock {
+                    1
                 } else {
-                    0.20 // More variation when no locked color
+                    count - 1
                 };
+                self.blend_top = (self.blend_top + step) % count;
+                if self.blend_top == self.selected_block_id {
+                    self.blend_top = (self.blend_top + step) % count;
+                }
+            }
+            Action::IncreaseWeight | Action::DecreaseWeight => {
+                if let Some(weight) = self
+                    .mix_state
+                    .selected()
+                    .and_then(|selected| self.mix_weights.get_mut(selected))
+                {
+                    *weight = if action == Action::IncreaseWeight {
+                        (*weight + 1).min(MAX_MIX_WEIGHT)
+                    } else {
+                        weight.saturating_sub(1)
+                    };
+                }
+            }
+            Action::OpenCollections => {
+                self.collection_state.select_first();
+                self.current_page = CurrentPage::Collections;
+            }
+            Action::OpenChartPreview => self.current_page = CurrentPage::ChartPreview,
+            Action::OpenProportionPreview => self.current_page = CurrentPage::ProportionPreview,
+            Action::OpenBalance => self.current_page = CurrentPage::Balance,
+            Action::OpenGradients => {
+                if self.gradients_state.selected().is_none() {
+                    self.gradients_state.select_first();
+                }
+                self.current_page = CurrentPage::Gradients;
+            }
+            Action::AddGradient => self.add_gradient(Gradient::from_colors(
+                "",
+                &self.palette_hexes(),
+                Space::default(),
+            )),
+            Action::RenameGradient => {
+                if let Some(gradient) = self.selected_gradient() {
+                    self.gradient_name_field = gradient.name.clone();
+                    self.current_page = CurrentPage::NameGradient;
+                }
+            }
+            Action::DeleteGradient => {
+                if let Some(selected) = self
+                    .gradients_state
+                    .selected()
+                    .filter(|&selected| selected < self.gradients.len())
+                {
+                    let gradient = self.gradients.remove(selected);
+                    self.status_bar_msg = t!("msg-gradient-deleted", name = gradient.name);
+                }
+            }
+            Action::CycleGradientSpace => {
+                if let Some(selected) = self.gradients_state.selected()
+                    && let Some(gradient) = self.gradients.get_mut(selected)
+                {
+                    gradient.space = cycle(Space::iter(), gradient.space, true);
+                    let space = space_name(gradient.space);
+                    self.status_bar_msg = t!(
+                        "msg-gradient-space",
+                        name = gradient.name.as_str(),
+                        space = space
+                    );
+                }
+            }
+            Action::OpenCodePreview => self.current_page = CurrentPage::CodePreview,
+            Action::OpenArtPreview => self.current_page = CurrentPage::ArtPreview,
+
+            Action::OpenDiagnostics => self.open_diagnostics(),
+            Action::OpenPairings => self.open_pairings(),
+
+            Action::OpenRolePicker => {
+                if let Some(block) = self.selected_block() {
+                    let selected = match block.role {
+                        Some(role) => Role::iter().position(|r| r == role).map_or(0, |i| i + 1),
+                        None => 0,
+                    };
+                    self.role_picker_state.select(Some(selected));
+                    self.current_page = CurrentPage::RolePicker;
+                }
+            }
 
-                // Create variation: center around base, spread based on variation_index
-                let sat_offset = (variation_factor - 0.5) * sat_variation_range * 2.0;
-                let val_offset = (variation_factor - 0.5) * val_variation_range * 2.0;
+            Action::PreviousCollection | Action::NextCollection => {
+                let step = if action == Action::NextCollection {
+                    1
+                } else {
+                    COLLECTIONS.len() - 1
+                };
+                self.collection = (self.collection + step) % COLLECTIONS.len();
+                self.collection_state.select_first();
+            }
+
+            Action::Back => match (self.current_page, self.library_input) {
+                (CurrentPage::OpenPalette, LibraryInput::Search) => {
+                    self.library_input = LibraryInput::None;
+                    self.library_query.clear();
+                    self.filter_library();
+                }
+                (CurrentPage::OpenPalette, LibraryInput::Tags) => {
+                    self.library_input = LibraryInput::None
+                }
+                // Skipping keeps the defaults, and writes them so setup doesn't come back
+                (CurrentPage::Setup, _) => self.finish_setup(),
+                (CurrentPage::NameGradient, _) => self.current_page = CurrentPage::Gradients,
+                _ => self.current_page = CurrentPage::Main,
+            },
+
+            Action::Confirm => self.confirm(),
+
+            Action::ListFirst => {
+                if let Some(state) = self.list_state_mut() {
+                    state.select_first();
+                }
+            }
+            Action::ListLast => {
+                if let Some(state) = self.list_state_mut() {
+                    state.select_last();
+                }
+            }
+            Action::ListPrevious => {
+                if let Some(state) = self.list_state_mut() {
+                    state.select_previous();
+                }
+            }
+            Action::ListNext => {
+                if let Some(state) = self.list_state_mut() {
+                    state.select_next();
+                }
+            }
+
+            Action::Input(c) => match (self.current_page, self.library_input) {
+                (CurrentPage::EditColor, _) if self.edit_color_field.len() < 32 => {
+                    self.edit_color_field.push(c)
+                }
+                (CurrentPage::EditLabel, _) if self.edit_label_field.len() < 24 => {
+                    self.edit_label_field.push(c)
+                }
+                (CurrentPage::EditNote, _) if self.edit_note_field.len() < 120 => {
+                    self.edit_note_field.push(c)
+                }
+                (CurrentPage::NameGradient, _) if self.gradient_name_field.len() < 32 => {
+                    self.gradient_name_field.push(c)
+                }
+                (CurrentPage::SavePalette, _) if self.save_name_field.len() < 32 => {
+                    self.save_name_field.push(c)
+                }
+                (CurrentPage::CommandLine, _) if self.command_field.len() < 64 => {
+                    self.command_field.push(c)
+                }
+                (CurrentPage::OpenPalette, LibraryInput::Search) => {
+                    self.library_query.push(c);
+                    self.filter_library();
+                }
+                (CurrentPage::OpenPalette, LibraryInput::Tags) => self.tags_field.push(c),
+                (CurrentPage::ColorHistory, _) if c.is_ascii_digit() || c == '-' => {
+                    self.color_history_query.push(c);
+                    self.filter_color_history();
+                }
+                _ => {}
+            },
+
+            Action::Backspace => match (self.current_page, self.library_input) {
+                (CurrentPage::EditColor, _) => {
+                    self.edit_color_field.pop();
+                }
+                (CurrentPage::EditLabel, _) => {
+                    self.edit_label_field.pop();
+                }
+                (CurrentPage::EditNote, _) => {
+                    self.edit_note_field.pop();
+                }
+                (CurrentPage::NameGradient, _) => {
+                    self.gradient_name_field.pop();
+                }
+                (CurrentPage::SavePalette, _) => {
+                    self.save_name_field.pop();
+                }
+                (CurrentPage::CommandLine, _) => {
+                    self.command_field.pop();
+                }
+                (CurrentPage::OpenPalette, LibraryInput::Search) => {
+                    self.library_query.pop();
+                    self.filter_library();
+                }
+                (CurrentPage::OpenPalette, LibraryInput::Tags) => {
+                    self.tags_field.pop();
+                }
+                (CurrentPage::ColorHistory, _) => {
+                    self.color_history_query.pop();
+                    self.filter_color_history();
+                }
+                _ => {}
+            },
+
+            Action::ClearInput => {
+                if self.current_page == CurrentPage::EditColor {
+                    self.edit_color_field = String::new();
+                }
+            }
+
+            Action::StartSearch => self.library_input = LibraryInput::Search,
+
+            Action::EditTags => {
+                if let Some(palette) = self.selected_library_palette() {
+                    self.tags_field = palette.tags.join(", ");
+                    self.library_input = LibraryInput::Tags;
+                }
+            }
+
+            Action::TogglePaletteStar => self.toggle_selected_palette_star(),
+            Action::RestorePalette => self.restore_selected_palette(),
+            Action::ExportLibrary => self.export_library_in_background(),
+            Action::BlendPalette => self.pick_blend_palette(),
+            Action::MoreBlendSteps | Action::FewerBlendSteps => {
+                self.blend_steps = if action == Action::MoreBlendSteps {
+                    (self.blend_steps + 1).min(MAX_BLEND_STEPS)
+                } else {
+                    self.blend_steps.saturating_sub(1).max(1)
+                };
+                self.blend_state.select(Some(1));
+            }
+        }
+    }
+
+    /// Enter on the current popup
+    fn confirm(&mut self) {
+        match (self.current_page, self.library_input) {
+            (CurrentPage::TheorySelector, _) => {
+                if let Some(selected) = self.theory_selector_state.selected() {
+                    let theories: Vec<ColorTheories> = ColorTheories::iter().collect();
+                    // Plugin theories are listed after the built-in ones
+                    match theories.get(selected) {
+                        Some(theory) => {
+                            self.current_color_theory = *theory;
+                            self.active_plugin = None;
+                        }
+                        None => {
+                            let plugin = self
+                                .theory_plugins()
+                                .nth(selected - theories.len())
+                                .map(|(idx, _)| idx);
+                            self.active_plugin = plugin;
+                        }
+                    }
+                    self.current_page = CurrentPage::Main;
+                    if self.config.generate_on_theory_change {
+                        self.run_repeatable(RepeatableAction::Generate);
+                    }
+                }
+            }
+
+            (CurrentPage::EditColor, _) => match convert::parse_color_exact(&self.edit_color_field)
+            {
+                Ok(parsed) => {
+                    self.edit_color_field.clear();
+                    let (r, g, b) = parsed.to_rgb();
+                    let hex = convert::rgb_to_hex(r, g, b);
+                    remember_color(&hex);
+                    self.run_repeatable(RepeatableAction::SetHex(hex.clone()));
+                    if let Some(color) = parsed.out_of_gamut() {
+                        if let Some(block) = self.selected_block_mut() {
+                            block.set_oklch(color);
+                        }
+                        self.status_bar_msg = t!("msg-out-of-gamut", hex = hex);
+                    }
+                }
+                Err(e) => self.status_bar_msg = t!("msg-invalid-color", error = e.to_string()),
+            },
+
+            (CurrentPage::EditLabel, _) => {
+                let label = self.edit_label_field.trim().to_string();
+                if let Some(block) = self.selected_block_mut() {
+                    block.label = label;
+                }
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::ClipboardOffer, _) => {
+                self.current_page = CurrentPage::Main;
+                if let Some(hex) = self.clipboard_offer.take() {
+                    remember_color(&hex);
+                    self.run_repeatable(RepeatableAction::SetHex(hex));
+                }
+            }
+
+            (CurrentPage::EditNote, _) => {
+                let note = self.edit_note_field.trim().to_string();
+                if let Some(block) = self.selected_block_mut() {
+                    block.note = note;
+                }
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::NameGradient, _) => {
+                let name = self.gradient_name_field.trim().to_string();
+                if let Some(selected) = self.gradients_state.selected()
+                    && let Some(gradient) = self.gradients.get_mut(selected)
+                    && !name.is_empty()
+                {
+                    gradient.name = name;
+                }
+                self.current_page = CurrentPage::Gradients;
+            }
+
+            (CurrentPage::Gradients, _) => {
+                if let Some(gradient) = self.selected_gradient() {
+                    let (name, css) = (gradient.name.clone(), gradient.css());
+                    self.status_bar_msg = match self.clipboard.set_text(&css) {
+                        Ok(()) => t!("msg-gradient-copied", name = name),
+                        Err(e) => logged_error(t!(
+                            "msg-gradient-copy-failed",
+                            name = name,
+                            error = e.to_string()
+                        )),
+                    };
+                }
+            }
+
+            (CurrentPage::SavePalette, _) => self.save_current_palette(),
+
+            (CurrentPage::Setup, _) => {
+                if let Some(selected) = self.setup_state.selected() {
+                    self.apply_setup_choice(self.setup_step, selected);
+                }
+                let steps: Vec<SetupStep> = SetupStep::iter().collect();
+                let current = steps.iter().position(|&s| s == self.setup_step);
+                match current.and_then(|idx| steps.get(idx + 1)) {
+                    Some(&next) => self.show_setup_step(next),
+                    None => self.finish_setup(),
+                }
+            }
+
+            (CurrentPage::CommandLine, _) => {
+                self.current_page = CurrentPage::Main;
+                match command::parse(&self.command_field) {
+                    Ok(command) => self.run_command(command),
+                    Err(message) => self.status_bar_msg = message,
+                }
+            }
+
+            (CurrentPage::Diagnostics, _) => {
+                // Jump to the block to fix
+                if let Some(idx) = self
+                    .diagnostics_state
+                    .selected()
+                    .and_then(|selected| self.violations.get(selected))
+                    .and_then(|violation| {
+                        let role = violation.role();
+                        self.color_blocks.iter().position(|b| b.role == Some(role))
+                    })
+                {
+                    self.selected_block_id = idx;
+                }
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::BlendModes, _) => {
+                let Some(mode) = self
+                    .blend_mode_state
+                    .selected()
+                    .and_then(|selected| BlendMode::iter().nth(selected))
+                else {
+                    return;
+                };
+                let hex = mode.apply(
+                    &self.color_blocks[self.selected_block_id].get_hex(),
+                    &self.color_blocks[self.blend_top].get_hex(),
+                );
+                self.insert_locked_color(&hex);
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::Mix, _) => {
+                let Some(selected) = self.mix_state.selected() else {
+                    return;
+                };
+                // Enter on a block takes it in or out, on a result inserts it
+                if let Some(weight) = self.mix_weights.get_mut(selected) {
+                    *weight = if *weight == 0 { 1 } else { 0 };
+                } else if let Some((_, Some(hex))) = self
+                    .mix_results()
+                    .get(selected - self.mix_weights.len())
+                    .cloned()
+                {
+                    self.insert_locked_color(&hex);
+                    self.current_page = CurrentPage::Main;
+                }
+            }
+
+            (CurrentPage::Blend, _) => {
+                let ratios = self.blend_ratios();
+                if let Some(&ratio) = self
+                    .blend_state
+                    .selected()
+                    .and_then(|selected| ratios.get(selected))
+                {
+                    self.load_blend(ratio);
+                }
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::ColorHistory, _) => {
+                if let Some(hex) = self
+                    .color_history_state
+                    .selected()
+                    .and_then(|selected| self.color_history_filtered.get(selected))
+                    .map(|&idx| self.color_history[idx].clone())
+                {
+                    self.insert_locked_color(&hex);
+                }
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::Collections, _) => {
+                if let Some(hex) = self
+                    .collection_state
+                    .selected()
+                    .and_then(|selected| COLLECTIONS[self.collection].hex(selected))
+                {
+                    self.insert_locked_color(&hex);
+                }
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::Pairings, _) => {
+                if let Some(hex) = self
+                    .pairings_state
+                    .selected()
+                    .and_then(|selected| self.pairings.get(selected))
+                    .map(|suggestion| suggestion.color.get_hex())
+                {
+                    self.insert_locked_color(&hex);
+                }
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::RolePicker, _) => {
+                if let Some(selected) = self.role_picker_state.selected() {
+                    let role = selected.checked_sub(1).and_then(|i| Role::iter().nth(i));
+                    roles::assign(&mut self.color_blocks, self.selected_block_id, role);
+                }
+                self.current_page = CurrentPage::Main;
+            }
+
+            (CurrentPage::RecentSwitcher, _) => {
+                if let Some(entry) = self
+                    .recent_state
+                    .selected()
+                    .and_then(|selected| self.recent_entries.get(selected).cloned())
+                {
+                    self.open_recent(&entry);
+                }
+            }
+
+            (CurrentPage::Favorites, _) => {
+                let entry = self
+                    .favorites_state
+                    .selected()
+                    .and_then(|selected| self.favorite_entries.get(selected).cloned());
+
+                match entry {
+                    Some(FavoriteEntry::Palette(palette)) => self.open_library_palette(&palette),
+                    Some(FavoriteEntry::Color(hex)) => self.insert_locked_color(&hex),
+                    None => {}
+                }
+            }
+
+            (CurrentPage::OpenPalette, LibraryInput::Search) => {
+                self.library_input = LibraryInput::None
+            }
+            (CurrentPage::OpenPalette, LibraryInput::Tags) => self.save_selected_tags(),
+            (CurrentPage::OpenPalette, LibraryInput::None) => {
+                if let Some(palette) = self.selected_library_palette().cloned() {
+                    self.open_library_palette(&palette);
+                }
+            }
 
-                let new_sat = (base_sat + sat_offset).clamp(0.0, 1.0);
-                let new_val = (base_val + val_offset).clamp(0.0, 1.0);
+            (CurrentPage::Main, _)
+            | (CurrentPage::ChartPreview, _)
+            | (CurrentPage::ProportionPreview, _)
+            | (CurrentPage::Balance, _)
+            | (CurrentPage::CodePreview, _)
+            | (CurrentPage::ArtPreview, _)
+            | (CurrentPage::Settings, _) => {}
+        }
+    }
 
-                color_block.change_color(new_hue, new_sat, new_val);
-            }
+    /// The list the current popup navigates, if it has one
+    fn list_state_mut(&mut self) -> Option<&mut ListState> {
+        match self.current_page {
+            CurrentPage::TheorySelector => Some(&mut self.theory_selector_state),
+            CurrentPage::OpenPalette => Some(&mut self.open_palette_state),
+            CurrentPage::Favorites => Some(&mut self.favorites_state),
+            CurrentPage::Collections => Some(&mut self.collection_state),
+            CurrentPage::ColorHistory => Some(&mut self.color_history_state),
+            CurrentPage::Blend => Some(&mut self.blend_state),
+            CurrentPage::Mix => Some(&mut self.mix_state),
+            CurrentPage::BlendModes => Some(&mut self.blend_mode_state),
+            CurrentPage::Setup => Some(&mut self.setup_state),
+            CurrentPage::Settings => Some(&mut self.settings_state),
+            CurrentPage::RecentSwitcher => Some(&mut self.recent_state),
+            CurrentPage::RolePicker => Some(&mut self.role_picker_state),
+            CurrentPage::Pairings => Some(&mut self.pairings_state),
+            CurrentPage::Gradients => Some(&mut self.gradients_state),
+            CurrentPage::Diagnostics => Some(&mut self.diagnostics_state),
+            _ => None,
         }
     }
 
-    fn generate_hexad(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let rand_rate = 4; // Minimal randomness for cleaner hexad relationships
+    /// Mirror background task messages in the status bar, and take the colors of
+    /// pictures they made
+    fn poll_tasks(&mut self) {
+        for message in self.tasks.drain() {
+            self.status_bar_msg = match message {
+                TaskMessage::Progress { label, done, total } => {
+                    t!(
+                        "msg-task-progress",
+                        label = label,
+                        done = done,
+                        total = total
+                    )
+                }
+                TaskMessage::Finished {
+                    result: Ok(TaskOutput::Summary(summary)),
+                    ..
+                } => summary,
+                TaskMessage::Finished {
+                    result: Ok(TaskOutput::Picture(counted)),
+                    ..
+                } => {
+                    self.take_picture(&counted);
+                    continue;
+                }
+                TaskMessage::Finished {
+                    label,
+                    result: Err(e),
+                } => logged_error(t!("msg-task-failed", label = label, error = e)),
+            };
+        }
+    }
 
-        let mut base_sat: f32 = 0.65;
-        let mut base_val: f32 = 0.60;
+    fn export_library_in_background(&mut self) {
+        let path = PathBuf::from(LIBRARY_EXPORT_FILE);
+
+        self.tasks
+            .spawn(&t!("task-export-library"), move |progress| {
+                let count = library::export_library_with_progress(&path, |done, total| {
+                    progress.report(done, total)
+                })?;
+                Ok(t!(
+                    "msg-library-exported",
+                    count = count,
+                    path = path.display().to_string()
+                )
+                .into())
+            });
+        self.status_bar_msg = t!("msg-exporting-library");
+    }
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-            base_sat = ColorBlock::get_avg_saturation(&locked_blocks);
-            base_val = ColorBlock::get_avg_value(&locked_blocks);
-        } else {
-            // Generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-                base_sat = color_block.hsv.saturation;
-                base_val = color_block.hsv.value;
+    fn run_repeatable(&mut self, action: RepeatableAction) {
+        match &action {
+            RepeatableAction::Generate => {
+                self.push_history();
+                self.generate();
             }
+            RepeatableAction::AddBlock => self.add_block(),
+            RepeatableAction::DeleteBlock => self.del_block(),
+            RepeatableAction::SetHex(hex) => self.set_selected_hex(hex),
         }
+        self.last_action = Some(action);
+    }
 
-        // Collect all existing blocks to calculate logical positions
-        let mut block_info: Vec<(usize, bool)> = Vec::new();
-        for (i, block) in self.color_blocks.iter().enumerate() {
-            if let Some(_block) = block {
-                block_info.push((i, _block.locked));
+    /// Start recording a macro, or stop and keep the recorded one for `@`
+    fn toggle_recording(&mut self) {
+        match self.recording.take() {
+            Some(actions) => {
+                self.status_bar_msg = t!("msg-macro-recorded", count = actions.len());
+                self.macro_actions = actions;
+            }
+            None => {
+                self.recording = Some(Vec::new());
+                self.status_bar_msg = t!("msg-macro-recording");
             }
         }
+    }
 
-        if block_info.is_empty() {
+    fn play_macro(&mut self) {
+        if self.macro_actions.is_empty() {
+            self.status_bar_msg = t!("msg-no-macro");
             return;
         }
-
-        // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
-        let mut logical_positions: Vec<(usize, usize, bool)> = Vec::new();
-        for (logical_pos, (array_pos, is_locked)) in block_info.iter().enumerate() {
-            logical_positions.push((*array_pos, logical_pos, *is_locked));
+        info!(actions = ?self.macro_actions, "replaying macro");
+        for action in self.macro_actions.clone() {
+            self.update(action);
         }
+    }
 
-        let total_blocks = block_info.len();
-
-        // Determine how many base colors we have (6 for hexad)
-        let base_colors = 6;
-        let colors_per_group = (total_blocks + base_colors - 1) / base_colors; // Round up division
+    fn repeat_last_action(&mut self) {
+        if let Some(action) = self.last_action.clone() {
+            self.run_repeatable(action);
+        }
+    }
 
-        for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
-            if *is_locked {
-                continue; // Skip locked blocks
+    fn generate(&mut self) {
+        let before = self.color_blocks.clone();
+        if self.explore {
+            self.walk();
+        } else if self.ansi_scheme {
+            self.generate_scheme();
+        } else if let Some(idx) = self.active_plugin {
+            self.generate_with_plugin(idx);
+        } else {
+            self.generate_builtin();
+        }
+        for (block, original) in self.color_blocks.iter_mut().zip(&before) {
+            if !block.locked {
+                block.restore_frozen(original);
             }
+        }
+        if self.config.animate_transitions {
+            self.transition = Some(Transition::new(&before));
+        }
+        self.previous_palette = Some(before);
+    }
 
-            if let Some(color_block) = self.color_blocks[*array_pos].as_mut() {
-                let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
+    fn walk(&mut self) {
+        let step = f32::from(self.explore_step);
+        generators::random_walk(&mut self.color_blocks, step, &mut rand::rng());
+        // A walk depends on where it started, so a seed alone can't reproduce it
+        self.seed = None;
+        info!(step, result = ?self.palette_hexes(), "explored palette");
+    }
 
-                // Determine which base color group (0-5 for hexad)
-                let color_group = *logical_pos % base_colors;
-                let variation_index = *logical_pos / base_colors;
+    /// Enter the scheme designer, turning the palette into a generated scheme unless it is
+    /// one already; leaving keeps the colors and frees the block count again
+    fn toggle_ansi_scheme(&mut self) {
+        self.ansi_scheme = !self.ansi_scheme;
+        if !self.ansi_scheme {
+            self.status_bar_msg = t!("msg-scheme-off");
+            return;
+        }
 
-                // Calculate base hue for this group
-                let group_base_hue = match color_group {
-                    0 => base_hue,
-                    1 => (base_hue + 60.0) % 360.0,
-                    2 => (base_hue + 120.0) % 360.0,
-                    3 => (base_hue + 180.0) % 360.0,
-                    4 => (base_hue + 240.0) % 360.0,
-                    5 => (base_hue + 300.0) % 360.0,
-                    _ => unreachable!(),
-                };
+        if !ansi::is_scheme(&self.color_blocks) {
+            self.push_history();
+            self.color_blocks = ansi::new_scheme(self.config.ansi_dim_variants);
+            self.selected_block_id = 0;
+            self.generate_scheme();
+        }
+        self.status_bar_msg = t!("msg-scheme-on");
+    }
 
-                // Create variations within each color group (if more blocks than base colors)
-                let variation_factor = if colors_per_group > 1 {
-                    (variation_index as f32) / (colors_per_group - 1) as f32 // 0.0 to 1.0
-                } else {
-                    0.5
-                };
+    fn derive_variants(&mut self) {
+        if !self.ansi_scheme {
+            self.status_bar_msg = t!("msg-not-scheme");
+            return;
+        }
 
-                let new_hue = (group_base_hue + randomness) % 360.0;
+        self.push_history();
+        let before = self.color_blocks.clone();
+        let changed = ansi::derive_variants(&mut self.color_blocks);
+        if self.config.animate_transitions {
+            self.transition = Some(Transition::new(&before));
+        }
+        self.status_bar_msg = t!("msg-variants-derived", count = changed);
+    }
 
-                // Vary saturation and value to create distinct variations within each group
-                let sat_variation_range = if !locked_blocks.is_empty() {
-                    0.10 // Moderate variation when locked color exists
-                } else {
-                    0.14 // More variation when no locked color
-                };
-                let val_variation_range = if !locked_blocks.is_empty() {
-                    0.12 // Moderate variation when locked color exists
-                } else {
-                    0.18 // More variation when no locked color
-                };
+    /// Bring the selected block's clipped out-of-gamut color into sRGB keeping its hue
+    fn fit_gamut(&mut self) {
+        if self
+            .selected_block()
+            .is_none_or(|block| block.out_of_gamut.is_none())
+        {
+            self.status_bar_msg = t!("msg-in-gamut");
+            return;
+        }
 
-                // Create variation: center around base, spread based on variation_index
-                let sat_offset = (variation_factor - 0.5) * sat_variation_range * 2.0;
-                let val_offset = (variation_factor - 0.5) * val_variation_range * 2.0;
+        self.push_history();
+        if let Some(block) = self.selected_block_mut() {
+            block.fit_gamut();
+            let hex = block.get_hex();
+            self.status_bar_msg = t!("msg-gamut-fitted", hex = hex);
+        }
+    }
 
-                let new_sat = (base_sat + sat_offset).clamp(0.0, 1.0);
-                let new_val = (base_val + val_offset).clamp(0.0, 1.0);
+    /// Insert hover, active, focus and disabled variants of the selected block, labeled
+    /// after it. Disabled fades into the background role, or white without one.
+    fn derive_states(&mut self) {
+        if self.ansi_scheme {
+            self.status_bar_msg = t!("msg-scheme-fixed");
+            return;
+        }
+        let count = State::iter().count();
+        if self.color_blocks.len() + count > MAX_BLOCKS {
+            self.status_bar_msg = t!("msg-no-room", count = count, max = MAX_BLOCKS);
+            return;
+        }
 
-                color_block.change_color(new_hue, new_sat, new_val);
+        let base = &self.color_blocks[self.selected_block_id];
+        let name = match base.label.as_str() {
+            "" => base.get_hex(),
+            label => label.to_string(),
+        };
+        let background = roles::find(&self.color_blocks, Role::Background)
+            .map_or_else(|| String::from("#FFFFFF"), ColorBlock::get_hex);
+        let variants = states::variants(&base.get_hex(), &background, &self.config.states);
+
+        self.push_history();
+        for (state, hex) in variants {
+            self.insert_locked_color(&hex);
+            if let Some(block) = self.color_blocks.last_mut() {
+                block.label = format!("{name} {}", state.key());
             }
         }
+        self.status_bar_msg = t!("msg-states-derived", name = name);
     }
 
-    fn generate_triad(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let rand_rate = 4; // Minimal randomness for cleaner triadic relationships
+    fn generate_scheme(&mut self) {
+        let seed = u64::from(rand::random::<u32>());
+        let mut rng = StdRng::seed_from_u64(seed);
+        ansi::generate(self.current_color_theory, &mut self.color_blocks, &mut rng);
+        self.seed = Some(seed);
+        info!(
+            theory = ?self.current_color_theory,
+            jitter = self.effective_jitter().key(),
+            seed,
+            result = ?self.palette_hexes(),
+            "generated terminal scheme"
+        );
+    }
 
-        let mut base_sat: f32 = 0.72;
-        let mut base_val: f32 = 0.68;
+    fn generate_builtin(&mut self) {
+        let locked: Vec<String> = self
+            .color_blocks
+            .iter()
+            .filter(|block| block.locked)
+            .map(|block| block.get_hex())
+            .collect();
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-            base_sat = ColorBlock::get_avg_saturation(&locked_blocks);
-            base_val = ColorBlock::get_avg_value(&locked_blocks);
-        } else {
-            // Generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-                base_sat = color_block.hsv.saturation;
-                base_val = color_block.hsv.value;
+        // 32 bits keep the seed short enough to read off the status bar
+        let seed = u64::from(rand::random::<u32>());
+        let generator = self
+            .current_color_theory
+            .configured(self.monochrome_scale, self.effective_jitter());
+        generator.generate(&mut self.color_blocks, &mut StdRng::seed_from_u64(seed));
+        self.seed = Some(seed);
+
+        info!(
+            theory = ?self.current_color_theory,
+            seed,
+            blocks = self.color_blocks.len(),
+            ?locked,
+            result = ?self.palette_hexes(),
+            "generated palette"
+        );
+    }
+
+    fn generate_with_plugin(&mut self, idx: usize) {
+        let plugin = &self.plugins[idx];
+        let locked: Vec<(usize, String)> = self
+            .color_blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| block.locked)
+            .map(|(pos, block)| (pos, block.get_hex()))
+            .collect();
+
+        match plugin.generate(&locked, self.color_blocks.len()) {
+            Ok(hexes) => {
+                self.seed = None;
+                info!(plugin = %plugin.name, ?locked, result = ?hexes, "generated palette");
+                for (block, hex) in self.color_blocks.iter_mut().zip(hexes) {
+                    if !block.locked {
+                        let (r, g, b) = hex2rgb(&hex);
+                        let (h, s, v) = rgb2hsv(r, g, b);
+                        block.change_color(h, s, v);
+                    }
+                }
             }
+            Err(e) => {
+                let name = plugin.name.clone();
+                self.report_error(t!("msg-plugin-failed", name = name, error = e.to_string()));
+            }
+        }
+    }
+
+    /// Name of the theory the next generation uses, plugin theories included
+    fn theory_name(&self) -> String {
+        if self.explore {
+            return t!("theory-explore", step = self.explore_step);
         }
+        if self.ansi_scheme {
+            return t!(
+                "theory-ansi",
+                theory = format!("{:?}", self.current_color_theory)
+            );
+        }
+        match self.active_plugin.and_then(|idx| self.plugins.get(idx)) {
+            Some(plugin) => t!("theory-plugin", name = plugin.name.clone()),
+            None => self.builtin_theory_name(self.current_color_theory),
+        }
+    }
 
-        // Collect all existing blocks to calculate logical positions
-        let mut block_info: Vec<(usize, bool)> = Vec::new();
-        for (i, block) in self.color_blocks.iter().enumerate() {
-            if let Some(_block) = block {
-                block_info.push((i, _block.locked));
+    /// Name of a built-in theory, with the lightness scale for Monochrome
+    fn builtin_theory_name(&self, theory: ColorTheories) -> String {
+        match (theory, self.monochrome_scale) {
+            (ColorTheories::Monochrome, scale) if scale != LightnessScale::Linear => {
+                t!(
+                    "theory-scaled",
+                    theory = format!("{theory:?}"),
+                    scale = format!("{scale:?}")
+                )
             }
+            _ => format!("{theory:?}"),
         }
+    }
 
-        if block_info.is_empty() {
-            return;
+    /// Jitter the built-in theories generate with
+    fn effective_jitter(&self) -> Jitter {
+        if self.strict {
+            Jitter::None
+        } else {
+            self.jitter
         }
+    }
 
-        // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
-        let mut logical_positions: Vec<(usize, usize, bool)> = Vec::new();
-        for (logical_pos, (array_pos, is_locked)) in block_info.iter().enumerate() {
-            logical_positions.push((*array_pos, logical_pos, *is_locked));
+    /// Jitter level shown in the theory selector
+    fn jitter_name(&self) -> String {
+        if self.strict {
+            t!("theory-strict")
+        } else {
+            t!(
+                "theory-jitter",
+                level = i18n::translate(&format!("jitter-{}", self.jitter.key()), None)
+            )
         }
+    }
 
-        let total_blocks = block_info.len();
+    /// Loaded plugins providing a theory, with their index in `plugins`
+    fn theory_plugins(&self) -> impl Iterator<Item = (usize, &Plugin)> {
+        self.plugins
+            .iter()
+            .enumerate()
+            .filter(|(_, plugin)| plugin.generates())
+    }
 
-        // Determine how many base colors we have (3 for triadic)
-        let base_colors = 3;
-        let colors_per_group = (total_blocks + base_colors - 1) / base_colors; // Round up division
+    fn set_selected_hex(&mut self, hex: &str) {
+        if let Some(block) = self.selected_block_mut() {
+            let (r, g, b) = hex2rgb(hex);
+            let (h, s, v) = rgb2hsv(r, g, b);
+            block.change_color(h, s, v);
+        }
+    }
 
-        for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
-            if *is_locked {
-                continue; // Skip locked blocks
-            }
+    fn selected_block(&self) -> Option<&ColorBlock> {
+        self.color_blocks.get(self.selected_block_id)
+    }
 
-            if let Some(color_block) = self.color_blocks[*array_pos].as_mut() {
-                let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
+    fn selected_block_mut(&mut self) -> Option<&mut ColorBlock> {
+        self.color_blocks.get_mut(self.selected_block_id)
+    }
 
-                // Determine which base color group (0, 1, or 2 for triadic)
-                let color_group = *logical_pos % base_colors;
-                let variation_index = *logical_pos / base_colors;
+    fn selected_gradient(&self) -> Option<&Gradient> {
+        self.gradients_state
+            .selected()
+            .and_then(|selected| self.gradients.get(selected))
+    }
 
-                // Calculate base hue for this group
-                let group_base_hue = match color_group {
-                    0 => base_hue,
-                    1 => (base_hue + 120.0) % 360.0,
-                    2 => (base_hue + 240.0) % 360.0,
-                    _ => unreachable!(),
-                };
+    fn palette_hexes(&self) -> Vec<String> {
+        self.color_blocks
+            .iter()
+            .map(|block| block.get_hex())
+            .collect()
+    }
 
-                // Create variations within each color group
-                // Variation index determines how much to vary saturation/value
-                let variation_factor = if colors_per_group > 1 {
-                    (variation_index as f32) / (colors_per_group - 1) as f32 // 0.0 to 1.0
-                } else {
-                    0.5
-                };
+    /// Regenerate every `slideshow_seconds` until a key is pressed
+    pub fn start_slideshow(&mut self) {
+        let interval = self.slideshow_interval();
+        self.slideshow = Some(Instant::now() + interval);
+        self.status_bar_msg = t!("msg-slideshow-started", seconds = interval.as_secs());
+        info!(seconds = interval.as_secs(), "slideshow started");
+    }
 
-                let new_hue = (group_base_hue + randomness) % 360.0;
+    fn slideshow_interval(&self) -> Duration {
+        Duration::from_secs(self.config.slideshow_seconds.max(1))
+    }
 
-                // Vary saturation and value to create distinct variations within each group
-                // Create a progression: lighter/darker or more/less saturated variations
-                let sat_variation_range = if !locked_blocks.is_empty() {
-                    0.12 // Moderate variation when locked color exists
-                } else {
-                    0.18 // More variation when no locked color
-                };
-                let val_variation_range = if !locked_blocks.is_empty() {
-                    0.15 // Moderate variation when locked color exists
-                } else {
-                    0.22 // More variation when no locked color
-                };
+    fn tick_slideshow(&mut self) {
+        let Some(due) = self.slideshow else {
+            return;
+        };
+        if Instant::now() < due {
+            return;
+        }
 
-                // Create variation: center around base, spread based on variation_index
-                let sat_offset = (variation_factor - 0.5) * sat_variation_range * 2.0; // -range to +range
-                let val_offset = (variation_factor - 0.5) * val_variation_range * 2.0; // -range to +range
+        self.push_history();
+        self.generate();
+        self.slideshow = Some(Instant::now() + self.slideshow_interval());
 
-                let new_sat = (base_sat + sat_offset).clamp(0.0, 1.0);
-                let new_val = (base_val + val_offset).clamp(0.0, 1.0);
+        if self.config.slideshow_apply_to_terminal
+            && let Err(e) = self.apply_to_terminal()
+        {
+            self.report_error(t!("msg-recolor-failed", error = e.to_string()));
+        }
+    }
 
-                color_block.change_color(new_hue, new_sat, new_val);
-            }
+    /// Set the terminal's ANSI colors to the blocks and run the apply hooks
+    fn apply_to_terminal(&mut self) -> io::Result<()> {
+        let colors: Vec<(u8, u8, u8)> = self
+            .color_blocks
+            .iter()
+            .map(ColorBlock::get_rgb_values)
+            .collect();
+        osc::apply_palette(&colors)?;
+        self.terminal_recolored = true;
+        if !self.config.apply_targets.is_empty() {
+            let targets = self.config.apply_targets.clone();
+            self.tasks.spawn(&t!("task-recolor-terminals"), move |_| {
+                let count = osc::broadcast(&colors, &targets)?;
+                Ok(t!("msg-terminals-recolored", count = count).into())
+            });
         }
+        if self.config.announce {
+            announce::announce(&ExportPalette::from(&self.to_project()))?;
+        }
+        self.run_hooks(hooks::Event::Apply);
+        Ok(())
     }
 
-    fn generate_complementary(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let rand_rate = 4; // Minimal randomness for cleaner complementary relationships
+    /// Run the hooks configured for `event` in the background, see [`hooks`]
+    fn run_hooks(&mut self, event: hooks::Event) {
+        if event.commands(&self.config.hooks).is_empty() {
+            return;
+        }
+        let configured = self.config.hooks.clone();
+        let palette = ExportPalette::from(&self.to_project());
+        self.tasks.spawn(&t!("task-run-hooks"), move |_| {
+            let count = hooks::run(&configured, &event, &palette)?;
+            Ok(t!("msg-hooks-ran", count = count).into())
+        });
+    }
 
-        let mut base_sat: f32 = 0.70;
-        let mut base_val: f32 = 0.65;
+    /// Hand the palette to `$EDITOR` as a list of hex codes and read it back
+    fn edit_in_editor(&mut self) {
+        let text: String = self
+            .palette_hexes()
+            .iter()
+            .map(|hex| format!("{hex}\n"))
+            .collect();
+        match editor::edit(&text) {
+            Ok(edited) => self.apply_color_list(&edited),
+            Err(e) => self.report_error(t!("msg-editor-failed", error = e.to_string())),
+        }
+    }
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-            base_sat = ColorBlock::get_avg_saturation(&locked_blocks);
-            base_val = ColorBlock::get_avg_value(&locked_blocks);
-        } else {
-            // Generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-                base_sat = color_block.hsv.saturation;
-                base_val = color_block.hsv.value;
+    /// Recolor the palette from `text`, one color per line in any format the color input
+    /// takes. Blocks keep their label, lock and role by position; extra lines add blocks and
+    /// missing ones remove them from the end. Nothing changes if a line doesn't parse.
+    pub fn apply_color_list(&mut self, text: &str) {
+        let colors: Result<Vec<(u8, u8, u8)>, String> = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                convert::parse_color(line)
+                    .map_err(|e| t!("msg-editor-bad-line", line = idx + 1, error = e.to_string()))
+            })
+            .collect();
+        let colors = match colors {
+            Ok(colors) => colors,
+            Err(message) => {
+                self.status_bar_msg = message;
+                return;
             }
+        };
+        if !(MIN_BLOCKS..=MAX_BLOCKS).contains(&colors.len()) {
+            self.status_bar_msg = t!(
+                "msg-editor-count",
+                count = colors.len(),
+                min = MIN_BLOCKS,
+                max = MAX_BLOCKS
+            );
+            return;
+        }
+        let hexes: Vec<String> = colors
+            .iter()
+            .map(|&(r, g, b)| convert::rgb_to_hex(r, g, b))
+            .collect();
+        if hexes == self.palette_hexes() {
+            self.status_bar_msg = t!("msg-editor-unchanged");
+            return;
         }
 
-        // Collect all existing blocks to calculate logical positions
-        let mut block_info: Vec<(usize, bool)> = Vec::new();
-        for (i, block) in self.color_blocks.iter().enumerate() {
-            if let Some(_block) = block {
-                block_info.push((i, _block.locked));
-            }
+        self.push_history();
+        self.resize_palette(colors.len());
+        for (block, (r, g, b)) in self.color_blocks.iter_mut().zip(colors) {
+            let (h, s, v) = rgb2hsv(r, g, b);
+            block.change_color(h, s, v);
+        }
+        self.status_bar_msg = t!("msg-editor-applied", count = self.color_blocks.len());
+    }
+
+    /// Add `gradient` under a numbered name and ask for a better one
+    fn add_gradient(&mut self, mut gradient: Gradient) {
+        gradient.name = t!("gradient-default-name", number = self.gradients.len() + 1);
+        self.gradient_name_field = gradient.name.clone();
+        self.gradients.push(gradient);
+        self.gradients_state.select(Some(self.gradients.len() - 1));
+        self.current_page = CurrentPage::NameGradient;
+    }
+
+    /// Recolor the blocks with the stops of `gradient`, sampling it evenly when it has
+    /// fewer or more stops than a palette takes
+    fn split_gradient(&mut self, gradient: &Gradient) {
+        let count = gradient.stops.len().clamp(MIN_BLOCKS, MAX_BLOCKS);
+        let hexes: Vec<String> = if count == gradient.stops.len() {
+            gradient.stops.iter().map(|stop| stop.hex.clone()).collect()
+        } else {
+            (0..count)
+                .map(|idx| gradient.sample(idx as f32 / (count - 1) as f32))
+                .collect()
+        };
+        self.apply_color_list(&hexes.join("\n"));
+        if self.palette_hexes() == hexes {
+            self.status_bar_msg = t!("msg-gradient-split", count = count);
+        }
+    }
+
+    /// Recolor the blocks with the colors `counted` in a pasted file, keeping the most
+    /// distinct ones when there are more than the palette has blocks. With
+    /// `weight_imports` set, blocks are as wide as the share of the file their color covers.
+    fn import_colors(&mut self, counted: &[((u8, u8, u8), usize)]) {
+        let colors: Vec<_> = counted.iter().map(|&(color, _)| color).collect();
+        let picks = convert::most_distinct(&colors, self.color_blocks.len());
+        let coverage = convert::coverage(counted, &picks);
+        let picks: Vec<_> = picks.into_iter().zip(coverage).collect();
+        if let Some((hex, share)) = self.take_colors(&picks) {
+            self.status_bar_msg = t!(
+                "msg-colors-imported",
+                count = picks.len(),
+                found = colors.len(),
+                hex = hex,
+                percent = share.round() as u32
+            );
         }
+    }
 
-        if block_info.is_empty() {
+    /// Recolor the blocks with the colors of the frame showing `at` into the GIF at
+    /// `source`, a file or an `http(s)` URL downloaded in the background
+    fn extract_frame(&mut self, source: &str, at: Duration) {
+        if extract::is_url(source) {
+            let url = source.to_string();
+            self.tasks
+                .spawn(&t!("task-download-picture"), move |progress| {
+                    let bytes = extract::download(&url, |done, size| {
+                        if let Some(size) = size.filter(|&size| size > 0) {
+                            progress.report(done * 100 / size, 100);
+                        }
+                    })?;
+                    Ok(TaskOutput::Picture(extract::frame_colors_of(
+                        &bytes, at, &url,
+                    )?))
+                });
+            self.status_bar_msg = t!("msg-downloading-picture", url = source);
             return;
         }
 
-        // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
-        let mut logical_positions: Vec<(usize, usize, bool)> = Vec::new();
-        for (logical_pos, (array_pos, is_locked)) in block_info.iter().enumerate() {
-            logical_positions.push((*array_pos, logical_pos, *is_locked));
+        match extract::frame_colors(Path::new(source), at) {
+            Ok(counted) => self.take_picture(&counted),
+            Err(e) => self.report_error(t!("msg-extract-failed", error = e.to_string())),
+        }
+    }
+
+    /// Recolor the blocks with the `counted` colors of a picture, as many as there are
+    /// blocks, see [`extract::palette`]
+    fn take_picture(&mut self, counted: &[((u8, u8, u8), usize)]) {
+        let picks = extract::palette(counted, self.color_blocks.len());
+        if picks.len() < MIN_BLOCKS {
+            self.status_bar_msg = t!("msg-extract-too-few", count = picks.len());
+            return;
+        }
+        if let Some((hex, share)) = self.take_colors(&picks) {
+            self.status_bar_msg = t!(
+                "msg-frame-extracted",
+                count = picks.len(),
+                hex = hex,
+                percent = share.round() as u32
+            );
         }
+    }
 
-        let total_blocks = block_info.len();
+    /// Recolor the blocks with `picks`, each with the share in percent of its source it
+    /// covers; with `weight_imports` set, blocks are as wide as their share. The most
+    /// covering color and its share, `None` if the colors weren't taken.
+    fn take_colors(&mut self, picks: &[((u8, u8, u8), f32)]) -> Option<(String, f32)> {
+        let hexes: Vec<String> = picks
+            .iter()
+            .map(|&((r, g, b), _)| convert::rgb_to_hex(r, g, b))
+            .collect();
+        self.apply_color_list(&hexes.join("\n"));
+        if self.palette_hexes() != hexes {
+            return None;
+        }
 
-        // Determine how many base colors we have (2 for complementary)
-        let base_colors = 2;
-        let colors_per_group = (total_blocks + base_colors - 1) / base_colors; // Round up division
+        let (dominant, top) =
+            picks
+                .iter()
+                .enumerate()
+                .fold(
+                    (0, 0.0),
+                    |best, (idx, &(_, share))| {
+                        if share > best.1 { (idx, share) } else { best }
+                    },
+                );
+        if self.config.weight_imports {
+            for (block, (_, share)) in self.color_blocks.iter_mut().zip(picks) {
+                let width = (share / top * MAX_BLOCK_WIDTH as f32).round() as u16;
+                block.width = width.clamp(1, MAX_BLOCK_WIDTH);
+            }
+        }
+        Some((hexes[dominant].clone(), top))
+    }
 
-        for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
-            if *is_locked {
-                continue; // Skip locked blocks
+    /// Start or stop looking for colors in the clipboard
+    fn toggle_clipboard_watch(&mut self) {
+        if self.clipboard_watch.take().is_some() {
+            self.status_bar_msg = t!("msg-clipboard-watch-off");
+            return;
+        }
+        match self.clipboard.get_text() {
+            Ok(text) => {
+                // Only what is copied from now on gets offered
+                self.clipboard_seen = text;
+                self.clipboard_watch = Some(Instant::now() + CLIPBOARD_POLL_INTERVAL);
+                self.status_bar_msg = t!("msg-clipboard-watch-on");
             }
+            Err(e) => self.report_error(t!(
+                "msg-clipboard-unreadable",
+                backend = self.clipboard.name(),
+                error = e.to_string()
+            )),
+        }
+    }
+
+    /// Offer a color that newly turned up in the clipboard for the selected block. Waits
+    /// while a popup is open, so the offer never cuts into one.
+    pub fn tick_clipboard_watch(&mut self) {
+        let Some(due) = self.clipboard_watch else {
+            return;
+        };
+        if Instant::now() < due || self.current_page != CurrentPage::Main {
+            return;
+        }
+        self.clipboard_watch = Some(Instant::now() + CLIPBOARD_POLL_INTERVAL);
 
-            if let Some(color_block) = self.color_blocks[*array_pos].as_mut() {
-                let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
+        let text = match self.clipboard.get_text() {
+            Ok(text) if text != self.clipboard_seen => text,
+            _ => return,
+        };
+        let hex = (text.len() <= CLIPBOARD_COLOR_MAX_LEN)
+            .then(|| convert::parse_color(&text).ok())
+            .flatten()
+            .map(|(r, g, b)| convert::rgb_to_hex(r, g, b));
+        self.clipboard_seen = text;
+        if let Some(hex) = hex
+            && self
+                .selected_block()
+                .is_some_and(|block| block.get_hex() != hex)
+        {
+            self.clipboard_offer = Some(hex);
+            self.status_bar_msg.clear();
+            self.current_page = CurrentPage::ClipboardOffer;
+        }
+    }
 
-                // Determine which base color group (0 = base, 1 = complement)
-                let color_group = *logical_pos % base_colors;
-                let variation_index = *logical_pos / base_colors;
+    /// Remember the current palette before it gets replaced
+    fn push_history(&mut self) {
+        let hexes = self.palette_hexes();
+        if self.history.last() == Some(&hexes) {
+            return;
+        }
 
-                // Calculate base hue for this group
-                let group_base_hue = if color_group == 0 {
-                    base_hue
-                } else {
-                    (base_hue + 180.0) % 360.0
-                };
+        self.history.push(hexes);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+    }
 
-                // Create variations within each color group
-                // Variation index determines how much to vary saturation/value
-                let variation_factor = if colors_per_group > 1 {
-                    (variation_index as f32) / (colors_per_group - 1) as f32 // 0.0 to 1.0
-                } else {
-                    0.5
-                };
+    fn to_project(&self) -> Project {
+        Project {
+            version: project::PROJECT_VERSION,
+            name: self.palette_name.clone(),
+            theory: self.current_color_theory,
+            selected: self.selected_block_id,
+            blocks: self
+                .color_blocks
+                .iter()
+                .map(|block| {
+                    let (h, s, v) = block.get_hsv_values();
+                    ProjectBlock {
+                        hex: block.get_hex(),
+                        hsv: [h, s, v],
+                        locked: block.locked,
+                        frozen: block.frozen,
+                        label: block.label.clone(),
+                        note: block.note.clone(),
+                        width: (block.width != 1).then_some(block.width),
+                        role: block.role,
+                        oklch: block
+                            .out_of_gamut
+                            .map(|color| [color.l, color.chroma, color.hue.into_degrees()]),
+                    }
+                })
+                .collect(),
+            metadata: self.project_metadata.clone(),
+            history: self.history.clone(),
+            gradients: self.gradients.clone(),
+        }
+    }
 
-                let new_hue = (group_base_hue + randomness) % 360.0;
+    /// Open `project`, refusing one with fewer blocks than the app keeps, see
+    /// [`check_block_count`]
+    fn apply_project(&mut self, project: Project) -> io::Result<()> {
+        check_block_count(project.blocks.len())?;
+        self.color_blocks = project
+            .blocks
+            .iter()
+            .take(MAX_BLOCKS)
+            .enumerate()
+            .map(|(i, saved)| {
+                let [h, s, v] = saved.hsv;
+                let mut block = ColorBlock::new(i + 1, h, s, v);
+                block.locked = saved.locked;
+                block.frozen = saved.frozen;
+                block.label = saved.label.clone();
+                block.note = saved.note.clone();
+                block.width = saved.width.unwrap_or(1).clamp(1, MAX_BLOCK_WIDTH);
+                block.role = saved.role;
+                block.out_of_gamut = saved.oklch.map(|[l, c, h]| Oklch::new(l, c, h));
+                block
+            })
+            .collect();
+        self.selected_block_id = project
+            .selected
+            .min(self.color_blocks.len().saturating_sub(1));
+        self.ansi_scheme = ansi::is_scheme(&self.color_blocks);
+        self.current_color_theory = project.theory;
+        self.active_plugin = None;
+        self.seed = None;
+        self.palette_name = project.name;
+        self.project_metadata = project.metadata;
+        self.history = project.history;
+        self.gradients = project.gradients;
+        self.gradients_state.select_first();
+        Ok(())
+    }
 
-                // Vary saturation and value to create distinct variations within each group
-                // Create a progression: lighter/darker or more/less saturated variations
-                let sat_variation_range = if !locked_blocks.is_empty() {
-                    0.12 // Moderate variation when locked color exists
-                } else {
-                    0.18 // More variation when no locked color
-                };
-                let val_variation_range = if !locked_blocks.is_empty() {
-                    0.15 // Moderate variation when locked color exists
-                } else {
-                    0.22 // More variation when no locked color
-                };
+    /// Open a `.tpal` project, or start a new one at `path` if it doesn't exist yet
+    pub fn open_project(&mut self, path: &Path) -> io::Result<()> {
+        if path.exists() {
+            let project = project::load_project(path)?;
+            self.apply_project(project)?;
+            self.remember_recent(path);
+        } else {
+            self.status_bar_msg = t!("msg-new-project", path = path.display().to_string());
+        }
 
-                // Create variation: center around base, spread based on variation_index
-                let sat_offset = (variation_factor - 0.5) * sat_variation_range * 2.0; // -range to +range
-                let val_offset = (variation_factor - 0.5) * val_variation_range * 2.0; // -range to +range
+        self.project_path = Some(path.to_path_buf());
+        Ok(())
+    }
 
-                let new_sat = (base_sat + sat_offset).clamp(0.0, 1.0);
-                let new_val = (base_val + val_offset).clamp(0.0, 1.0);
+    fn save_project(&mut self) {
+        let path = self.project_path.clone().unwrap_or_else(|| {
+            let stem = match library::slugify(&self.palette_name) {
+                slug if slug.is_empty() => String::from("untitled"),
+                slug => slug,
+            };
+            PathBuf::from(format!("{stem}.{}", project::PROJECT_EXTENSION))
+        });
 
-                color_block.change_color(new_hue, new_sat, new_val);
+        let mut project = self.to_project();
+        match project::save_project(&mut project, &path) {
+            Ok(()) => {
+                self.project_metadata = project.metadata;
+                self.status_bar_msg = t!("msg-project-saved", path = path.display().to_string());
+                self.remember_recent(&path);
+                self.project_path = Some(path);
             }
+            Err(e) => self.report_error(t!("msg-project-save-failed", error = e.to_string())),
         }
     }
 
-    fn generate_analogous(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let mut base_sat: f32 = 0.65;
-        let mut base_val: f32 = 0.65;
-        let rand_rate = 3; // Minimal randomness for cleaner analogous relationships
+    fn to_saved_palette(&self, name: &str) -> SavedPalette {
+        SavedPalette {
+            name: name.trim().to_string(),
+            theory: self.current_color_theory,
+            colors: self
+                .color_blocks
+                .iter()
+                .map(|block| SavedColor {
+                    hex: block.get_hex(),
+                    locked: block.locked,
+                    frozen: block.frozen,
+                    role: block.role,
+                })
+                .collect(),
+            tags: Vec::new(),
+            starred: false,
+        }
+    }
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-            base_sat = ColorBlock::get_avg_saturation(&locked_blocks);
-            base_val = ColorBlock::get_avg_value(&locked_blocks);
-        } else {
-            // generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-                base_sat = color_block.hsv.saturation;
-                base_val = color_block.hsv.value;
-            }
+    /// Open `palette`, refusing one with fewer colors than the app keeps blocks, see
+    /// [`check_block_count`]
+    fn apply_saved_palette(&mut self, palette: &SavedPalette) -> io::Result<()> {
+        check_block_count(palette.colors.len())?;
+        self.color_blocks = palette
+            .colors
+            .iter()
+            .take(MAX_BLOCKS)
+            .enumerate()
+            .map(|(i, color)| {
+                let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+                let (h, s, v) = rgb2hsv(r, g, b);
+                let mut block = ColorBlock::new(i + 1, h, s, v);
+                block.locked = color.locked;
+                block.frozen = color.frozen;
+                block.role = color.role;
+                block
+            })
+            .collect();
+        self.selected_block_id = 0;
+        self.ansi_scheme = false;
+        self.current_color_theory = palette.theory;
+        self.active_plugin = None;
+        self.seed = None;
+        self.palette_name = palette.name.clone();
+        Ok(())
+    }
+
+    /// Load a palette file given on the command line
+    pub fn load_palette_file(&mut self, path: &Path) -> io::Result<()> {
+        let palette = library::load_palette(path)?;
+        self.apply_saved_palette(&palette)?;
+        self.remember_recent(path);
+        Ok(())
+    }
+
+    fn open_library_palette(&mut self, palette: &SavedPalette) {
+        let path = library::palette_path(&palette.name);
+        if let Err(e) = self.apply_saved_palette(palette) {
+            self.report_error(t!(
+                "msg-open-failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            ));
+            return;
         }
+        self.remember_recent(&path);
+        self.status_bar_msg = t!("msg-loaded", name = palette.name.clone());
+        self.current_page = CurrentPage::Main;
+    }
 
-        // Collect all existing blocks to calculate logical positions
-        let mut block_info: Vec<(usize, bool)> = Vec::new();
-        for (i, block) in self.color_blocks.iter().enumerate() {
-            if let Some(_block) = block {
-                block_info.push((i, _block.locked));
-            }
+    /// Record the current palette in the quick switcher. Best effort, failures are ignored.
+    fn remember_recent(&self, path: &Path) {
+        let name = if self.palette_name.is_empty() {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        } else {
+            self.palette_name.clone()
+        };
+
+        let _ = library::remember_recent(RecentEntry {
+            name,
+            path: path.to_path_buf(),
+            colors: self.palette_hexes(),
+        });
+    }
+
+    fn open_recent_switcher(&mut self) {
+        self.recent_entries = library::load_recent().unwrap_or_default();
+
+        if self.recent_entries.is_empty() {
+            self.status_bar_msg = t!("msg-no-recent");
+            return;
         }
 
-        if block_info.is_empty() {
+        // Like alt-tab: the first press already points at the previous palette
+        self.recent_state
+            .select(Some(1.min(self.recent_entries.len() - 1)));
+        self.current_page = CurrentPage::RecentSwitcher;
+    }
+
+    fn open_recent(&mut self, entry: &RecentEntry) {
+        self.current_page = CurrentPage::Main;
+
+        if !entry.path.exists() {
+            self.status_bar_msg = t!("msg-missing-file", path = entry.path.display().to_string());
             return;
         }
 
-        let total_blocks = block_info.len();
+        let result = if project::is_project_path(&entry.path) {
+            self.open_project(&entry.path)
+        } else {
+            self.load_palette_file(&entry.path)
+        };
+
+        self.status_bar_msg = match result {
+            Ok(()) => t!("msg-loaded", name = entry.name.clone()),
+            Err(e) => logged_error(t!(
+                "msg-open-failed",
+                path = entry.path.display().to_string(),
+                error = e.to_string()
+            )),
+        };
+    }
 
-        // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
-        let mut logical_positions: Vec<(usize, usize, bool)> = Vec::new();
-        for (logical_pos, (array_pos, is_locked)) in block_info.iter().enumerate() {
-            logical_positions.push((*array_pos, logical_pos, *is_locked));
+    fn run_command(&mut self, command: Command) {
+        info!(?command, "command");
+        match command {
+            Command::Run(action) => self.update(action),
+            Command::Theory(theory) => {
+                self.current_color_theory = theory;
+                self.active_plugin = None;
+                self.status_bar_msg = t!("msg-theory-set", theory = self.theory_name());
+            }
+            Command::SetBlocks(_) if self.ansi_scheme => {
+                self.status_bar_msg = t!("msg-scheme-fixed")
+            }
+            Command::SetBlocks(count) => {
+                self.resize_palette(count);
+                self.status_bar_msg = t!("msg-blocks-set", count = self.color_blocks.len());
+            }
+            Command::SetJitter(jitter) => self.jitter = jitter,
+            Command::SetScale(scale) => self.monochrome_scale = scale,
+            Command::SetStrict(strict) => self.strict = strict,
+            Command::Save(name) => {
+                self.save_name_field = name;
+                self.save_current_palette();
+            }
+            Command::Export(format, path) => self.export_palette(format, path),
+            Command::Extract(source, at) => self.extract_frame(&source, at),
         }
+    }
 
-        // Best practice: analogous colors should stay within a reasonable range
-        // to maintain true analogous harmony while having noticeable differences
-        // Professional tools like palettegenerator.com distribute colors bidirectionally
-        // Use a fixed step size for consistent, noticeable differences between colors
-        let step_size = 10.0; // Fixed 10° step for clear, noticeable differences
+    /// Add blocks at the end or drop the last ones until there are `count`, within
+    /// [`MIN_BLOCKS`] and [`MAX_BLOCKS`]
+    fn resize_palette(&mut self, count: usize) {
+        let count = count.clamp(MIN_BLOCKS, MAX_BLOCKS);
+        while self.color_blocks.len() < count {
+            self.add_block();
+        }
+        self.color_blocks.truncate(count);
+        self.selected_block_id = self.selected_block_id.min(count - 1);
+    }
 
-        // Find the locked block's logical position to use as center (if any)
-        let center_logical_pos = logical_positions
-            .iter()
-            .find(|(_, _, is_locked)| *is_locked)
-            .map(|(_, logical_pos, _)| *logical_pos)
-            .unwrap_or(total_blocks / 2); // Use middle if no locked block
-
-        for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
-            if *is_locked {
-                continue; // Skip locked blocks
-            }
-
-            if let Some(color_block) = self.color_blocks[*array_pos].as_mut() {
-                let randomness = rng.random_range(-rand_rate..rand_rate) as f32;
-
-                // Distribute colors bidirectionally around base hue
-                // Colors before center go negative, colors after go positive
-                let offset = if *logical_pos < center_logical_pos {
-                    // Before center: negative offset
-                    let diff = (center_logical_pos - *logical_pos) as f32;
-                    -(diff * step_size)
-                } else if *logical_pos > center_logical_pos {
-                    // After center: positive offset
-                    let diff = (*logical_pos - center_logical_pos) as f32;
-                    diff * step_size
-                } else {
-                    // At center (shouldn't happen for unlocked, but safety)
-                    0.0
-                };
-                let new_hue = ((base_hue + offset + randomness) % 360.0 + 360.0) % 360.0;
+    /// Ask the first-run questions, starting from the current config
+    pub fn start_setup(&mut self) {
+        self.show_setup_step(SetupStep::Theory);
+    }
 
-                // Vary saturation and value very slightly for visual interest while maintaining harmony
-                // Analogous colors should stay very close to the base color's characteristics
-                // Use locked blocks' saturation/value as base when available
-                let sat_variation = if !locked_blocks.is_empty() {
-                    0.05 // Very small variation when locked color exists (±5%)
-                } else {
-                    0.10 // Slightly more variation when no locked color (±10%)
-                };
-                let val_variation = if !locked_blocks.is_empty() {
-                    0.05 // Very small variation when locked color exists (±5%)
-                } else {
-                    0.10 // Slightly more variation when no locked color (±10%)
-                };
+    fn show_setup_step(&mut self, step: SetupStep) {
+        let current = match step {
+            SetupStep::Theory => ColorTheories::iter().position(|t| t == self.config.theory),
+            SetupStep::Blocks => SETUP_BLOCK_COUNTS
+                .iter()
+                .position(|&count| count == self.config.blocks),
+            SetupStep::CopyFormat => ColorFormat::iter().position(|f| f == self.config.copy_format),
+            SetupStep::Recolor => Some(usize::from(self.config.slideshow_apply_to_terminal)),
+        };
+        self.setup_step = step;
+        self.setup_state.select(Some(current.unwrap_or(0)));
+        self.current_page = CurrentPage::Setup;
+    }
+
+    /// Answers to `step`, in the order of [`App::apply_setup_choice`]'s indexes
+    fn setup_options(&self, step: SetupStep) -> Vec<String> {
+        match step {
+            SetupStep::Theory => ColorTheories::iter().map(|t| format!("{t:?}")).collect(),
+            SetupStep::Blocks => SETUP_BLOCK_COUNTS
+                .iter()
+                .map(|&count| t!("setup-block-count", count = count))
+                .collect(),
+            // Shown on an example color
+            SetupStep::CopyFormat => ColorFormat::iter()
+                .map(|format| format.format(224, 122, 95))
+                .collect(),
+            SetupStep::Recolor => vec![t!("setup-recolor-off"), t!("setup-recolor-on")],
+        }
+    }
 
-                let new_sat = (base_sat
-                    + rng.random_range(-sat_variation..sat_variation) as f32 / 100.0)
-                    .clamp(0.0, 1.0);
-                let new_val = (base_val
-                    + rng.random_range(-val_variation..val_variation) as f32 / 100.0)
-                    .clamp(0.0, 1.0);
+    fn apply_setup_choice(&mut self, step: SetupStep, idx: usize) {
+        match step {
+            SetupStep::Theory => {
+                if let Some(theory) = ColorTheories::iter().nth(idx) {
+                    self.config.theory = theory;
+                }
+            }
+            SetupStep::Blocks => {
+                if let Some(&count) = SETUP_BLOCK_COUNTS.get(idx) {
+                    self.config.blocks = count;
+                }
+            }
+            SetupStep::CopyFormat => {
+                if let Some(format) = ColorFormat::iter().nth(idx) {
+                    self.config.copy_format = format;
+                }
+            }
+            SetupStep::Recolor => self.config.slideshow_apply_to_terminal = idx == 1,
+        }
+    }
 
-                color_block.change_color(new_hue, new_sat, new_val);
+    /// Write the config and start over with it
+    fn finish_setup(&mut self) {
+        self.current_page = CurrentPage::Main;
+        self.current_color_theory = self.config.theory;
+        self.active_plugin = None;
+        self.resize_palette(self.config.blocks);
+        if self.config.generate_on_startup {
+            self.generate();
+        }
+        match config::save(&self.config) {
+            Ok(path) => {
+                self.status_bar_msg = t!("msg-setup-done", path = path.display().to_string())
             }
+            Err(e) => self.report_error(t!("msg-config-save-failed", error = e.to_string())),
         }
     }
 
-    fn generate_monochrome(&mut self) {
-        let mut rng = rand::rng();
-        let locked_blocks = self.get_locked_blocks();
-        let mut base_hue: f32 = 0.0;
-        let hue_variation = 3.0; // Minimal hue variation for true monochrome (±3 degrees)
-        let rand_rate = 2; // Very low randomness for hue to maintain monochromatic integrity
-
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-        } else {
-            // Generate initial random color for first block
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
+    /// The value of `setting` as the settings page shows it
+    fn setting_value(&self, setting: Setting) -> String {
+        let on_off = |on: bool| {
+            if on {
+                t!("setting-on")
+            } else {
+                t!("setting-off")
             }
+        };
+        let config = &self.config;
+        match setting {
+            Setting::Theory => format!("{:?}", config.theory),
+            Setting::Blocks => config.blocks.to_string(),
+            Setting::CopyFormat => config.copy_format.key().to_string(),
+            Setting::Jitter => i18n::translate(&format!("jitter-{}", config.jitter.key()), None),
+            Setting::Strict => on_off(config.strict),
+            Setting::MonochromeScale => config.monochrome_scale.key().to_string(),
+            Setting::AnimateTransitions => on_off(config.animate_transitions),
+            Setting::GenerateOnStartup => on_off(config.generate_on_startup),
+            Setting::GenerateOnTheoryChange => on_off(config.generate_on_theory_change),
+            Setting::SlideshowSeconds => t!("setting-seconds", seconds = config.slideshow_seconds),
+            Setting::SlideshowApplyToTerminal => on_off(config.slideshow_apply_to_terminal),
+            Setting::AnsiDimVariants => on_off(config.ansi_dim_variants),
+            Setting::ThemeChrome => on_off(config.theme_chrome),
+            Setting::Autosave => on_off(config.autosave),
+            Setting::WeightImports => on_off(config.weight_imports),
+            Setting::Announce => on_off(config.announce),
         }
+    }
 
-        // Collect all existing blocks to calculate logical positions
-        let mut block_info: Vec<(usize, bool)> = Vec::new();
-        for (i, block) in self.color_blocks.iter().enumerate() {
-            if let Some(_block) = block {
-                block_info.push((i, _block.locked));
+    /// Step the highlighted setting to its next (or previous) value, apply it to the
+    /// session right away and write the config file
+    fn change_setting(&mut self, forward: bool) {
+        let Some(setting) = self
+            .settings_state
+            .selected()
+            .and_then(|selected| Setting::iter().nth(selected))
+        else {
+            return;
+        };
+        let config = &mut self.config;
+        match setting {
+            Setting::Theory => {
+                config.theory = cycle(ColorTheories::iter(), config.theory, forward);
+                self.current_color_theory = config.theory;
+                self.active_plugin = None;
+            }
+            Setting::Blocks => {
+                config.blocks = if forward {
+                    config.blocks + 1
+                } else {
+                    config.blocks.saturating_sub(1)
+                }
+                .clamp(MIN_BLOCKS, MAX_BLOCKS);
+                self.resize_palette(self.config.blocks);
+            }
+            Setting::CopyFormat => {
+                config.copy_format = cycle(ColorFormat::iter(), config.copy_format, forward)
+            }
+            Setting::Jitter => {
+                config.jitter = cycle(Jitter::iter(), config.jitter, forward);
+                self.jitter = config.jitter;
+            }
+            Setting::Strict => {
+                config.strict = !config.strict;
+                self.strict = config.strict;
+            }
+            Setting::MonochromeScale => {
+                config.monochrome_scale =
+                    cycle(LightnessScale::iter(), config.monochrome_scale, forward);
+                self.monochrome_scale = config.monochrome_scale;
+            }
+            Setting::AnimateTransitions => config.animate_transitions = !config.animate_transitions,
+            Setting::GenerateOnStartup => config.generate_on_startup = !config.generate_on_startup,
+            Setting::GenerateOnTheoryChange => {
+                config.generate_on_theory_change = !config.generate_on_theory_change
             }
+            Setting::SlideshowSeconds => {
+                config.slideshow_seconds = if forward {
+                    config.slideshow_seconds + 1
+                } else {
+                    config.slideshow_seconds.saturating_sub(1)
+                }
+                .max(1)
+            }
+            Setting::SlideshowApplyToTerminal => {
+                config.slideshow_apply_to_terminal = !config.slideshow_apply_to_terminal
+            }
+            Setting::AnsiDimVariants => config.ansi_dim_variants = !config.ansi_dim_variants,
+            Setting::ThemeChrome => {
+                config.theme_chrome = !config.theme_chrome;
+                self.theme_chrome = config.theme_chrome;
+            }
+            Setting::Autosave => config.autosave = !config.autosave,
+            Setting::WeightImports => config.weight_imports = !config.weight_imports,
+            Setting::Announce => config.announce = !config.announce,
+        }
+        if let Err(e) = config::save(&self.config) {
+            self.report_error(t!("msg-config-save-failed", error = e.to_string()));
         }
+    }
 
-        if block_info.is_empty() {
-            return;
+    /// Write the palette in `format` to `path`, or next to the working directory under
+    /// the palette's name
+    fn export_palette(&mut self, format: Format, path: Option<PathBuf>) {
+        let path = path.unwrap_or_else(|| {
+            let name = match self.palette_name.trim() {
+                "" => "palette",
+                name => name,
+            };
+            PathBuf::from(format!("{name}.{}", format.extension()))
+        });
+        let palette = ExportPalette::from(&self.to_project());
+        let written = export::export_bytes(format, &palette, &self.config.export)
+            .and_then(|contents| fs::write(&path, contents));
+        match written {
+            Ok(()) => {
+                self.status_bar_msg = t!("msg-exported", path = path.display().to_string());
+                self.run_hooks(hooks::Event::Export(Some(path)));
+            }
+            Err(e) => self.report_error(t!("msg-export-failed", error = e.to_string())),
         }
+    }
 
-        let total_blocks = block_info.len();
+    fn save_current_palette(&mut self) {
+        let mut palette = self.to_saved_palette(&self.save_name_field);
 
-        // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
-        let mut logical_positions: Vec<(usize, usize, bool)> = Vec::new();
-        for (logical_pos, (array_pos, is_locked)) in block_info.iter().enumerate() {
-            logical_positions.push((*array_pos, logical_pos, *is_locked));
+        // Overwriting keeps the tags and star the palette already had in the library
+        if let Ok(existing) = library::load_palette(&library::palette_path(&palette.name)) {
+            palette.tags = existing.tags;
+            palette.starred = existing.starred;
         }
 
-        // Get anchor saturation and value from locked blocks or first block
-        let (anchor_sat, anchor_val) = if !locked_blocks.is_empty() {
-            if let Some(Some(anchor_block)) = locked_blocks.first() {
-                let (_, sat, val) = anchor_block.get_hsv_values();
-                (sat, val)
-            } else {
-                (0.6, 0.6) // Default fallback
+        match library::save_palette(&palette) {
+            Ok(path) => {
+                self.palette_name = palette.name;
+                self.remember_recent(&path);
+                self.status_bar_msg = t!("msg-palette-saved", path = path.display().to_string());
+                self.current_page = CurrentPage::Main;
             }
-        } else {
-            if let Some(color_block) = self.color_blocks[0].as_ref() {
-                let (_, sat, val) = color_block.get_hsv_values();
-                (sat, val)
-            } else {
-                (0.6, 0.6) // Default fallback
+            Err(e) => self.report_error(t!("msg-save-failed", error = e.to_string())),
+        }
+    }
+
+    fn open_palette_dialog(&mut self) {
+        match library::list_palettes() {
+            Ok(palettes) if palettes.is_empty() => {
+                self.status_bar_msg = t!("msg-library-empty");
             }
-        };
+            Ok(palettes) => {
+                self.saved_palettes = palettes;
+                self.library_input = LibraryInput::None;
+                self.library_query.clear();
+                self.filter_library();
+                self.current_page = CurrentPage::OpenPalette;
+            }
+            Err(e) => self.report_error(t!("msg-library-read-failed", error = e.to_string())),
+        }
+    }
 
-        // For monochrome, we create variations in both saturation and brightness
-        // This creates tints (lighter), tones (muted), and shades (darker)
-        // Saturation range: from low (0.1) to high (0.9)
-        // Brightness range: from low (0.2) to high (0.9)
+    fn filter_library(&mut self) {
+        self.library_filtered = library::search_palettes(&self.saved_palettes, &self.library_query);
+        self.open_palette_state.select_first();
+    }
 
-        let sat_range_start = 0.1;
-        let sat_range_end = 0.9;
-        let val_range_start = 0.2;
-        let val_range_end = 0.9;
+    fn selected_library_palette(&self) -> Option<&SavedPalette> {
+        let selected = self.open_palette_state.selected()?;
+        let idx = self.library_filtered.get(selected)?;
+        self.saved_palettes.get(*idx)
+    }
 
-        // Calculate step sizes for even distribution
-        let sat_step = if total_blocks > 1 {
-            (sat_range_end - sat_range_start) / (total_blocks - 1) as f32
-        } else {
-            0.0
+    fn restore_selected_palette(&mut self) {
+        let Some(selected) = self.open_palette_state.selected() else {
+            return;
         };
-
-        let val_step = if total_blocks > 1 {
-            (val_range_end - val_range_start) / (total_blocks - 1) as f32
-        } else {
-            0.0
+        let Some(&idx) = self.library_filtered.get(selected) else {
+            return;
         };
 
-        // Apply monochrome progression to all unlocked blocks
-        for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
-            if *is_locked {
-                continue; // Skip locked blocks
+        let name = self.saved_palettes[idx].name.clone();
+        match library::restore_previous_version(&name) {
+            Ok(restored) => {
+                self.saved_palettes[idx] = restored;
+                self.status_bar_msg = t!("msg-restored", name = name.clone());
             }
+            Err(e) => self.report_error(t!(
+                "msg-restore-failed",
+                name = name.clone(),
+                error = e.to_string()
+            )),
+        }
+    }
 
-            if let Some(color_block) = self.color_blocks[*array_pos].as_mut() {
-                // Keep hue constant with minimal variation for true monochrome
-                let hue_randomness = rng.random_range(-rand_rate..rand_rate) as f32;
-                let new_hue = (base_hue + hue_randomness * hue_variation / 10.0) % 360.0;
-
-                // Vary saturation across the range for visual interest
-                // Create a smooth progression that doesn't necessarily follow anchor
-                let new_sat = if locked_blocks.is_empty() {
-                    // No locked blocks: distribute evenly across range
-                    sat_range_start + (sat_step * *logical_pos as f32)
-                } else {
-                    // With locked blocks: use anchor saturation as reference but still vary
-                    // Create variation around anchor while maintaining smooth progression
-                    let base_sat_progress = sat_range_start + (sat_step * *logical_pos as f32);
-                    // Blend with anchor saturation for smoother transitions
-                    (base_sat_progress * 0.7 + anchor_sat * 0.3)
-                        .clamp(sat_range_start, sat_range_end)
-                };
+    fn toggle_selected_palette_star(&mut self) {
+        let Some(selected) = self.open_palette_state.selected() else {
+            return;
+        };
+        let Some(&idx) = self.library_filtered.get(selected) else {
+            return;
+        };
 
-                // Vary brightness across the range
-                // Alternate between lighter and darker for more interesting palette
-                let new_val = if locked_blocks.is_empty() {
-                    // No locked blocks: distribute evenly across range
-                    val_range_start + (val_step * *logical_pos as f32)
-                } else {
-                    // With locked blocks: use anchor value as reference but still vary
-                    let base_val_progress = val_range_start + (val_step * *logical_pos as f32);
-                    // Blend with anchor value for smoother transitions
-                    (base_val_progress * 0.7 + anchor_val * 0.3)
-                        .clamp(val_range_start, val_range_end)
-                };
+        let palette = &mut self.saved_palettes[idx];
+        palette.starred = !palette.starred;
 
-                color_block.change_color(new_hue, new_sat, new_val);
-            }
+        if let Err(e) = library::save_palette(palette) {
+            self.report_error(t!("msg-star-failed", error = e.to_string()));
         }
     }
 
-    fn generate_shades(&mut self, to_light: bool) {
-        // Full range: 0.0 (black) to 1.0 (white) - no constraints
-        let black = 0.0;
-        let white = 1.0;
+    fn toggle_selected_favorite(&mut self) {
+        let Some(hex) = self.selected_block().map(|block| block.get_hex()) else {
+            return;
+        };
 
-        // Get base hue from locked blocks or generate
-        let locked_blocks = self.get_locked_blocks();
-        let base_hue: f32;
+        let starred = self.favorites.toggle_color(&hex);
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-        } else {
-            // Generate initial random color for first block if no locks
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                base_hue = color_block.hsv.hue.into_degrees();
-            } else {
-                return; // No blocks available
-            }
-        }
+        self.status_bar_msg = match library::save_favorites(&self.favorites) {
+            Ok(()) if starred => t!("msg-color-starred", hex = hex.as_str()),
+            Ok(()) => t!("msg-color-unstarred", hex = hex.as_str()),
+            Err(e) => logged_error(t!("msg-favorites-save-failed", error = e.to_string())),
+        };
+    }
 
-        // Collect all existing blocks with their array positions, values, saturations, and lock status
-        // Then map them to logical positions (0, 1, 2, ...) for even distribution
-        let mut block_info: Vec<(usize, f32, f32, bool)> = Vec::new();
-        for (i, block) in self.color_blocks.iter().enumerate() {
-            if let Some(block) = block {
-                block_info.push((i, block.hsv.value, block.hsv.saturation, block.locked));
-            }
-        }
+    fn open_favorites(&mut self) {
+        let palettes = library::list_palettes().unwrap_or_default();
+
+        self.favorite_entries = palettes
+            .into_iter()
+            .filter(|palette| palette.starred)
+            .map(FavoriteEntry::Palette)
+            .chain(
+                self.favorites
+                    .colors
+                    .iter()
+                    .cloned()
+                    .map(FavoriteEntry::Color),
+            )
+            .collect();
 
-        if block_info.is_empty() {
+        if self.favorite_entries.is_empty() {
+            self.status_bar_msg = t!("msg-no-favorites");
             return;
         }
 
-        let total_blocks = block_info.len();
-
-        // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
-        // This ensures even distribution regardless of gaps in the array
-        let mut logical_positions: Vec<(usize, usize, f32, f32, bool)> = Vec::new();
-        for (logical_pos, (array_pos, val, sat, is_locked)) in block_info.iter().enumerate() {
-            logical_positions.push((*array_pos, logical_pos, *val, *sat, *is_locked));
-        }
+        self.favorites_state.select_first();
+        self.current_page = CurrentPage::Favorites;
+    }
 
-        // Find locked blocks and use the first one as anchor
-        let locked_info: Vec<(usize, usize, f32, f32)> = logical_positions
+    /// Every mix of the blocks weighted in the mix popup, with the Fluent id of its name;
+    /// `None` while no block is taken in
+    fn mix_results(&self) -> [(&'static str, Option<String>); 3] {
+        let weighted: Vec<(String, u8)> = self
+            .color_blocks
             .iter()
-            .filter_map(|(array_pos, logical_pos, val, sat, is_locked)| {
-                if *is_locked {
-                    Some((*array_pos, *logical_pos, *val, *sat))
-                } else {
-                    None
-                }
-            })
+            .zip(&self.mix_weights)
+            .filter(|(_, weight)| **weight > 0)
+            .map(|(block, weight)| (block.get_hex(), *weight))
+            .collect();
+        let hexes: Vec<&str> = weighted.iter().map(|(hex, _)| hex.as_str()).collect();
+        let average: Vec<(&str, f32)> = weighted
+            .iter()
+            .map(|(hex, weight)| (hex.as_str(), f32::from(*weight)))
             .collect();
 
-        // Determine anchor (first locked block, or first block if none)
-        let (_anchor_array_pos, anchor_logical_pos, anchor_val, anchor_sat) =
-            if let Some((_array_pos, logical_pos, val, sat)) = locked_info.first() {
-                (*_array_pos, *logical_pos, *val, *sat)
-            } else {
-                // No locked blocks - use first block as anchor
-                let (array_pos, logical_pos, val, sat, _) = logical_positions[0];
-                (array_pos, logical_pos, val, sat)
-            };
+        [
+            ("mix-average", blend::weighted_average(&average)),
+            ("mix-additive", blend::additive(&hexes)),
+            ("mix-multiply", blend::multiply(&hexes)),
+        ]
+    }
 
-        // Calculate dynamic step size based on total block count
-        // More blocks = smaller step (smoother transition)
-        // Fewer blocks = larger step (bigger jumps)
-
-        // Calculate how many blocks are after the anchor (including the anchor itself)
-        let blocks_after_anchor = total_blocks - anchor_logical_pos;
-
-        // Calculate progression from anchor to target
-        // For Lights: anchor -> one step below white (evenly incremented)
-        // For Shadows: anchor -> one step above black (evenly incremented)
-        let step_from_anchor = if to_light {
-            // Lights: target is one step below white
-            // Calculate step size: (white - anchor_val) divided by number of blocks after anchor
-            // This ensures even increments and last block is one step below white
-            if blocks_after_anchor > 0 {
-                (white - anchor_val) / blocks_after_anchor as f32
-            } else {
-                0.0
+    fn pick_blend_palette(&mut self) {
+        let Some(picked) = self.selected_library_palette().cloned() else {
+            return;
+        };
+
+        match self.blend_from.take() {
+            Some(from) if from.name != picked.name => {
+                self.blend_ends = Some((from, picked));
+                // The first intermediate, the ends are the palettes themselves
+                self.blend_state.select(Some(1));
+                self.library_input = LibraryInput::None;
+                self.current_page = CurrentPage::Blend;
             }
-        } else {
-            // Shadows: target is one step above black
-            // Calculate step size: anchor_val divided by number of blocks after anchor
-            // This ensures even increments and last block is one step above black
-            if blocks_after_anchor > 0 {
-                anchor_val / blocks_after_anchor as f32
-            } else {
-                0.0
+            _ => {
+                self.status_bar_msg = t!("msg-blend-first", name = picked.name.clone());
+                self.blend_from = Some(picked);
             }
-        };
+        }
+    }
 
-        // Calculate how many blocks are before the anchor
-        let blocks_before_anchor = anchor_logical_pos;
+    /// Ratio of every row of the blend popup, both ends included
+    fn blend_ratios(&self) -> Vec<f32> {
+        iter::once(0.0)
+            .chain(blend::intermediate_ratios(self.blend_steps))
+            .chain(iter::once(1.0))
+            .collect()
+    }
 
-        // Calculate step size from start to anchor (if there are blocks before)
-        let step_to_anchor = if blocks_before_anchor > 0 {
-            if to_light {
-                // For lights: start is black, anchor is somewhere above
-                (anchor_val - black) / blocks_before_anchor as f32
-            } else {
-                // For shadows: start is white, anchor is somewhere below
-                (white - anchor_val) / blocks_before_anchor as f32
-            }
-        } else {
-            0.0
+    /// The blend popup's two palettes mixed at `ratio`, as hex colors
+    fn blended_hexes(&self, ratio: f32) -> Vec<String> {
+        let Some((from, to)) = &self.blend_ends else {
+            return Vec::new();
         };
-
-        // For Lights mode: calculate desaturation step (from anchor saturation to 0.0)
-        // For Shadows mode: keep saturation constant (as requested - never change)
-        let sat_step_from_anchor = if to_light && blocks_after_anchor > 1 {
-            // Lights: desaturate from anchor_sat to 0.0 (white has no saturation)
-            anchor_sat / (blocks_after_anchor - 1) as f32
-        } else {
-            0.0
+        let hexes = |palette: &SavedPalette| -> Vec<String> {
+            palette.colors.iter().map(|c| c.hex.clone()).collect()
         };
+        blend::palettes(&hexes(from), &hexes(to), ratio)
+    }
 
-        let sat_step_to_anchor = if to_light && blocks_before_anchor > 0 {
-            // Lights: before anchor, increase saturation from 0.0 to anchor_sat
-            anchor_sat / blocks_before_anchor as f32
-        } else {
-            0.0
+    fn load_blend(&mut self, ratio: f32) {
+        let Some((from, to)) = &self.blend_ends else {
+            return;
+        };
+        let percent = (ratio * 100.0).round() as u32;
+        let palette = SavedPalette {
+            name: t!(
+                "blend-name",
+                from = from.name.clone(),
+                to = to.name.clone(),
+                percent = percent
+            ),
+            theory: from.theory,
+            colors: self
+                .blended_hexes(ratio)
+                .into_iter()
+                .map(|hex| SavedColor {
+                    hex,
+                    locked: false,
+                    frozen: None,
+                    role: None,
+                })
+                .collect(),
+            tags: Vec::new(),
+            starred: false,
         };
 
-        // Apply progression to all unlocked blocks
-        for (array_pos, logical_pos, _current_val, _current_sat, is_locked) in
-            logical_positions.iter()
-        {
-            if *is_locked {
-                continue; // Skip locked blocks
-            }
-
-            if let Some(color_block) = self.color_blocks[*array_pos].as_mut() {
-                // Calculate new value (brightness)
-                let new_val = if *logical_pos < anchor_logical_pos {
-                    // Before anchor: progress from start toward anchor
-                    if to_light {
-                        black + (step_to_anchor * *logical_pos as f32)
-                    } else {
-                        white - (step_to_anchor * *logical_pos as f32)
-                    }
-                } else if *logical_pos == anchor_logical_pos {
-                    // At anchor: use anchor value (shouldn't happen for unlocked, but just in case)
-                    anchor_val
-                } else {
-                    // After anchor: progress from anchor toward target
-                    let steps_after = (*logical_pos - anchor_logical_pos) as f32;
-                    if to_light {
-                        anchor_val + (step_from_anchor * steps_after)
-                    } else {
-                        anchor_val - (step_from_anchor * steps_after)
-                    }
-                };
-
-                // Clamp value to valid range [0.0, 1.0]
-                let clamped_val = new_val.clamp(black, white);
-
-                // Calculate new saturation
-                let new_sat = if to_light {
-                    // Lights mode: desaturate as we get lighter
-                    if *logical_pos < anchor_logical_pos {
-                        // Before anchor: increase saturation toward anchor
-                        (sat_step_to_anchor * *logical_pos as f32).min(anchor_sat)
-                    } else if *logical_pos == anchor_logical_pos {
-                        anchor_sat
-                    } else {
-                        // After anchor: decrease saturation toward 0.0 (white)
-                        let steps_after = (*logical_pos - anchor_logical_pos) as f32;
-                        (anchor_sat - (sat_step_from_anchor * steps_after)).max(0.0)
-                    }
-                } else {
-                    // Shadows mode: keep saturation constant (never change)
-                    anchor_sat
-                };
+        if let Err(e) = check_block_count(palette.colors.len()) {
+            self.report_error(e.to_string());
+            return;
+        }
+        self.push_history();
+        let _ = self.apply_saved_palette(&palette);
+        self.status_bar_msg = t!("msg-blend-loaded", percent = percent);
+    }
 
-                color_block.change_color(base_hue, new_sat, clamped_val);
-            }
+    fn open_color_history(&mut self) {
+        self.color_history = library::load_color_history().unwrap_or_default();
+        if self.color_history.is_empty() {
+            self.status_bar_msg = t!("msg-no-color-history");
+            return;
         }
+
+        self.color_history_query.clear();
+        self.filter_color_history();
+        self.current_page = CurrentPage::ColorHistory;
     }
 
-    fn generate_neutrals(&mut self) {
-        // Get base hue and anchor color from locked blocks or generate
-        let locked_blocks = self.get_locked_blocks();
-        let base_hue: f32;
-        let anchor_sat: f32;
-        let anchor_val: f32;
+    fn filter_color_history(&mut self) {
+        self.color_history_filtered =
+            library::search_colors_by_hue(&self.color_history, &self.color_history_query);
+        self.color_history_state.select_first();
+    }
 
-        if !locked_blocks.is_empty() {
-            base_hue = ColorBlock::get_avg_hue(&locked_blocks);
-            // Use the first locked block's saturation and value as anchor
-            if let Some(Some(anchor_block)) = locked_blocks.first() {
-                let (_, sat, val) = anchor_block.get_hsv_values();
-                anchor_sat = sat;
-                anchor_val = val;
-            } else {
-                return; // Should not happen, but safety check
-            }
-        } else {
-            // Generate initial random color for first block if no locks
-            if let Some(color_block) = self.color_blocks[0].as_mut() {
-                color_block.generate_random_color();
-                let (h, s, v) = color_block.get_hsv_values();
-                base_hue = h;
-                anchor_sat = s;
-                anchor_val = v;
-            } else {
-                return; // No blocks available
-            }
+    fn fill_roles(&mut self) {
+        self.push_history();
+        let before = self.color_blocks.clone();
+        let (filled, left_out) = roles::fill_missing(&mut self.color_blocks, &mut rand::rng());
+        if self.config.animate_transitions {
+            self.transition = Some(Transition::new(&before));
         }
+        info!(?filled, ?left_out, result = ?self.palette_hexes(), "filled roles");
+
+        let names = |roles: &[Role]| {
+            roles
+                .iter()
+                .map(|role| role_name(*role))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        self.status_bar_msg = if !left_out.is_empty() {
+            t!("msg-roles-left-out", roles = names(&left_out))
+        } else if filled.is_empty() {
+            t!("msg-roles-complete")
+        } else {
+            t!("msg-roles-filled", roles = names(&filled))
+        };
+    }
 
-        // Collect all existing blocks with their array positions and lock status
-        let mut block_info: Vec<(usize, bool)> = Vec::new();
-        for (i, block) in self.color_blocks.iter().enumerate() {
-            if let Some(block) = block {
-                block_info.push((i, block.locked));
-            }
+    fn open_diagnostics(&mut self) {
+        if self.color_blocks.iter().all(|block| block.role.is_none()) {
+            self.status_bar_msg = t!("msg-no-roles");
+            return;
         }
 
-        if block_info.is_empty() {
+        self.violations = roles::validate(&self.color_blocks);
+        if self.violations.is_empty() {
+            self.status_bar_msg = t!("msg-roles-ok");
             return;
         }
 
-        let total_blocks = block_info.len();
+        self.diagnostics_state.select_first();
+        self.current_page = CurrentPage::Diagnostics;
+    }
 
-        // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
-        let mut logical_positions: Vec<(usize, usize, bool)> = Vec::new();
-        for (logical_pos, (array_pos, is_locked)) in block_info.iter().enumerate() {
-            logical_positions.push((*array_pos, logical_pos, *is_locked));
-        }
+    fn open_pairings(&mut self) {
+        let Some(block) = self.selected_block() else {
+            return;
+        };
+        let hex = block.get_hex();
 
-        // Find locked blocks and use the first one as anchor
-        let locked_info: Vec<(usize, usize)> = logical_positions
-            .iter()
-            .filter_map(|(array_pos, logical_pos, is_locked)| {
-                if *is_locked {
-                    Some((*array_pos, *logical_pos))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        self.pairings = pairing::suggest(&self.color_blocks, self.selected_block_id);
+        if self.pairings.is_empty() {
+            self.status_bar_msg = t!("msg-no-pairings", hex = hex);
+            return;
+        }
 
-        // Determine anchor logical position (first locked block, or first block if none)
-        let anchor_logical_pos = if let Some((_, logical_pos)) = locked_info.first() {
-            *logical_pos
-        } else {
-            // No locked blocks - use first block as anchor
-            logical_positions[0].1
-        };
+        self.pairings_state.select_first();
+        self.current_page = CurrentPage::Pairings;
+    }
 
-        // Calculate desaturation progression
-        // We'll create a smooth transition from anchor saturation to 0 (fully desaturated)
-        // The anchor maintains its saturation, and other blocks desaturate progressively
+    /// Add a new block holding `hex`, locked so the next generation builds around it
+    fn insert_locked_color(&mut self, hex: &str) {
+        if self.ansi_scheme {
+            self.status_bar_msg = t!("msg-scheme-fixed");
+            return;
+        }
+        if self.color_blocks.len() >= MAX_BLOCKS {
+            self.status_bar_msg = t!("msg-palette-full", max = MAX_BLOCKS);
+            return;
+        }
 
-        // Calculate how many blocks are after the anchor (including anchor)
-        let blocks_after_anchor = total_blocks - anchor_logical_pos;
+        let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+        let (h, s, v) = rgb2hsv(r, g, b);
+        let mut block = ColorBlock::new(self.color_blocks.len() + 1, h, s, v);
+        block.locked = true;
 
-        // Calculate how many blocks are before the anchor
-        let blocks_before_anchor = anchor_logical_pos;
+        self.color_blocks.push(block);
+        self.status_bar_msg = t!("msg-color-inserted", hex = hex);
+    }
 
-        // Desaturation step: from anchor_sat to 0.0
-        // Blocks before anchor: increase saturation from 0.0 to anchor_sat
-        // Anchor: keep anchor_sat
-        // Blocks after anchor: decrease saturation from anchor_sat to 0.0
-        let sat_step_to_anchor = if blocks_before_anchor > 0 {
-            anchor_sat / blocks_before_anchor as f32
-        } else {
-            0.0
+    fn save_selected_tags(&mut self) {
+        let Some(selected) = self.open_palette_state.selected() else {
+            return;
         };
-
-        let sat_step_from_anchor = if blocks_after_anchor > 1 {
-            anchor_sat / (blocks_after_anchor - 1) as f32
-        } else {
-            0.0
+        let Some(&idx) = self.library_filtered.get(selected) else {
+            return;
         };
 
-        // Apply neutral progression to all unlocked blocks
-        for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
-            if *is_locked {
-                continue; // Skip locked blocks
-            }
-
-            if let Some(color_block) = self.color_blocks[*array_pos].as_mut() {
-                // Calculate new saturation (desaturation progression)
-                let new_sat = if *logical_pos < anchor_logical_pos {
-                    // Before anchor: increase saturation from 0.0 toward anchor
-                    (sat_step_to_anchor * *logical_pos as f32).min(anchor_sat)
-                } else if *logical_pos == anchor_logical_pos {
-                    // At anchor: use anchor saturation (shouldn't happen for unlocked, but safety)
-                    anchor_sat
-                } else {
-                    // After anchor: decrease saturation from anchor toward 0.0
-                    let steps_after = (*logical_pos - anchor_logical_pos) as f32;
-                    (anchor_sat - (sat_step_from_anchor * steps_after)).max(0.0)
-                };
-
-                // For neutrals, we keep the value relatively stable but add slight variation
-                // for visual depth. This creates a more interesting neutral palette.
-                // Value variation: ±5% from anchor value
-                let value_variation = 0.05;
-                let value_range = (anchor_val - value_variation).max(0.0)
-                    ..=(anchor_val + value_variation).min(1.0);
+        let palette = &mut self.saved_palettes[idx];
+        palette.tags = library::parse_tags(&self.tags_field);
 
-                // Distribute value slightly across blocks for subtle depth
-                let value_progress = if total_blocks > 1 {
-                    (*logical_pos as f32) / ((total_blocks - 1) as f32)
-                } else {
-                    0.0
-                };
-
-                // Create a subtle value curve: slightly darker in middle, lighter at edges
-                // This creates a more natural neutral palette
-                let value_offset = (value_progress - 0.5) * 2.0; // -1.0 to 1.0
-                let value_adjustment = value_offset * value_variation * 0.5; // Reduced variation
-                let new_val =
-                    (anchor_val + value_adjustment).clamp(*value_range.start(), *value_range.end());
-
-                color_block.change_color(base_hue, new_sat, new_val);
-            }
+        match library::save_palette(palette) {
+            Ok(_) => self.status_bar_msg = t!("msg-tagged", name = palette.name.clone()),
+            Err(e) => self.report_error(t!("msg-tags-save-failed", error = e.to_string())),
         }
+        self.library_input = LibraryInput::None;
     }
 
-    fn get_existing_block_indices(&self) -> Vec<usize> {
-        self.color_blocks
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, block)| if block.is_some() { Some(idx) } else { None })
-            .collect()
-    }
-
-    fn get_array_index_for_logical_position(&self, logical_pos: usize) -> Option<usize> {
-        let existing_blocks = self.get_existing_block_indices();
-        existing_blocks.get(logical_pos).copied()
+    /// Show `message` in the status bar and keep it in the log
+    fn report_error(&mut self, message: String) {
+        self.status_bar_msg = logged_error(message);
     }
 
     fn exit(&mut self) {
         self.exit = true;
     }
 
-    fn increment_counter(&mut self) {
-        // Get actual count of existing blocks (not just count)
-        let actual_count = self.color_blocks.iter().filter(|b| b.is_some()).count();
+    fn select_next(&mut self) {
+        let actual_count = self.color_blocks.len();
         if actual_count > 0 {
             self.selected_block_id = self
                 .selected_block_id
@@ -1257,9 +3785,8 @@ impl App {
         }
     }
 
-    fn decrement_counter(&mut self) {
-        // Get actual count of existing blocks (not just count)
-        let actual_count = self.color_blocks.iter().filter(|b| b.is_some()).count();
+    fn select_previous(&mut self) {
+        let actual_count = self.color_blocks.len();
         if actual_count > 0 {
             self.selected_block_id = self
                 .selected_block_id
@@ -1268,83 +3795,444 @@ impl App {
         }
     }
 
-    fn toggle_lock(&mut self, id: usize) {
-        self.color_blocks[id - 1].as_mut().map(|color_block| {
+    /// Jump one grid row up or down, see [`App::select_row`]
+    fn move_selection_row(&mut self, down: bool) {
+        let (row, _) = Grid::new(self.color_blocks.len()).cell(self.selected_block_id);
+        let target = if down {
+            Some(row + 1)
+        } else {
+            row.checked_sub(1)
+        };
+        if let Some(row) = target {
+            self.select_row(row);
+        }
+    }
+
+    /// Select in `row` of the grid, in the column the selection last moved up or down
+    /// from, or the row's last block when it is shorter. Nothing happens past the last row.
+    fn select_row(&mut self, row: usize) {
+        let grid = Grid::new(self.color_blocks.len());
+        let (current_row, current_column) = grid.cell(self.selected_block_id);
+        // The column aimed for holds as long as the selection stays where it led
+        let column = match grid.index(current_row, self.selected_column) {
+            Some(idx) if idx == self.selected_block_id => self.selected_column,
+            _ => current_column,
+        };
+        if let Some(idx) = grid.index(row, column) {
+            self.selected_block_id = idx;
+            self.selected_column = column;
+        }
+    }
+
+    /// Position of the `num`-th (1-based) block in the selected block's row, if there is one
+    fn position_in_row(&self, num: usize) -> Option<usize> {
+        let columns = grid_columns(self.color_blocks.len());
+        let row_start = self.selected_block_id / columns * columns;
+        let position = row_start + num.checked_sub(1)?;
+        (num <= columns && position < self.color_blocks.len()).then_some(position)
+    }
+
+    /// Toggle the lock of the block at `position` (0-based, like `selected_block_id`)
+    fn toggle_lock(&mut self, position: usize) {
+        if let Some(color_block) = self.color_blocks.get_mut(position) {
             color_block.locked = !color_block.locked;
-        });
+            color_block.frozen = None;
+            if color_block.locked {
+                remember_color(&color_block.get_hex());
+            }
+        }
+    }
+
+    /// Lock only the hue of the selected block, then only its lightness, then nothing
+    fn cycle_freeze(&mut self) {
+        if let Some(block) = self.selected_block_mut() {
+            block.frozen = match block.frozen {
+                None => Some(Freeze::Hue),
+                Some(Freeze::Hue) => Some(Freeze::Lightness),
+                Some(Freeze::Lightness) => None,
+            };
+            // A partial lock replaces a full one
+            block.locked = false;
+        }
+    }
+
+    /// Give the selected block one share more (or less) of its row
+    fn resize_block(&mut self, wider: bool) {
+        let columns = grid_columns(self.color_blocks.len());
+        let selected = self.selected_block_id;
+        let Some(block) = self.color_blocks.get_mut(selected) else {
+            return;
+        };
+        block.width = if wider {
+            (block.width + 1).min(MAX_BLOCK_WIDTH)
+        } else {
+            block.width.saturating_sub(1).max(1)
+        };
+
+        let row_start = selected / columns * columns;
+        let row = &self.color_blocks[row_start..(row_start + columns).min(self.color_blocks.len())];
+        let total: u16 = row.iter().map(|block| block.width).sum();
+        let percent = u32::from(self.color_blocks[selected].width) * 100 / u32::from(total);
+        self.status_bar_msg = t!("msg-block-width", block = selected + 1, percent = percent);
     }
 
     fn add_block(&mut self) {
-        if let Some(idx) = self.color_blocks.iter().position(|x| x.is_none()) {
-            self.color_blocks[idx] = Some(ColorBlock::new(idx, 0 as f32, 0 as f32, 0 as f32));
-            self.color_block_count += 1;
+        if self.color_blocks.len() >= MAX_BLOCKS {
+            return;
         }
+
+        let block_id = self.color_blocks.len() + 1;
+        self.color_blocks
+            .push(ColorBlock::new(block_id, 0 as f32, 0 as f32, 0 as f32));
     }
 
     fn del_block(&mut self) {
-        if let Some(array_idx) = self.get_array_index_for_logical_position(self.selected_block_id) {
-            // Delete the block
-            self.color_blocks[array_idx] = None;
-            self.color_block_count -= 1;
-
-            // Adjust selected_block_id to stay within bounds
-            let new_count = self.color_blocks.iter().filter(|b| b.is_some()).count();
-            if new_count > 0 {
-                self.selected_block_id = self.selected_block_id.min(new_count - 1);
-            } else {
-                self.selected_block_id = 0;
-            }
+        if self.color_blocks.len() <= MIN_BLOCKS
+            || self.selected_block_id >= self.color_blocks.len()
+        {
+            return;
         }
+
+        self.color_blocks.remove(self.selected_block_id);
+        ColorBlock::renumber(&mut self.color_blocks);
+
+        // Adjust selected_block_id to stay within bounds
+        self.selected_block_id = self.selected_block_id.min(self.color_blocks.len() - 1);
     }
 }
 
-impl Default for App {
-    fn default() -> Self {
-        let color_block_count: usize = 5;
-        let mut color_blocks: [Option<ColorBlock>; 9] = [None; 9];
+/// Add `hex` to the color history; losing an entry isn't worth interrupting the user for
+fn remember_color(hex: &str) {
+    if let Err(e) = library::remember_color(hex) {
+        warn!(error = %e, "could not update the color history");
+    }
+}
 
-        for i in 1..color_block_count + 1 {
-            color_blocks[i - 1] = Some(ColorBlock::new(i, 0.0, 0.0, 0.0));
+/// Log `message` as a warning and hand it back for the status bar
+fn logged_error(message: String) -> String {
+    warn!("{message}");
+    message
+}
+
+/// Translated name of `illuminant`
+fn illuminant_name(illuminant: Illuminant) -> String {
+    i18n::translate(&format!("illuminant-{}", illuminant.key()), None)
+}
+
+/// Translated name of the gradient color `space`
+fn space_name(space: Space) -> String {
+    i18n::translate(&format!("gradient-space-{}", space.key()), None)
+}
+
+/// One line of the diagnostics panel
+fn violation_message(violation: &Violation) -> String {
+    match violation {
+        Violation::LowContrast { on, ratio } => t!(
+            "diagnostic-low-contrast",
+            role = role_name(*on),
+            ratio = format!("{ratio:.1}"),
+            min = roles::MIN_TEXT_CONTRAST
+        ),
+        Violation::UnexpectedHue { role, hue } => t!(
+            "diagnostic-unexpected-hue",
+            role = role_name(*role),
+            hue = hue.round() as i64
+        ),
+        Violation::SurfaceInverted {
+            dark_background: true,
+        } => t!("diagnostic-surface-darker"),
+        Violation::SurfaceInverted {
+            dark_background: false,
+        } => t!("diagnostic-surface-lighter"),
+    }
+}
+
+impl App {
+    /// The app for the user's `config`, with the best clipboard for this environment
+    pub fn new(config: Config) -> Self {
+        let mut app = Self {
+            current_color_theory: config.theory,
+            monochrome_scale: config.monochrome_scale,
+            jitter: config.jitter,
+            strict: config.strict,
+            theme_chrome: config.theme_chrome,
+            config,
+            ..Self::with_clipboard(clipboard::detect())
+        };
+        if let Some(path) = app.config.swatch_book.clone() {
+            let path = config::config_dir().join(path);
+            match swatches::load(&path) {
+                Ok(book) => app.swatch_book = Some(book),
+                Err(e) => {
+                    app.status_bar_msg = logged_error(t!(
+                        "msg-swatch-book-failed",
+                        path = path.display().to_string(),
+                        error = e.to_string()
+                    ))
+                }
+            }
+        }
+        app.resize_palette(app.config.blocks);
+        if app.config.generate_on_startup {
+            app.generate();
         }
+        app
+    }
 
-        Self {
-            counter: 0,
+    /// A fresh session copying through `clipboard`
+    pub fn with_clipboard(clipboard: Box<dyn ClipboardProvider>) -> Self {
+        let color_block_count: usize = 5;
+        let color_blocks: Vec<ColorBlock> = (1..color_block_count + 1)
+            .map(|i| ColorBlock::new(i, 0.0, 0.0, 0.0))
+            .collect();
+
+        let (plugins, failed_plugins) = plugins::load_plugins();
+        let status_bar_msg = match failed_plugins.first() {
+            Some((path, e)) => logged_error(t!(
+                "msg-plugin-load-failed",
+                path = path.display().to_string(),
+                error = e.to_string()
+            )),
+            None => String::new(),
+        };
 
-            clipboard: Clipboard::new().unwrap(),
+        let undo_base = Snapshot {
+            color_blocks: color_blocks.clone(),
+            selected_block_id: 0,
+            theory: ColorTheories::Analogous,
+            ansi_scheme: false,
+        };
+
+        Self {
+            clipboard,
 
             theory_selector_state: ListState::default(),
             current_page: CurrentPage::Main,
             current_color_theory: ColorTheories::Analogous,
 
             title: " Color Palette!!!!! ",
-            color_block_count: color_block_count,
             selected_block_id: 0,
+            selected_column: 0,
 
-            color_blocks: color_blocks,
+            color_blocks,
 
-            status_bar_msg: "",
+            status_bar_msg,
 
             edit_color_field: String::new(),
+            edit_label_field: String::new(),
+            edit_note_field: String::new(),
+            command_field: String::new(),
+            setup_step: SetupStep::Theory,
+            setup_state: ListState::default(),
+            settings_state: ListState::default(),
+
+            project_path: None,
+            project_metadata: ProjectMetadata::new(),
+            history: Vec::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            undo_base,
+
+            palette_name: String::new(),
+            save_name_field: String::new(),
+            saved_palettes: Vec::new(),
+            open_palette_state: ListState::default(),
+            library_filtered: Vec::new(),
+            library_input: LibraryInput::None,
+            library_query: String::new(),
+            tags_field: String::new(),
+
+            favorites: library::load_favorites().unwrap_or_default(),
+            favorite_entries: Vec::new(),
+            favorites_state: ListState::default(),
+            mix_weights: Vec::new(),
+            mix_state: ListState::default(),
+            monochrome_scale: LightnessScale::Linear,
+            jitter: Jitter::Medium,
+            strict: false,
+            theme_chrome: false,
+            blend_top: 0,
+            blend_mode_state: ListState::default(),
+            blend_from: None,
+            blend_ends: None,
+            blend_steps: 3,
+            blend_state: ListState::default(),
+            color_history: Vec::new(),
+            color_history_query: String::new(),
+            color_history_filtered: Vec::new(),
+            color_history_state: ListState::default(),
+            collection: 0,
+            collection_state: ListState::default(),
+
+            recent_entries: Vec::new(),
+            recent_state: ListState::default(),
+            role_picker_state: ListState::default(),
+            violations: Vec::new(),
+            diagnostics_state: ListState::default(),
+            pairings: Vec::new(),
+            pairings_state: ListState::default(),
+            gradients: Vec::new(),
+            gradients_state: ListState::default(),
+            gradient_name_field: String::new(),
+
+            last_action: None,
+            recording: None,
+            macro_actions: Vec::new(),
+            tasks: TaskRunner::new(),
+            ipc: None,
+
+            plugins,
+            active_plugin: None,
+            seed: None,
+            hint_page: 0,
+
+            config: Config::default(),
+            swatch_book: None,
+            transition: None,
+            ansi_scheme: false,
+            previous_palette: None,
+            compare: false,
+            illuminant: Illuminant::D65,
+            night_light: None,
+            explore: false,
+            explore_step: EXPLORE_STEP_DEFAULT,
+            slideshow: None,
+            schedule: (None, None),
+            appearance: None,
+            editor_requested: false,
+            clipboard_watch: None,
+            clipboard_seen: String::new(),
+            clipboard_offer: None,
+            terminal_recolored: false,
+            graphics: None,
+            rasters: Vec::new(),
+            rasters_shown: Vec::new(),
 
             exit: false,
         }
     }
 }
 
-impl Widget for &App {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        // SELECTED BLOCK
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Fill(1), Constraint::Length(3)])
-            .split(area);
+impl App {
+    /// The blocks as drawn: mid-transition, under the illuminant and through the night
+    /// light
+    fn shown_blocks(&self) -> Cow<'_, [ColorBlock]> {
+        let blocks = match &self.transition {
+            Some(transition) => Cow::Owned(transition.blend(&self.color_blocks)),
+            None => Cow::Borrowed(self.color_blocks.as_slice()),
+        };
+        match (self.illuminant, self.night_light) {
+            (Illuminant::D65, None) => blocks,
+            (illuminant, night_light) => blocks
+                .iter()
+                .map(|block| {
+                    let block = illuminant::adapt(block, illuminant);
+                    match night_light {
+                        Some(kelvin) => night_light::filter(&block, kelvin),
+                        None => block,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether the blocks' colors are left to images: on the main view without popups,
+    /// which images would cover
+    fn raster(&self) -> bool {
+        self.graphics.is_some() && self.current_page == CurrentPage::Main
+    }
+
+    /// The strip of the palette before generating, when comparing, and the blocks in the
+    /// main view's `area`
+    fn main_view_areas(&self, area: Rect) -> (Option<Rect>, Rect) {
+        match self.previous_palette.as_deref() {
+            Some(_) if self.compare => {
+                let layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(vec![Constraint::Percentage(25), Constraint::Fill(1)])
+                    .split(area);
+                (Some(layout[0]), layout[1])
+            }
+            _ => (None, area),
+        }
+    }
+}
+
+/// Fails for palettes and projects of fewer than [`MIN_BLOCKS`] colors, which the app
+/// can't open; longer ones are cut at [`MAX_BLOCKS`]
+fn check_block_count(count: usize) -> io::Result<()> {
+    match count {
+        count if count < MIN_BLOCKS => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("a palette takes at least {MIN_BLOCKS} colors, got {count}"),
+        )),
+        _ => Ok(()),
+    }
+}
 
-        let (main_area, footer_area) = (layout[0], layout[1]);
+/// The screen above the status bar and the status bar
+fn split_footer(area: Rect) -> (Rect, Rect) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Fill(1), Constraint::Length(3)])
+        .split(area);
+    (layout[0], layout[1])
+}
 
-        let mut main_content = MainContent::new(self.color_blocks, self.selected_block_id);
-        main_content.render(main_area, buf);
+impl Widget for &App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (main_area, footer_area) = split_footer(area);
+        let color_blocks = &self.shown_blocks();
+        if self.current_page == CurrentPage::CodePreview {
+            CodePreview::new(color_blocks).render(main_area, buf);
+        } else if self.current_page == CurrentPage::ArtPreview {
+            ArtPreview::new(color_blocks).render(main_area, buf);
+        } else if self.current_page == CurrentPage::ProportionPreview {
+            ProportionPreview::new(color_blocks).render(main_area, buf);
+        } else if self.current_page == CurrentPage::ChartPreview {
+            // Plugin theories and explore walks don't promise any order
+            let ramp = self.active_plugin.is_none()
+                && !self.explore
+                && self.current_color_theory.is_ramp();
+            ChartPreview::new(color_blocks)
+                .heatmap(ramp)
+                .render(main_area, buf);
+        } else {
+            let (strip_area, main_area) = self.main_view_areas(main_area);
+            if let (Some(area), Some(previous)) = (strip_area, self.previous_palette.as_deref()) {
+                PaletteStrip::new(previous, t!("title-before")).render(area, buf);
+            }
+            MainContent::new(color_blocks, self.selected_block_id)
+                .swatch_book(self.swatch_book.as_ref())
+                .starred(&self.favorites.colors)
+                .raster(self.raster())
+                .render(main_area, buf);
+        }
 
-        let status_bar = StatusBar::new(self.current_page);
+        let swatch = self
+            .swatch_book
+            .as_ref()
+            .zip(self.selected_block())
+            .and_then(|(book, block)| book.nearest(block))
+            .map(|(swatch, _)| swatch.name.as_str());
+        let status_bar = StatusBar::new(self.current_page, &self.status_bar_msg)
+            .info(self.theory_name(), self.seed)
+            .details(
+                self.selected_block(),
+                self.selected_block_id,
+                self.color_blocks.len(),
+                swatch,
+            )
+            .hints(self.library_input, self.hint_page)
+            .recording(self.recording.is_some())
+            .lighting(
+                (self.illuminant != Illuminant::D65).then(|| illuminant_name(self.illuminant)),
+            )
+            .night_light(self.night_light)
+            .accent(&self.color_blocks);
         status_bar.render(footer_area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,2248 @@
+//! Drives `App` with synthetic key events and snapshots what it renders.
+//!
+//! Every test shares one throwaway data directory seeded with a small library, so the
+//! library, favorites and recent popups have something to show and nothing touches the
+//! user's real data. Tests only read from it, which keeps them independent of each other;
+//! the exceptions are the color history, which locking and editing colors add to, the
+//! config file the setup and settings page write into the same directory, which tests
+//! take turns on through [`CONFIG_FILE`], and saving a palette, which adds to the library
+//! and the recent list while the tests showing them wait on [`LIBRARY`].
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::{Arc, Mutex, Once},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use insta::assert_snapshot;
+use palette::{FromColor, Oklch, Srgb};
+use ratatui::{Terminal, backend::TestBackend};
+use terminal_palette::{lint, palette_file};
+
+use super::*;
+use crate::clipboard::NoClipboard;
+
+static SEED_LIBRARY: Once = Once::new();
+
+/// Held by tests that write the config file
+static CONFIG_FILE: Mutex<()> = Mutex::new(());
+
+/// Held by tests that save into the library, and by those listing its palettes or the
+/// recent ones
+static LIBRARY: Mutex<()> = Mutex::new(());
+
+fn seed_library() {
+    SEED_LIBRARY.call_once(|| {
+        let data_dir = env::temp_dir().join(format!("terminal-palette-tests-{}", process::id()));
+        let _ = fs::remove_dir_all(&data_dir);
+
+        // SAFETY: runs once, before any test reads the environment
+        unsafe {
+            env::set_var("XDG_DATA_HOME", &data_dir);
+            env::set_var("XDG_CONFIG_HOME", &data_dir);
+            env::set_var("XDG_STATE_HOME", &data_dir);
+        }
+
+        let colors = ["#E07A5F", "#3D405B", "#81B29A"]
+            .iter()
+            .map(|hex| SavedColor {
+                hex: hex.to_string(),
+                locked: false,
+                frozen: None,
+                role: None,
+            })
+            .collect();
+        let sunset = SavedPalette {
+            name: String::from("sunset"),
+            theory: ColorTheories::Triad,
+            colors,
+            tags: vec![String::from("warm")],
+            starred: true,
+        };
+        let path = library::save_palette(&sunset).unwrap();
+        library::save_palette(&SavedPalette {
+            name: String::from("mint"),
+            theory: ColorTheories::Analogous,
+            colors: ["#2A9D8F", "#E9F5DB", "#264653", "#F4A261"]
+                .iter()
+                .map(|hex| SavedColor {
+                    hex: hex.to_string(),
+                    locked: false,
+                    frozen: None,
+                    role: None,
+                })
+                .collect(),
+            tags: Vec::new(),
+            starred: false,
+        })
+        .unwrap();
+
+        library::save_favorites(&Favorites {
+            colors: vec![String::from("#F2CC8F")],
+        })
+        .unwrap();
+
+        // Most recent last
+        for (name, path) in [("ocean", data_dir.join("ocean.tpal")), ("sunset", path)] {
+            library::remember_recent(RecentEntry {
+                name: name.to_string(),
+                path,
+                colors: vec![String::from("#E07A5F")],
+            })
+            .unwrap();
+        }
+    });
+}
+
+fn app() -> App {
+    seed_library();
+    App::with_clipboard(Box::new(NoClipboard))
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+fn press_with(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    app.handle_key_event(KeyEvent::new(code, modifiers));
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
+
+fn render(app: &mut App) -> TestBackend {
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+    terminal.backend().clone()
+}
+
+#[test]
+fn main_view() {
+    let mut app = app();
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn main_view_with_lock_label_and_color() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('z'));
+    type_text(&mut app, "e07a5f");
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('n'));
+    type_text(&mut app, "accent");
+    press(&mut app, KeyCode::Enter);
+    press_with(&mut app, KeyCode::Char('3'), KeyModifiers::ALT);
+
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert!(app.color_blocks[2].locked);
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn footer_details_follow_the_selection() {
+    let mut app = app();
+    app.color_blocks[1].label = String::from("accent");
+    app.color_blocks[1].role = Some(Role::Primary);
+    app.color_blocks[1].locked = true;
+    let details = |app: &mut App| {
+        let backend = render(app);
+        let buffer = backend.buffer();
+        let row = buffer.area.height - 3;
+        (0..buffer.area.width)
+            .map(|x| buffer[(x, row)].symbol())
+            .collect::<String>()
+    };
+
+    assert!(details(&mut app).starts_with(" Block 1/5  #"));
+    press(&mut app, KeyCode::Right);
+    let hex = app.color_blocks[1].get_hex();
+    assert_eq!(
+        details(&mut app).trim_end(),
+        format!(" Block 2/5  {hex}  accent  Primary  LOCKED")
+    );
+}
+
+#[test]
+fn main_view_wraps_large_palettes() {
+    let mut app = app();
+    for _ in 0..11 {
+        press(&mut app, KeyCode::Char('a'));
+    }
+    press(&mut app, KeyCode::Down);
+
+    assert_eq!(app.color_blocks.len(), 16);
+    assert_eq!(app.selected_block_id, 8);
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn selection_moves_by_row_and_column() {
+    let mut app = app();
+    for _ in 0..8 {
+        press(&mut app, KeyCode::Char('a'));
+    }
+    assert_eq!(app.color_blocks.len(), 13);
+    let selected = |app: &mut App, code| {
+        press(app, code);
+        app.selected_block_id
+    };
+    app.selected_block_id = 6;
+
+    // The short second row ends below column 4, the column stays in mind going back up
+    assert_eq!(selected(&mut app, KeyCode::Down), 12);
+    assert_eq!(selected(&mut app, KeyCode::Up), 6);
+    assert_eq!(selected(&mut app, KeyCode::PageDown), 12);
+    assert_eq!(selected(&mut app, KeyCode::Down), 12);
+    assert_eq!(selected(&mut app, KeyCode::Left), 11);
+    assert_eq!(selected(&mut app, KeyCode::PageUp), 3);
+    assert_eq!(selected(&mut app, KeyCode::End), 7);
+    assert_eq!(selected(&mut app, KeyCode::Home), 0);
+    assert_eq!(selected(&mut app, KeyCode::Up), 0);
+    assert_eq!(selected(&mut app, KeyCode::PageDown), 8);
+    assert_eq!(selected(&mut app, KeyCode::End), 12);
+}
+
+#[test]
+fn status_message_replaces_hints() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('c'));
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn theory_selector() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('x'));
+    press(&mut app, KeyCode::Down);
+
+    assert_eq!(app.current_page, CurrentPage::TheorySelector);
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_color_theory, ColorTheories::Complementary);
+    // Picking a theory regenerates right away
+    assert!(app.seed.is_some());
+}
+
+#[test]
+fn edit_color_popup() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('z'));
+    type_text(&mut app, "e07a");
+
+    assert_eq!(app.edit_color_field, "e07a");
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn pasting_sets_selected_color() {
+    let mut app = app();
+    app.handle_paste("rgb(224, 122, 95)");
+    assert_eq!(app.color_blocks[0].get_hex(), "#E07A5F");
+
+    app.handle_paste("rgb(300, 0, 0)");
+    assert_eq!(app.color_blocks[0].get_hex(), "#E07A5F");
+    assert_eq!(
+        app.status_bar_msg,
+        "Invalid color: red 300 is outside 0-255"
+    );
+
+    press(&mut app, KeyCode::Char('z'));
+    app.handle_paste("Light Sea\nGreen");
+    assert_eq!(app.edit_color_field, "Light SeaGreen");
+}
+
+#[test]
+fn edit_label_popup() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('n'));
+    type_text(&mut app, "background");
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn save_palette_popup() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('s'));
+    type_text(&mut app, "dusk");
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn open_palette_popup() {
+    let _library = LIBRARY.lock().unwrap();
+    let mut app = app();
+    press(&mut app, KeyCode::Char('o'));
+
+    assert_eq!(app.current_page, CurrentPage::OpenPalette);
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn open_palette_search() {
+    let _library = LIBRARY.lock().unwrap();
+    let mut app = app();
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('/'));
+    type_text(&mut app, "#e07b60");
+
+    assert_eq!(app.library_filtered.len(), 1);
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn favorites_popup() {
+    let _library = LIBRARY.lock().unwrap();
+    let mut app = app();
+    press(&mut app, KeyCode::Char('F'));
+
+    assert_eq!(app.current_page, CurrentPage::Favorites);
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn recent_switcher_popup() {
+    let _library = LIBRARY.lock().unwrap();
+    let mut app = app();
+    press_with(&mut app, KeyCode::Char('r'), KeyModifiers::CONTROL);
+
+    assert_eq!(app.current_page, CurrentPage::RecentSwitcher);
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn shown_seed_reproduces_the_palette() {
+    let mut app = app();
+    let before = app.color_blocks.clone();
+    press(&mut app, KeyCode::Char(' '));
+
+    let seed = app.seed.expect("built-in theories record their seed");
+    let mut replayed = before;
+    generators::generate_seeded(app.current_color_theory, &mut replayed, seed);
+    let replayed: Vec<String> = replayed.iter().map(ColorBlock::get_hex).collect();
+    assert_eq!(replayed, app.palette_hexes());
+
+    let status_bar = format!("{:?}", render(&mut app));
+    assert!(status_bar.contains(&format!("seed {seed:x}")));
+}
+
+#[test]
+fn hints_page_through_the_keymap() {
+    let mut app = app();
+    let first = render(&mut app);
+    press(&mut app, KeyCode::Char('?'));
+    let second = render(&mut app);
+
+    assert_ne!(first, second);
+    assert_snapshot!(second);
+
+    // A new page starts back at its first hints
+    press(&mut app, KeyCode::Char('x'));
+    assert_eq!(app.hint_page, 0);
+}
+
+#[test]
+fn slideshow_generates_until_a_key_pauses_it() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('p'));
+    assert!(app.slideshow.is_some());
+
+    // Pretend the interval elapsed
+    app.slideshow = Some(std::time::Instant::now());
+    app.tick_slideshow();
+    assert!(app.seed.is_some());
+    assert!(app.slideshow.is_some());
+
+    // The key pausing the slideshow does nothing else
+    press(&mut app, KeyCode::Char('d'));
+    assert!(app.slideshow.is_none());
+    assert_eq!(app.color_blocks.len(), 5);
+    assert_eq!(app.status_bar_msg, "Slideshow paused, [p] resumes");
+}
+
+#[test]
+fn explore_walks_from_the_current_palette() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char(' '));
+    let before: Vec<(f32, f32, f32)> = app.color_blocks.iter().map(hsv).collect();
+
+    press(&mut app, KeyCode::Char('e'));
+    press(&mut app, KeyCode::Char('-'));
+    press(&mut app, KeyCode::Char(' '));
+    assert!(app.explore);
+    assert_eq!(
+        app.explore_step,
+        EXPLORE_STEP_DEFAULT - EXPLORE_STEP_INCREMENT
+    );
+
+    let step = f32::from(app.explore_step);
+    for (old, new) in before.iter().zip(app.color_blocks.iter().map(hsv)) {
+        let hue_change = (new.0 - old.0).abs();
+        assert!(hue_change.min(360.0 - hue_change) <= step + 0.01);
+        assert!((new.1 - old.1).abs() <= step / 100.0 + 0.001);
+        assert!((new.2 - old.2).abs() <= step / 100.0 + 0.001);
+    }
+}
+
+fn hsv(block: &ColorBlock) -> (f32, f32, f32) {
+    let hsv = block.hsv;
+    (hsv.hue.into_positive_degrees(), hsv.saturation, hsv.value)
+}
+
+#[test]
+fn chart_preview() {
+    let mut app = app();
+    for _ in 0..2 {
+        press(&mut app, KeyCode::Char('d'));
+    }
+    press(&mut app, KeyCode::Char('v'));
+
+    assert_eq!(app.current_page, CurrentPage::ChartPreview);
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.current_page, CurrentPage::Main);
+}
+
+#[test]
+fn chart_preview_shades_ramps_as_heatmap() {
+    let mut app = app();
+    app.current_color_theory = ColorTheories::Shadows;
+    generators::generate_seeded(ColorTheories::Shadows, &mut app.color_blocks, 7);
+    press(&mut app, KeyCode::Char('v'));
+
+    let backend = render(&mut app);
+    assert_snapshot!(backend);
+
+    // The field rises to the right: first block at the left edge, last one at the right
+    let rgb = |block: &ColorBlock| {
+        let (r, g, b) = block.get_rgb_values();
+        Color::Rgb(r, g, b)
+    };
+    let buffer = backend.buffer();
+    assert_eq!(buffer[(1, 25)].fg, rgb(&app.color_blocks[0]));
+    assert_eq!(buffer[(118, 25)].bg, rgb(app.color_blocks.last().unwrap()));
+}
+
+#[test]
+fn role_picker_moves_a_role_between_blocks() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('r'));
+    for _ in 0..4 {
+        press(&mut app, KeyCode::Down);
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.color_blocks[0].role, Some(Role::Primary));
+
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Char('r'));
+    for _ in 0..4 {
+        press(&mut app, KeyCode::Down);
+    }
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.color_blocks[0].role, None);
+    assert_eq!(app.color_blocks[1].role, Some(Role::Primary));
+    assert_eq!(app.to_project().blocks[1].role, Some(Role::Primary));
+}
+
+#[test]
+fn diagnostics_list_broken_role_rules() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('i'));
+    assert_eq!(app.current_page, CurrentPage::Main);
+
+    // Dark background, a surface darker still, gray text and a green "error"
+    for (idx, hex, role) in [
+        (0, "1e1e2e", Role::Background),
+        (1, "11111b", Role::Surface),
+        (2, "45475a", Role::Text),
+        (3, "a6e3a1", Role::Error),
+    ] {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(format!("#{}", hex.to_uppercase())));
+        roles::assign(&mut app.color_blocks, idx, Some(role));
+    }
+    app.selected_block_id = 0;
+
+    press(&mut app, KeyCode::Char('i'));
+    assert_eq!(app.current_page, CurrentPage::Diagnostics);
+    assert_eq!(app.violations.len(), 4);
+    assert_snapshot!(render(&mut app));
+
+    // The last one is about the error color
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(app.selected_block_id, 3);
+}
+
+#[test]
+fn missing_roles_are_generated_to_pass_the_checks() {
+    let mut app = app();
+    for (idx, hex, role) in [
+        (0, "#FAF4ED", Role::Background),
+        (1, "#907AA9", Role::Primary),
+    ] {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(hex.to_string()));
+        roles::assign(&mut app.color_blocks, idx, Some(role));
+    }
+
+    // 5 blocks hold 5 of the 8 roles
+    press(&mut app, KeyCode::Char('R'));
+    assert!(app.status_bar_msg.contains("Warning, Success"));
+    for _ in 0..3 {
+        press(&mut app, KeyCode::Char('a'));
+    }
+    press(&mut app, KeyCode::Char('R'));
+
+    assert!(app.color_blocks.iter().all(|block| block.role.is_some()));
+    assert_eq!(app.color_blocks[0].get_hex(), "#FAF4ED");
+    assert_eq!(app.color_blocks[1].get_hex(), "#907AA9");
+    assert_eq!(roles::validate(&app.color_blocks), Vec::new());
+    // Dark text for the light background
+    let text = roles::find(&app.color_blocks, Role::Text).unwrap();
+    assert!(text.get_relative_luminance() < 0.2);
+}
+
+#[test]
+fn ansi_scheme_designer() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('A'));
+
+    assert!(app.ansi_scheme);
+    assert!(ansi::is_scheme(&app.color_blocks));
+    for (normal, slot_hue) in [
+        (1, 0.0),
+        (2, 120.0),
+        (3, 60.0),
+        (4, 240.0),
+        (5, 300.0),
+        (6, 180.0),
+    ] {
+        let (hue, _, _) = hsv(&app.color_blocks[normal]);
+        let offset = (hue - slot_hue + 180.0_f32).rem_euclid(360.0) - 180.0;
+        assert!(offset.abs() <= 20.5, "{} at {hue}°", ansi::SLOTS[normal]);
+        let bright = &app.color_blocks[normal + ansi::BRIGHT_OFFSET];
+        assert!(
+            bright.get_relative_luminance() > app.color_blocks[normal].get_relative_luminance()
+        );
+    }
+
+    // Slots are fixed, locked ones survive generation
+    press(&mut app, KeyCode::Char('d'));
+    assert_eq!(app.color_blocks.len(), ansi::SLOT_COUNT);
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Char('l'));
+    let red = app.color_blocks[1].get_hex();
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.color_blocks[1].get_hex(), red);
+    assert!(ansi::is_scheme(&app.color_blocks));
+
+    press(&mut app, KeyCode::Char('A'));
+    press(&mut app, KeyCode::Char('d'));
+    assert_eq!(app.color_blocks.len(), ansi::SLOT_COUNT - 1);
+}
+
+#[test]
+fn variants_follow_the_normal_colors() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('B'));
+    assert!(app.status_bar_msg.contains("[A]"));
+
+    app.config.ansi_dim_variants = true;
+    press(&mut app, KeyCode::Char('A'));
+    assert_eq!(
+        app.color_blocks.len(),
+        ansi::SLOT_COUNT + ansi::DIM_SLOTS.len()
+    );
+
+    // Hand-tuned red; its bright and dim slots catch up
+    app.selected_block_id = 1;
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#CC3355")));
+    press(&mut app, KeyCode::Char('B'));
+
+    let lightness = |idx: usize| {
+        let (r, g, b) = app.color_blocks[idx].get_rgb_values();
+        Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>()).l
+    };
+    let red = lightness(1);
+    let bright_red = lightness(1 + ansi::BRIGHT_OFFSET);
+    let dim_red = lightness(1 + ansi::DIM_OFFSET);
+    assert!(
+        (bright_red - red - 0.08).abs() < 0.01,
+        "{red} -> {bright_red}"
+    );
+    assert!((red - dim_red - 0.12).abs() < 0.01, "{red} -> {dim_red}");
+    assert!(lightness(ansi::BRIGHT_OFFSET) >= 0.44);
+}
+
+#[test]
+fn code_preview_colors_tokens_by_role() {
+    let mut app = app();
+    for (idx, hex, role) in [
+        (0, "#1E1E2E", Role::Background),
+        (1, "#CDD6F4", Role::Text),
+        (2, "#CBA6F7", Role::Primary),
+        (3, "#A6E3A1", Role::Success),
+    ] {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(hex.to_string()));
+        roles::assign(&mut app.color_blocks, idx, Some(role));
+    }
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(app.current_page, CurrentPage::CodePreview);
+
+    let backend = render(&mut app);
+    assert_snapshot!(backend);
+
+    // `fn` on the second line is a keyword, the string further down uses success
+    let buffer = backend.buffer();
+    assert_eq!(buffer[(6, 2)].symbol(), "f");
+    assert_eq!(buffer[(6, 2)].fg, Color::Rgb(0xCB, 0xA6, 0xF7));
+    assert_eq!(buffer[(6, 2)].bg, Color::Rgb(0x1E, 0x1E, 0x2E));
+    assert_eq!(buffer[(17, 9)].symbol(), "\"");
+    assert_eq!(buffer[(17, 9)].fg, Color::Rgb(0xA6, 0xE3, 0xA1));
+
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(app.current_page, CurrentPage::Main);
+}
+
+#[test]
+fn pairings_reach_contrast_and_insert_a_variant() {
+    let mut app = app();
+    for (idx, hex) in [(0, "1e1e2e"), (1, "cdd6f4"), (2, "45475a")] {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(format!("#{}", hex.to_uppercase())));
+    }
+    app.selected_block_id = 0;
+    let blocks = app.color_blocks.len();
+
+    press(&mut app, KeyCode::Char('g'));
+    assert_eq!(app.current_page, CurrentPage::Pairings);
+    assert!(app.pairings.iter().all(|s| s.ratio >= pairing::AA));
+    assert!(app.pairings.is_sorted_by(|a, b| a.harmony <= b.harmony));
+    // The gray is too dark on the background as it is, only lightened versions are offered
+    assert!(app.pairings.iter().any(|s| s.source == 1 && !s.adjusted));
+    assert!(
+        app.pairings
+            .iter()
+            .filter(|s| s.source == 2)
+            .all(|s| s.adjusted)
+    );
+    assert_snapshot!(render(&mut app));
+
+    let picked = app.pairings[0].color.get_hex();
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(app.color_blocks.len(), blocks + 1);
+    let inserted = app.color_blocks.last().unwrap();
+    assert!(inserted.locked);
+    assert_eq!(inserted.get_hex(), picked);
+}
+
+#[test]
+#[cfg(feature = "collections")]
+fn collections_insert_named_colors() {
+    let mut app = app();
+    let blocks = app.color_blocks.len();
+
+    press(&mut app, KeyCode::Char('N'));
+    press(&mut app, KeyCode::Left);
+    assert_eq!(app.collection, COLLECTIONS.len() - 1);
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Down);
+    assert_eq!(COLLECTIONS[app.collection].name, "xkcd");
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(app.color_blocks.len(), blocks + 1);
+    let inserted = app.color_blocks.last().unwrap();
+    assert!(inserted.locked);
+    assert_eq!(inserted.get_hex(), "#15B01A");
+}
+
+#[test]
+fn blocks_show_their_nearest_swatch() {
+    let mut app = app();
+    let book = Path::new(env!("CARGO_MANIFEST_DIR")).join("swatches/tailwind.toml");
+    app.swatch_book = Some(swatches::load(&book).unwrap());
+
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#3D84F5")));
+    let book = app.swatch_book.as_ref().unwrap();
+    let (swatch, delta_e) = book.nearest(&app.color_blocks[0]).unwrap();
+    assert_eq!(swatch.name, "blue-500");
+    assert!(delta_e < 2.0);
+    assert_snapshot!(render(&mut app));
+}
+
+#[test]
+fn color_history_keeps_edited_colors() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('z'));
+    type_text(&mut app, "7b2cbf");
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Esc);
+    let blocks = app.color_blocks.len();
+
+    // Other tests add to the history too, a narrow hue range leaves just this color
+    press(&mut app, KeyCode::Char('H'));
+    assert_eq!(app.current_page, CurrentPage::ColorHistory);
+    assert!(app.color_history.contains(&String::from("#7B2CBF")));
+    type_text(&mut app, "265-275");
+    assert_eq!(app.color_history_filtered.len(), 1);
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.color_blocks.len(), blocks + 1);
+    assert_eq!(app.color_blocks.last().unwrap().get_hex(), "#7B2CBF");
+}
+
+#[test]
+fn blend_two_saved_palettes() {
+    let _library = LIBRARY.lock().unwrap();
+    let mut app = app();
+    press(&mut app, KeyCode::Char('o'));
+    press(&mut app, KeyCode::Char('b'));
+    assert_eq!(app.current_page, CurrentPage::OpenPalette);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char('b'));
+    assert_eq!(app.current_page, CurrentPage::Blend);
+    assert_snapshot!(render(&mut app));
+
+    // 20%, 40%, 60%, 80%: the first intermediate is a fifth of the way
+    press(&mut app, KeyCode::Char('+'));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(app.palette_name, "mint → sunset 20%");
+    // Mint has a fourth color sunset lacks, it stays as it is
+    assert_eq!(app.color_blocks.len(), 4);
+    assert_eq!(app.color_blocks[3].get_hex(), "#F4A261");
+    assert_eq!(
+        app.color_blocks[0].get_hex(),
+        blend::mix_hex("#2A9D8F", "#E07A5F", 0.2)
+    );
+}
+
+#[test]
+fn compare_with_the_palette_before_generating() {
+    let mut app = app();
+    app.config.animate_transitions = false;
+    press(&mut app, KeyCode::Char('w'));
+    assert!(!app.compare);
+
+    let before = ["#264653", "#2A9D8F", "#E9C46A", "#F4A261", "#E76F51"];
+    for (idx, hex) in before.iter().enumerate() {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(hex.to_string()));
+    }
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Char('w'));
+    assert!(app.compare);
+    let previous: Vec<String> = app
+        .previous_palette
+        .iter()
+        .flatten()
+        .map(|block| block.get_hex())
+        .collect();
+    assert_eq!(previous, before);
+
+    // Generation is random, pin the new colors (and drop the seed) for the snapshot
+    for (idx, hex) in ["#1D3557", "#457B9D", "#A8DADC", "#F1FAEE", "#E63946"]
+        .iter()
+        .enumerate()
+    {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(hex.to_string()));
+    }
+    app.seed = None;
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Char('w'));
+    assert!(!app.compare);
+}
+
+#[test]
+fn mix_weighted_blocks_and_insert_the_result() {
+    let mut app = app();
+    let blocks = app.color_blocks.len();
+    for (idx, hex) in ["#D62828", "#003049", "#FCBF49"].iter().enumerate() {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(hex.to_string()));
+    }
+    app.selected_block_id = 0;
+
+    press(&mut app, KeyCode::Char('m'));
+    assert_eq!(app.mix_weights[..3], [1, 0, 0]);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Char('+'));
+    press(&mut app, KeyCode::Char('+'));
+    assert_eq!(app.mix_weights[..3], [1, 3, 0]);
+    press(&mut app, KeyCode::Char('-'));
+    assert_snapshot!(render(&mut app));
+
+    for _ in 1..blocks {
+        press(&mut app, KeyCode::Down);
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(app.color_blocks.len(), blocks + 1);
+    let average = blend::weighted_average(&[("#D62828", 1.0), ("#003049", 2.0)]);
+    assert_eq!(Some(app.color_blocks[blocks].get_hex()), average);
+}
+
+#[test]
+fn blend_modes_layer_one_block_over_another() {
+    let mut app = app();
+    let blocks = app.color_blocks.len();
+    for (idx, hex) in ["#3D405B", "#E07A5F", "#F2CC8F"].iter().enumerate() {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(hex.to_string()));
+    }
+    app.selected_block_id = 1;
+
+    press(&mut app, KeyCode::Char('M'));
+    assert_eq!(app.blend_top, 2);
+    press(&mut app, KeyCode::Left);
+    assert_eq!(app.blend_top, 0, "the base block is skipped");
+    press(&mut app, KeyCode::Down);
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(
+        app.color_blocks[blocks].get_hex(),
+        BlendMode::Screen.apply("#E07A5F", "#3D405B")
+    );
+}
+
+#[test]
+fn state_variants_are_inserted_and_labeled() {
+    let mut app = app();
+    let blocks = app.color_blocks.len();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#3D405B")));
+    app.color_blocks[0].label = String::from("button");
+
+    press(&mut app, KeyCode::Char('u'));
+    let labels: Vec<&str> = app.color_blocks[blocks..]
+        .iter()
+        .map(|block| block.label.as_str())
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            "button hover",
+            "button active",
+            "button focus",
+            "button disabled"
+        ]
+    );
+    assert!(app.color_blocks[blocks..].iter().all(|block| block.locked));
+
+    // A dark base lightens, further for each state
+    let lightness = |block: &ColorBlock| {
+        let (r, g, b) = block.get_rgb_values();
+        Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>()).l
+    };
+    let base = lightness(&app.color_blocks[0]);
+    let hover = lightness(&app.color_blocks[blocks]);
+    let active = lightness(&app.color_blocks[blocks + 1]);
+    assert!(base < hover && hover < active);
+}
+
+#[test]
+fn out_of_gamut_colors_are_flagged_until_fitted() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('z'));
+    type_text(&mut app, "oklch(0.45 0.35 264)");
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Esc);
+    assert!(app.color_blocks[0].out_of_gamut.is_some());
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Char('G'));
+    let block = &app.color_blocks[0];
+    assert!(block.out_of_gamut.is_none());
+    // Fitting keeps the blue that channel clipping turns purple
+    let (r, g, b) = block.get_rgb_values();
+    let fitted = Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>());
+    assert!((fitted.hue.into_positive_degrees() - 264.0).abs() < 3.0);
+
+    press(&mut app, KeyCode::Char('G'));
+    assert_eq!(app.status_bar_msg, t!("msg-in-gamut"));
+}
+
+#[test]
+fn monochrome_follows_a_lightness_scale() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('x'));
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Down);
+    }
+    press(&mut app, KeyCode::Char('s'));
+    assert_eq!(app.monochrome_scale, LightnessScale::Tailwind);
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_color_theory, ColorTheories::Monochrome);
+    // 5 blocks take every other-ish step of 11: 50, 300, 500, 800 and 950
+    let steps = LightnessScale::Tailwind.steps().unwrap();
+    for (block, step) in app.color_blocks.iter().zip([0, 3, 5, 8, 10]) {
+        let (r, g, b) = block.get_rgb_values();
+        let lightness = Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>()).l;
+        assert!((lightness - steps[step]).abs() < 0.01);
+    }
+}
+
+#[test]
+fn ctrl_z_takes_back_generations_edits_and_locks() {
+    let mut app = app();
+    let ctrl = KeyModifiers::CONTROL;
+    let start = app.palette_hexes();
+
+    press(&mut app, KeyCode::Char(' '));
+    let generated = app.palette_hexes();
+    press(&mut app, KeyCode::Char('l'));
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Right);
+    app.handle_paste("#D62828");
+    assert_eq!(app.undo_stack.len(), 4);
+
+    press_with(&mut app, KeyCode::Char('z'), ctrl);
+    assert_eq!(app.color_blocks.len(), start.len() + 1);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(app.status_bar_msg, t!("msg-undone", left = 3));
+    press_with(&mut app, KeyCode::Char('z'), ctrl);
+    assert_eq!(app.palette_hexes(), generated);
+    press_with(&mut app, KeyCode::Char('z'), ctrl);
+    assert!(!app.color_blocks[0].locked);
+    press_with(&mut app, KeyCode::Char('z'), ctrl);
+    assert_eq!(app.palette_hexes(), start);
+    press_with(&mut app, KeyCode::Char('z'), ctrl);
+    assert_eq!(app.status_bar_msg, t!("msg-nothing-to-undo"));
+
+    press_with(&mut app, KeyCode::Char('y'), ctrl);
+    press_with(&mut app, KeyCode::Char('y'), ctrl);
+    assert_eq!(app.palette_hexes(), generated);
+    assert!(app.color_blocks[0].locked);
+
+    // A new change drops what was undone
+    press(&mut app, KeyCode::Char('d'));
+    press_with(&mut app, KeyCode::Char('y'), ctrl);
+    assert_eq!(app.status_bar_msg, t!("msg-nothing-to-redo"));
+}
+
+#[test]
+fn no_jitter_repeats_the_exact_harmony() {
+    let mut app = app();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#3D84F5")));
+    press(&mut app, KeyCode::Char('l'));
+
+    press(&mut app, KeyCode::Char('x'));
+    // Medium, then high, then none
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(app.jitter, Jitter::None);
+    assert!(format!("{:?}", render(&mut app)).contains("Jitter: none"));
+    press(&mut app, KeyCode::Enter);
+
+    press(&mut app, KeyCode::Char(' '));
+    let first = app.palette_hexes();
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.palette_hexes(), first);
+}
+
+#[test]
+fn strict_mode_generates_exact_harmonies() {
+    let mut app = app();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#3D84F5")));
+    press(&mut app, KeyCode::Char('l'));
+    let base_hue = app.color_blocks[0].hsv.hue.into_positive_degrees();
+
+    press(&mut app, KeyCode::Char('x'));
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char('t'));
+    assert!(format!("{:?}", render(&mut app)).contains("Strict: exact harmonies"));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_color_theory, ColorTheories::Complementary);
+
+    press(&mut app, KeyCode::Char(' '));
+    // Every hue is the anchor's or its exact opposite
+    for block in &app.color_blocks {
+        let offset = (block.hsv.hue.into_positive_degrees() - base_hue).rem_euclid(180.0);
+        assert!(offset.min(180.0 - offset) < 0.01, "{offset}");
+    }
+
+    // The jitter level comes back once strict mode is off
+    press(&mut app, KeyCode::Char('x'));
+    press(&mut app, KeyCode::Char('t'));
+    assert!(!app.strict);
+    assert_eq!(app.jitter, Jitter::Medium);
+}
+
+#[test]
+fn partial_locks_keep_hue_or_lightness() {
+    let mut app = app();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#3D84F5")));
+    press(&mut app, KeyCode::Char('k'));
+    assert_eq!(app.color_blocks[0].frozen, Some(Freeze::Hue));
+    assert!(format!("{:?}", render(&mut app)).contains("HUE LOCKED"));
+
+    app.selected_block_id = 1;
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#E07A5F")));
+    press(&mut app, KeyCode::Char('k'));
+    press(&mut app, KeyCode::Char('k'));
+    assert_eq!(app.color_blocks[1].frozen, Some(Freeze::Lightness));
+
+    let hue = app.color_blocks[0].hsv.hue.into_positive_degrees();
+    let lightness = app.color_blocks[1].get_oklch().l;
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Char(' '));
+        assert!((app.color_blocks[0].hsv.hue.into_positive_degrees() - hue).abs() < 0.01);
+        assert!((app.color_blocks[1].get_oklch().l - lightness).abs() < 0.01);
+    }
+
+    // A full lock replaces the partial one
+    press(&mut app, KeyCode::Char('l'));
+    assert!(app.color_blocks[1].locked);
+    assert_eq!(app.color_blocks[1].frozen, None);
+}
+
+#[test]
+fn row_locks_follow_positions_after_deletions() {
+    let mut app = app();
+    for _ in 0..8 {
+        press(&mut app, KeyCode::Char('a'));
+    }
+    // 13 blocks wrap into rows of 8; delete the second, leaving 12
+    app.selected_block_id = 1;
+    press(&mut app, KeyCode::Char('d'));
+    let ids: Vec<usize> = app
+        .color_blocks
+        .iter()
+        .map(|block| block.block_id)
+        .collect();
+    assert_eq!(ids, (1..=12).collect::<Vec<_>>());
+
+    press_with(&mut app, KeyCode::Char('2'), KeyModifiers::ALT);
+    assert!(app.color_blocks[1].locked);
+
+    // The second row holds 4 blocks: digits past them do nothing
+    app.selected_block_id = 10;
+    press_with(&mut app, KeyCode::Char('9'), KeyModifiers::ALT);
+    press_with(&mut app, KeyCode::Char('5'), KeyModifiers::ALT);
+    press_with(&mut app, KeyCode::Char('4'), KeyModifiers::ALT);
+    let locked: Vec<usize> = app
+        .color_blocks
+        .iter()
+        .filter(|block| block.locked)
+        .map(|block| block.block_id)
+        .collect();
+    assert_eq!(locked, [2, 12]);
+}
+
+#[test]
+fn macros_replay_recorded_actions() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('@'));
+    assert!(app.status_bar_msg.contains("No macro"));
+
+    press(&mut app, KeyCode::Char('Q'));
+    assert!(format!("{:?}", render(&mut app)).contains("● REC"));
+    press(&mut app, KeyCode::Char('l'));
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Char('Q'));
+    assert_eq!(app.macro_actions, [Action::ToggleLock, Action::SelectNext]);
+    assert!(!format!("{:?}", render(&mut app)).contains("● REC"));
+
+    press(&mut app, KeyCode::Char('@'));
+    press(&mut app, KeyCode::Char('@'));
+    let locked: Vec<bool> = app.color_blocks.iter().map(|block| block.locked).collect();
+    assert_eq!(locked, [true, true, true, false, false]);
+    assert_eq!(app.selected_block_id, 3);
+}
+
+#[test]
+fn command_line_runs_fuzzy_commands() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, "thr ");
+    assert_snapshot!(render(&mut app));
+    type_text(&mut app, "tri");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(app.current_color_theory, ColorTheories::Triad);
+
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, "set blocks 7");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.color_blocks.len(), 7);
+
+    // Every main-view action is a command, completed with Tab
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, "gene");
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.command_field, "generate ");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.seed.is_some());
+
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, "zzz");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.status_bar_msg.contains("Unknown command: zzz"));
+
+    // Ranked like palette search, with the same word ahead of longer matches
+    assert_eq!(
+        command::parse("export svg"),
+        Ok(Command::Export(Format::Svg, None))
+    );
+    assert_eq!(
+        command::parse("export grsvg"),
+        Ok(Command::Export(Format::GradientSvg, None))
+    );
+}
+
+#[test]
+fn setup_writes_the_chosen_config() {
+    let _config = CONFIG_FILE.lock().unwrap();
+    let mut app = app();
+    app.start_setup();
+    assert_eq!(app.current_page, CurrentPage::Setup);
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    // 5 blocks are preselected, two down is 8
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.setup_step, SetupStep::Recolor);
+    press(&mut app, KeyCode::Enter);
+
+    assert_eq!(app.current_page, CurrentPage::Main);
+    assert_eq!(app.current_color_theory, ColorTheories::Complementary);
+    assert_eq!(app.color_blocks.len(), 8);
+    let written = config::load().unwrap();
+    assert_eq!(written.theory, ColorTheories::Complementary);
+    assert_eq!(written.blocks, 8);
+    assert_eq!(written.copy_format, ColorFormat::Rgb);
+    assert!(!written.slideshow_apply_to_terminal);
+
+    press(&mut app, KeyCode::Char('c'));
+    assert!(app.status_bar_msg.contains("rgb("));
+}
+
+#[test]
+fn settings_apply_and_persist_right_away() {
+    let _config = CONFIG_FILE.lock().unwrap();
+    let mut app = app();
+    press(&mut app, KeyCode::Char(','));
+    assert_eq!(app.current_page, CurrentPage::Settings);
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Right);
+    assert_eq!(app.current_color_theory, ColorTheories::Complementary);
+    press(&mut app, KeyCode::Left);
+    press(&mut app, KeyCode::Left);
+    assert_eq!(app.current_color_theory, ColorTheories::Neutrals);
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Right);
+    assert_eq!(app.color_blocks.len(), 6);
+
+    // Jitter, then strict mode
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.jitter, Jitter::High);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    assert!(app.strict);
+
+    let written = config::load().unwrap();
+    assert_eq!(written.theory, ColorTheories::Neutrals);
+    assert_eq!(written.blocks, 6);
+    assert_eq!(written.jitter, Jitter::High);
+    assert!(written.strict);
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.current_page, CurrentPage::Main);
+}
+
+#[test]
+fn status_bar_hints_take_the_palette_accent() {
+    let mut app = app();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#1E1E2E")));
+    app.selected_block_id = 1;
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#E07A5F")));
+    app.color_blocks[1].role = Some(Role::Primary);
+
+    // The first hint key, `[q]`, in the middle line of the status bar
+    let hint_color = |app: &mut App| {
+        let backend = render(app);
+        let buffer = backend.buffer();
+        (0..120)
+            .map(|x| &buffer[(x, 28)])
+            .find(|cell| cell.symbol() == "[")
+            .unwrap()
+            .fg
+    };
+    assert_eq!(hint_color(&mut app), Color::Rgb(0xE0, 0x7A, 0x5F));
+
+    // Without a primary block the first one leads, lightened until it reads on black
+    app.color_blocks[1].role = None;
+    let Color::Rgb(r, g, b) = hint_color(&mut app) else {
+        panic!("accent isn't an RGB color");
+    };
+    let (h, s, v) = convert::rgb_to_hsv(r, g, b);
+    let accent = ColorBlock::new(0, h, s, v);
+    assert!(accent.contrast_ratio(&ColorBlock::new(0, 0.0, 0.0, 0.0)) >= pairing::AA);
+    assert!(v > app.color_blocks[0].hsv.value);
+}
+
+#[test]
+fn chrome_follows_the_palette_roles() {
+    let mut app = app();
+    for (idx, hex, role) in [
+        (0, "#1E1E2E", Role::Background),
+        (1, "#CDD6F4", Role::Text),
+        (2, "#E07A5F", Role::Primary),
+    ] {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(String::from(hex)));
+        app.color_blocks[idx].role = Some(role);
+    }
+    press(&mut app, KeyCode::Char('T'));
+    assert!(app.theme_chrome);
+    press(&mut app, KeyCode::Char('x'));
+
+    let backend = render(&mut app);
+    let buffer = backend.buffer();
+    let background = Color::Rgb(0x1E, 0x1E, 0x2E);
+    // Top left corner of the theory selector, its highlight mark and a theory name
+    assert_eq!(buffer[(40, 12)].symbol(), "┌");
+    assert_eq!(buffer[(40, 12)].fg, Color::Rgb(0xE0, 0x7A, 0x5F));
+    assert_eq!(buffer[(41, 13)].symbol(), ">");
+    assert_eq!(buffer[(41, 13)].fg, Color::Rgb(0xE0, 0x7A, 0x5F));
+    assert_eq!(buffer[(42, 13)].fg, Color::Rgb(0xCD, 0xD6, 0xF4));
+    assert_eq!(buffer[(42, 13)].bg, background);
+    // Swatches keep their own color, and no surface leaves the status bar alone
+    assert_eq!(buffer[(60, 3)].bg, Color::Rgb(0xE0, 0x7A, 0x5F));
+    assert_eq!(buffer[(0, 28)].bg, Color::Black);
+
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('T'));
+    press(&mut app, KeyCode::Char('x'));
+    let backend = render(&mut app);
+    assert_eq!(backend.buffer()[(40, 12)].fg, Color::Reset);
+    assert_eq!(backend.buffer()[(42, 13)].bg, Color::Reset);
+}
+
+#[test]
+fn narrow_blocks_keep_their_state_glyphs() {
+    let mut app = app();
+    for _ in 0..11 {
+        press(&mut app, KeyCode::Char('a'));
+    }
+    // Lightness lock, then starred in memory only, leaving the shared favorites file alone
+    press(&mut app, KeyCode::Char('k'));
+    press(&mut app, KeyCode::Char('k'));
+    app.favorites.colors.push(app.color_blocks[0].get_hex());
+    app.selected_block_id = 1;
+
+    let backend = render(&mut app);
+    let top: String = (0..15).map(|x| backend.buffer()[(x, 0)].symbol()).collect();
+    assert_eq!(top.trim(), "◑ ★");
+    assert_eq!(backend.buffer()[(0, 1)].symbol(), "╭");
+}
+
+#[test]
+fn widened_blocks_take_a_share_of_their_row() {
+    let mut app = app();
+    let ctrl_shift = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+    for _ in 0..5 {
+        press_with(&mut app, KeyCode::Right, ctrl_shift);
+    }
+    assert_eq!(app.selected_block_id, 0);
+    assert_eq!(app.color_blocks[0].width, 6);
+    assert!(app.status_bar_msg.contains("60%"));
+    press(&mut app, KeyCode::Right);
+    press_with(&mut app, KeyCode::Left, ctrl_shift);
+    assert_eq!(app.color_blocks[1].width, 1);
+    press(&mut app, KeyCode::Left);
+
+    // 6 of 10 shares of the 120 columns
+    let backend = render(&mut app);
+    assert_eq!(backend.buffer()[(0, 1)].symbol(), "╔");
+    assert_eq!(backend.buffer()[(71, 1)].symbol(), "╗");
+
+    let project = app.to_project();
+    assert_eq!(project.blocks[0].width, Some(6));
+    assert_eq!(project.blocks[1].width, None);
+    let mut reopened = App::with_clipboard(Box::new(NoClipboard));
+    reopened.apply_project(project).unwrap();
+    assert_eq!(reopened.color_blocks[0].width, 6);
+}
+
+#[test]
+fn proportions_follow_block_widths() {
+    let mut app = app();
+    for (idx, hex) in ["#264653", "#2A9D8F", "#E9C46A", "#F4A261", "#E76F51"]
+        .into_iter()
+        .enumerate()
+    {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(String::from(hex)));
+    }
+    app.selected_block_id = 0;
+    for _ in 0..5 {
+        press_with(
+            &mut app,
+            KeyCode::Right,
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        );
+    }
+    press(&mut app, KeyCode::Char('P'));
+    assert_eq!(app.current_page, CurrentPage::ProportionPreview);
+    assert_snapshot!(render(&mut app));
+
+    let palette = ExportPalette::from(&app.to_project());
+    let css = export::export(Format::ProportionsCss, &palette);
+    assert!(
+        css.contains(
+            "  /* dominant, 60%: backgrounds and large surfaces */\n  --color-1: #264653;"
+        )
+    );
+    let svg = export::export(Format::ProportionsSvg, &palette);
+    assert!(svg.contains(r##"<rect x="0.0" y="0" width="360.0" height="120" fill="#264653">"##));
+}
+
+#[test]
+fn balance_weighs_warm_against_cool() {
+    let mut app = app();
+    for (idx, hex) in ["#E76F51", "#F4A261", "#E9C46A", "#2A9D8F", "#F5F5F5"]
+        .into_iter()
+        .enumerate()
+    {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(String::from(hex)));
+    }
+    press(&mut app, KeyCode::Char('b'));
+    assert_eq!(app.current_page, CurrentPage::Balance);
+    let balance = balance::analyze(&app.color_blocks);
+    assert_eq!(
+        (balance.warm, balance.cool, balance.neutral),
+        (60.0, 20.0, 20.0)
+    );
+    assert_eq!(balance.lean(), balance::Lean::Warm);
+    assert_snapshot!(render(&mut app));
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.current_page, CurrentPage::Main);
+}
+
+#[test]
+fn illuminants_recolor_only_what_is_drawn() {
+    let mut app = app();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#FFFFFF")));
+    press(&mut app, KeyCode::Char('W'));
+    press(&mut app, KeyCode::Char('W'));
+    assert_eq!(app.illuminant, Illuminant::A);
+
+    let backend = render(&mut app);
+    let buffer = backend.buffer();
+    let (r, g, b) = illuminant::adapt(&app.color_blocks[0], Illuminant::A).get_rgb_values();
+    assert_eq!(buffer[(10, 3)].bg, Color::Rgb(r, g, b));
+    assert_ne!(buffer[(10, 3)].bg, Color::Rgb(0xFF, 0xFF, 0xFF));
+    assert_eq!(app.color_blocks[0].get_hex(), "#FFFFFF");
+    let status: String = (0..120).map(|x| buffer[(x, 28)].symbol()).collect();
+    assert!(status.contains("☀ warm indoor light (A)"));
+
+    for _ in 0..3 {
+        press(&mut app, KeyCode::Char('W'));
+    }
+    assert_eq!(app.illuminant, Illuminant::D65);
+    let backend = render(&mut app);
+    assert_eq!(backend.buffer()[(10, 3)].bg, Color::Rgb(0xFF, 0xFF, 0xFF));
+}
+
+#[test]
+fn night_light_cuts_blue_from_what_is_drawn() {
+    let mut app = app();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#FFFFFF")));
+    press(&mut app, KeyCode::Char('L'));
+    press(&mut app, KeyCode::Char('L'));
+    assert_eq!(app.night_light, Some(3400));
+    assert_eq!(
+        app.status_bar_msg,
+        "Showing the palette through a night-light filter at 3400 K"
+    );
+
+    let backend = render(&mut app);
+    let buffer = backend.buffer();
+    let Color::Rgb(r, g, b) = buffer[(10, 3)].bg else {
+        panic!("swatches are drawn in RGB");
+    };
+    assert!(r == 255 && g < r && b < g);
+    assert_eq!(app.color_blocks[0].get_hex(), "#FFFFFF");
+    let status: String = (0..120).map(|x| buffer[(x, 28)].symbol()).collect();
+    assert!(status.contains("☾ 3400 K"));
+
+    for _ in 0..2 {
+        press(&mut app, KeyCode::Char('L'));
+    }
+    assert_eq!(app.night_light, None);
+}
+
+#[test]
+fn block_notes_reach_projects_and_exports() {
+    let mut app = app();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#D62828")));
+    app.color_blocks[0].label = String::from("danger");
+    press_with(&mut app, KeyCode::Char('n'), KeyModifiers::CONTROL);
+    assert_eq!(app.current_page, CurrentPage::EditNote);
+    type_text(&mut app, "use only for destructive actions */");
+    assert_snapshot!(render(&mut app));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(
+        app.color_blocks[0].note,
+        "use only for destructive actions */"
+    );
+
+    let project = app.to_project();
+    let mut reopened = App::with_clipboard(Box::new(NoClipboard));
+    reopened.apply_project(project.clone()).unwrap();
+    assert_eq!(reopened.color_blocks[0].note, app.color_blocks[0].note);
+
+    let css = export::export(Format::Css, &ExportPalette::from(&project));
+    assert!(css.contains("  /* use only for destructive actions * / */\n  --danger: #D62828;\n"));
+}
+
+#[test]
+fn saved_palettes_reopen_with_their_locks_and_theory() {
+    let _library = LIBRARY.lock().unwrap();
+    let recent = fs::read(library::recent_path()).unwrap();
+    let mut app = app();
+    app.current_color_theory = ColorTheories::Tetrad;
+    app.color_blocks[1].locked = true;
+    app.color_blocks[2].frozen = Some(Freeze::Hue);
+    let hexes = app.palette_hexes();
+    press(&mut app, KeyCode::Char('s'));
+    type_text(&mut app, "dusk");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.current_page, CurrentPage::Main);
+
+    let path = library::palette_path("dusk");
+    let loaded = library::load_palette(&path);
+    // Leave the library and the recent list as the other tests expect them
+    let _ = fs::remove_file(&path);
+    fs::write(library::recent_path(), recent).unwrap();
+
+    let mut reopened = App::with_clipboard(Box::new(NoClipboard));
+    reopened.apply_saved_palette(&loaded.unwrap()).unwrap();
+    assert_eq!(reopened.palette_hexes(), hexes);
+    assert_eq!(reopened.current_color_theory, ColorTheories::Tetrad);
+    assert_eq!(reopened.palette_name, "dusk");
+    assert!(reopened.color_blocks[1].locked);
+    assert_eq!(reopened.color_blocks[2].frozen, Some(Freeze::Hue));
+}
+
+#[test]
+fn palettes_too_short_for_the_app_are_refused() {
+    let path = env::temp_dir().join(format!("terminal-palette-{}-empty.json", process::id()));
+    fs::write(
+        &path,
+        r#"{ "name": "empty", "theory": "Triad", "colors": [] }"#,
+    )
+    .unwrap();
+    let mut app = app();
+    let hexes = app.palette_hexes();
+
+    let opened = app.load_palette_file(&path);
+    let _ = fs::remove_file(&path);
+    assert_eq!(
+        opened.unwrap_err().to_string(),
+        "a palette takes at least 3 colors, got 0"
+    );
+    let mut project = app.to_project();
+    project.blocks.truncate(2);
+    assert!(app.apply_project(project).is_err());
+    assert_eq!(app.palette_hexes(), hexes);
+}
+
+#[test]
+fn palette_files_save_one_line_per_color_and_resave_unchanged() {
+    let path = env::temp_dir().join(format!("terminal-palette-{}.palette", process::id()));
+    let mut app = app();
+    app.run_repeatable(RepeatableAction::SetHex(String::from("#D62828")));
+    app.color_blocks[0].locked = true;
+    app.color_blocks[0].label = String::from("say \"stop\"");
+    project::save_project(&mut app.to_project(), &path).unwrap();
+    let saved = fs::read_to_string(&path).unwrap();
+    assert_eq!(saved.lines().count(), 3 + app.color_blocks.len());
+    assert!(saved.contains("\n#D62828 locked label=\"say \\\"stop\\\"\"\n"));
+    assert_eq!(palette_file::check(&saved), Ok(()));
+
+    let mut reopened = App::with_clipboard(Box::new(NoClipboard));
+    reopened
+        .apply_project(project::load_project(&path).unwrap())
+        .unwrap();
+    assert_eq!(reopened.color_blocks[0].label, "say \"stop\"");
+    project::save_project(&mut reopened.to_project(), &path).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), saved);
+    let _ = fs::remove_file(&path);
+}
+
+/// A saved palette whose hex codes leave out the `#`, as [`library::is_valid_hex`] allows
+fn palette_without_hashes(dir: &Path) -> PathBuf {
+    let path = dir.join("sunset.json");
+    let palette = SavedPalette {
+        name: String::from("sunset"),
+        theory: ColorTheories::Triad,
+        colors: ["E07A5F", "3D405B"]
+            .iter()
+            .map(|hex| SavedColor {
+                hex: hex.to_string(),
+                locked: false,
+                frozen: None,
+                role: None,
+            })
+            .collect(),
+        tags: Vec::new(),
+        starred: false,
+    };
+    fs::write(&path, serde_json::to_string(&palette).unwrap()).unwrap();
+    path
+}
+
+#[test]
+fn lint_reads_palette_hexes_without_a_hash() {
+    let dir = env::temp_dir().join(format!("terminal-palette-{}-lint", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let palette = palette_without_hashes(&dir);
+    fs::write(dir.join("a.css"), ".a { color: #E07A5F; }\n").unwrap();
+
+    let colors = crate::palette_rgb(&crate::load_export_palette(&palette).unwrap());
+    let found = lint::literals(".a { color: #E07A5F; }", &colors);
+    let linted = crate::lint(&dir, &palette, 1.0);
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(colors, [(0xE0, 0x7A, 0x5F), (0x3D, 0x40, 0x5B)]);
+    assert_eq!((found[0].nearest, found[0].delta_e), (0, 0.0));
+    linted.unwrap();
+}
+
+#[test]
+fn rewrite_matches_palette_hexes_without_a_hash() {
+    let dir = env::temp_dir().join(format!("terminal-palette-{}-rewrite", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let palette = palette_without_hashes(&dir);
+    fs::write(dir.join("a.css"), ".a { color: #3d405b; }\n").unwrap();
+    let patch = dir.join("palette.patch");
+
+    let app = app();
+    let rewritten = crate::rewrite(&app.config, &dir, &palette, 1.0, Some(&patch));
+    let patch = fs::read_to_string(&patch);
+    fs::remove_dir_all(&dir).unwrap();
+    rewritten.unwrap();
+    assert!(
+        patch
+            .unwrap()
+            .contains("\n+.a { color: var(--sunset-2); }\n"),
+        "the second color's variable"
+    );
+}
+
+/// A clipboard the test copies into behind the app's back
+#[derive(Clone, Default)]
+struct SharedClipboard(Arc<Mutex<String>>);
+
+impl ClipboardProvider for SharedClipboard {
+    fn set_text(&mut self, text: &str) -> io::Result<()> {
+        *self.0.lock().unwrap() = text.to_string();
+        Ok(())
+    }
+
+    fn get_text(&mut self) -> io::Result<String> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    fn name(&self) -> &'static str {
+        "shared"
+    }
+}
+
+#[test]
+fn clipboard_watch_offers_newly_copied_colors() {
+    seed_library();
+    let mut clipboard = SharedClipboard::default();
+    clipboard.set_text("#112233").unwrap();
+    let mut app = App::with_clipboard(Box::new(clipboard.clone()));
+    let look = |app: &mut App| {
+        app.clipboard_watch = Some(Instant::now());
+        app.tick_clipboard_watch();
+    };
+
+    press(&mut app, KeyCode::Char('V'));
+    assert!(app.clipboard_watch.is_some());
+    // What was copied before watching isn't offered, nor text that isn't a color
+    look(&mut app);
+    clipboard.set_text("not a color at all").unwrap();
+    look(&mut app);
+    assert_eq!(app.current_page, CurrentPage::Main);
+
+    clipboard.set_text("rgb(224, 122, 95)").unwrap();
+    look(&mut app);
+    assert_eq!(app.current_page, CurrentPage::ClipboardOffer);
+    assert_snapshot!(render(&mut app));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.color_blocks[0].get_hex(), "#E07A5F");
+
+    // Each copy is offered once, and skipping leaves the block alone
+    look(&mut app);
+    assert_eq!(app.current_page, CurrentPage::Main);
+    clipboard.set_text("teal").unwrap();
+    look(&mut app);
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.color_blocks[0].get_hex(), "#E07A5F");
+
+    press(&mut app, KeyCode::Char('V'));
+    assert!(app.clipboard_watch.is_none());
+}
+
+#[test]
+fn color_lists_from_the_editor_recolor_by_position() {
+    let mut app = app();
+    app.color_blocks[1].label = String::from("accent");
+    app.color_blocks[1].locked = true;
+
+    app.apply_color_list("#264653\n#2a9d8f\n\nrgb(233, 196, 106)\n#F4A261\nbanana\n");
+    assert_eq!(
+        app.status_bar_msg,
+        "Line 6 isn't a color, nothing changed: unknown color name \"banana\""
+    );
+    assert_eq!(app.palette_hexes()[0], "#000000");
+
+    app.apply_color_list("#264653\n#2A9D8F\n");
+    assert_eq!(app.color_blocks.len(), 5);
+
+    app.apply_color_list("#264653\n#2a9d8f\n\nrgb(233, 196, 106)\n#F4A261\n#E76F51\ncoral\n");
+    assert_eq!(
+        app.palette_hexes(),
+        [
+            "#264653", "#2A9D8F", "#E9C46A", "#F4A261", "#E76F51", "#FF7F50"
+        ]
+    );
+    assert_eq!(app.color_blocks[1].label, "accent");
+    assert!(app.color_blocks[1].locked);
+    assert_eq!(app.history.len(), 1);
+}
+
+#[test]
+fn gradients_run_through_the_blocks_and_export() {
+    seed_library();
+    let clipboard = SharedClipboard::default();
+    let mut app = App::with_clipboard(Box::new(clipboard.clone()));
+    app.color_blocks.truncate(2);
+    for (idx, hex) in ["#1D3557", "#E9C46A"].into_iter().enumerate() {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(String::from(hex)));
+    }
+
+    press(&mut app, KeyCode::Char('t'));
+    assert_eq!(app.current_page, CurrentPage::Gradients);
+    press(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.current_page, CurrentPage::NameGradient);
+    assert_eq!(app.gradient_name_field, "Gradient 1");
+    for _ in 0.."Gradient 1".len() {
+        press(&mut app, KeyCode::Backspace);
+    }
+    type_text(&mut app, "dusk");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.gradients[0].name, "dusk");
+    let backend = render(&mut app);
+    // Two samples per cell of the bar, starting on the first stop
+    let bar = backend
+        .buffer()
+        .content()
+        .iter()
+        .find(|cell| cell.symbol() == "▌");
+    let bar = bar.expect("the gradient is drawn as half blocks");
+    assert_eq!(bar.fg, Color::Rgb(0x1D, 0x35, 0x57));
+    assert_ne!(bar.bg, bar.fg);
+    assert_snapshot!(backend);
+
+    press(&mut app, KeyCode::Char('s'));
+    assert_eq!(app.gradients[0].space, Space::Oklch);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(
+        *clipboard.0.lock().unwrap(),
+        "linear-gradient(in oklch to right, #1D3557 0%, #E9C46A 100%)"
+    );
+
+    let project = app.to_project();
+    let text = palette_file::write(&project);
+    assert!(text.ends_with("gradient \"dusk\" oklch #1D3557@0 #E9C46A@100\n"));
+    assert_eq!(
+        palette_file::parse(&text).unwrap().gradients,
+        project.gradients
+    );
+
+    let palette = ExportPalette::from(&project);
+    let svg = export::export(Format::GradientSvg, &palette);
+    assert!(svg.contains("<linearGradient id=\"gradient-1\">"));
+    // SVG blends in sRGB, so the OKLCH ramp arrives sampled
+    assert_eq!(svg.matches("<stop ").count(), 9);
+    let ggr = export::export(Format::Ggr, &palette);
+    assert!(ggr.starts_with("GIMP Gradient\nName: dusk\n8\n"));
+
+    press(&mut app, KeyCode::Char('d'));
+    assert!(app.gradients.is_empty());
+}
+
+#[test]
+fn pasted_css_gradients_become_blocks_or_gradients() {
+    let mut app = app();
+    let css = "background: linear-gradient(90deg, #020024 0%, #090979 35%, #00D4FF 100%);";
+    app.handle_paste(css);
+    assert_eq!(app.palette_hexes(), ["#020024", "#090979", "#00D4FF"]);
+
+    // Two stops are too few for a palette, so the ramp between them is sampled
+    app.handle_paste("linear-gradient(#000000, #FFFFFF)");
+    assert_eq!(app.palette_hexes(), ["#000000", "#808080", "#FFFFFF"]);
+
+    press(&mut app, KeyCode::Char('t'));
+    app.handle_paste(css);
+    assert_eq!(app.current_page, CurrentPage::NameGradient);
+    press(&mut app, KeyCode::Enter);
+    let gradient = &app.gradients[0];
+    assert_eq!(
+        (gradient.name.as_str(), gradient.space),
+        ("Gradient 1", Space::Srgb)
+    );
+    assert_eq!(gradient.stops[1].position, 0.35);
+
+    app.handle_paste("linear-gradient(red, bleu)");
+    assert_eq!(app.gradients.len(), 1);
+    assert_eq!(
+        app.status_bar_msg,
+        "Can't read the gradient: \"bleu\": unknown color name \"bleu\""
+    );
+}
+
+#[test]
+fn pasted_text_with_more_colors_than_blocks_keeps_the_most_distinct() {
+    let mut app = app();
+    // A theme file with a long run of near-white grays before its accents
+    let mut theme: String = (0..40)
+        .map(|idx| format!("gray{idx} = \"#{0:02X}{0:02X}{0:02X}\"\n", 255 - idx))
+        .collect();
+    let accents = ["#D20F39", "#40A02B", "#1E66F5", "#DF8E1D", "#11111B"];
+    for accent in accents {
+        theme.push_str(&format!("accent = \"{accent}\"\n"));
+    }
+    app.handle_paste(&theme);
+
+    // The palette keeps its number of blocks, the grays sharing one of them
+    let hexes = app.palette_hexes();
+    assert_eq!(
+        hexes,
+        ["#FFFFFF", "#D20F39", "#40A02B", "#1E66F5", "#11111B"]
+    );
+    // The grays left out count towards the white nearest to them
+    assert_eq!(
+        app.status_bar_msg,
+        "Took 5 of the 45 colors in the pasted text, #FFFFFF covers 89%"
+    );
+    assert!(app.color_blocks.iter().all(|block| block.width == 1));
+
+    // Two colors are too few for a palette, so it's not taken as a list
+    app.handle_paste("#D20F39 and #40A02B");
+    assert_eq!(app.palette_hexes(), hexes);
+
+    // Weighted, the most used color is widest and the rest keep their share
+    app.config.weight_imports = true;
+    let css = "body { background: #1E1E2E; color: #CDD6F4 }
+        .card { background: #1E1E2E; border: 1px solid #1E1E2E }
+        pre { background: #1E1E2E; color: #CDD6F4 }
+        a { color: #F38BA8 }";
+    app.handle_paste(css);
+    assert_eq!(app.palette_hexes(), ["#1E1E2E", "#CDD6F4", "#F38BA8"]);
+    let widths: Vec<u16> = app.color_blocks.iter().map(|block| block.width).collect();
+    assert_eq!(widths, [MAX_BLOCK_WIDTH, 6, 3]);
+    assert_eq!(
+        app.status_bar_msg,
+        "Took 3 of the 3 colors in the pasted text, #1E1E2E covers 57%"
+    );
+}
+
+#[test]
+#[cfg(feature = "image")]
+fn extract_takes_the_frame_showing_at_a_time() {
+    use std::io::{Read, Write};
+
+    let path = env::temp_dir().join(format!("terminal-palette-{}.gif", process::id()));
+    let colors = [
+        (0x1E, 0x1E, 0x2E),
+        (0xCD, 0xD6, 0xF4),
+        (0xF3, 0x8B, 0xA8),
+        (0xFF, 0xFF, 0xFF),
+        (0x00, 0x00, 0xFF),
+        (0x00, 0xFF, 0x00),
+    ];
+    let table: Vec<u8> = colors.iter().flat_map(|&(r, g, b)| [r, g, b]).collect();
+    {
+        let mut file = fs::File::create(&path).unwrap();
+        let mut encoder = gif::Encoder::new(&mut file, 10, 10, &table).unwrap();
+        // One second of 60% background, 30% text and 10% accent, then the other three
+        for (first, delay) in [(0u8, 100), (3, 100)] {
+            let mut pixels = vec![first; 60];
+            pixels.extend([first + 1; 30]);
+            pixels.extend([first + 2; 10]);
+            let frame = gif::Frame {
+                width: 10,
+                height: 10,
+                delay,
+                buffer: pixels.into(),
+                ..gif::Frame::default()
+            };
+            encoder.write_frame(&frame).unwrap();
+        }
+    }
+
+    let mut app = app();
+    app.run_command(Command::SetBlocks(3));
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, &format!("extract {}", path.display()));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.palette_hexes(), ["#1E1E2E", "#CDD6F4", "#F38BA8"]);
+    assert_eq!(
+        app.status_bar_msg,
+        "Took 3 colors from the frame, #1E1E2E covers 60%"
+    );
+
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, &format!("extract {} 0:01.5", path.display()));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.palette_hexes(), ["#FFFFFF", "#0000FF", "#00FF00"]);
+
+    // Past the end the last frame stays, other pictures aren't read
+    let source = path.display().to_string();
+    app.extract_frame(&source, Duration::from_secs(60));
+    assert_eq!(app.palette_hexes(), ["#FFFFFF", "#0000FF", "#00FF00"]);
+    app.extract_frame(&source.replace(".gif", ".mp4"), Duration::ZERO);
+    assert!(app.status_bar_msg.contains("only GIF files are read"));
+
+    // URLs download in the background, in builds that download
+    if !cfg!(feature = "network") {
+        fs::remove_file(&path).unwrap();
+        return;
+    }
+    let gif = fs::read(&path).unwrap();
+    let server = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/loop.gif", server.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let (mut stream, _) = server.accept().unwrap();
+        let mut request = [0; 1024];
+        let _ = stream.read(&mut request);
+        let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", gif.len());
+        stream.write_all(head.as_bytes()).unwrap();
+        stream.write_all(&gif).unwrap();
+    });
+    let wait = |app: &mut App| {
+        for _ in 0..500 {
+            app.poll_tasks();
+            if !app.tasks.is_busy() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    };
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, &format!("extract {url}"));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.status_bar_msg, format!("Downloading {url}..."));
+    wait(&mut app);
+    assert_eq!(app.palette_hexes(), ["#1E1E2E", "#CDD6F4", "#F38BA8"]);
+    fs::remove_file(&path).unwrap();
+
+    // Failures are reported when done, leaving the blocks alone
+    press(&mut app, KeyCode::Char(':'));
+    type_text(&mut app, "extract http://127.0.0.1:9/still.gif 2");
+    press(&mut app, KeyCode::Enter);
+    wait(&mut app);
+    assert!(
+        app.status_bar_msg
+            .starts_with("Downloading the picture failed: http://127.0.0.1:9/still.gif: "),
+        "{}",
+        app.status_bar_msg
+    );
+    assert_eq!(app.palette_hexes(), ["#1E1E2E", "#CDD6F4", "#F38BA8"]);
+}
+
+#[test]
+fn art_preview_shades_by_lightness_whatever_the_order() {
+    let mut app = app();
+    app.color_blocks.truncate(4);
+    // Neither lightest nor darkest first
+    let hexes = ["#4C6A92", "#F2E6C9", "#1B1B2F", "#C0605A"];
+    for (idx, hex) in hexes.iter().enumerate() {
+        app.selected_block_id = idx;
+        app.run_repeatable(RepeatableAction::SetHex(hex.to_string()));
+    }
+    press(&mut app, KeyCode::Char('I'));
+    assert_eq!(app.current_page, CurrentPage::ArtPreview);
+
+    let backend = render(&mut app);
+    let buffer = backend.buffer();
+    let rgb = |hex: &str| {
+        let value = u32::from_str_radix(&hex[1..], 16).unwrap();
+        Color::Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8)
+    };
+    let art: Vec<_> = buffer
+        .content()
+        .iter()
+        .filter(|cell| cell.symbol() == "▀")
+        .collect();
+    assert_eq!(art.len(), 64 * 16);
+    for hex in hexes {
+        assert!(art.iter().any(|cell| cell.fg == rgb(hex)), "{hex} unused");
+    }
+    // The sky lightens from the darkest block down, the sun is the lightest
+    assert_eq!(art[0].fg, rgb("#1B1B2F"));
+    assert_eq!(art[64].fg, rgb("#4C6A92"));
+    assert!(
+        art[64 * 2..64 * 3]
+            .iter()
+            .any(|cell| cell.bg == rgb("#F2E6C9"))
+    );
+
+    press(&mut app, KeyCode::Char('I'));
+    assert_eq!(app.current_page, CurrentPage::Main);
+}
+
+#[test]
+fn export_hooks_get_the_palette() {
+    let css = env::temp_dir().join(format!("terminal-palette-{}-hooked.css", process::id()));
+    let out = env::temp_dir().join(format!("terminal-palette-{}-hook.txt", process::id()));
+    let wait = |app: &mut App| {
+        for _ in 0..500 {
+            app.poll_tasks();
+            if !app.tasks.is_busy() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    };
+
+    let mut app = app();
+    app.apply_color_list("#E07A5F\n#3D405B\n#81B29A\n");
+    app.color_blocks[1].role = Some(Role::Background);
+    app.config.hooks.export = vec![
+        format!(
+            "echo \"$TERMINAL_PALETTE_EVENT $TERMINAL_PALETTE_COLORS\" > {0}; \
+             echo \"$TERMINAL_PALETTE_COLOR2 $TERMINAL_PALETTE_BACKGROUND\" >> {0}; \
+             echo \"$TERMINAL_PALETTE_EXPORT\" >> {0}; cat \"$TERMINAL_PALETTE_JSON\" >> {0}",
+            out.display()
+        ),
+        String::from("echo 'no such bar' >&2; exit 3"),
+    ];
+    app.run_command(Command::Export(Format::Css, Some(css.clone())));
+    wait(&mut app);
+
+    // Every hook runs even after one fails, and the first failure is reported
+    assert_eq!(
+        app.status_bar_msg,
+        "Running hooks failed: 1 of 2 hooks failed, first: echo 'no such bar' >&2; exit 3: no such bar"
+    );
+    let written = fs::read_to_string(&out).unwrap();
+    let mut lines = written.lines();
+    assert_eq!(lines.next(), Some("export #E07A5F #3D405B #81B29A"));
+    assert_eq!(lines.next(), Some("#81B29A #3D405B"));
+    assert_eq!(lines.next(), Some(css.display().to_string().as_str()));
+    let json: serde_json::Value =
+        serde_json::from_str(&lines.collect::<Vec<_>>().join("\n")).unwrap();
+    assert_eq!(json["colors"][1]["role"], "background");
+    assert_eq!(json["export"], css.display().to_string());
+
+    app.config.hooks.export.pop();
+    app.run_command(Command::Export(Format::Css, Some(css.clone())));
+    wait(&mut app);
+    assert_eq!(app.status_bar_msg, "Hooks finished, 1 ran");
+    fs::remove_file(&css).unwrap();
+    fs::remove_file(&out).unwrap();
+}
+
+#[test]
+fn requests_on_the_socket_drive_the_app() {
+    let socket = env::temp_dir().join(format!("terminal-palette-{}.sock", process::id()));
+    let mut app = app();
+    app.ipc = Some(ipc::Server::bind(&socket).unwrap());
+    assert!(
+        ipc::Server::bind(&socket).is_err(),
+        "one instance per socket"
+    );
+
+    let client = {
+        let socket = socket.clone();
+        std::thread::spawn(move || {
+            [
+                "palette #1E1E2E, #CDD6F4 and rgb(243 139 168)",
+                "lock",
+                "generate",
+                "get",
+                "palette #FFFFFF",
+                "theory nonsense",
+            ]
+            .map(|request| ipc::send(&socket, request).unwrap())
+        })
+    };
+    while !client.is_finished() {
+        app.answer_requests(Duration::from_millis(10));
+    }
+    let replies = client.join().unwrap();
+
+    assert_eq!(replies[0], "ok #1E1E2E #CDD6F4 #F38BA8");
+    assert!(replies[2].starts_with("ok #1E1E2E "), "{}", replies[2]);
+    assert_eq!(replies[3], format!("ok {}", app.palette_hexes().join(" ")));
+    assert_eq!(replies[4], "error a palette takes 3 to 32 colors, got 1");
+    assert_eq!(replies[5], "error Can't use nonsense here");
+
+    // The socket goes away with the app
+    drop(app);
+    assert!(!socket.exists());
+    assert!(ipc::send(&socket, "get").is_err());
+}
+
+#[test]
+fn quit_on_the_socket_is_answered_before_the_daemon_exits() {
+    let socket = env::temp_dir().join(format!("terminal-palette-quit-{}.sock", process::id()));
+    let mut app = app();
+    app.ipc = Some(ipc::Server::bind(&socket).unwrap());
+    let client = {
+        let socket = socket.clone();
+        std::thread::spawn(move || ipc::send(&socket, "quit"))
+    };
+
+    app.serve().unwrap();
+    drop(app);
+    let reply = client.join().unwrap().unwrap();
+    assert!(reply.starts_with("ok"), "{reply}");
+}
+
+#[test]
+fn applied_palettes_are_announced_in_the_state_file() {
+    let mut app = app();
+    app.apply_color_list("#1E1E2E\n#CDD6F4\n#F38BA8\n");
+    app.color_blocks[0].role = Some(Role::Background);
+    assert_eq!(app.handle_request("apply"), "ok #1E1E2E #CDD6F4 #F38BA8");
+    assert!(app.terminal_recolored);
+    // Off by default
+    assert!(!announce::state_path().exists());
+
+    app.config.announce = true;
+    app.handle_request("apply");
+    let written = fs::read_to_string(announce::state_path()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&written).unwrap();
+    assert_eq!(json["event"], "apply");
+    assert_eq!(json["colors"][0]["hex"], "#1E1E2E");
+    assert_eq!(json["colors"][0]["role"], "background");
+    assert_eq!(json["colors"][2]["hex"], "#F38BA8");
+    fs::remove_file(announce::state_path()).unwrap();
+}
+
+#[test]
+fn applying_recolors_the_listed_terminals_too() {
+    let dir = env::temp_dir().join(format!("terminal-palette-{}-ttys", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for tty in ["1", "2", "ptmx"] {
+        fs::write(dir.join(tty), "").unwrap();
+    }
+    let wait = |app: &mut App| {
+        for _ in 0..500 {
+            app.poll_tasks();
+            if !app.tasks.is_busy() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    };
+
+    let mut app = app();
+    app.apply_color_list("#1E1E2E\n#CDD6F4\n#F38BA8\n");
+    app.config.apply_targets = vec![dir.join("*")];
+    app.handle_request("apply");
+    wait(&mut app);
+    assert_eq!(app.status_bar_msg, "Other terminals recolored: 2");
+    let applied = "\x1b]4;0;rgb:1e/1e/2e\x07\x1b]4;1;rgb:cd/d6/f4\x07\x1b]4;2;rgb:f3/8b/a8\x07";
+    assert_eq!(fs::read_to_string(dir.join("2")).unwrap(), applied);
+    assert_eq!(fs::read_to_string(dir.join("ptmx")).unwrap(), "");
+
+    // Terminals listed by name must be there, the rest still get their colors
+    let missing = dir.join("gone").join("3");
+    app.config.apply_targets.push(missing.clone());
+    app.handle_request("apply");
+    wait(&mut app);
+    assert!(
+        app.status_bar_msg.starts_with(&format!(
+            "Recoloring other terminals failed: 1 of 3 terminals failed, first: {}: ",
+            missing.display()
+        )),
+        "{}",
+        app.status_bar_msg
+    );
+
+    app.restore_terminals().unwrap();
+    let written = fs::read_to_string(dir.join("1")).unwrap();
+    assert_eq!(written, format!("{applied}{applied}\x1b]104\x07"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn the_schedule_tells_day_from_night() {
+    use chrono::{FixedOffset, TimeZone};
+
+    let berlin = FixedOffset::east_opt(2 * 3600).unwrap();
+    let at = |hour, minute| {
+        berlin
+            .with_ymd_and_hms(2026, 6, 21, hour, minute, 0)
+            .unwrap()
+    };
+    let mut schedule = config::Schedule::default();
+    assert!(!schedule.is_day(&at(6, 59)).unwrap());
+    assert!(schedule.is_day(&at(7, 0)).unwrap());
+    assert!(!schedule.is_day(&at(19, 0)).unwrap());
+
+    // By the sun, which rises at 4:43 and sets at 21:33 there
+    schedule.latitude = Some(52.52);
+    schedule.longitude = Some(13.405);
+    assert!(!schedule.is_day(&at(4, 30)).unwrap());
+    assert!(schedule.is_day(&at(5, 0)).unwrap());
+    assert!(schedule.is_day(&at(21, 0)).unwrap());
+    assert!(!schedule.is_day(&at(22, 0)).unwrap());
+
+    schedule.latitude = None;
+    schedule.night_starts = String::from("7pm");
+    assert!(schedule.is_day(&at(12, 0)).is_err());
+}
+
+#[test]
+fn the_daemon_applies_the_scheduled_palette() {
+    let mut app = app();
+    app.config.schedule.light = Some(String::from("sunset"));
+    app.config.schedule.dark = Some(String::from("mint"));
+    // A night without end
+    app.config.schedule.day_starts = String::from("00:00");
+    app.config.schedule.night_starts = String::from("00:00");
+
+    app.tick_schedule();
+    assert_eq!(
+        app.palette_hexes(),
+        ["#2A9D8F", "#E9F5DB", "#264653", "#F4A261"]
+    );
+    assert!(app.terminal_recolored);
+    assert_eq!(app.schedule.1, Some(false));
+
+    // Palettes set in between stay until the day begins
+    app.apply_color_list("#000000\n#808080\n#FFFFFF\n");
+    app.schedule.0 = None;
+    app.tick_schedule();
+    assert_eq!(app.palette_hexes(), ["#000000", "#808080", "#FFFFFF"]);
+
+    app.config.schedule.dark = Some(String::from("no such palette"));
+    app.schedule = (None, None);
+    app.tick_schedule();
+    assert!(
+        app.status_bar_msg
+            .starts_with("Couldn't apply the scheduled palette no such palette: "),
+        "{}",
+        app.status_bar_msg
+    );
+}
+
+#[test]
+fn d_switches_between_the_light_and_dark_palettes() {
+    assert_eq!(
+        appearance::from_color_scheme("'prefer-dark'\n"),
+        Some(Appearance::Dark)
+    );
+    assert_eq!(appearance::from_color_scheme("'default'"), None);
+    assert_eq!(appearance::from_colorfgbg("0;15"), Some(Appearance::Light));
+    assert_eq!(
+        appearance::from_colorfgbg("15;default;0"),
+        Some(Appearance::Dark)
+    );
+    assert_eq!(appearance::from_colorfgbg("default"), None);
+
+    let mut app = app();
+    press(&mut app, KeyCode::Char('D'));
+    assert_eq!(
+        app.status_bar_msg,
+        "Name a light and a dark palette in the [schedule] of the config first"
+    );
+
+    app.config.schedule.light = Some(String::from("sunset"));
+    app.config.schedule.dark = Some(String::from("mint"));
+    app.switch_appearance(Appearance::Dark);
+    assert_eq!(app.status_bar_msg, "Switched to the dark palette mint");
+    assert_eq!(
+        app.palette_hexes(),
+        ["#2A9D8F", "#E9F5DB", "#264653", "#F4A261"]
+    );
+
+    press(&mut app, KeyCode::Char('D'));
+    assert_eq!(app.appearance, Some(Appearance::Light));
+    assert_eq!(app.palette_hexes(), ["#E07A5F", "#3D405B", "#81B29A"]);
+    press(&mut app, KeyCode::Char('D'));
+    assert_eq!(app.appearance, Some(Appearance::Dark));
+}
+
+#[test]
+fn blocks_leave_their_color_to_images_where_the_terminal_shows_them() {
+    assert_eq!(
+        graphics::from_env("xterm-kitty", "", false),
+        Some(Protocol::Kitty)
+    );
+    assert_eq!(graphics::from_env("foot", "", false), Some(Protocol::Sixel));
+    assert_eq!(graphics::from_env("tmux-256color", "WezTerm", false), None);
+    assert_eq!(graphics::from_env("xterm-256color", "", false), None);
+
+    let mut app = app();
+    app.graphics = Some(Protocol::Sixel);
+    let backend = render(&mut app);
+    assert_eq!(app.rasters.len(), app.color_blocks.len());
+    // The swatch sits inside the selected block's border, above its text
+    let first = &app.rasters[0];
+    assert_eq!(first.color, app.color_blocks[0].get_rgb_values());
+    assert_eq!((first.area.x, first.area.y), (2, 2));
+    let cell = &backend.buffer()[(first.area.x, first.area.y)];
+    assert_eq!(cell.bg, Color::Reset);
+
+    // Popups cover the blocks, which go back to cells under them
+    press(&mut app, KeyCode::Char('t'));
+    let backend = render(&mut app);
+    assert!(app.rasters.is_empty());
+    let (r, g, b) = app.color_blocks[0].get_rgb_values();
+    assert_eq!(backend.buffer()[(2, 2)].bg, Color::Rgb(r, g, b));
+
+    // Two bands of red sixels, the corner pixels of the rounding left out
+    let image = graphics::rounded_swatch((255, 0, 0), 24, 12);
+    assert_eq!(
+        graphics::sixel(&image),
+        "\x1bP0;1;0q\"1;1;24;12#0;2;100;0;0#0}!22~}$-#0^!22~^$-\x1b\\"
+    );
+}
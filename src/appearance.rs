@@ -0,0 +1,104 @@
+//! The system's light or dark preference, so a session can start with the matching one of
+//! the light and dark palettes in `[schedule]`.
+//!
+//! Asked of `defaults` on macOS, the registry on Windows and `gsettings` elsewhere (GNOME
+//! and most desktops following the freedesktop color-scheme key). Where none of them
+//! answers, the terminal's own background as `$COLORFGBG` tells, if it is set.
+
+use std::{env, process::Command};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Appearance {
+    Light,
+    Dark,
+}
+
+impl Appearance {
+    pub fn toggled(self) -> Self {
+        match self {
+            Appearance::Light => Appearance::Dark,
+            Appearance::Dark => Appearance::Light,
+        }
+    }
+
+    /// Stable lowercase name, for logs and messages
+    pub fn key(self) -> &'static str {
+        match self {
+            Appearance::Light => "light",
+            Appearance::Dark => "dark",
+        }
+    }
+}
+
+/// The system's preference, `None` when it can't be found out
+pub fn detect() -> Option<Appearance> {
+    system().or_else(|| {
+        env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| from_colorfgbg(&value))
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn system() -> Option<Appearance> {
+    // Only set in dark mode, reading it fails in light mode
+    let output = Command::new("defaults")
+        .args(["read", "-g", "AppleInterfaceStyle"])
+        .output()
+        .ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "Dark" => Some(Appearance::Dark),
+        _ => Some(Appearance::Light),
+    }
+}
+
+#[cfg(windows)]
+fn system() -> Option<Appearance> {
+    let output = Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+            "/v",
+            "AppsUseLightTheme",
+        ])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let value = text.split_whitespace().last()?;
+    match value {
+        "0x0" => Some(Appearance::Dark),
+        "0x1" => Some(Appearance::Light),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn system() -> Option<Appearance> {
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    from_color_scheme(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The freedesktop `color-scheme` setting as `gsettings` prints it, like `'prefer-dark'`;
+/// `'default'` says nothing either way
+pub fn from_color_scheme(value: &str) -> Option<Appearance> {
+    match value.trim().trim_matches('\'') {
+        "prefer-dark" => Some(Appearance::Dark),
+        "prefer-light" => Some(Appearance::Light),
+        _ => None,
+    }
+}
+
+/// `$COLORFGBG`, the foreground and background ANSI colors some terminals announce like
+/// `15;0`: a background of white (7) or bright white (15) is light, anything else dark
+pub fn from_colorfgbg(value: &str) -> Option<Appearance> {
+    let background: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    match background {
+        7 | 15 => Some(Appearance::Light),
+        0..=15 => Some(Appearance::Dark),
+        _ => None,
+    }
+}
@@ -0,0 +1,101 @@
+//! How a palette's hues spread between warm and cool.
+//!
+//! [`analyze`] splits the palette into warm hues (red-violet through yellow), cool hues
+//! (green through violet) and neutrals without a hue worth counting, each block weighted by
+//! its width, and counts the hues into a histogram around the wheel.
+//!
+//! ```
+//! use terminal_palette::balance::{self, Lean};
+//! use terminal_palette::color::ColorBlock;
+//!
+//! let blocks = [
+//!     ColorBlock::new(1, 15.0, 0.7, 0.9),
+//!     ColorBlock::new(2, 40.0, 0.6, 0.9),
+//!     ColorBlock::new(3, 55.0, 0.8, 0.95),
+//!     ColorBlock::new(4, 210.0, 0.5, 0.6),
+//!     ColorBlock::new(5, 0.0, 0.0, 0.95),
+//! ];
+//! let balance = balance::analyze(&blocks);
+//! assert_eq!((balance.warm, balance.cool, balance.neutral), (60.0, 20.0, 20.0));
+//! assert_eq!(balance.lean(), Lean::Warm);
+//! ```
+
+use crate::color::ColorBlock;
+
+/// Bins of [`Balance::histogram`], each spanning 360° / `HUE_BINS` starting at red
+pub const HUE_BINS: usize = 12;
+/// Warm hues start at this angle and wrap around red up to [`WARM_END`]
+pub const WARM_START: f32 = 330.0;
+pub const WARM_END: f32 = 90.0;
+/// Below this saturation or value a color counts as neutral
+const NEUTRAL_SATURATION: f32 = 0.1;
+const NEUTRAL_VALUE: f32 = 0.1;
+/// Share of the hued colors one side needs before the palette leans its way
+const LEAN_SHARE: f32 = 0.7;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Balance {
+    /// Percentages of the palette's width, adding up to 100 unless the palette is empty
+    pub warm: f32,
+    pub cool: f32,
+    pub neutral: f32,
+    /// Width of the hued blocks per [`HUE_BINS`] slice of the wheel
+    pub histogram: [u32; HUE_BINS],
+}
+
+/// Which way the hued colors of a palette tip
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lean {
+    Warm,
+    Cool,
+    Even,
+    /// Nothing but neutrals
+    NoHues,
+}
+
+impl Balance {
+    pub fn lean(&self) -> Lean {
+        let hued = self.warm + self.cool;
+        match hued {
+            0.0 => Lean::NoHues,
+            _ if self.warm / hued >= LEAN_SHARE => Lean::Warm,
+            _ if self.cool / hued >= LEAN_SHARE => Lean::Cool,
+            _ => Lean::Even,
+        }
+    }
+}
+
+/// Whether `hue` (degrees, 0-360) reads as warm
+pub fn is_warm(hue: f32) -> bool {
+    !(WARM_END..WARM_START).contains(&hue)
+}
+
+pub fn analyze(color_blocks: &[ColorBlock]) -> Balance {
+    let (mut warm, mut cool, mut neutral) = (0u32, 0u32, 0u32);
+    let mut histogram = [0; HUE_BINS];
+    for block in color_blocks {
+        let (hue, saturation, value) = block.get_hsv_values();
+        let width = u32::from(block.width);
+        if saturation < NEUTRAL_SATURATION || value < NEUTRAL_VALUE {
+            neutral += width;
+            continue;
+        }
+        let hue = hue.rem_euclid(360.0);
+        if is_warm(hue) {
+            warm += width;
+        } else {
+            cool += width;
+        }
+        let bin = (hue / (360.0 / HUE_BINS as f32)) as usize;
+        histogram[bin.min(HUE_BINS - 1)] += width;
+    }
+
+    let total = (warm + cool + neutral).max(1) as f32;
+    let percent = |part: u32| part as f32 * 100.0 / total;
+    Balance {
+        warm: percent(warm),
+        cool: percent(cool),
+        neutral: percent(neutral),
+        histogram,
+    }
+}
@@ -0,0 +1,161 @@
+//! Mixing colors and whole palettes in OKLab, and the rest of the small color algebra.
+//!
+//! Mixing in OKLab keeps the in-between colors looking in between: a blend of blue and
+//! yellow passes through a neutral instead of a muddy green or a detour through pink, and
+//! lightness changes evenly along the way. [`additive`] and [`multiply`] instead model
+//! light and ink: overlapping lights add up in linear RGB, overlapping inks multiply.
+//! [`BlendMode`] layers one color over another the way image editors do.
+//!
+//! ```
+//! use terminal_palette::blend;
+//!
+//! let old = vec![String::from("#1D3557"), String::from("#E63946")];
+//! let new = vec![String::from("#264653"), String::from("#E9C46A")];
+//! let halfway = blend::palettes(&old, &new, 0.5);
+//! assert_eq!(halfway.len(), 2);
+//! assert_eq!(blend::palettes(&old, &new, 0.0), old);
+//! ```
+
+use palette::{Clamp, FromColor, LinSrgb, Mix, Oklab, Srgb};
+use strum_macros::EnumIter;
+
+use crate::convert;
+
+/// `a` and `b` (hex strings) mixed in OKLab: `ratio` 0 is all `a`, 1 is all `b`
+pub fn mix_hex(a: &str, b: &str, ratio: f32) -> String {
+    to_hex(Srgb::from_color(
+        oklab(a).mix(oklab(b), ratio.clamp(0.0, 1.0)),
+    ))
+}
+
+/// Average of hex colors in OKLab, each counting `weight` times; `None` if no weight is
+/// positive
+pub fn weighted_average(colors: &[(&str, f32)]) -> Option<String> {
+    let total: f32 = colors.iter().map(|(_, weight)| weight.max(0.0)).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let sum = colors
+        .iter()
+        .fold(Oklab::new(0.0, 0.0, 0.0), |sum, (hex, weight)| {
+            sum + oklab(hex) * (weight.max(0.0) / total)
+        });
+    Some(to_hex(Srgb::from_color(sum)))
+}
+
+/// The colors added as lights in linear RGB, clipped at white; `None` for no colors
+pub fn additive(colors: &[&str]) -> Option<String> {
+    let sum = colors
+        .iter()
+        .map(|hex| LinSrgb::from_color(srgb(hex)))
+        .reduce(|a, b| a + b)?;
+    Some(to_hex(Srgb::from_linear(sum)))
+}
+
+/// The colors multiplied channel by channel, like inks or the multiply blend mode;
+/// `None` for no colors
+pub fn multiply(colors: &[&str]) -> Option<String> {
+    let product = colors.iter().map(|hex| srgb(hex)).reduce(|a, b| a * b)?;
+    Some(to_hex(product))
+}
+
+/// Layer blend modes, per channel in sRGB as in image editors and CSS `mix-blend-mode`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum BlendMode {
+    /// Always darker: inks on top of each other
+    Multiply,
+    /// Always lighter: the inverse of multiplying the inverses
+    Screen,
+    /// Multiply in the base's shadows, screen in its highlights
+    Overlay,
+    /// A gentler overlay led by the top layer, per the W3C compositing spec
+    SoftLight,
+}
+
+impl BlendMode {
+    /// Identifier used in message ids, e.g. `soft-light`
+    pub fn key(self) -> &'static str {
+        match self {
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::SoftLight => "soft-light",
+        }
+    }
+
+    /// `top` layered over `base` (hex strings) in this mode
+    ///
+    /// ```
+    /// use terminal_palette::blend::BlendMode;
+    ///
+    /// assert_eq!(BlendMode::Multiply.apply("#FFFFFF", "#3D405B"), "#3D405B");
+    /// assert_eq!(BlendMode::Screen.apply("#000000", "#3D405B"), "#3D405B");
+    /// ```
+    pub fn apply(self, base: &str, top: &str) -> String {
+        let (base, top) = (srgb(base), srgb(top));
+        to_hex(Srgb::new(
+            self.channel(base.red, top.red),
+            self.channel(base.green, top.green),
+            self.channel(base.blue, top.blue),
+        ))
+    }
+
+    fn channel(self, base: f32, top: f32) -> f32 {
+        match self {
+            BlendMode::Multiply => base * top,
+            BlendMode::Screen => base + top - base * top,
+            BlendMode::Overlay if base <= 0.5 => 2.0 * base * top,
+            BlendMode::Overlay => 1.0 - 2.0 * (1.0 - base) * (1.0 - top),
+            BlendMode::SoftLight if top <= 0.5 => base - (1.0 - 2.0 * top) * base * (1.0 - base),
+            BlendMode::SoftLight => {
+                let d = if base <= 0.25 {
+                    ((16.0 * base - 12.0) * base + 4.0) * base
+                } else {
+                    base.sqrt()
+                };
+                base + (2.0 * top - 1.0) * (d - base)
+            }
+        }
+    }
+}
+
+/// `top` at `alpha` opacity over an opaque `background` (hex strings), composited in sRGB
+/// like browsers do
+pub fn composite(top: &str, alpha: f32, background: &str) -> String {
+    to_hex(srgb(background).mix(srgb(top), alpha.clamp(0.0, 1.0)))
+}
+
+/// Slot-wise blend of two palettes of hex colors, see [`mix_hex`]. Slots past the end of
+/// the shorter palette keep the longer one's color.
+pub fn palettes(a: &[String], b: &[String], ratio: f32) -> Vec<String> {
+    (0..a.len().max(b.len()))
+        .map(|slot| match (a.get(slot), b.get(slot)) {
+            (Some(a), Some(b)) => mix_hex(a, b, ratio),
+            (Some(only), None) | (None, Some(only)) => only.to_uppercase(),
+            (None, None) => unreachable!("slot is below the longer length"),
+        })
+        .collect()
+}
+
+/// `count` ratios spaced evenly strictly between 0 and 1: 3 gives 0.25, 0.5 and 0.75
+pub fn intermediate_ratios(count: usize) -> Vec<f32> {
+    (1..=count)
+        .map(|step| step as f32 / (count + 1) as f32)
+        .collect()
+}
+
+fn srgb(hex: &str) -> Srgb {
+    let (r, g, b) = convert::parse_hex(hex).unwrap_or((0, 0, 0));
+    Srgb::new(r, g, b).into_format()
+}
+
+fn oklab(hex: &str) -> Oklab {
+    Oklab::from_color(srgb(hex))
+}
+
+/// Hex of `color`, clipped into sRGB first
+pub(crate) fn to_hex(color: Srgb) -> String {
+    let rgb: Srgb<u8> = color.clamp().into_format();
+    convert::rgb_to_hex(rgb.red, rgb.green, rgb.blue)
+}
@@ -0,0 +1,245 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use terminal_palette::export::Format;
+use terminal_palette::extract;
+use terminal_palette::library::ConflictStrategy;
+
+/// Generate and pick color palettes from the terminal
+#[derive(Debug, Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// A `.tpal` project or a saved palette `.json` to open
+    pub file: Option<PathBuf>,
+
+    /// Append debug logs (actions, generation parameters, errors) to this file;
+    /// `RUST_LOG` overrides the default level
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Start in the slideshow, regenerating on a timer until a key is pressed
+    #[arg(long)]
+    pub slideshow: bool,
+
+    /// Validate palette files and exit, failing if any is broken; `.palette` files must
+    /// also be written exactly as the app writes them. For CI and pre-commit hooks
+    #[arg(long, value_name = "FILE", num_args = 1..)]
+    pub check: Vec<PathBuf>,
+
+    /// Run without a UI, answering requests on the socket until one asks to quit
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// Also answer requests on the socket while the app runs
+    #[arg(long, conflicts_with = "daemon")]
+    pub listen: bool,
+
+    /// Socket to listen on or send to, instead of `$XDG_RUNTIME_DIR/terminal-palette.sock`
+    #[arg(long, global = true, value_name = "PATH")]
+    pub socket: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print a color as hex, RGB and HSL; accepts anything the edit color popup does
+    Color { color: String },
+    /// Move the saved palette library between machines
+    #[command(subcommand)]
+    Library(LibraryCommand),
+    /// Inspect and run Lua plugins
+    #[command(subcommand)]
+    Plugin(PluginCommand),
+    /// Export a saved palette or `.tpal` project in a built-in format
+    Export {
+        #[arg(value_enum)]
+        format: ExportFormat,
+        path: PathBuf,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Width and height of a PNG in pixels, like `1600x400`; defaults to `png_width`
+        /// and `png_height` in `[export]`
+        #[arg(long, value_parser = parse_size)]
+        size: Option<(u32, u32)>,
+    },
+    /// Print the colors of a GIF frame with the share of the picture each covers
+    Extract {
+        /// A GIF file or an `http(s)` URL of one
+        source: String,
+        /// Where in an animation to take the frame, in seconds, `m:ss` or `h:mm:ss`
+        #[arg(long, value_parser = parse_time, default_value = "0")]
+        at: Duration,
+        /// Most colors to take
+        #[arg(short = 'n', long, default_value_t = 5)]
+        colors: usize,
+    },
+    /// Report every color written in the files below a directory that isn't in a palette,
+    /// as `file:line:column`; fails if there is any
+    Lint {
+        /// Directory (or single file) to check
+        path: PathBuf,
+        /// Saved palette, `.tpal` project or `.palette` file holding the allowed colors
+        #[arg(short, long)]
+        palette: PathBuf,
+        /// Colors at most this far (ΔE) from a palette color count as that color
+        #[arg(long, default_value_t = 0.0)]
+        tolerance: f32,
+    },
+    /// Write a patch replacing the hex codes in the CSS, SCSS and Sass files below a
+    /// directory with the palette's variables, for review before `git apply`
+    Rewrite {
+        /// Directory (or single file) to rewrite
+        path: PathBuf,
+        /// Saved palette, `.tpal` project or `.palette` file whose variables to use
+        #[arg(short, long)]
+        palette: PathBuf,
+        /// Replace colors at most this far (ΔE) from a palette color too
+        #[arg(long, default_value_t = 0.0)]
+        tolerance: f32,
+        /// Write the patch to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Send a request to an instance started with `--daemon` or `--listen` and print the
+    /// reply, e.g. `get`, `generate` or `palette #1E1E2E #CDD6F4 #F38BA8`
+    Send {
+        #[arg(required = true, num_args = 1..)]
+        request: Vec<String>,
+    },
+    /// Theme everything after a wallpaper: build an ANSI scheme around its colors and
+    /// write every `theme_exports` file of the config
+    Theme {
+        /// A GIF file or an `http(s)` URL of one
+        wallpaper: String,
+        /// Also recolor this terminal's 16 ANSI colors
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+fn parse_size(text: &str) -> Result<(u32, u32), String> {
+    text.split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .filter(|&(width, height)| width > 0 && height > 0)
+        .ok_or_else(|| format!("{text:?} isn't a size such as 1200x300"))
+}
+
+fn parse_time(text: &str) -> Result<Duration, String> {
+    extract::parse_time(text).ok_or_else(|| format!("{text:?} isn't a time such as 12.5 or 1:02"))
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PluginCommand {
+    /// List installed plugins and the hooks they provide
+    List,
+    /// Export a saved palette or `.tpal` project with a plugin's exporter
+    Export {
+        /// Plugin name as shown by `plugin list`
+        plugin: String,
+        path: PathBuf,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LibraryCommand {
+    /// Write every saved palette and favorite into a single JSON bundle
+    Export { path: PathBuf },
+    /// Merge a bundle written by `library export` into the local library
+    Import {
+        path: PathBuf,
+        /// What to do when a palette with the same name but different colors exists
+        #[arg(long, value_enum, default_value_t = OnConflict::Rename)]
+        on_conflict: OnConflict,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Css,
+    /// SCSS variables and a map of them, named after `scss_prefix` in `[export]`
+    Scss,
+    /// CSS commented with each color's share and use (60-30-10)
+    ProportionsCss,
+    /// SVG swatch strip weighted by each color's share
+    ProportionsSvg,
+    /// A CSS `linear-gradient` per gradient of a project, or one through all the colors
+    GradientCss,
+    /// The same gradients as SVG `<linearGradient>` stripes
+    GradientSvg,
+    /// The first gradient as a GIMP `.ggr` gradient
+    Ggr,
+    /// Figma variables JSON with a light and a dark mode
+    Figma,
+    /// A `.sketchpalette` for the Sketch Palettes plugin
+    Sketch,
+    /// An Adobe Color Swatch `.aco` file for Affinity and Photoshop; binary
+    Aco,
+    /// A Unity `.colors` color preset library
+    Unity,
+    /// A Godot `ColorPalette` resource
+    Godot,
+    /// Palette JSON for scripts, which opens again like a saved palette
+    Json,
+    /// A Markdown table with an HTML swatch per color, for wikis and issues
+    Markdown,
+    /// The same table for Org mode
+    Org,
+    /// A GIMP `.gpl` palette, which Krita and Inkscape read too
+    Gpl,
+    /// An Adobe Swatch Exchange `.ase` file for Photoshop and Illustrator; binary
+    Ase,
+    /// A PNG strip of the swatches with their hex codes, sized by `--size`
+    Png,
+    /// An SVG of the swatches labeled with their names and hex codes
+    Svg,
+}
+
+impl From<ExportFormat> for Format {
+    fn from(value: ExportFormat) -> Self {
+        match value {
+            ExportFormat::Css => Format::Css,
+            ExportFormat::Scss => Format::Scss,
+            ExportFormat::ProportionsCss => Format::ProportionsCss,
+            ExportFormat::ProportionsSvg => Format::ProportionsSvg,
+            ExportFormat::GradientCss => Format::GradientCss,
+            ExportFormat::GradientSvg => Format::GradientSvg,
+            ExportFormat::Ggr => Format::Ggr,
+            ExportFormat::Figma => Format::Figma,
+            ExportFormat::Sketch => Format::Sketch,
+            ExportFormat::Aco => Format::Aco,
+            ExportFormat::Unity => Format::Unity,
+            ExportFormat::Godot => Format::Godot,
+            ExportFormat::Json => Format::Json,
+            ExportFormat::Markdown => Format::Markdown,
+            ExportFormat::Org => Format::Org,
+            ExportFormat::Gpl => Format::Gpl,
+            ExportFormat::Ase => Format::Ase,
+            ExportFormat::Png => Format::Png,
+            ExportFormat::Svg => Format::Svg,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum OnConflict {
+    Keep,
+    Replace,
+    Rename,
+}
+
+impl From<OnConflict> for ConflictStrategy {
+    fn from(value: OnConflict) -> Self {
+        match value {
+            OnConflict::Keep => ConflictStrategy::Keep,
+            OnConflict::Replace => ConflictStrategy::Replace,
+            OnConflict::Rename => ConflictStrategy::Rename,
+        }
+    }
+}
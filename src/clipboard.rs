@@ -0,0 +1,127 @@
+//! Clipboard backends, picked at startup so a missing display server never crashes the app.
+//!
+//! `TERMINAL_PALETTE_CLIPBOARD=arboard|osc52|none` forces a backend; otherwise the system
+//! clipboard is used when reachable, OSC 52 over SSH or as a fallback, and nothing as a
+//! last resort. Builds without the `clipboard` feature have no system clipboard, so
+//! `arboard` falls back to nothing and detection starts at OSC 52.
+
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+};
+
+#[cfg(feature = "clipboard")]
+use arboard::Clipboard;
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+pub trait ClipboardProvider {
+    fn set_text(&mut self, text: &str) -> io::Result<()>;
+
+    /// What the clipboard holds now, for watching it for colors
+    fn get_text(&mut self) -> io::Result<String>;
+
+    /// Short backend name for status messages
+    fn name(&self) -> &'static str;
+}
+
+/// The desktop clipboard (X11, Wayland, macOS, Windows)
+#[cfg(feature = "clipboard")]
+pub struct SystemClipboard(Clipboard);
+
+#[cfg(feature = "clipboard")]
+impl ClipboardProvider for SystemClipboard {
+    fn set_text(&mut self, text: &str) -> io::Result<()> {
+        self.0.set_text(text).map_err(io::Error::other)
+    }
+
+    fn get_text(&mut self) -> io::Result<String> {
+        self.0.get_text().map_err(io::Error::other)
+    }
+
+    fn name(&self) -> &'static str {
+        "system"
+    }
+}
+
+/// Asks the terminal to set the clipboard via the OSC 52 escape sequence,
+/// which also works through SSH and tmux (with `set-clipboard on`)
+pub struct Osc52Clipboard;
+
+impl ClipboardProvider for Osc52Clipboard {
+    fn set_text(&mut self, text: &str) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+        stdout.flush()
+    }
+
+    /// Reading back takes a reply from the terminal, which few of them send
+    fn get_text(&mut self) -> io::Result<String> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "OSC 52 can only write the clipboard",
+        ))
+    }
+
+    fn name(&self) -> &'static str {
+        "osc52"
+    }
+}
+
+/// Used when no clipboard is reachable; copying reports an error instead of panicking
+pub struct NoClipboard;
+
+impl ClipboardProvider for NoClipboard {
+    fn set_text(&mut self, _text: &str) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no clipboard available",
+        ))
+    }
+
+    fn get_text(&mut self) -> io::Result<String> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no clipboard available",
+        ))
+    }
+
+    fn name(&self) -> &'static str {
+        "none"
+    }
+}
+
+/// The best clipboard backend for this environment
+pub fn detect() -> Box<dyn ClipboardProvider> {
+    match env::var("TERMINAL_PALETTE_CLIPBOARD").as_deref() {
+        Ok("arboard") => return system_or_none(),
+        Ok("osc52") => return Box::new(Osc52Clipboard),
+        Ok("none") => return Box::new(NoClipboard),
+        _ => {}
+    }
+
+    let over_ssh = env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some();
+    if !over_ssh && let Some(clipboard) = system() {
+        return clipboard;
+    }
+
+    if io::stdout().is_terminal() && env::var("TERM").is_ok_and(|term| term != "dumb") {
+        Box::new(Osc52Clipboard)
+    } else {
+        Box::new(NoClipboard)
+    }
+}
+
+fn system_or_none() -> Box<dyn ClipboardProvider> {
+    system().unwrap_or_else(|| Box::new(NoClipboard))
+}
+
+#[cfg(feature = "clipboard")]
+fn system() -> Option<Box<dyn ClipboardProvider>> {
+    let clipboard = Clipboard::new().ok()?;
+    Some(Box::new(SystemClipboard(clipboard)))
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn system() -> Option<Box<dyn ClipboardProvider>> {
+    None
+}
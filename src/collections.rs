@@ -0,0 +1,157 @@
+//! Built-in collections of named colors to start a palette from.
+//!
+//! The CSS named colors are always there, since [`convert::parse_color`] knows them anyway.
+//! The others come with the `collections` feature (on by default): the most common names
+//! of the xkcd color survey, the classic 16-crayon box and a selection of Japanese
+//! traditional colors.
+
+use crate::convert;
+
+/// A named set of colors, each as a name and `0xRRGGBB`
+#[derive(Debug)]
+pub struct Collection {
+    pub name: &'static str,
+    pub colors: &'static [(&'static str, u32)],
+}
+
+impl Collection {
+    /// The color at `index` as a `#RRGGBB` hex string
+    pub fn hex(&self, index: usize) -> Option<String> {
+        self.colors
+            .get(index)
+            .map(|(_, rgb)| convert::rgb_to_hex((rgb >> 16) as u8, (rgb >> 8) as u8, *rgb as u8))
+    }
+}
+
+/// Every collection built in, CSS first
+pub const COLLECTIONS: &[Collection] = &[
+    Collection {
+        name: "CSS",
+        colors: &convert::NAMED_COLORS,
+    },
+    #[cfg(feature = "collections")]
+    Collection {
+        name: "xkcd",
+        colors: &XKCD,
+    },
+    #[cfg(feature = "collections")]
+    Collection {
+        name: "Crayons",
+        colors: &CRAYONS,
+    },
+    #[cfg(feature = "collections")]
+    Collection {
+        name: "Nippon",
+        colors: &NIPPON,
+    },
+];
+
+/// The most common answers of the xkcd color survey (public domain), most common first
+#[cfg(feature = "collections")]
+const XKCD: [(&str, u32); 49] = [
+    ("purple", 0x7E1E9C),
+    ("green", 0x15B01A),
+    ("blue", 0x0343DF),
+    ("pink", 0xFF81C0),
+    ("brown", 0x653700),
+    ("red", 0xE50000),
+    ("light blue", 0x95D0FC),
+    ("teal", 0x029386),
+    ("orange", 0xF97306),
+    ("light green", 0x96F97B),
+    ("magenta", 0xC20078),
+    ("yellow", 0xFFFF14),
+    ("sky blue", 0x75BBFD),
+    ("grey", 0x929591),
+    ("lime green", 0x89FE05),
+    ("light purple", 0xBF77F6),
+    ("violet", 0x9A0EEA),
+    ("dark green", 0x033500),
+    ("turquoise", 0x06C2AC),
+    ("lavender", 0xC79FEF),
+    ("dark blue", 0x00035B),
+    ("tan", 0xD1B26F),
+    ("cyan", 0x00FFFF),
+    ("aqua", 0x13EAC9),
+    ("forest green", 0x06470C),
+    ("mauve", 0xAE7181),
+    ("dark purple", 0x35063E),
+    ("bright green", 0x01FF07),
+    ("maroon", 0x650021),
+    ("olive", 0x6E750E),
+    ("salmon", 0xFF796C),
+    ("beige", 0xE6DAA6),
+    ("royal blue", 0x0504AA),
+    ("navy blue", 0x001146),
+    ("lilac", 0xCEA2FD),
+    ("black", 0x000000),
+    ("hot pink", 0xFF028D),
+    ("light brown", 0xAD8150),
+    ("pale green", 0xC7FDB5),
+    ("peach", 0xFFB07C),
+    ("olive green", 0x677A04),
+    ("dark pink", 0xCB416B),
+    ("periwinkle", 0x8E82FE),
+    ("sea green", 0x53FCA1),
+    ("lime", 0xAAFF32),
+    ("indigo", 0x380282),
+    ("mustard", 0xCEB301),
+    ("light pink", 0xFFD1DF),
+    ("white", 0xFFFFFF),
+];
+
+/// The classic 16-crayon box
+#[cfg(feature = "collections")]
+const CRAYONS: [(&str, u32); 16] = [
+    ("Black", 0x232323),
+    ("Blue", 0x1F75FE),
+    ("Blue Green", 0x0D98BA),
+    ("Blue Violet", 0x7366BD),
+    ("Brown", 0xB4674D),
+    ("Carnation Pink", 0xFFAACC),
+    ("Green", 0x1CAC78),
+    ("Orange", 0xFF7538),
+    ("Red", 0xEE204D),
+    ("Red Orange", 0xFF5349),
+    ("Red Violet", 0xC0448F),
+    ("Violet", 0x926EAE),
+    ("White", 0xEDEDED),
+    ("Yellow", 0xFCE883),
+    ("Yellow Green", 0xC5E384),
+    ("Yellow Orange", 0xFFB653),
+];
+
+/// Japanese traditional colors, reds through blues to the neutrals
+#[cfg(feature = "collections")]
+const NIPPON: [(&str, u32); 30] = [
+    ("Nadeshiko 撫子", 0xDC9FB4),
+    ("Kohbai 紅梅", 0xE16B8C),
+    ("Sakura 桜", 0xFEDFE1),
+    ("Ichigo 苺", 0xB5495B),
+    ("Karakurenai 韓紅花", 0xD0104C),
+    ("Enji 臙脂", 0x9F353A),
+    ("Akabeni 赤紅", 0xCB4042),
+    ("Sango 珊瑚", 0xF17C67),
+    ("Benihi 紅緋", 0xE83015),
+    ("Kuchiba 朽葉", 0xE2943B),
+    ("Yamabuki 山吹", 0xFFB11B),
+    ("Kuchinashi 梔子", 0xF6C555),
+    ("Hiwa 鶸", 0xBEC23F),
+    ("Moegi 萌黄", 0x7BA23F),
+    ("Wakatake 若竹", 0x5DAC81),
+    ("Tokiwa 常磐", 0x1B813E),
+    ("Byakuroku 白緑", 0xA8D8B9),
+    ("Seiji 青磁", 0x69B0AC),
+    ("Asagi 浅葱", 0x33A6B8),
+    ("Mizu 水", 0x81C7D4),
+    ("Sora 空", 0x58B2DC),
+    ("Wasurenagusa 勿忘草", 0x7DB9DE),
+    ("Ruri 瑠璃", 0x005CAF),
+    ("Ai 藍", 0x0D5661),
+    ("Kon 紺", 0x0F2540),
+    ("Kikyo 桔梗", 0x6A4C9C),
+    ("Fuji 藤", 0x8B81C3),
+    ("Kurumi 胡桃", 0x947A6D),
+    ("Sumi 墨", 0x1C1C1C),
+    ("Shironeri 白練", 0xFCFAF2),
+];
@@ -0,0 +1,286 @@
+//! The palette color model. The conversions themselves live in [`crate::convert`].
+
+use rand::{Rng, RngCore};
+
+use palette::{FromColor, Hsv, Oklch, RgbHue, Srgb};
+use serde::{Deserialize, Serialize};
+
+use crate::convert;
+use crate::gamut;
+use crate::roles::Role;
+
+/// Characters accepted in hex color input
+pub const HEX_CHARS: [char; 22] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'A', 'B', 'C', 'D', 'E', 'F', '0', '1', '2', '3', '4', '5', '6',
+    '7', '8', '9',
+];
+
+/// Lenient hex parse for colors that were validated already (`#` optional).
+/// Anything [`convert::parse_hex`] rejects becomes black; use that to report bad input.
+pub fn hex2rgb(hex: &str) -> (u8, u8, u8) {
+    convert::parse_hex(hex).unwrap_or((0, 0, 0))
+}
+
+/// Convert RGB to HSV as (hue in degrees, saturation 0-1, value 0-1), see [`convert::rgb_to_hsv`]
+pub fn rgb2hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    convert::rgb_to_hsv(r, g, b)
+}
+
+/// The one channel a partly locked block keeps through generation
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Freeze {
+    /// The hue; generators may change saturation and value
+    Hue,
+    /// The OKLCH lightness; generators may change hue and chroma
+    Lightness,
+}
+
+impl Freeze {
+    /// Identifier used in message ids, e.g. `lightness`
+    pub fn key(self) -> &'static str {
+        match self {
+            Freeze::Hue => "hue",
+            Freeze::Lightness => "lightness",
+        }
+    }
+}
+
+/// One color of a palette
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorBlock {
+    /// 1-based position in the palette, the number shown for the block; see
+    /// [`ColorBlock::renumber`]
+    pub block_id: usize,
+
+    pub hsv: Hsv,
+
+    pub label: String,
+
+    /// Free-text usage note, e.g. "only for destructive actions"
+    pub note: String,
+
+    /// Share of its row relative to the other blocks, 1 for an even split
+    pub width: u16,
+
+    pub locked: bool,
+
+    /// Channel kept through generation while the rest of an unlocked block changes
+    pub frozen: Option<Freeze>,
+
+    pub role: Option<Role>,
+
+    /// The OKLCH color asked for when it lay outside sRGB; the block holds it clipped
+    /// until [`ColorBlock::fit_gamut`] or any other change of color
+    pub out_of_gamut: Option<Oklch>,
+}
+
+impl ColorBlock {
+    pub fn new(block_id: usize, hue: f32, sat: f32, val: f32) -> Self {
+        let hue = RgbHue::from_degrees(hue);
+        let hsv: Hsv = Hsv::new(hue, sat, val);
+
+        Self {
+            block_id,
+
+            hsv,
+
+            label: String::new(),
+
+            note: String::new(),
+
+            width: 1,
+
+            locked: false,
+
+            frozen: None,
+
+            role: None,
+
+            out_of_gamut: None,
+        }
+    }
+
+    /// Give every block its position again after blocks were removed or moved
+    pub fn renumber(blocks: &mut [ColorBlock]) {
+        for (position, block) in blocks.iter_mut().enumerate() {
+            block.block_id = position + 1;
+        }
+    }
+
+    pub fn generate_random_color(&mut self) {
+        self.generate_random_color_with(&mut rand::rng());
+    }
+
+    /// [`generate_random_color`](Self::generate_random_color) drawing from `rng`
+    pub fn generate_random_color_with(&mut self, rng: &mut dyn RngCore) {
+        let hue = rng.random_range(0..360);
+        let sat = rng.random_range(50..90); // MIGHT GONNA EDIT THESE LATER
+        let val = rng.random_range(50..90);
+
+        self.change_color(hue as f32, sat as f32 / 100.0, val as f32 / 100.0);
+    }
+
+    pub fn change_color(&mut self, hue: f32, sat: f32, val: f32) {
+        let new_hue = RgbHue::from_degrees(hue);
+        let hsv: Hsv = Hsv::new(new_hue, sat, val);
+
+        self.hsv = hsv;
+        self.out_of_gamut = None;
+    }
+
+    /// Set an OKLCH color, clipping it channel by channel and remembering it in
+    /// [`ColorBlock::out_of_gamut`] if it lies outside sRGB
+    pub fn set_oklch(&mut self, color: Oklch) {
+        let (r, g, b) = gamut::clip(color);
+        let (hue, sat, val) = convert::rgb_to_hsv(r, g, b);
+        self.change_color(hue, sat, val);
+        self.out_of_gamut = (!gamut::in_gamut(color)).then_some(color);
+    }
+
+    /// Replace a clipped out-of-gamut color with [`gamut::fit`], which keeps its hue.
+    /// Returns whether there was one.
+    pub fn fit_gamut(&mut self) -> bool {
+        let Some(color) = self.out_of_gamut else {
+            return false;
+        };
+        self.set_oklch(gamut::fit(color));
+        true
+    }
+
+    pub fn get_rgb_values(&self) -> (u8, u8, u8) {
+        convert::hsv_to_rgb(
+            self.hsv.hue.into_positive_degrees(),
+            self.hsv.saturation,
+            self.hsv.value,
+        )
+    }
+
+    pub fn get_hsv_values(&self) -> (f32, f32, f32) {
+        let hue: f32 = self.hsv.hue.into_raw_degrees();
+        let saturation: f32 = self.hsv.saturation;
+        let value: f32 = self.hsv.value;
+
+        (hue, saturation, value)
+    }
+
+    /// The color in OKLCH, unclipped if it lies outside sRGB
+    pub fn get_oklch(&self) -> Oklch {
+        self.out_of_gamut.unwrap_or_else(|| {
+            let (r, g, b) = self.get_rgb_values();
+            Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>())
+        })
+    }
+
+    /// Put the [`frozen`](Self::frozen) channel of `original` back after a generator
+    /// recolored the block
+    pub fn restore_frozen(&mut self, original: &ColorBlock) {
+        match self.frozen {
+            Some(Freeze::Hue) => {
+                let (_, sat, val) = self.get_hsv_values();
+                self.change_color(original.hsv.hue.into_positive_degrees(), sat, val);
+            }
+            Some(Freeze::Lightness) => {
+                let color = Oklch {
+                    l: original.get_oklch().l,
+                    ..self.get_oklch()
+                };
+                self.set_oklch(gamut::fit(color));
+            }
+            None => {}
+        }
+    }
+
+    pub fn get_hex(&self) -> String {
+        let (r, g, b) = self.get_rgb_values();
+        convert::rgb_to_hex(r, g, b)
+    }
+
+    /// Calculate relative luminance using WCAG formula
+    /// Returns a value between 0.0 (black) and 1.0 (white)
+    pub fn get_relative_luminance(&self) -> f32 {
+        let (r, g, b) = self.get_rgb_values();
+
+        // Convert to linear RGB values (0.0 to 1.0)
+        let r_linear = if r as f32 / 255.0 <= 0.03928 {
+            (r as f32 / 255.0) / 12.92
+        } else {
+            ((r as f32 / 255.0 + 0.055) / 1.055).powf(2.4)
+        };
+
+        let g_linear = if g as f32 / 255.0 <= 0.03928 {
+            (g as f32 / 255.0) / 12.92
+        } else {
+            ((g as f32 / 255.0 + 0.055) / 1.055).powf(2.4)
+        };
+
+        let b_linear = if b as f32 / 255.0 <= 0.03928 {
+            (b as f32 / 255.0) / 12.92
+        } else {
+            ((b as f32 / 255.0 + 0.055) / 1.055).powf(2.4)
+        };
+
+        // Calculate relative luminance
+        0.2126 * r_linear + 0.7152 * g_linear + 0.0722 * b_linear
+    }
+
+    /// WCAG contrast ratio against `other`, from 1.0 (same luminance) to 21.0
+    pub fn contrast_ratio(&self, other: &ColorBlock) -> f32 {
+        let a = self.get_relative_luminance();
+        let b = other.get_relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Lighten (or darken) until reaching `min` contrast against `against`, keeping the hue;
+    /// lightening past full value takes saturation out instead. Returns whether `min` was
+    /// reached, the color is as far as it could go otherwise.
+    pub fn push_contrast(&mut self, against: &ColorBlock, min: f32, lighten: bool) -> bool {
+        const STEP: f32 = 0.02;
+
+        while self.contrast_ratio(against) < min {
+            let (hue, sat, val) = self.get_hsv_values();
+            let (sat, val) = if lighten && val < 1.0 {
+                (sat, (val + STEP).min(1.0))
+            } else if lighten && sat > 0.0 {
+                ((sat - STEP).max(0.0), val)
+            } else if !lighten && val > 0.0 {
+                (sat, (val - STEP).max(0.0))
+            } else {
+                return false;
+            };
+            self.change_color(hue, sat, val);
+        }
+        true
+    }
+
+    pub fn get_avg_hue(blocks: &[ColorBlock]) -> f32 {
+        let mut hue_as_deg: f32 = 0.0;
+
+        for block in blocks.iter() {
+            hue_as_deg += block.hsv.hue.into_degrees();
+        }
+
+        //return
+        hue_as_deg / blocks.len() as f32
+    }
+
+    pub fn get_avg_saturation(blocks: &[ColorBlock]) -> f32 {
+        let mut total_sat: f32 = 0.0;
+
+        for block in blocks.iter() {
+            total_sat += block.hsv.saturation;
+        }
+
+        total_sat / blocks.len() as f32
+    }
+
+    pub fn get_avg_value(blocks: &[ColorBlock]) -> f32 {
+        let mut total_val: f32 = 0.0;
+
+        for block in blocks.iter() {
+            total_val += block.hsv.value;
+        }
+
+        total_val / blocks.len() as f32
+    }
+}
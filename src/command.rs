@@ -0,0 +1,230 @@
+//! The `:` command line, a typed way to every feature.
+//!
+//! Every action of the main view is a command named after its hint without the `hint-`
+//! prefix (`generate`, `lock`, `mix`); a few more take arguments, like `theory triad`,
+//! `set blocks 7`, `save sunset` and `export css`. Names and keyword arguments match
+//! fuzzily, so `thr tri` runs `theory triad`.
+
+use std::{path::PathBuf, time::Duration};
+
+use strum::IntoEnumIterator;
+
+use terminal_palette::export::Format;
+use terminal_palette::generators::{ColorTheories, Jitter, LightnessScale};
+use terminal_palette::{extract, library};
+
+use crate::action::{Action, bindings};
+use crate::app::{CurrentPage, LibraryInput};
+use crate::t;
+
+/// Most suggestions the command line lists
+const MAX_SUGGESTIONS: usize = 12;
+
+/// Commands taking arguments; without any, `theory` and `save` open their popups instead
+const WITH_ARGUMENTS: &[&str] = &["theory", "set", "save", "export", "extract"];
+
+const SETTINGS: &[&str] = &["blocks", "jitter", "scale", "strict"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// An action of the main view
+    Run(Action),
+    Theory(ColorTheories),
+    SetBlocks(usize),
+    SetJitter(Jitter),
+    SetScale(LightnessScale),
+    SetStrict(bool),
+    /// Save the palette to the library under a name
+    Save(String),
+    /// Write the palette to a file, named after the palette unless given
+    Export(Format, Option<PathBuf>),
+    /// Take the colors of a GIF frame, the one showing this far into an animation; from a
+    /// file or an `http(s)` URL
+    Extract(String, Duration),
+}
+
+/// The command `line` stands for, or a message saying what is wrong with it
+pub fn parse(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let Some(word) = words.next() else {
+        return Err(t!("msg-command-empty"));
+    };
+    let args: Vec<&str> = words.collect();
+
+    let names = command_names();
+    let name = pick(word, names.iter().map(|(name, _)| *name), |name| name)
+        .ok_or_else(|| t!("msg-command-unknown", command = word))?;
+    let bad_argument = |argument: &str| t!("msg-command-bad-argument", argument = argument);
+
+    match (name, args.as_slice()) {
+        ("theory", [theory, ..]) => pick(theory, ColorTheories::iter(), ColorTheories::key)
+            .map(Command::Theory)
+            .ok_or_else(|| bad_argument(theory)),
+        ("set", [setting, value]) => {
+            let value_of = |found: Option<Command>| found.ok_or_else(|| bad_argument(value));
+            match pick(setting, SETTINGS.iter().copied(), |setting| setting) {
+                Some("blocks") => value
+                    .parse()
+                    .map(Command::SetBlocks)
+                    .map_err(|_| bad_argument(value)),
+                Some("jitter") => {
+                    value_of(pick(value, Jitter::iter(), Jitter::key).map(Command::SetJitter))
+                }
+                Some("scale") => value_of(
+                    pick(value, LightnessScale::iter(), LightnessScale::key).map(Command::SetScale),
+                ),
+                Some("strict") => value_of(match *value {
+                    "on" | "true" | "yes" => Some(Command::SetStrict(true)),
+                    "off" | "false" | "no" => Some(Command::SetStrict(false)),
+                    _ => None,
+                }),
+                _ => Err(bad_argument(setting)),
+            }
+        }
+        ("set", _) => Err(t!(
+            "msg-command-usage",
+            usage = "set <blocks|jitter|scale|strict> <value>"
+        )),
+        ("save", [_, ..]) => Ok(Command::Save(args.join(" "))),
+        ("export", [format, rest @ ..]) => {
+            let format =
+                pick(format, Format::iter(), Format::key).ok_or_else(|| bad_argument(format))?;
+            let path = (!rest.is_empty()).then(|| PathBuf::from(rest.join(" ")));
+            Ok(Command::Export(format, path))
+        }
+        ("export", []) => Err(t!("msg-command-usage", usage = "export <format> [path]")),
+        ("extract", [path @ .., last]) => {
+            // A last word reading as a time is where in an animation, the rest is the path
+            let (path, at) = match extract::parse_time(last) {
+                Some(at) if !path.is_empty() => (path.join(" "), at),
+                _ => (args.join(" "), Duration::ZERO),
+            };
+            Ok(Command::Extract(path, at))
+        }
+        ("extract", []) => Err(t!(
+            "msg-command-usage",
+            usage = "extract <file.gif|url> [time]"
+        )),
+        _ => names
+            .into_iter()
+            .find_map(|(candidate, action)| (candidate == name).then_some(action).flatten())
+            .map(Command::Run)
+            .ok_or_else(|| t!("msg-command-usage", usage = format!("{name} …"))),
+    }
+}
+
+/// Completions of `line`, best first: command names while typing the first word, then the
+/// keyword arguments of `theory`, `set` and `export`
+pub fn suggestions(line: &str) -> Vec<String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let typing_new_word = line.ends_with(' ');
+    let names = command_names();
+
+    let (prefix, pattern, candidates): (String, &str, Vec<String>) =
+        match (words.as_slice(), typing_new_word) {
+            ([], _) | ([_], false) => (
+                String::new(),
+                words.first().copied().unwrap_or(""),
+                names.iter().map(|(name, _)| name.to_string()).collect(),
+            ),
+            ([word, rest @ ..], _) => {
+                let Some(name) = pick(word, names.iter().map(|(name, _)| *name), |name| name)
+                else {
+                    return Vec::new();
+                };
+                let pattern = if typing_new_word {
+                    ""
+                } else {
+                    rest.last().copied().unwrap_or("")
+                };
+                let done = if typing_new_word {
+                    rest
+                } else {
+                    &rest[..rest.len().saturating_sub(1)]
+                };
+                let candidates: Vec<String> = match (name, done) {
+                    ("theory", []) => ColorTheories::iter().map(|t| t.key().to_string()).collect(),
+                    ("set", []) => SETTINGS.iter().map(|s| s.to_string()).collect(),
+                    ("set", [setting]) => match pick(setting, SETTINGS.iter().copied(), |s| s) {
+                        Some("jitter") => Jitter::iter().map(|j| j.key().to_string()).collect(),
+                        Some("scale") => LightnessScale::iter()
+                            .map(|s| s.key().to_string())
+                            .collect(),
+                        Some("strict") => vec![String::from("on"), String::from("off")],
+                        _ => Vec::new(),
+                    },
+                    ("export", []) => Format::iter().map(|f| f.key().to_string()).collect(),
+                    _ => Vec::new(),
+                };
+                let prefix = std::iter::once(name)
+                    .chain(done.iter().copied())
+                    .collect::<Vec<_>>();
+                (format!("{} ", prefix.join(" ")), pattern, candidates)
+            }
+        };
+
+    let mut scored: Vec<(i32, String)> = candidates
+        .into_iter()
+        .filter_map(|candidate| fuzzy_score(pattern, &candidate).map(|score| (score, candidate)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| format!("{prefix}{candidate}"))
+        .collect()
+}
+
+/// Every command name with the main-view action it runs, `None` for commands that need
+/// arguments. Hints shared by several actions (the four moves) don't make a command.
+fn command_names() -> Vec<(&'static str, Option<Action>)> {
+    let main = bindings(CurrentPage::Main, LibraryInput::None);
+    let mut names: Vec<(&'static str, Option<Action>)> =
+        WITH_ARGUMENTS.iter().map(|name| (*name, None)).collect();
+
+    for binding in main {
+        let Some(name) = binding.hint.strip_prefix("hint-") else {
+            continue;
+        };
+        let shared = main
+            .iter()
+            .any(|other| other.hint == binding.hint && other.action != binding.action);
+        if shared {
+            continue;
+        }
+        match names.iter_mut().find(|(existing, _)| *existing == name) {
+            // `theory` and `save` without arguments open their popups
+            Some((_, action @ None)) => *action = Some(binding.action.clone()),
+            Some(_) => {}
+            None => names.push((name, Some(binding.action.clone()))),
+        }
+    }
+    names
+}
+
+/// The option whose `key` matches `pattern` best, see [`fuzzy_score`]
+fn pick<T: Copy>(
+    pattern: &str,
+    options: impl Iterator<Item = T>,
+    key: impl Fn(T) -> &'static str,
+) -> Option<T> {
+    options
+        .filter_map(|option| fuzzy_score(pattern, key(option)).map(|score| (score, option)))
+        .min_by_key(|(score, _)| std::cmp::Reverse(*score))
+        .map(|(_, option)| option)
+}
+
+/// How well `pattern` matches `candidate`, higher is better: the same word above
+/// anything else, then [`library::fuzzy_score`] as in palette search. Like an empty search,
+/// an empty pattern matches everything equally, keeping the candidates' order.
+fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i32> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    if candidate.eq_ignore_ascii_case(pattern) {
+        Some(i32::MAX)
+    } else {
+        library::fuzzy_score(pattern, candidate)
+    }
+}
@@ -0,0 +1,229 @@
+//! User settings from `$XDG_CONFIG_HOME/terminal-palette/config.toml`.
+
+use std::{env, fs, io, path::PathBuf};
+
+use chrono::{DateTime, Datelike, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use terminal_palette::convert::ColorFormat;
+use terminal_palette::daylight;
+use terminal_palette::export;
+use terminal_palette::generators::{ColorTheories, Jitter, LightnessScale};
+use terminal_palette::states::StateOffsets;
+
+use crate::graphics;
+
+/// Every field is optional in the file; missing ones take their default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// UI language such as `de` or `pt-BR`; defaults to `$LANG`, then English
+    pub locale: Option<String>,
+    /// Fade blocks from their old to their new color when a palette is generated
+    pub animate_transitions: bool,
+    /// Theory a session starts with
+    pub theory: ColorTheories,
+    /// Number of blocks a session starts with
+    pub blocks: usize,
+    /// How `c` writes the selected color to the clipboard
+    pub copy_format: ColorFormat,
+    /// Generate a palette with the default theory at launch instead of showing black blocks
+    pub generate_on_startup: bool,
+    /// Regenerate the unlocked blocks whenever a theory is picked in the selector
+    pub generate_on_theory_change: bool,
+    /// Seconds between generations in the slideshow
+    pub slideshow_seconds: u64,
+    /// Also apply every slideshow palette to the terminal's ANSI colors
+    pub slideshow_apply_to_terminal: bool,
+    /// Give new ANSI schemes 8 dim slots next to the bright ones
+    pub ansi_dim_variants: bool,
+    /// Swatch book to name the closest match of each block from; relative paths start in
+    /// the config directory
+    pub swatch_book: Option<PathBuf>,
+    /// Lightness steps the Monochrome theory starts with
+    pub monochrome_scale: LightnessScale,
+    /// How far built-in theories stray at random from their exact harmony
+    pub jitter: Jitter,
+    /// Start in strict mode: no jitter at all
+    pub strict: bool,
+    /// Color the app itself with the palette's background, surface, text and primary roles
+    pub theme_chrome: bool,
+    /// Save the open project when quitting
+    pub autosave: bool,
+    /// Make blocks taken from pasted text as wide as the share of it their color covers
+    pub weight_imports: bool,
+    /// Offsets of the hover, active, focus and disabled variants made with `u`
+    pub states: StateOffsets,
+    /// Settings of the export formats
+    pub export: export::Options,
+    /// Files `terminal-palette theme <wallpaper>` writes, in order
+    pub theme_exports: Vec<ThemeExport>,
+    /// Write every palette set as the terminal's colors to a state file, see
+    /// [`crate::announce`]
+    pub announce: bool,
+    /// Other terminals applied palettes go to as well, see [`crate::osc::broadcast`]
+    pub apply_targets: Vec<PathBuf>,
+    /// Saved palettes `--daemon` applies by day and by night
+    pub schedule: Schedule,
+    /// Open the schedule's light or dark palette at startup, as the system prefers
+    pub follow_system_appearance: bool,
+    /// Shell commands run after palettes are applied or exported, see [`crate::hooks`]
+    pub hooks: Hooks,
+    /// Draw blocks as images where the terminal shows them, see [`crate::graphics`]
+    pub graphics: graphics::Mode,
+}
+
+/// One file the `theme` command writes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeExport {
+    /// A built-in format such as `css`, or the name of a plugin with an exporter
+    pub format: String,
+    /// Where to write it; relative paths start in the config directory
+    pub path: PathBuf,
+}
+
+/// Commands run by `sh -c` after each event, in order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    /// After a palette is set as the terminal's colors
+    pub apply: Vec<String>,
+    /// After a palette is exported
+    pub export: Vec<String>,
+}
+
+/// When `--daemon` switches between a light and a dark palette; off unless both are set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    /// Saved palette applied during the day
+    pub light: Option<String>,
+    /// Saved palette applied at night
+    pub dark: Option<String>,
+    /// Local time the day starts, like `07:00`, unless following the sun
+    pub day_starts: String,
+    /// Local time the night starts
+    pub night_starts: String,
+    /// Where to follow sunrise and sunset instead, in degrees north
+    pub latitude: Option<f64>,
+    /// In degrees east
+    pub longitude: Option<f64>,
+}
+
+impl Schedule {
+    /// The light and dark palette, when both are set
+    pub fn palettes(&self) -> Option<(&str, &str)> {
+        Some((self.light.as_deref()?, self.dark.as_deref()?))
+    }
+
+    /// Whether it is day at `now`: between sunrise and sunset with a location, else
+    /// between `day_starts` and `night_starts`
+    pub fn is_day<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> io::Result<bool> {
+        if let (Some(latitude), Some(longitude)) = (self.latitude, self.longitude) {
+            let utc = now.with_timezone(&Utc);
+            let minute = f64::from(utc.hour() * 60 + utc.minute());
+            return Ok(daylight::sun(utc.ordinal(), latitude, longitude).is_up(minute));
+        }
+
+        let clock = |text: &str| {
+            daylight::parse_clock(text).map(f64::from).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{text:?} isn't a time of day like 07:30"),
+                )
+            })
+        };
+        let minute = f64::from(now.hour() * 60 + now.minute());
+        Ok(daylight::is_between(
+            minute,
+            clock(&self.day_starts)?,
+            clock(&self.night_starts)?,
+        ))
+    }
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            light: None,
+            dark: None,
+            day_starts: String::from("07:00"),
+            night_starts: String::from("19:00"),
+            latitude: None,
+            longitude: None,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            locale: None,
+            animate_transitions: true,
+            theory: ColorTheories::Analogous,
+            blocks: 5,
+            copy_format: ColorFormat::Hex,
+            generate_on_startup: true,
+            generate_on_theory_change: true,
+            slideshow_seconds: 5,
+            slideshow_apply_to_terminal: false,
+            ansi_dim_variants: false,
+            swatch_book: None,
+            monochrome_scale: LightnessScale::Linear,
+            jitter: Jitter::Medium,
+            strict: false,
+            theme_chrome: false,
+            autosave: false,
+            weight_imports: false,
+            states: StateOffsets::default(),
+            export: export::Options::default(),
+            theme_exports: Vec::new(),
+            announce: false,
+            apply_targets: Vec::new(),
+            schedule: Schedule::default(),
+            follow_system_appearance: false,
+            hooks: Hooks::default(),
+            graphics: graphics::Mode::Auto,
+        }
+    }
+}
+
+pub fn config_dir() -> PathBuf {
+    let base = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var("HOME").unwrap_or_else(|_| String::from("."));
+            PathBuf::from(home).join(".config")
+        }
+    };
+    base.join("terminal-palette")
+}
+
+pub fn config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+/// The config file, or defaults when there is none
+pub fn load() -> io::Result<Config> {
+    let path = config_path();
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let text = fs::read_to_string(&path)?;
+    toml::from_str(&text).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {e}", path.display()),
+        )
+    })
+}
+
+/// Write `config` to [`config_path`], creating the directory if needed
+pub fn save(config: &Config) -> io::Result<PathBuf> {
+    let path = config_path();
+    fs::create_dir_all(config_dir())?;
+    let text = toml::to_string_pretty(config)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    fs::write(&path, text)?;
+    Ok(path)
+}
@@ -0,0 +1,817 @@
+//! Conversions between hex strings, RGB, HSV and HSL, and [`parse_color`] for anything a
+//! user may type or paste.
+//!
+//! Conventions shared by every function here:
+//!
+//! - RGB channels are `u8`. Going from floats to channels rounds to the nearest integer
+//!   (halves away from zero), so `rgb -> hsv -> rgb` returns the original channels.
+//! - Hue is in degrees. Outputs are always in `[0, 360)`; inputs are wrapped, so `-30.0`
+//!   and `330.0` are the same hue.
+//! - Saturation, value and lightness are in `[0, 1]`; inputs outside that range are clamped.
+//! - Non-finite float inputs (`NaN`, infinities) are treated as `0.0`, except that an
+//!   infinite saturation/value/lightness clamps like any other out-of-range number.
+//!
+//! ```
+//! use terminal_palette::convert::{hsv_to_rgb, parse_hex, rgb_to_hex, rgb_to_hsv};
+//!
+//! let rgb = parse_hex("#e07a5f").unwrap();
+//! let (h, s, v) = rgb_to_hsv(rgb.0, rgb.1, rgb.2);
+//! assert_eq!(hsv_to_rgb(h, s, v), rgb);
+//! assert_eq!(rgb_to_hex(rgb.0, rgb.1, rgb.2), "#E07A5F");
+//! assert!(parse_hex("e07a").is_err());
+//!
+//! use terminal_palette::convert::parse_color;
+//!
+//! assert_eq!(parse_color("0xE07A5F"), Ok(rgb));
+//! assert_eq!(parse_color("rgb(224, 122, 95)"), Ok(rgb));
+//! assert_eq!(parse_color("rebeccapurple"), Ok((102, 51, 153)));
+//! ```
+//!
+//! `oklch()`, `lab()` and `color(display-p3 ...)` can describe colors outside sRGB.
+//! [`parse_color`] clips those channel by channel; [`parse_color_exact`] keeps them as
+//! written, see [`crate::gamut`].
+
+use std::{
+    error::Error,
+    fmt,
+    ops::{Range, RangeInclusive},
+};
+
+use palette::{FromColor, Lab, Oklch, Srgb};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::gamut;
+
+/// Why a string isn't a hex color
+#[derive(Debug, Clone, PartialEq)]
+pub enum HexError {
+    Empty,
+    /// Number of digits given; only 3 (`#rgb`) and 6 (`#rrggbb`) are valid
+    InvalidLength(usize),
+    InvalidDigit(char),
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexError::Empty => write!(f, "no hex digits given"),
+            HexError::InvalidLength(len) => write!(f, "expected 3 or 6 hex digits, got {len}"),
+            HexError::InvalidDigit(c) => write!(f, "{c:?} is not a hex digit"),
+        }
+    }
+}
+
+impl Error for HexError {}
+
+/// Why [`parse_color`] rejected its input
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+    Empty,
+    Hex(HexError),
+    UnknownName(String),
+    UnknownFunction(String),
+    /// Color space named in `color()` other than `display-p3`
+    UnknownColorSpace(String),
+    /// A color function without its closing parenthesis
+    Unclosed(String),
+    /// Function name and number of components given; all of them take exactly 3
+    ComponentCount(String, usize),
+    InvalidNumber(String),
+    /// Component name, value as typed and the allowed range
+    OutOfRange(&'static str, String, &'static str),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorError::Empty => write!(f, "no color given"),
+            ColorError::Hex(e) => e.fmt(f),
+            ColorError::UnknownName(name) => write!(f, "unknown color name {name:?}"),
+            ColorError::UnknownFunction(name) => write!(
+                f,
+                "unknown function {name:?}, expected rgb(), hsl(), oklch(), lab() or color()"
+            ),
+            ColorError::UnknownColorSpace(space) => {
+                write!(f, "unknown color space {space:?}, expected display-p3")
+            }
+            ColorError::Unclosed(name) => write!(f, "{name}( is missing its closing \")\""),
+            ColorError::ComponentCount(name, count) => {
+                write!(f, "{name}() takes 3 components, got {count}")
+            }
+            ColorError::InvalidNumber(text) => write!(f, "{text:?} is not a number"),
+            ColorError::OutOfRange(component, value, range) => {
+                write!(f, "{component} {value} is outside {range}")
+            }
+        }
+    }
+}
+
+impl Error for ColorError {}
+
+impl From<HexError> for ColorError {
+    fn from(e: HexError) -> Self {
+        ColorError::Hex(e)
+    }
+}
+
+/// Parse `#rrggbb` or the `#rgb` shorthand, with or without `#`, in any case.
+/// Surrounding whitespace is ignored; anything else that isn't a hex digit is an error.
+pub fn parse_hex(input: &str) -> Result<(u8, u8, u8), HexError> {
+    let digits = input.trim();
+    let digits = digits.strip_prefix('#').unwrap_or(digits);
+
+    if digits.is_empty() {
+        return Err(HexError::Empty);
+    }
+    if let Some(bad) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(HexError::InvalidDigit(bad));
+    }
+
+    let nibbles: Vec<u8> = digits
+        .chars()
+        .map(|c| c.to_digit(16).unwrap() as u8)
+        .collect();
+
+    match nibbles[..] {
+        [r, g, b] => Ok((r * 17, g * 17, b * 17)),
+        [r1, r2, g1, g2, b1, b2] => Ok((r1 << 4 | r2, g1 << 4 | g2, b1 << 4 | b2)),
+        _ => Err(HexError::InvalidLength(nibbles.len())),
+    }
+}
+
+/// Parse any notation a user is likely to type or paste:
+///
+/// - hex as `#abc`, `abc`, `#aabbcc`, `aabbcc` or `0xAABBCC`
+/// - `rgb(r, g, b)` with channels in `0-255` or percentages; commas are optional
+/// - `hsl(h, s%, l%)` with the hue in degrees (`deg` suffix optional)
+/// - `oklch(l c h)` with lightness in `0-1` or `0%-100%`, chroma from 0 and the hue in degrees
+/// - `lab(l a b)` (CIELAB, D65) with lightness in `0-100` or `0%-100%`
+/// - `color(display-p3 r g b)` with channels in `0-1` or `0%-100%`
+/// - CSS color names, ignoring case, spaces, `-` and `_` (`Light Sea Green`)
+///
+/// Colors outside sRGB are clipped channel by channel. Errors say what exactly is wrong,
+/// so they can be shown to the user as is.
+pub fn parse_color(input: &str) -> Result<(u8, u8, u8), ColorError> {
+    parse_color_exact(input).map(ParsedColor::to_rgb)
+}
+
+/// A parsed color: 8-bit sRGB, or OKLCH as written for `oklch()`, `lab()` and `color()` input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParsedColor {
+    Rgb(u8, u8, u8),
+    Oklch(Oklch),
+}
+
+impl ParsedColor {
+    /// The color in 8-bit sRGB, clipped channel by channel if it lies outside
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            ParsedColor::Rgb(r, g, b) => (r, g, b),
+            ParsedColor::Oklch(color) => gamut::clip(color),
+        }
+    }
+
+    /// The color as written if it lies outside sRGB, `None` if it fits
+    pub fn out_of_gamut(self) -> Option<Oklch> {
+        match self {
+            ParsedColor::Oklch(color) if !gamut::in_gamut(color) => Some(color),
+            _ => None,
+        }
+    }
+}
+
+/// [`parse_color`] without clipping, for callers that handle out-of-gamut colors themselves
+pub fn parse_color_exact(input: &str) -> Result<ParsedColor, ColorError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(ColorError::Empty);
+    }
+
+    let lower = input.to_ascii_lowercase();
+    if let Some(digits) = lower.strip_prefix("0x") {
+        return Ok(rgb(parse_hex(digits)?));
+    }
+    if let Some((name, args)) = lower.split_once('(') {
+        return parse_function(name.trim(), args);
+    }
+    if lower.starts_with('#') || lower.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(rgb(parse_hex(&lower)?));
+    }
+    if lower
+        .chars()
+        .all(|c| c.is_ascii_alphabetic() || matches!(c, ' ' | '-' | '_'))
+    {
+        return named_color(&lower)
+            .map(rgb)
+            .ok_or_else(|| ColorError::UnknownName(input.to_string()));
+    }
+    Ok(rgb(parse_hex(&lower)?))
+}
+
+/// Functions [`count_colors`] looks for
+const SCANNED_FUNCTIONS: [&str; 5] = ["rgb(", "hsl(", "oklch(", "lab(", "color("];
+
+/// Every distinct color written in `text`, in the order they first appear, for picking
+/// the colors out of a stylesheet or theme file. Finds `#` hex codes and the functions
+/// [`parse_color`] takes, but not color names, which clash with too many other words.
+///
+/// ```
+/// use terminal_palette::convert::scan_colors;
+///
+/// let css = ".button { color: #fff; background: rgb(224 122 95); border: 1px #FFFFFF; }";
+/// assert_eq!(scan_colors(css), vec![(255, 255, 255), (224, 122, 95)]);
+/// ```
+pub fn scan_colors(text: &str) -> Vec<(u8, u8, u8)> {
+    count_colors(text)
+        .into_iter()
+        .map(|(color, _)| color)
+        .collect()
+}
+
+/// [`scan_colors`] with how many times each color is written, as a rough measure of how
+/// much of a design it covers
+///
+/// ```
+/// use terminal_palette::convert::count_colors;
+///
+/// let css = "a { color: #E07A5F } a:hover { color: #e07a5f; background: #3D405B }";
+/// assert_eq!(count_colors(css), vec![((224, 122, 95), 2), ((61, 64, 91), 1)]);
+/// ```
+pub fn count_colors(text: &str) -> Vec<((u8, u8, u8), usize)> {
+    let mut colors: Vec<((u8, u8, u8), usize)> = Vec::new();
+    for (_, color) in find_colors(text) {
+        match colors.iter_mut().find(|(seen, _)| *seen == color) {
+            Some((_, count)) => *count += 1,
+            None => colors.push((color, 1)),
+        }
+    }
+    colors
+}
+
+/// Where [`scan_colors`] finds each color written in `text`, as byte ranges, in order
+///
+/// ```
+/// use terminal_palette::convert::find_colors;
+///
+/// let css = "a { color: #E07A5F; border: 1px solid rgb(0 0 0) }";
+/// assert_eq!(find_colors(css), vec![(11..18, (224, 122, 95)), (38..48, (0, 0, 0))]);
+/// ```
+pub fn find_colors(text: &str) -> Vec<(Range<usize>, (u8, u8, u8))> {
+    let lower = text.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let word_char = |idx: usize| bytes[idx].is_ascii_alphanumeric() || bytes[idx] == b'-';
+
+    let mut found = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let rest = &lower[idx..];
+        let end = if let Some(hex) = rest.strip_prefix('#') {
+            let digits = hex.bytes().take_while(u8::is_ascii_hexdigit).count();
+            let end = idx + 1 + digits;
+            (end == bytes.len() || !word_char(end)).then_some(end)
+        } else if (idx == 0 || !word_char(idx - 1))
+            && SCANNED_FUNCTIONS.iter().any(|name| rest.starts_with(name))
+        {
+            rest.find(')').map(|close| idx + close + 1)
+        } else {
+            None
+        };
+
+        match end.map(|end| (end, parse_color(&lower[idx..end]))) {
+            Some((end, Ok(color))) => {
+                found.push((idx..end, color));
+                idx = end;
+            }
+            _ => idx += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    found
+}
+
+/// `#RRGGBB` in upper case
+pub fn rgb_to_hex(r: u8, g: u8, b: u8) -> String {
+    format!("#{r:02X}{g:02X}{b:02X}")
+}
+
+/// How a color is written out, e.g. when copied; every format reads back with
+/// [`parse_color`]
+///
+/// ```
+/// use terminal_palette::convert::{ColorFormat, parse_color};
+///
+/// assert_eq!(ColorFormat::Rgb.format(224, 122, 95), "rgb(224 122 95)");
+/// assert_eq!(ColorFormat::Hsl.format(224, 122, 95), "hsl(13 68% 63%)");
+/// let oklch = ColorFormat::Oklch.format(224, 122, 95);
+/// assert_eq!(parse_color(&oklch), Ok((224, 122, 95)));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorFormat {
+    /// `#E07A5F`
+    #[default]
+    Hex,
+    /// `rgb(224 122 95)`
+    Rgb,
+    /// `hsl(13 68% 63%)`, rounded to whole degrees and percent
+    Hsl,
+    /// `oklch(0.6878 0.1331 35.78)`, precise enough to round-trip every 8-bit color
+    Oklch,
+}
+
+impl ColorFormat {
+    /// Identifier used in the config file, e.g. `oklch`
+    pub fn key(self) -> &'static str {
+        match self {
+            ColorFormat::Hex => "hex",
+            ColorFormat::Rgb => "rgb",
+            ColorFormat::Hsl => "hsl",
+            ColorFormat::Oklch => "oklch",
+        }
+    }
+
+    pub fn format(self, r: u8, g: u8, b: u8) -> String {
+        match self {
+            ColorFormat::Hex => rgb_to_hex(r, g, b),
+            ColorFormat::Rgb => format!("rgb({r} {g} {b})"),
+            ColorFormat::Hsl => {
+                let (h, s, l) = rgb_to_hsl(r, g, b);
+                format!("hsl({:.0} {:.0}% {:.0}%)", h, s * 100.0, l * 100.0)
+            }
+            ColorFormat::Oklch => {
+                let color = Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>());
+                let hue = color.hue.into_positive_degrees();
+                format!("oklch({:.4} {:.4} {hue:.2})", color.l, color.chroma)
+            }
+        }
+    }
+}
+
+/// RGB to (hue in degrees, saturation, value)
+pub fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (r, g, b) = (unit(r), unit(g), unit(b));
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+
+    let s = if max == 0.0 { 0.0 } else { delta / max };
+    (hue(r, g, b, max, delta), s, max)
+}
+
+/// (hue in degrees, saturation, value) to RGB
+pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
+    let (s, v) = (clamp_unit(s), clamp_unit(v));
+    let chroma = v * s;
+    from_chroma(wrap_hue(h), chroma, v - chroma)
+}
+
+/// RGB to (hue in degrees, saturation, lightness)
+pub fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (r, g, b) = (unit(r), unit(g), unit(b));
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let l = (max + min) / 2.0;
+    let s = if delta == 0.0 {
+        0.0
+    } else {
+        (delta / (1.0 - (2.0 * l - 1.0).abs())).min(1.0)
+    };
+    (hue(r, g, b, max, delta), s, l)
+}
+
+/// (hue in degrees, saturation, lightness) to RGB
+pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
+    let (s, l) = (clamp_unit(s), clamp_unit(l));
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    from_chroma(wrap_hue(h), chroma, l - chroma / 2.0)
+}
+
+/// Wrap any hue into `[0, 360)`
+pub fn wrap_hue(h: f32) -> f32 {
+    if !h.is_finite() {
+        return 0.0;
+    }
+    let wrapped = h.rem_euclid(360.0);
+    // rem_euclid can round up to exactly 360 for tiny negative inputs
+    if wrapped >= 360.0 { 0.0 } else { wrapped }
+}
+
+/// CIE76 color difference between two sRGB colors
+pub fn delta_e(a: (u8, u8, u8), b: (u8, u8, u8)) -> f32 {
+    let to_lab = |(r, g, b): (u8, u8, u8)| -> Lab {
+        Lab::from_color(Srgb::new(r, g, b).into_format::<f32>())
+    };
+    let (a, b) = (to_lab(a), to_lab(b));
+
+    ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+}
+
+/// The `count` colors of `colors` furthest apart from each other, for when a source has
+/// more colors than a palette takes. Starting from the first color, each pick is the one
+/// with the largest [`delta_e`] to its nearest pick so far (max-min ΔE), so a handful of
+/// near-duplicates can't crowd out a color that appears once. The picks keep the order
+/// they had in `colors`.
+///
+/// ```
+/// use terminal_palette::convert::most_distinct;
+///
+/// let colors = [(250, 250, 250), (255, 255, 255), (248, 248, 252), (200, 30, 40), (0, 0, 0)];
+/// assert_eq!(
+///     most_distinct(&colors, 3),
+///     vec![(250, 250, 250), (200, 30, 40), (0, 0, 0)]
+/// );
+/// ```
+pub fn most_distinct(colors: &[(u8, u8, u8)], count: usize) -> Vec<(u8, u8, u8)> {
+    if colors.len() <= count {
+        return colors.to_vec();
+    }
+
+    let mut picked = vec![false; colors.len()];
+    // Distance from each color to its nearest pick
+    let mut nearest = vec![f32::INFINITY; colors.len()];
+    let mut next = 0;
+    for _ in 0..count {
+        picked[next] = true;
+        for (idx, &color) in colors.iter().enumerate() {
+            nearest[idx] = nearest[idx].min(delta_e(colors[next], color));
+        }
+        next = (0..colors.len())
+            .filter(|&idx| !picked[idx])
+            .max_by(|&a, &b| nearest[a].total_cmp(&nearest[b]).then(b.cmp(&a)))
+            .unwrap_or(0);
+    }
+
+    colors
+        .iter()
+        .zip(picked)
+        .filter_map(|(&color, picked)| picked.then_some(color))
+        .collect()
+}
+
+/// The share in percent of `counted` colors each of `picks` stands for, when every color
+/// goes to the pick nearest to it by [`delta_e`]. Counts come from e.g.
+/// [`count_colors`], picks from [`most_distinct`].
+///
+/// ```
+/// use terminal_palette::convert::coverage;
+///
+/// let counted = [((255, 255, 255), 6), ((250, 250, 250), 2), ((200, 30, 40), 2)];
+/// assert_eq!(coverage(&counted, &[(255, 255, 255), (200, 30, 40)]), vec![80.0, 20.0]);
+/// ```
+pub fn coverage(counted: &[((u8, u8, u8), usize)], picks: &[(u8, u8, u8)]) -> Vec<f32> {
+    let mut shares = vec![0; picks.len()];
+    for &(color, count) in counted {
+        let nearest = picks
+            .iter()
+            .map(|&pick| delta_e(pick, color))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((idx, _)) = nearest {
+            shares[idx] += count;
+        }
+    }
+
+    let total: usize = shares.iter().sum();
+    shares
+        .iter()
+        .map(|&share| match total {
+            0 => 0.0,
+            total => share as f32 * 100.0 / total as f32,
+        })
+        .collect()
+}
+
+fn rgb((r, g, b): (u8, u8, u8)) -> ParsedColor {
+    ParsedColor::Rgb(r, g, b)
+}
+
+fn parse_function(name: &str, args: &str) -> Result<ParsedColor, ColorError> {
+    if !["rgb", "hsl", "oklch", "lab", "color"].contains(&name) {
+        return Err(ColorError::UnknownFunction(name.to_string()));
+    }
+    let args = args
+        .trim_end()
+        .strip_suffix(')')
+        .ok_or_else(|| ColorError::Unclosed(name.to_string()))?;
+
+    let mut parts: Vec<&str> = args
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    // `color()` names its color space before the 3 components
+    if name == "color" {
+        match parts.first() {
+            Some(&"display-p3") => {
+                parts.remove(0);
+            }
+            space => {
+                return Err(ColorError::UnknownColorSpace(
+                    space.unwrap_or(&"").to_string(),
+                ));
+            }
+        }
+    }
+    let [first, second, third] = parts[..] else {
+        return Err(ColorError::ComponentCount(name.to_string(), parts.len()));
+    };
+
+    match name {
+        "rgb" => Ok(ParsedColor::Rgb(
+            rgb_component("red", first)?,
+            rgb_component("green", second)?,
+            rgb_component("blue", third)?,
+        )),
+        "hsl" => {
+            let h = number(first.strip_suffix("deg").unwrap_or(first))?;
+            let s = percentage("saturation", second)?;
+            let l = percentage("lightness", third)?;
+            Ok(rgb(hsl_to_rgb(h, s, l)))
+        }
+        "oklch" => {
+            let l = unit_component("lightness", first)?;
+            let c = in_range("chroma", second, 0.0..=f32::MAX, "0 and up")?;
+            let h = number(third.strip_suffix("deg").unwrap_or(third))?;
+            Ok(ParsedColor::Oklch(Oklch::new(l, c, h)))
+        }
+        "color" => Ok(ParsedColor::Oklch(gamut::from_display_p3(
+            unit_component("red", first)?,
+            unit_component("green", second)?,
+            unit_component("blue", third)?,
+        ))),
+        _ => {
+            let l = match first.strip_suffix('%') {
+                Some(_) => percentage("lightness", first)? * 100.0,
+                None => in_range("lightness", first, 0.0..=100.0, "0-100")?,
+            };
+            let lab = Lab::new(l, number(second)?, number(third)?);
+            Ok(ParsedColor::Oklch(Oklch::from_color(lab)))
+        }
+    }
+}
+
+fn in_range(
+    component: &'static str,
+    text: &str,
+    range: RangeInclusive<f32>,
+    described: &'static str,
+) -> Result<f32, ColorError> {
+    let value = number(text)?;
+    if !range.contains(&value) {
+        return Err(ColorError::OutOfRange(
+            component,
+            text.to_string(),
+            described,
+        ));
+    }
+    Ok(value)
+}
+
+/// A channel as `0-255` or `0%-100%`
+fn rgb_component(component: &'static str, text: &str) -> Result<u8, ColorError> {
+    if text.ends_with('%') {
+        return Ok(channel(percentage(component, text)?));
+    }
+    let value = number(text)?;
+    if !(0.0..=255.0).contains(&value) {
+        return Err(ColorError::OutOfRange(component, text.to_string(), "0-255"));
+    }
+    Ok(value.round() as u8)
+}
+
+/// A channel as `0-1` or `0%-100%`
+fn unit_component(component: &'static str, text: &str) -> Result<f32, ColorError> {
+    match text.strip_suffix('%') {
+        Some(_) => percentage(component, text),
+        None => in_range(component, text, 0.0..=1.0, "0-1"),
+    }
+}
+
+/// `0%-100%` (the `%` is optional) as a fraction
+fn percentage(component: &'static str, text: &str) -> Result<f32, ColorError> {
+    let value = number(text.strip_suffix('%').unwrap_or(text))?;
+    if !(0.0..=100.0).contains(&value) {
+        return Err(ColorError::OutOfRange(
+            component,
+            text.to_string(),
+            "0%-100%",
+        ));
+    }
+    Ok(value / 100.0)
+}
+
+fn number(text: &str) -> Result<f32, ColorError> {
+    text.parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| ColorError::InvalidNumber(text.to_string()))
+}
+
+fn named_color(name: &str) -> Option<(u8, u8, u8)> {
+    let key: String = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect();
+    NAMED_COLORS
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, rgb)| ((rgb >> 16) as u8, (rgb >> 8) as u8, *rgb as u8))
+}
+
+fn clamp_unit(x: f32) -> f32 {
+    if x.is_nan() { 0.0 } else { x.clamp(0.0, 1.0) }
+}
+
+fn unit(channel: u8) -> f32 {
+    channel as f32 / 255.0
+}
+
+fn channel(x: f32) -> u8 {
+    (x * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+fn hue(r: f32, g: f32, b: f32, max: f32, delta: f32) -> f32 {
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * (((g - b) / delta) % 6.0)
+    } else if max == g {
+        60.0 * (((b - r) / delta) + 2.0)
+    } else {
+        60.0 * (((r - g) / delta) + 4.0)
+    };
+    wrap_hue(h)
+}
+
+/// Shared tail of HSV/HSL to RGB: place `chroma` by hue sector and lift by `offset`
+fn from_chroma(h: f32, chroma: f32, offset: f32) -> (u8, u8, u8) {
+    let sector = h / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    (
+        channel(r + offset),
+        channel(g + offset),
+        channel(b + offset),
+    )
+}
+
+/// The CSS named colors, lowercase without separators, as 0xRRGGBB
+pub const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
@@ -0,0 +1,117 @@
+//! When it is day: between two clock times, or between sunrise and sunset at a place.
+//!
+//! Times are minutes since midnight. Sunrise and sunset follow NOAA's approximation of the
+//! sun's position, which is off by a minute or two at most outside the polar circles;
+//! they come out in UTC, so compare them with the UTC time of day.
+//!
+//! ```
+//! use terminal_palette::daylight::{self, Sun};
+//!
+//! // Berlin at the June solstice: sunrise 4:43 and sunset 21:33 local time (UTC+2)
+//! let Sun::Rises { sunrise, sunset } = daylight::sun(172, 52.52, 13.405) else {
+//!     panic!("the sun rises in Berlin");
+//! };
+//! assert!((sunrise - (2.0 * 60.0 + 43.0)).abs() < 3.0, "{sunrise}");
+//! assert!((sunset - (19.0 * 60.0 + 33.0)).abs() < 3.0, "{sunset}");
+//!
+//! // Tromsø in December
+//! assert_eq!(daylight::sun(355, 69.65, 18.96), Sun::AlwaysDown);
+//! ```
+
+use std::f64::consts::TAU;
+
+pub const MINUTES_PER_DAY: f64 = 24.0 * 60.0;
+
+/// The sun's zenith angle at sunrise and sunset in degrees, allowing for refraction and
+/// the size of its disc
+const HORIZON_ZENITH: f64 = 90.833;
+
+/// Whether and when the sun rises on a day
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sun {
+    /// Sunrise and sunset in minutes since midnight UTC; sunset comes first when the UTC
+    /// day boundary falls in daylight
+    Rises { sunrise: f64, sunset: f64 },
+    /// Midnight sun
+    AlwaysUp,
+    /// Polar night
+    AlwaysDown,
+}
+
+impl Sun {
+    /// Whether the sun is up `minute` minutes after midnight UTC
+    pub fn is_up(self, minute: f64) -> bool {
+        match self {
+            Sun::Rises { sunrise, sunset } => is_between(minute, sunrise, sunset),
+            Sun::AlwaysUp => true,
+            Sun::AlwaysDown => false,
+        }
+    }
+}
+
+/// The sun on the `day_of_year`th day (1 for January 1st) at `latitude` and `longitude`
+/// in degrees, north and east positive
+pub fn sun(day_of_year: u32, latitude: f64, longitude: f64) -> Sun {
+    // Fractional year in radians, at noon
+    let year = TAU / 365.0 * (f64::from(day_of_year) - 1.0);
+    let (sin1, cos1) = year.sin_cos();
+    let (sin2, cos2) = (2.0 * year).sin_cos();
+    let (sin3, cos3) = (3.0 * year).sin_cos();
+
+    // How far solar time runs ahead of clock time, in minutes
+    let equation_of_time =
+        229.18 * (0.000075 + 0.001868 * cos1 - 0.032077 * sin1 - 0.014615 * cos2 - 0.040849 * sin2);
+    let declination = 0.006918 - 0.399912 * cos1 + 0.070257 * sin1 - 0.006758 * cos2
+        + 0.000907 * sin2
+        - 0.002697 * cos3
+        + 0.00148 * sin3;
+
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = HORIZON_ZENITH.to_radians().cos() / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if cos_hour_angle > 1.0 {
+        return Sun::AlwaysDown;
+    }
+    if cos_hour_angle < -1.0 {
+        return Sun::AlwaysUp;
+    }
+
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+    let noon = 720.0 - 4.0 * longitude - equation_of_time;
+    Sun::Rises {
+        sunrise: (noon - 4.0 * hour_angle).rem_euclid(MINUTES_PER_DAY),
+        sunset: (noon + 4.0 * hour_angle).rem_euclid(MINUTES_PER_DAY),
+    }
+}
+
+/// Whether `minute` lies from `start` up to `end`, going past midnight when `end` comes
+/// before `start`
+///
+/// ```
+/// use terminal_palette::daylight::is_between;
+///
+/// assert!(is_between(12.0 * 60.0, 7.0 * 60.0, 19.0 * 60.0));
+/// assert!(is_between(60.0, 22.0 * 60.0, 6.0 * 60.0));
+/// assert!(!is_between(12.0 * 60.0, 22.0 * 60.0, 6.0 * 60.0));
+/// ```
+pub fn is_between(minute: f64, start: f64, end: f64) -> bool {
+    if start <= end {
+        (start..end).contains(&minute)
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// A time of day like `7:30` or `19:05` as minutes since midnight
+///
+/// ```
+/// use terminal_palette::daylight::parse_clock;
+///
+/// assert_eq!(parse_clock("07:30"), Some(450));
+/// assert_eq!(parse_clock("24:00"), None);
+/// ```
+pub fn parse_clock(text: &str) -> Option<u32> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
@@ -0,0 +1,71 @@
+//! Editing text in the user's own editor, for bulk changes to the palette.
+//!
+//! The TUI steps aside while the editor runs: raw mode, the alternate screen and bracketed
+//! paste are switched off and back on afterwards, so the editor gets a normal terminal.
+
+use std::{
+    env, fs,
+    io::{self, Write},
+    process::{self, Command},
+};
+
+use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// Run when neither `$VISUAL` nor `$EDITOR` is set
+const FALLBACK_EDITOR: &str = "vi";
+
+/// `$VISUAL`, else `$EDITOR`, else [`FALLBACK_EDITOR`], split into program and arguments
+/// so settings like `code --wait` work
+fn editor_command() -> Vec<String> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| FALLBACK_EDITOR.to_string())
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+/// Open `text` in the editor and return it as saved. The terminal is taken back even when
+/// the editor fails to start or exits with an error.
+pub fn edit(text: &str) -> io::Result<String> {
+    let path = env::temp_dir().join(format!("terminal-palette-{}.txt", process::id()));
+    fs::write(&path, text)?;
+
+    let command = editor_command();
+    let program = &command[0];
+    suspend()?;
+    let status = Command::new(program)
+        .args(&command[1..])
+        .arg(&path)
+        .status();
+    resume()?;
+
+    let edited = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path),
+        Ok(status) => Err(io::Error::other(format!("{program} exited with {status}"))),
+        Err(e) => Err(io::Error::new(
+            e.kind(),
+            format!("could not start {program}: {e}"),
+        )),
+    };
+    let _ = fs::remove_file(&path);
+    edited
+}
+
+fn suspend() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    execute!(stdout, DisableBracketedPaste, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    stdout.flush()
+}
+
+fn resume() -> io::Result<()> {
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableBracketedPaste)
+}
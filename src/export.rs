@@ -0,0 +1,1226 @@
+//! Built-in export formats, next to the ones Lua plugins add.
+//!
+//! Every format reads an [`ExportPalette`], built from a `.tpal` project or a saved palette.
+//!
+//! ```
+//! use terminal_palette::export::{self, ExportColor, ExportPalette, Format};
+//!
+//! let palette = ExportPalette {
+//!     name: String::from("sunset"),
+//!     theory: None,
+//!     colors: vec![ExportColor::new("#E07A5F"), ExportColor::new("#3D405B")],
+//!     gradients: Vec::new(),
+//! };
+//! let css = export::export(Format::Css, &palette);
+//! assert!(css.contains("--sunset-1: #E07A5F;"));
+//! assert!(!css.contains("display-p3"), "both colors fit in sRGB");
+//!
+//! // Without gradients of its own, a palette exports one through all its colors
+//! let gradient = export::export(Format::GradientCss, &palette);
+//! assert!(gradient.contains("--sunset: linear-gradient(in oklab to right, #E07A5F 0%"));
+//! ```
+
+use std::io;
+
+use palette::{FromColor, Oklch, Srgb};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use strum_macros::EnumIter;
+
+use crate::color::hex2rgb;
+use crate::gamut;
+use crate::generators::ColorTheories;
+use crate::gradient::{self, Gradient, Space};
+use crate::library::SavedPalette;
+use crate::palette_json;
+use crate::project::Project;
+use crate::roles::Role;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum Format {
+    /// CSS custom properties, with Display P3 values for colors outside sRGB
+    Css,
+    /// A numbered SCSS variable per color and a map of them all, see [`scss`]
+    Scss,
+    /// The CSS custom properties, each with a comment on its share of a design and what
+    /// to use it for, see [`proportions`]
+    ProportionsCss,
+    /// A strip of swatches as wide as their share of a design
+    ProportionsSvg,
+    /// One `linear-gradient` custom property per gradient
+    GradientCss,
+    /// Every gradient as a `<linearGradient>` filling a stripe
+    GradientSvg,
+    /// The first gradient as a GIMP gradient, which holds only one
+    Ggr,
+    /// A Figma variable collection with a light and a dark mode, see [`figma`]
+    Figma,
+    /// A palette for the Sketch Palettes plugin
+    Sketch,
+    /// Adobe Color Swatch, which Affinity and Photoshop import; binary
+    Aco,
+    /// A Unity color preset library, see [`unity`]
+    Unity,
+    /// A Godot `ColorPalette` resource, for the presets of its color pickers
+    Godot,
+    /// JSON in the shape of [`palette_json`], which reads it back
+    Json,
+    /// A Markdown table with a swatch per color, see [`table`]
+    Markdown,
+    /// The same table for Org mode
+    Org,
+    /// A GIMP palette, which Krita and Inkscape read too
+    Gpl,
+    /// Adobe Swatch Exchange, which Photoshop and Illustrator import; binary, see [`ase`]
+    Ase,
+    /// A strip of swatches with their hex codes as a PNG picture, see [`strip`]
+    Png,
+    /// The swatches as labeled rectangles in an SVG picture, see [`swatches_svg`]
+    Svg,
+}
+
+impl Format {
+    /// Identifier used on the command line, e.g. `css`
+    pub fn key(self) -> &'static str {
+        match self {
+            Format::Css => "css",
+            Format::Scss => "scss",
+            Format::ProportionsCss => "proportions-css",
+            Format::ProportionsSvg => "proportions-svg",
+            Format::GradientCss => "gradient-css",
+            Format::GradientSvg => "gradient-svg",
+            Format::Ggr => "ggr",
+            Format::Figma => "figma",
+            Format::Sketch => "sketch",
+            Format::Aco => "aco",
+            Format::Unity => "unity",
+            Format::Godot => "godot",
+            Format::Json => "json",
+            Format::Markdown => "markdown",
+            Format::Org => "org",
+            Format::Gpl => "gpl",
+            Format::Ase => "ase",
+            Format::Png => "png",
+            Format::Svg => "svg",
+        }
+    }
+
+    /// File extension of exported files, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Css | Format::ProportionsCss | Format::GradientCss => "css",
+            Format::Scss => "scss",
+            Format::ProportionsSvg | Format::GradientSvg | Format::Svg => "svg",
+            Format::Ggr => "ggr",
+            Format::Figma | Format::Json => "json",
+            Format::Sketch => "sketchpalette",
+            Format::Aco => "aco",
+            Format::Unity => "colors",
+            Format::Godot => "tres",
+            Format::Markdown => "md",
+            Format::Org => "org",
+            Format::Gpl => "gpl",
+            Format::Ase => "ase",
+            Format::Png => "png",
+        }
+    }
+
+    /// Whether the format's files are binary, which only [`export_bytes`] writes
+    pub fn is_binary(self) -> bool {
+        matches!(self, Format::Aco | Format::Ase | Format::Png)
+    }
+}
+
+/// Settings of the formats that take any, the `[export]` table of the config
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// What SCSS variables are called before their number, `color` for `$color-1`
+    pub scss_prefix: String,
+    /// Width of PNG strips in pixels
+    pub png_width: u32,
+    /// Height of PNG strips in pixels
+    pub png_height: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            scss_prefix: String::from("color"),
+            png_width: 1200,
+            png_height: 300,
+        }
+    }
+}
+
+/// What a color of a proportioned palette is for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum Usage {
+    /// Backgrounds and large surfaces
+    Dominant,
+    /// Panels, cards and other supporting areas
+    Secondary,
+    /// Buttons, links and highlights
+    Accent,
+}
+
+impl Usage {
+    pub fn key(self) -> &'static str {
+        match self {
+            Usage::Dominant => "dominant",
+            Usage::Secondary => "secondary",
+            Usage::Accent => "accent",
+        }
+    }
+
+    /// What to use such a color for, as the CSS comments put it
+    fn guidance(self) -> &'static str {
+        match self {
+            Usage::Dominant => "backgrounds and large surfaces",
+            Usage::Secondary => "panels, cards and supporting areas",
+            Usage::Accent => "buttons, links and highlights",
+        }
+    }
+}
+
+/// Usage and percentage of every color, in palette order, from their relative `widths`.
+///
+/// The widest color dominates and the next widest is secondary, earlier colors winning
+/// ties; the rest are accents. Each color covers its share of the widths, except when all
+/// widths are equal: the 60-30-10 rule then gives the dominant color 60%, the secondary
+/// 30% and splits 10% among the accents.
+///
+/// ```
+/// use terminal_palette::export::{Usage, proportions};
+///
+/// let even = proportions(&[1, 1, 1, 1]);
+/// assert_eq!(even[0], (Usage::Dominant, 60.0));
+/// assert_eq!(even[3], (Usage::Accent, 5.0));
+///
+/// let weighted = proportions(&[1, 3, 6]);
+/// assert_eq!(weighted[2], (Usage::Dominant, 60.0));
+/// assert_eq!(weighted[1], (Usage::Secondary, 30.0));
+/// ```
+pub fn proportions(widths: &[u16]) -> Vec<(Usage, f32)> {
+    let mut order: Vec<usize> = (0..widths.len()).collect();
+    order.sort_by_key(|&idx| std::cmp::Reverse(widths[idx]));
+    let usage = |idx: usize| match order.iter().position(|&o| o == idx) {
+        Some(0) => Usage::Dominant,
+        Some(1) => Usage::Secondary,
+        _ => Usage::Accent,
+    };
+
+    let even = widths.windows(2).all(|pair| pair[0] == pair[1]);
+    let accents = widths.len().saturating_sub(2).max(1) as f32;
+    let total: f32 = widths.iter().map(|&width| f32::from(width)).sum();
+    (0..widths.len())
+        .map(|idx| {
+            let usage = usage(idx);
+            let percent = match (even, widths.len(), usage) {
+                (false, _, _) => f32::from(widths[idx]) * 100.0 / total,
+                // Too few colors for all three parts share them evenly
+                (true, 1 | 2, _) => 100.0 / widths.len() as f32,
+                (true, _, Usage::Dominant) => 60.0,
+                (true, _, Usage::Secondary) => 30.0,
+                (true, _, Usage::Accent) => 10.0 / accents,
+            };
+            (usage, percent)
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportColor {
+    /// `#RRGGBB`, the sRGB color or the fallback for a wider one
+    pub hex: String,
+    pub label: String,
+    /// Usage note, written as a comment above the color where the format has comments
+    pub note: String,
+    pub role: Option<Role>,
+    /// Kept through generation, for formats that carry on editing
+    pub locked: bool,
+    /// The color as asked for when it lies outside sRGB
+    pub wide: Option<Oklch>,
+    /// Share of the palette's width relative to the other colors, for formats that draw
+    /// swatches side by side
+    pub width: u16,
+}
+
+impl ExportColor {
+    /// A plain sRGB color without label or role
+    pub fn new(hex: &str) -> Self {
+        Self {
+            hex: hex.to_uppercase(),
+            label: String::new(),
+            note: String::new(),
+            role: None,
+            locked: false,
+            wide: None,
+            width: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportPalette {
+    pub name: String,
+    /// Theory the palette was generated with, `None` for palettes from elsewhere
+    pub theory: Option<ColorTheories>,
+    pub colors: Vec<ExportColor>,
+    /// Named gradients for the gradient formats, which fall back to one through all the
+    /// colors without them
+    pub gradients: Vec<Gradient>,
+}
+
+impl From<&Project> for ExportPalette {
+    fn from(project: &Project) -> Self {
+        Self {
+            name: project.name.clone(),
+            theory: Some(project.theory),
+            colors: project
+                .blocks
+                .iter()
+                .map(|block| ExportColor {
+                    hex: block.hex.clone(),
+                    label: block.label.clone(),
+                    note: block.note.clone(),
+                    role: block.role,
+                    locked: block.locked,
+                    wide: block.oklch.map(|[l, c, h]| Oklch::new(l, c, h)),
+                    width: block.width.unwrap_or(1),
+                })
+                .collect(),
+            gradients: project.gradients.clone(),
+        }
+    }
+}
+
+impl From<&SavedPalette> for ExportPalette {
+    fn from(palette: &SavedPalette) -> Self {
+        Self {
+            name: palette.name.clone(),
+            theory: Some(palette.theory),
+            colors: palette
+                .colors
+                .iter()
+                .map(|color| ExportColor {
+                    role: color.role,
+                    locked: color.locked,
+                    ..ExportColor::new(&color.hex)
+                })
+                .collect(),
+            gradients: Vec::new(),
+        }
+    }
+}
+
+/// The palette as a file of `format`. Only PNG fails, in builds without the `image`
+/// feature.
+pub fn export_bytes(
+    format: Format,
+    palette: &ExportPalette,
+    options: &Options,
+) -> io::Result<Vec<u8>> {
+    match format {
+        Format::Aco => Ok(aco(palette)),
+        Format::Ase => Ok(ase(palette)),
+        Format::Png => png(palette, options.png_width, options.png_height),
+        format => Ok(export_with(format, palette, options).into_bytes()),
+    }
+}
+
+/// The palette in a text `format` with the default [`Options`]. Binary formats have no
+/// text and come out empty, see [`export_bytes`].
+pub fn export(format: Format, palette: &ExportPalette) -> String {
+    export_with(format, palette, &Options::default())
+}
+
+/// [`export`] with the formats set up by `options`
+pub fn export_with(format: Format, palette: &ExportPalette, options: &Options) -> String {
+    match format {
+        Format::Css => css(palette),
+        Format::Scss => scss(palette, &options.scss_prefix),
+        Format::ProportionsCss => proportions_css(palette),
+        Format::ProportionsSvg => proportions_svg(palette),
+        Format::GradientCss => gradient_css(palette),
+        Format::GradientSvg => gradient_svg(palette),
+        Format::Ggr => gradients(palette)
+            .first()
+            .map(Gradient::ggr)
+            .unwrap_or_default(),
+        Format::Figma => figma(palette),
+        Format::Sketch => sketch(palette),
+        Format::Aco | Format::Ase | Format::Png => String::new(),
+        Format::Unity => unity(palette),
+        Format::Godot => godot(palette),
+        Format::Json => palette_json::write(palette),
+        Format::Markdown => table(palette, Table::Markdown),
+        Format::Org => table(palette, Table::Org),
+        Format::Gpl => gpl(palette),
+        Format::Svg => swatches_svg(palette),
+    }
+}
+
+/// The palette as a Figma variable collection named after it, for the variables import
+/// plugins: one `COLOR` variable per color, named like the CSS custom properties, with
+/// its note as the description. Values are Figma's `{ r, g, b, a }` from 0 to 1.
+///
+/// The `Light` mode holds the colors as they are, and `Dark` each with its OKLCH lightness
+/// mirrored, which turns a light background dark and dark text light while keeping
+/// hues; colors that no longer fit sRGB lose chroma.
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format};
+///
+/// let palette = ExportPalette {
+///     name: String::from("paper"),
+///     theory: None,
+///     colors: vec![ExportColor::new("#FFFFFF"), ExportColor::new("#000000")],
+///     gradients: Vec::new(),
+/// };
+/// let json: serde_json::Value =
+///     serde_json::from_str(&export::export(Format::Figma, &palette)).unwrap();
+/// let collection = &json["collections"][0];
+/// assert_eq!(collection["modes"], serde_json::json!(["Light", "Dark"]));
+///
+/// let paper = &collection["variables"][0];
+/// assert_eq!(paper["name"], "paper-1");
+/// assert_eq!(paper["type"], "COLOR");
+/// assert_eq!(paper["valuesByMode"]["Light"]["r"], 1.0);
+/// assert_eq!(paper["valuesByMode"]["Dark"]["r"], 0.0);
+/// ```
+fn figma(palette: &ExportPalette) -> String {
+    let variables: Vec<_> = variable_names(palette)
+        .into_iter()
+        .zip(&palette.colors)
+        .map(|(name, color)| {
+            let light = color.wide.map_or_else(
+                || {
+                    let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+                    Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>())
+                },
+                gamut::fit,
+            );
+            let dark = gamut::fit(Oklch {
+                l: 1.0 - light.l,
+                ..light
+            });
+            json!({
+                "name": name,
+                "type": "COLOR",
+                "description": color.note,
+                "valuesByMode": { "Light": figma_color(light), "Dark": figma_color(dark) },
+            })
+        })
+        .collect();
+    let document = json!({
+        "collections": [{
+            "name": palette.name,
+            "modes": ["Light", "Dark"],
+            "variables": variables,
+        }],
+    });
+    let mut out = serde_json::to_string_pretty(&document).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// A color as Figma's `RGBA`
+fn figma_color(color: Oklch) -> serde_json::Value {
+    let (r, g, b) = gamut::clip(color);
+    json!({ "r": unit(r), "g": unit(g), "b": unit(b), "a": 1.0 })
+}
+
+/// An 8-bit channel from 0 to 1, rounded to four places
+fn unit(channel: u8) -> f64 {
+    (f64::from(channel) / 255.0 * 10_000.0).round() / 10_000.0
+}
+
+/// The colors in the format of the Sketch Palettes plugin, named like the CSS custom
+/// properties
+fn sketch(palette: &ExportPalette) -> String {
+    let colors: Vec<_> = variable_names(palette)
+        .into_iter()
+        .zip(&palette.colors)
+        .map(|(name, color)| {
+            let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+            json!({ "name": name, "red": unit(r), "green": unit(g), "blue": unit(b), "alpha": 1.0 })
+        })
+        .collect();
+    let document = json!({
+        "compatibleVersion": "2.0",
+        "pluginVersion": "2.22",
+        "colors": colors,
+    });
+    let mut out = serde_json::to_string_pretty(&document).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// A Unity `ColorPresetLibrary` asset, named like the CSS custom properties. Dropped into
+/// an `Editor` folder of a project as `<name>.colors`, it shows up in every color picker.
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format};
+///
+/// let palette = ExportPalette {
+///     name: String::from("sunset"),
+///     theory: None,
+///     colors: vec![ExportColor::new("#E07A5F")],
+///     gradients: Vec::new(),
+/// };
+/// let colors = export::export(Format::Unity, &palette);
+/// assert!(colors.starts_with("%YAML 1.1\n"));
+/// assert!(colors.contains("  - m_Name: sunset-1\n    m_Color: {r: 0.8784, g: 0.4784, b: 0.3725, a: 1}\n"));
+/// ```
+fn unity(palette: &ExportPalette) -> String {
+    let mut out = String::from(
+        "%YAML 1.1\n\
+         %TAG !u! tag:unity3d.com,2011:\n\
+         --- !u!114 &1\n\
+         MonoBehaviour:\n  \
+         m_ObjectHideFlags: 52\n  \
+         m_CorrespondingSourceObject: {fileID: 0}\n  \
+         m_PrefabInstance: {fileID: 0}\n  \
+         m_PrefabAsset: {fileID: 0}\n  \
+         m_GameObject: {fileID: 0}\n  \
+         m_Enabled: 1\n  \
+         m_EditorHideFlags: 1\n  \
+         m_Script: {fileID: 12323, guid: 0000000000000000e000000000000000, type: 0}\n",
+    );
+    out.push_str(&format!(
+        "  m_Name: {}\n  m_EditorClassIdentifier: \n  m_Presets:\n",
+        slug(&palette.name)
+    ));
+    for (name, color) in variable_names(palette).iter().zip(&palette.colors) {
+        let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+        out.push_str(&format!(
+            "  - m_Name: {name}\n    m_Color: {{r: {}, g: {}, b: {}, a: 1}}\n",
+            unit(r),
+            unit(g),
+            unit(b)
+        ));
+    }
+    out
+}
+
+/// A Godot 4 `ColorPalette` resource, which color pickers load as their presets
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format};
+///
+/// let palette = ExportPalette {
+///     name: String::from("bw"),
+///     theory: None,
+///     colors: vec![ExportColor::new("#FFFFFF"), ExportColor::new("#000000")],
+///     gradients: Vec::new(),
+/// };
+/// assert_eq!(
+///     export::export(Format::Godot, &palette),
+///     "[gd_resource type=\"ColorPalette\" format=3]\n\n[resource]\n\
+///      colors = PackedColorArray(1, 1, 1, 1, 0, 0, 0, 1)\n"
+/// );
+/// ```
+fn godot(palette: &ExportPalette) -> String {
+    let channels: Vec<String> = palette
+        .colors
+        .iter()
+        .flat_map(|color| {
+            let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+            [unit(r), unit(g), unit(b), 1.0]
+        })
+        .map(|channel| channel.to_string())
+        .collect();
+    format!(
+        "[gd_resource type=\"ColorPalette\" format=3]\n\n[resource]\ncolors = PackedColorArray({})\n",
+        channels.join(", ")
+    )
+}
+
+/// A GIMP palette named after the palette, one row per color labelled as it is in the
+/// app or else like the CSS custom properties
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format};
+///
+/// let palette = ExportPalette {
+///     name: String::from("sunset"),
+///     theory: None,
+///     colors: vec![
+///         ExportColor { label: String::from("Terracotta"), ..ExportColor::new("#E07A5F") },
+///         ExportColor::new("#3D405B"),
+///     ],
+///     gradients: Vec::new(),
+/// };
+/// assert_eq!(
+///     export::export(Format::Gpl, &palette),
+///     "GIMP Palette\nName: sunset\nColumns: 2\n#\n224 122  95\tTerracotta\n 61  64  91\tsunset-2\n"
+/// );
+/// ```
+fn gpl(palette: &ExportPalette) -> String {
+    /// Most swatches GIMP shows in a row
+    const MAX_COLUMNS: usize = 16;
+
+    let mut out = format!(
+        "GIMP Palette\nName: {}\nColumns: {}\n#\n",
+        single_line(&palette.name),
+        palette.colors.len().min(MAX_COLUMNS)
+    );
+    for (name, color) in variable_names(palette).into_iter().zip(&palette.colors) {
+        let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+        let label = match single_line(&color.label) {
+            label if label.is_empty() => name,
+            label => label,
+        };
+        out.push_str(&format!("{r:3} {g:3} {b:3}\t{label}\n"));
+    }
+    out
+}
+
+/// `text` on one line and trimmed, for formats that end a field at the line's end
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Markup of the [`table`] export
+#[derive(Clone, Copy)]
+enum Table {
+    Markdown,
+    Org,
+}
+
+/// A table of swatch, name and hex code per color, to paste into wikis, issues and notes.
+/// Names are those of the CSS custom properties; swatches are inline HTML, which Org
+/// passes on in an export snippet, so they show in rendered pages and HTML exports.
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format};
+///
+/// let palette = ExportPalette {
+///     name: String::from("sunset"),
+///     theory: None,
+///     colors: vec![ExportColor::new("#E07A5F")],
+///     gradients: Vec::new(),
+/// };
+/// assert_eq!(
+///     export::export(Format::Markdown, &palette),
+///     "| Swatch | Name | Hex |\n| --- | --- | --- |\n\
+///      | <span style=\"display:inline-block;width:2em;height:1em;background:#E07A5F\"></span> \
+///      | sunset-1 | `#E07A5F` |\n"
+/// );
+/// assert!(export::export(Format::Org, &palette).ends_with(
+///     "|---+---+---|\n| @@html:<span style=\"display:inline-block;width:2em;height:1em;\
+///      background:#E07A5F\"></span>@@ | sunset-1 | =#E07A5F= |\n"
+/// ));
+/// ```
+fn table(palette: &ExportPalette, markup: Table) -> String {
+    let mut out = String::from("| Swatch | Name | Hex |\n");
+    out.push_str(match markup {
+        Table::Markdown => "| --- | --- | --- |\n",
+        Table::Org => "|---+---+---|\n",
+    });
+    for (name, color) in variable_names(palette).iter().zip(&palette.colors) {
+        let swatch = format!(
+            "<span style=\"display:inline-block;width:2em;height:1em;background:{}\"></span>",
+            color.hex
+        );
+        out.push_str(&match markup {
+            Table::Markdown => format!("| {swatch} | {name} | `{}` |\n", color.hex),
+            Table::Org => format!("| @@html:{swatch}@@ | {name} | ={}= |\n", color.hex),
+        });
+    }
+    out
+}
+
+/// Adobe Color Swatch: the colors once without names (version 1) for old readers, then
+/// again with their names (version 2), every number big-endian and every channel scaled
+/// to 16 bits
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format, Options};
+///
+/// let palette = ExportPalette {
+///     name: String::from("red"),
+///     theory: None,
+///     colors: vec![ExportColor::new("#FF0000")],
+///     gradients: Vec::new(),
+/// };
+/// let aco = export::export_bytes(Format::Aco, &palette, &Options::default()).unwrap();
+/// // Version 1, one color: RGB 65535 0 0
+/// assert_eq!(aco[..14], [0, 1, 0, 1, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0]);
+/// // Version 2 repeats it, named "red-1"
+/// assert_eq!(aco[14..18], [0, 2, 0, 1]);
+/// assert_eq!(aco[28..32], [0, 0, 0, 6]);
+/// assert_eq!(aco.len(), 32 + 6 * 2);
+/// ```
+fn aco(palette: &ExportPalette) -> Vec<u8> {
+    /// Color space number of RGB
+    const RGB: u16 = 0;
+
+    let count = palette.colors.len().min(usize::from(u16::MAX)) as u16;
+    let colors = &palette.colors[..usize::from(count)];
+    let names = variable_names(palette);
+    let mut out = Vec::new();
+    for version in [1u16, 2] {
+        out.extend(version.to_be_bytes());
+        out.extend(count.to_be_bytes());
+        for (color, name) in colors.iter().zip(&names) {
+            let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+            for value in [
+                RGB,
+                u16::from(r) * 257,
+                u16::from(g) * 257,
+                u16::from(b) * 257,
+                0,
+            ] {
+                out.extend(value.to_be_bytes());
+            }
+            if version == 2 {
+                // UTF-16 with a terminating 0, its length counting that
+                let name: Vec<u16> = name.encode_utf16().chain([0]).collect();
+                out.extend((name.len() as u32).to_be_bytes());
+                out.extend(name.iter().flat_map(|unit| unit.to_be_bytes()));
+            }
+        }
+    }
+    out
+}
+
+/// Adobe Swatch Exchange: the colors in a group named after the palette, each named like
+/// the CSS custom properties. Every block starts with its type and length and every number
+/// is big-endian; names are UTF-16 ending in a 0 that their length counts, and channels
+/// floats from 0 to 1.
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format, Options};
+///
+/// let palette = ExportPalette {
+///     name: String::from("red"),
+///     theory: None,
+///     colors: vec![ExportColor::new("#FF0000")],
+///     gradients: Vec::new(),
+/// };
+/// let ase = export::export_bytes(Format::Ase, &palette, &Options::default()).unwrap();
+/// // Signature, version 1.0 and three blocks: group start, color, group end
+/// assert_eq!(ase[..12], *b"ASEF\0\x01\0\0\0\0\0\x03");
+/// // The color "red-1" in RGB, 1 0 0
+/// let color = &ase[28..];
+/// assert_eq!(color[..2], [0, 1]);
+/// assert_eq!(color[6..8], [0, 6]);
+/// assert_eq!(color[20..24], *b"RGB ");
+/// assert_eq!(color[24..28], 1f32.to_be_bytes());
+/// assert_eq!(ase[ase.len() - 6..], [0xC0, 0x02, 0, 0, 0, 0]);
+/// ```
+fn ase(palette: &ExportPalette) -> Vec<u8> {
+    const GROUP_START: u16 = 0xC001;
+    const GROUP_END: u16 = 0xC002;
+    const COLOR: u16 = 0x0001;
+    /// Color type of colors that are neither global nor spot
+    const NORMAL: u16 = 2;
+
+    fn name(text: &str) -> Vec<u8> {
+        let units: Vec<u16> = text.encode_utf16().chain([0]).collect();
+        let mut out = (units.len() as u16).to_be_bytes().to_vec();
+        out.extend(units.iter().flat_map(|unit| unit.to_be_bytes()));
+        out
+    }
+    fn block(out: &mut Vec<u8>, kind: u16, body: &[u8]) {
+        out.extend(kind.to_be_bytes());
+        out.extend((body.len() as u32).to_be_bytes());
+        out.extend(body);
+    }
+
+    let mut out = b"ASEF".to_vec();
+    out.extend(1u16.to_be_bytes());
+    out.extend(0u16.to_be_bytes());
+    out.extend((palette.colors.len() as u32 + 2).to_be_bytes());
+
+    block(&mut out, GROUP_START, &name(&palette.name));
+    for (variable, color) in variable_names(palette).iter().zip(&palette.colors) {
+        let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+        let mut body = name(variable);
+        body.extend(b"RGB ");
+        for channel in [r, g, b] {
+            body.extend((f32::from(channel) / 255.0).to_be_bytes());
+        }
+        body.extend(NORMAL.to_be_bytes());
+        block(&mut out, COLOR, &body);
+    }
+    block(&mut out, GROUP_END, &[]);
+    out
+}
+
+/// Rows of the 5 by 7 glyphs [`strip`] writes hex codes with, highest bit on the left
+const HEX_FONT: [(char, [u8; 7]); 17] = [
+    ('#', [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A]),
+    ('0', [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E]),
+    ('1', [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('2', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F]),
+    ('3', [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E]),
+    ('4', [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02]),
+    ('5', [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E]),
+    ('6', [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E]),
+    ('7', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08]),
+    ('8', [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E]),
+    ('9', [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C]),
+    ('A', [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11]),
+    ('B', [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E]),
+    ('C', [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E]),
+    ('D', [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C]),
+    ('E', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F]),
+    ('F', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10]),
+];
+
+/// The palette as a `width` by `height` PNG, see [`strip`]
+#[cfg(feature = "image")]
+fn png(palette: &ExportPalette, width: u32, height: u32) -> io::Result<Vec<u8>> {
+    use image::{ExtendedColorType, ImageEncoder, codecs::png::PngEncoder};
+
+    let (width, height) = (width.max(1), height.max(1));
+    let mut out = Vec::new();
+    PngEncoder::new(&mut out)
+        .write_image(
+            &strip(palette, width, height),
+            width,
+            height,
+            ExtendedColorType::Rgb8,
+        )
+        .map_err(io::Error::other)?;
+    Ok(out)
+}
+
+#[cfg(not(feature = "image"))]
+fn png(_palette: &ExportPalette, _width: u32, _height: u32) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "built without image support",
+    ))
+}
+
+/// The colors side by side as bands filling `width` by `height` pixels, RGB row by row,
+/// each as wide as its share of the colors' summed [`ExportColor::width`] and with its hex
+/// code near the bottom in black or white, whichever reads. Codes are drawn as large as
+/// fits a band, and left out where not even the smallest does.
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette};
+///
+/// let palette = ExportPalette {
+///     name: String::from("bw"),
+///     theory: None,
+///     colors: vec![
+///         ExportColor { width: 3, ..ExportColor::new("#FFFFFF") },
+///         ExportColor::new("#000000"),
+///     ],
+///     gradients: Vec::new(),
+/// };
+/// let pixels = export::strip(&palette, 200, 50);
+/// assert_eq!(pixels.len(), 200 * 50 * 3);
+/// // Three quarters white, one black
+/// assert_eq!(pixels[149 * 3..150 * 3], [255, 255, 255]);
+/// assert_eq!(pixels[150 * 3..151 * 3], [0, 0, 0]);
+/// // Black text on the white band, white on the black one
+/// assert!(pixels.chunks(3).take(150 * 50).any(|pixel| pixel == [0, 0, 0]));
+/// ```
+pub fn strip(palette: &ExportPalette, width: u32, height: u32) -> Vec<u8> {
+    let (width, height) = (width as usize, height as usize);
+    let mut pixels = vec![0; width * height * 3];
+    let total: usize = palette.colors.iter().map(|c| usize::from(c.width)).sum();
+    let mut before = 0;
+    for color in &palette.colors {
+        let left = width * before / total.max(1);
+        before += usize::from(color.width);
+        let right = width * before / total.max(1);
+        let (r, g, b) = hex2rgb(color.hex.trim_start_matches('#'));
+        for y in 0..height {
+            for x in left..right {
+                pixels[(y * width + x) * 3..][..3].copy_from_slice(&[r, g, b]);
+            }
+        }
+
+        // Glyphs are 5 wide with a column between them, drawn at a whole multiple
+        let text = color.hex.to_uppercase();
+        let text_width = text.chars().count() * 6 - 1;
+        let scale = ((right - left) * 4 / 5 / text_width).min(height / 5 / 7);
+        if scale == 0 {
+            continue;
+        }
+        let ink = if is_light((r, g, b)) {
+            [0, 0, 0]
+        } else {
+            [255, 255, 255]
+        };
+        let x0 = left + (right - left - text_width * scale) / 2;
+        let y0 = height - 7 * scale - height / 10;
+        for (position, symbol) in text.chars().enumerate() {
+            let Some((_, rows)) = HEX_FONT.iter().find(|(glyph, _)| *glyph == symbol) else {
+                continue;
+            };
+            for (row, bits) in rows.iter().enumerate() {
+                for column in (0..5).filter(|column| bits & (0x10 >> column) != 0) {
+                    let x = x0 + (position * 6 + column) * scale;
+                    let y = y0 + row * scale;
+                    for dy in 0..scale {
+                        let start = ((y + dy) * width + x) * 3;
+                        for pixel in pixels[start..start + scale * 3].chunks_mut(3) {
+                            pixel.copy_from_slice(&ink);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// Whether black text reads better than white on `color`
+fn is_light((r, g, b): (u8, u8, u8)) -> bool {
+    Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>()).l > 0.65
+}
+
+/// One custom property per color on `:root`, below its note if it has one. Colors outside
+/// sRGB get their Display P3 value in a `color-gamut: p3` media query, so other screens
+/// keep the clipped fallback.
+fn css(palette: &ExportPalette) -> String {
+    let names = variable_names(palette);
+    let mut out = format!("/* {} */\n:root {{\n", palette.name);
+    for (name, color) in names.iter().zip(&palette.colors) {
+        out.push_str(&note_comment(color));
+        out.push_str(&format!("  --{name}: {};\n", color.hex));
+    }
+    out.push_str("}\n");
+    out.push_str(&p3_overrides(&names, palette));
+    out
+}
+
+/// A variable per color numbered after `prefix`, below its note if it has one, then a map
+/// of them all keyed like the CSS custom properties, for looping over the palette or
+/// looking colors up by label or role
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format, Options};
+/// use terminal_palette::roles::Role;
+///
+/// let palette = ExportPalette {
+///     name: String::from("sunset"),
+///     theory: None,
+///     colors: vec![
+///         ExportColor::new("#E07A5F"),
+///         ExportColor { role: Some(Role::Background), ..ExportColor::new("#3D405B") },
+///     ],
+///     gradients: Vec::new(),
+/// };
+/// let options = Options { scss_prefix: String::from("brand"), ..Options::default() };
+/// let scss = export::export_with(Format::Scss, &palette, &options);
+/// assert!(scss.contains("\n$brand-1: #E07A5F;\n$brand-2: #3D405B;\n"));
+/// assert!(scss.contains("$brand-palette: (\n  \"sunset-1\": $brand-1,\n  \"background\": $brand-2,\n);\n"));
+/// ```
+fn scss(palette: &ExportPalette, prefix: &str) -> String {
+    let variables = scss_names(palette, prefix);
+    let mut out = format!("// {}\n", palette.name);
+    for (variable, color) in variables.iter().zip(&palette.colors) {
+        if !color.note.is_empty() {
+            out.push_str(&format!("// {}\n", color.note.replace('\n', " ")));
+        }
+        out.push_str(&format!("${variable}: {};\n", color.hex));
+    }
+
+    out.push_str(&format!("\n${}-palette: (\n", scss_prefix(prefix)));
+    for (key, variable) in variable_names(palette).iter().zip(&variables) {
+        out.push_str(&format!("  \"{key}\": ${variable},\n"));
+    }
+    out.push_str(");\n");
+    out
+}
+
+/// Names of the variables of the SCSS export, without the `$`: `prefix` as a slug and the
+/// color's position
+pub fn scss_names(palette: &ExportPalette, prefix: &str) -> Vec<String> {
+    let prefix = scss_prefix(prefix);
+    (1..=palette.colors.len())
+        .map(|position| format!("{prefix}-{position}"))
+        .collect()
+}
+
+/// `prefix` as a slug, `color` when nothing is left of it
+fn scss_prefix(prefix: &str) -> String {
+    match slug(prefix) {
+        prefix if prefix.is_empty() => String::from("color"),
+        prefix => prefix,
+    }
+}
+
+/// [`css`] with a comment above every property on its share and use, dominant first
+fn proportions_css(palette: &ExportPalette) -> String {
+    let names = variable_names(palette);
+    let widths: Vec<u16> = palette.colors.iter().map(|color| color.width).collect();
+    let mut rows: Vec<_> = names
+        .iter()
+        .zip(&palette.colors)
+        .zip(proportions(&widths))
+        .collect();
+    rows.sort_by_key(|(_, (usage, _))| *usage as u8);
+
+    let mut out = format!("/* {}, in proportion */\n:root {{\n", palette.name);
+    for ((name, color), (usage, percent)) in rows {
+        out.push_str(&format!(
+            "  /* {}, {percent:.0}%: {} */\n",
+            usage.key(),
+            usage.guidance()
+        ));
+        out.push_str(&note_comment(color));
+        out.push_str(&format!("  --{name}: {};\n", color.hex));
+    }
+    out.push_str("}\n");
+    out.push_str(&p3_overrides(&names, palette));
+    out
+}
+
+/// Swatch strip 600 wide, each color as wide as its share, dominant first
+fn proportions_svg(palette: &ExportPalette) -> String {
+    const WIDTH: f32 = 600.0;
+    const HEIGHT: f32 = 120.0;
+
+    let widths: Vec<u16> = palette.colors.iter().map(|color| color.width).collect();
+    let mut swatches: Vec<_> = palette.colors.iter().zip(proportions(&widths)).collect();
+    swatches.sort_by_key(|(_, (usage, _))| *usage as u8);
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+         viewBox=\"0 0 {WIDTH} {HEIGHT}\">\n  <title>{}</title>\n",
+        xml_escape(&palette.name)
+    );
+    let mut x = 0.0;
+    for (color, (usage, percent)) in swatches {
+        let width = WIDTH * percent / 100.0;
+        out.push_str(&format!(
+            "  <rect x=\"{x:.1}\" y=\"0\" width=\"{width:.1}\" height=\"{HEIGHT}\" fill=\"{}\">\
+             <title>{} {}, {percent:.0}%{}</title></rect>\n",
+            color.hex,
+            color.hex,
+            usage.key(),
+            match color.note.as_str() {
+                "" => String::new(),
+                note => format!(": {}", xml_escape(note)),
+            }
+        ));
+        x += width;
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// The colors side by side as rounded swatches, each with its label (or variable name) and
+/// hex code written on it in black or white, whichever reads. Swatches are square for a
+/// [`ExportColor::width`] of 1 and as wide as that many squares and the gaps between them
+/// otherwise. The text is real text, so it stays sharp at any size and can be searched and
+/// copied.
+///
+/// ```
+/// use terminal_palette::export::{self, ExportColor, ExportPalette, Format};
+///
+/// let palette = ExportPalette {
+///     name: String::from("sunset"),
+///     theory: None,
+///     colors: vec![
+///         ExportColor {
+///             label: String::from("Terra & cotta"),
+///             width: 2,
+///             ..ExportColor::new("#E07A5F")
+///         },
+///         ExportColor::new("#3D405B"),
+///     ],
+///     gradients: Vec::new(),
+/// };
+/// let svg = export::export(Format::Svg, &palette);
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"400\" height=\"140\""));
+/// assert!(svg.contains("<rect x=\"10\" y=\"10\" width=\"250\" height=\"120\" rx=\"12\" fill=\"#E07A5F\"/>"));
+/// assert!(svg.contains("<rect x=\"270\" y=\"10\" width=\"120\" height=\"120\" rx=\"12\" fill=\"#3D405B\"/>"));
+/// assert!(svg.contains(">Terra &amp; cotta</text>"));
+/// assert!(svg.contains("fill=\"#FFFFFF\">sunset-2</text>"));
+/// ```
+pub fn swatches_svg(palette: &ExportPalette) -> String {
+    const SIDE: usize = 120;
+    const GAP: usize = 10;
+
+    let widths: Vec<usize> = palette
+        .colors
+        .iter()
+        .map(|color| usize::from(color.width.max(1)) * (SIDE + GAP) - GAP)
+        .collect();
+    let width = GAP + widths.iter().map(|width| width + GAP).sum::<usize>();
+    let height = SIDE + 2 * GAP;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"monospace\">\n  <title>{}</title>\n",
+        xml_escape(&palette.name)
+    );
+    let mut x = GAP;
+    for ((name, color), swatch_width) in variable_names(palette)
+        .into_iter()
+        .zip(&palette.colors)
+        .zip(widths)
+    {
+        let hex = color.hex.to_uppercase();
+        let ink = if is_light(hex2rgb(hex.trim_start_matches('#'))) {
+            "#000000"
+        } else {
+            "#FFFFFF"
+        };
+        let label = match single_line(&color.label) {
+            label if label.is_empty() => name,
+            label => label,
+        };
+        out.push_str(&format!(
+            "  <g>\n    <rect x=\"{x}\" y=\"{GAP}\" width=\"{swatch_width}\" height=\"{SIDE}\" rx=\"12\" \
+             fill=\"{hex}\"/>\n    <text x=\"{}\" y=\"{}\" font-size=\"13\" fill=\"{ink}\">{}</text>\n    \
+             <text x=\"{}\" y=\"{}\" font-size=\"12\" fill=\"{ink}\">{hex}</text>\n  </g>\n",
+            x + 10,
+            GAP + SIDE - 30,
+            xml_escape(&label),
+            x + 10,
+            GAP + SIDE - 12,
+        ));
+        x += swatch_width + GAP;
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// The palette's gradients, or one in OKLab through all its colors when it has none
+fn gradients(palette: &ExportPalette) -> Vec<Gradient> {
+    if !palette.gradients.is_empty() {
+        return palette.gradients.clone();
+    }
+    let colors: Vec<String> = palette
+        .colors
+        .iter()
+        .map(|color| color.hex.clone())
+        .collect();
+    vec![Gradient::from_colors(&palette.name, &colors, Space::Oklab)]
+}
+
+/// One custom property per gradient on `:root`, named after it
+fn gradient_css(palette: &ExportPalette) -> String {
+    let mut out = format!("/* {} gradients */\n:root {{\n", palette.name);
+    for (idx, gradient) in gradients(palette).iter().enumerate() {
+        let name = match slug(&gradient.name) {
+            name if name.is_empty() => format!("gradient-{}", idx + 1),
+            name => name,
+        };
+        out.push_str(&format!("  --{name}: {};\n", gradient.css()));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// The gradients as stripes 600 wide, one below the other. SVG blends in sRGB, so
+/// gradients in other spaces get the in-between samples of [`Gradient::srgb_stops`].
+fn gradient_svg(palette: &ExportPalette) -> String {
+    const WIDTH: f32 = 600.0;
+    const STRIPE: f32 = 80.0;
+
+    let gradients = gradients(palette);
+    let height = STRIPE * gradients.len() as f32;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{height}\" \
+         viewBox=\"0 0 {WIDTH} {height}\">\n  <title>{}</title>\n  <defs>\n",
+        xml_escape(&palette.name)
+    );
+    for (idx, ramp) in gradients.iter().enumerate() {
+        out.push_str(&format!(
+            "    <linearGradient id=\"gradient-{}\">\n",
+            idx + 1
+        ));
+        for stop in ramp.srgb_stops() {
+            out.push_str(&format!(
+                "      <stop offset=\"{}%\" stop-color=\"{}\"/>\n",
+                gradient::percent(stop.position),
+                stop.hex
+            ));
+        }
+        out.push_str("    </linearGradient>\n");
+    }
+    out.push_str("  </defs>\n");
+    for (idx, gradient) in gradients.iter().enumerate() {
+        out.push_str(&format!(
+            "  <rect x=\"0\" y=\"{}\" width=\"{WIDTH}\" height=\"{STRIPE}\" \
+             fill=\"url(#gradient-{})\"><title>{}</title></rect>\n",
+            STRIPE * idx as f32,
+            idx + 1,
+            xml_escape(&gradient.name)
+        ));
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// The note of `color` as an indented CSS comment line, empty without a note. A `*/` in
+/// the note is broken up so it can't end the comment early.
+fn note_comment(color: &ExportColor) -> String {
+    match color.note.as_str() {
+        "" => String::new(),
+        note => format!("  /* {} */\n", note.replace("*/", "* /")),
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The Display P3 values of the colors outside sRGB, in a `color-gamut: p3` media query
+fn p3_overrides(names: &[String], palette: &ExportPalette) -> String {
+    let mut out = String::new();
+    let wide: Vec<_> = names
+        .iter()
+        .zip(&palette.colors)
+        .filter_map(|(name, color)| color.wide.map(|wide| (name, wide)))
+        .collect();
+    if !wide.is_empty() {
+        out.push_str("\n@media (color-gamut: p3) {\n  :root {\n");
+        for (name, color) in wide {
+            let (r, g, b) = gamut::to_display_p3(color);
+            out.push_str(&format!(
+                "    --{name}: color(display-p3 {r:.4} {g:.4} {b:.4});\n"
+            ));
+        }
+        out.push_str("  }\n}\n");
+    }
+    out
+}
+
+/// Identifier of every color: its label, else its role, else the palette name and position.
+/// Repeats get their position appended.
+pub fn variable_names(palette: &ExportPalette) -> Vec<String> {
+    let prefix = match slug(&palette.name) {
+        name if name.is_empty() => String::from("color"),
+        name => name,
+    };
+    let mut names: Vec<String> = Vec::new();
+    for (idx, color) in palette.colors.iter().enumerate() {
+        let name = match (slug(&color.label), color.role) {
+            (label, _) if !label.is_empty() => label,
+            (_, Some(role)) => role.key().to_string(),
+            _ => format!("{prefix}-{}", idx + 1),
+        };
+        let name = if names.contains(&name) {
+            format!("{name}-{}", idx + 1)
+        } else {
+            name
+        };
+        names.push(name);
+    }
+    names
+}
+
+/// Lowercase ASCII letters and digits, anything else collapsed into single dashes
+fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,268 @@
+//! Palettes taken from pictures: the colors of a GIF frame, weighed by how many pixels
+//! they cover.
+//!
+//! Stills and animations are read as GIF; for a film still, cut the frame out first, e.g.
+//! `ffmpeg -ss 1:23 -i film.mkv -frames:v 1 still.gif`. Pictures on the web are fetched
+//! with [`download`]. Builds without the `image` feature fail every extraction, and
+//! builds without `network` every download, with [`io::ErrorKind::Unsupported`].
+//!
+//! ```no_run
+//! use std::{path::Path, time::Duration};
+//! use terminal_palette::extract;
+//!
+//! let counted = extract::frame_colors(Path::new("clip.gif"), Duration::from_secs(3))?;
+//! for ((r, g, b), share) in extract::palette(&counted, 5) {
+//!     println!("#{r:02X}{g:02X}{b:02X} covers {share:.0}%");
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::{collections::HashMap, fs, io, io::Read, path::Path, time::Duration};
+
+use crate::convert;
+
+/// Extension of the files [`frame_colors`] reads
+pub const EXTENSION: &str = "gif";
+
+/// Colors with the number of pixels each covers
+pub type PixelCounts = Vec<((u8, u8, u8), usize)>;
+
+/// Largest picture [`download`] accepts, in bytes
+pub const MAX_DOWNLOAD: usize = 64 * 1024 * 1024;
+
+/// Colors covering less of a picture than this (in percent) are noise or anti-aliasing,
+/// not part of its palette
+const MIN_SHARE: f32 = 0.5;
+
+/// How many low bits of each channel [`palette`] ignores when grouping similar colors
+const BUCKET_BITS: u8 = 3;
+
+/// Every color of the frame of the GIF at `path` that is showing `at` into the animation,
+/// with the number of pixels it covers, most common first. Stills have a single frame
+/// showing all the time; past the end of an animation its last frame shows. Transparent
+/// pixels don't count.
+pub fn frame_colors(path: &Path, at: Duration) -> io::Result<PixelCounts> {
+    if !path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "{}: only GIF files are read, cut video frames out first",
+                path.display()
+            ),
+        ));
+    }
+    decode_frame(fs::File::open(path)?, at, &path.display().to_string())
+}
+
+/// [`frame_colors`] of a GIF already in memory, such as a [`download`]; `source` names it
+/// in errors
+pub fn frame_colors_of(bytes: &[u8], at: Duration, source: &str) -> io::Result<PixelCounts> {
+    if !bytes.starts_with(b"GIF8") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{source}: only GIF pictures are read"),
+        ));
+    }
+    decode_frame(bytes, at, source)
+}
+
+#[cfg(feature = "image")]
+fn decode_frame(reader: impl Read, at: Duration, source: &str) -> io::Result<PixelCounts> {
+    use gif::{ColorOutput, DecodeOptions, DisposalMethod};
+
+    let invalid = |e: gif::DecodingError| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{source}: {e}"))
+    };
+    let mut options = DecodeOptions::new();
+    options.set_color_output(ColorOutput::RGBA);
+    let mut decoder = options.read_info(reader).map_err(invalid)?;
+    let width = usize::from(decoder.width());
+    let mut canvas = vec![0u8; width * usize::from(decoder.height()) * 4];
+    let pixel = |x: usize, y: usize| (y * width + x) * 4..(y * width + x + 1) * 4;
+
+    // Disposal of the frame before, which only happens once the next one is drawn
+    let mut dispose: Option<(DisposalMethod, [usize; 4], Vec<u8>)> = None;
+    let mut shown = Duration::ZERO;
+    while let Some(frame) = decoder.read_next_frame().map_err(invalid)? {
+        match dispose.take() {
+            Some((DisposalMethod::Previous, _, previous)) => canvas = previous,
+            Some((DisposalMethod::Background, [left, top, frame_width, frame_height], _)) => {
+                for y in top..top + frame_height {
+                    for x in left..(left + frame_width).min(width) {
+                        if let Some(target) = canvas.get_mut(pixel(x, y)) {
+                            target.fill(0);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let rect = [frame.left, frame.top, frame.width, frame.height].map(usize::from);
+        let previous = match frame.dispose {
+            DisposalMethod::Previous => canvas.clone(),
+            _ => Vec::new(),
+        };
+        let [left, top, frame_width, _] = rect;
+        for (idx, color) in frame.buffer.chunks_exact(4).enumerate() {
+            let (x, y) = (left + idx % frame_width, top + idx / frame_width);
+            if color[3] == 0 || x >= width {
+                continue;
+            }
+            if let Some(target) = canvas.get_mut(pixel(x, y)) {
+                target.copy_from_slice(color);
+            }
+        }
+
+        // Like browsers, delays of 0 or 1 hundredths play at 10 frames per second
+        let delay = match frame.delay {
+            0 | 1 => 10,
+            delay => delay,
+        };
+        shown += Duration::from_millis(u64::from(delay) * 10);
+        if at < shown {
+            break;
+        }
+        dispose = Some((frame.dispose, rect, previous));
+    }
+
+    let mut counts: HashMap<(u8, u8, u8), usize> = HashMap::new();
+    for pixel in canvas.chunks_exact(4).filter(|pixel| pixel[3] > 0) {
+        *counts.entry((pixel[0], pixel[1], pixel[2])).or_default() += 1;
+    }
+    let mut counted: Vec<_> = counts.into_iter().collect();
+    counted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    Ok(counted)
+}
+
+#[cfg(not(feature = "image"))]
+fn decode_frame(_reader: impl Read, _at: Duration, _source: &str) -> io::Result<PixelCounts> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "built without image support",
+    ))
+}
+
+/// Whether `source` is an `http(s)://` URL for [`download`] rather than a file
+pub fn is_url(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
+}
+
+/// Fetch the picture at `url`, reporting the bytes received and the size the server
+/// announced (if it did) to `progress` as they arrive. Fails for error responses and
+/// for pictures over [`MAX_DOWNLOAD`].
+#[cfg(feature = "network")]
+pub fn download(url: &str, mut progress: impl FnMut(usize, Option<usize>)) -> io::Result<Vec<u8>> {
+    let response = ureq::get(url).call().map_err(|e| match e {
+        ureq::Error::Status(code, response) => {
+            io::Error::other(format!("{url}: {code} {}", response.status_text()))
+        }
+        ureq::Error::Transport(e) => io::Error::other(format!("{url}: {e}")),
+    })?;
+    let size: Option<usize> = response
+        .header("Content-Length")
+        .and_then(|length| length.parse().ok());
+    if size.is_some_and(|size| size > MAX_DOWNLOAD) {
+        return Err(too_large(url));
+    }
+
+    let mut reader = response.into_reader().take(MAX_DOWNLOAD as u64 + 1);
+    let mut bytes = Vec::with_capacity(size.unwrap_or(0));
+    let mut chunk = [0; 64 * 1024];
+    loop {
+        match reader.read(&mut chunk)? {
+            0 => break,
+            read => bytes.extend_from_slice(&chunk[..read]),
+        }
+        progress(bytes.len(), size);
+    }
+    if bytes.len() > MAX_DOWNLOAD {
+        Err(too_large(url))
+    } else {
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "network")]
+fn too_large(url: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{url}: larger than {} MiB", MAX_DOWNLOAD / 1024 / 1024),
+    )
+}
+
+#[cfg(not(feature = "network"))]
+pub fn download(_url: &str, _progress: impl FnMut(usize, Option<usize>)) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "built without network support",
+    ))
+}
+
+/// Up to `count` colors standing for the `counted` pixels of a picture, each with the
+/// share of them in percent it covers. Similar colors are grouped and rare ones left out
+/// before the most distinct are picked, so dithering and anti-aliasing don't end up in
+/// the palette; see [`convert::most_distinct`] and [`convert::coverage`].
+///
+/// ```
+/// use terminal_palette::extract::palette;
+///
+/// // A dark picture with a red accent, a few slightly different dark pixels and a stray one
+/// let counted = [((20, 20, 30), 700), ((22, 21, 30), 100), ((200, 30, 40), 199), ((0, 255, 0), 1)];
+/// // The stray pixel still counts, towards the color nearest to it
+/// assert_eq!(palette(&counted, 5), vec![((20, 20, 30), 80.1), ((200, 30, 40), 19.9)]);
+/// ```
+pub fn palette(counted: &[((u8, u8, u8), usize)], count: usize) -> Vec<((u8, u8, u8), f32)> {
+    // Sums of the channels and the pixel count per bucket
+    let mut buckets: HashMap<(u8, u8, u8), ([usize; 3], usize)> = HashMap::new();
+    for &((r, g, b), pixels) in counted {
+        let key = (r >> BUCKET_BITS, g >> BUCKET_BITS, b >> BUCKET_BITS);
+        let (sums, total) = buckets.entry(key).or_default();
+        for (sum, channel) in sums.iter_mut().zip([r, g, b]) {
+            *sum += usize::from(channel) * pixels;
+        }
+        *total += pixels;
+    }
+
+    let mut grouped: Vec<((u8, u8, u8), usize)> = buckets
+        .into_values()
+        .map(|(sums, total)| {
+            let mean = |sum: usize| ((sum + total / 2) / total) as u8;
+            ((mean(sums[0]), mean(sums[1]), mean(sums[2])), total)
+        })
+        .collect();
+    grouped.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let all: usize = grouped.iter().map(|(_, pixels)| pixels).sum();
+    let common = grouped
+        .iter()
+        .take_while(|(_, pixels)| *pixels as f32 * 100.0 >= MIN_SHARE * all as f32)
+        .count();
+    let colors: Vec<_> = grouped[..common].iter().map(|&(color, _)| color).collect();
+
+    let picks = convert::most_distinct(&colors, count);
+    let coverage = convert::coverage(&grouped, &picks);
+    picks.into_iter().zip(coverage).collect()
+}
+
+/// A time into an animation as seconds (`12.5`), `m:ss` or `h:mm:ss`, fractions allowed
+///
+/// ```
+/// use std::time::Duration;
+/// use terminal_palette::extract::parse_time;
+///
+/// assert_eq!(parse_time("1:02.5"), Some(Duration::from_millis(62_500)));
+/// assert_eq!(parse_time("1:00:00"), Some(Duration::from_secs(3600)));
+/// assert_eq!(parse_time("-3"), None);
+/// ```
+pub fn parse_time(text: &str) -> Option<Duration> {
+    let mut seconds = 0.0;
+    for part in text.trim().split(':') {
+        let value: f64 = part.parse().ok().filter(|value: &f64| *value >= 0.0)?;
+        seconds = seconds * 60.0 + value;
+    }
+    Duration::try_from_secs_f64(seconds).ok()
+}
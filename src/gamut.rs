@@ -0,0 +1,114 @@
+//! Keeping colors inside sRGB, the gamut every block is stored in.
+//!
+//! Colors described in OKLCH or CIELAB can ask for more chroma than a screen shows. Clipping
+//! each channel on its own gets them in, but shifts the hue on the way: a vivid OKLCH blue
+//! turns purple. [`fit`] lowers the chroma instead, keeping lightness and hue.
+//!
+//! Display P3, the gamut of most recent screens, holds about a quarter more colors than sRGB.
+//! [`to_display_p3`] and [`from_display_p3`] convert to and from it for CSS `color()` values.
+//!
+//! ```
+//! use palette::Oklch;
+//! use terminal_palette::gamut;
+//!
+//! let vivid_blue = Oklch::new(0.45, 0.35, 264.0);
+//! assert!(!gamut::in_gamut(vivid_blue));
+//!
+//! let fitted = gamut::fit(vivid_blue);
+//! assert!(gamut::in_gamut(fitted));
+//! assert_eq!(fitted.hue, vivid_blue.hue);
+//! ```
+
+use palette::convert::FromColorUnclamped;
+use palette::{FromColor, LinSrgb, Oklch, Srgb};
+
+/// How far past 0 or 1 a linear channel may be and still count as in gamut, which absorbs
+/// float error on colors right at the edge
+const TOLERANCE: f32 = 1e-4;
+/// Chroma precision [`fit`] searches to, well below one 8-bit step
+const CHROMA_PRECISION: f32 = 1e-4;
+
+/// Whether `color` lies inside sRGB
+pub fn in_gamut(color: Oklch) -> bool {
+    let rgb = LinSrgb::from_color_unclamped(color);
+    [rgb.red, rgb.green, rgb.blue]
+        .iter()
+        .all(|channel| (-TOLERANCE..=1.0 + TOLERANCE).contains(channel))
+}
+
+/// `color` brought into sRGB at the same lightness and hue, with as much chroma as fits.
+/// Lightness itself is clamped to `[0, 1]` first.
+pub fn fit(color: Oklch) -> Oklch {
+    let color = Oklch {
+        l: color.l.clamp(0.0, 1.0),
+        ..color
+    };
+    if in_gamut(color) {
+        return color;
+    }
+
+    let (mut low, mut high) = (0.0, color.chroma);
+    while high - low > CHROMA_PRECISION {
+        let chroma = (low + high) / 2.0;
+        if in_gamut(Oklch { chroma, ..color }) {
+            low = chroma;
+        } else {
+            high = chroma;
+        }
+    }
+    Oklch {
+        chroma: low,
+        ..color
+    }
+}
+
+/// Linear sRGB to linear Display P3; both share the D65 white point
+const SRGB_TO_P3: [[f32; 3]; 3] = [
+    [0.822_462_1, 0.177_538, 0.0],
+    [0.033_194_2, 0.966_805_8, 0.0],
+    [0.017_082_7, 0.072_397_4, 0.910_519_9],
+];
+/// Linear Display P3 to linear sRGB, the inverse of [`SRGB_TO_P3`]
+const P3_TO_SRGB: [[f32; 3]; 3] = [
+    [1.224_940_1, -0.224_940_4, 0.0],
+    [-0.042_056_9, 1.042_057_1, 0.0],
+    [-0.019_637_6, -0.078_636_1, 1.098_273_5],
+];
+
+/// Whether `color` lies inside Display P3
+pub fn in_display_p3(color: Oklch) -> bool {
+    linear_p3(color)
+        .iter()
+        .all(|channel| (-TOLERANCE..=1.0 + TOLERANCE).contains(channel))
+}
+
+/// `color` as Display P3 channels in `[0, 1]`, gamma encoded like sRGB as CSS
+/// `color(display-p3 r g b)` expects them; clipped if it lies outside P3 too
+pub fn to_display_p3(color: Oklch) -> (f32, f32, f32) {
+    let [r, g, b] = linear_p3(color).map(|channel| channel.clamp(0.0, 1.0));
+    // P3 uses the sRGB transfer curve, so sRGB's encoder does for it
+    let encoded = Srgb::from_linear(LinSrgb::new(r, g, b));
+    (encoded.red, encoded.green, encoded.blue)
+}
+
+/// The color of gamma-encoded Display P3 channels in `[0, 1]`
+pub fn from_display_p3(r: f32, g: f32, b: f32) -> Oklch {
+    let linear: LinSrgb = Srgb::new(r, g, b).into_linear();
+    let [r, g, b] = multiply(P3_TO_SRGB, [linear.red, linear.green, linear.blue]);
+    Oklch::from_color_unclamped(LinSrgb::new(r, g, b))
+}
+
+fn linear_p3(color: Oklch) -> [f32; 3] {
+    let rgb = LinSrgb::from_color_unclamped(color);
+    multiply(SRGB_TO_P3, [rgb.red, rgb.green, rgb.blue])
+}
+
+fn multiply(matrix: [[f32; 3]; 3], vector: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row.iter().zip(vector).map(|(a, b)| a * b).sum())
+}
+
+/// `color` as 8-bit sRGB, each channel clipped on its own if it lies outside
+pub fn clip(color: Oklch) -> (u8, u8, u8) {
+    let rgb: Srgb<u8> = Srgb::from_color(color).into_format();
+    (rgb.red, rgb.green, rgb.blue)
+}
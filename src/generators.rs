@@ -0,0 +1,973 @@
+//! Palette generators for every supported color theory.
+//!
+//! Each generator rewrites the unlocked blocks of a palette in place and builds around the
+//! locked ones: their average hue (and for most theories saturation/value) becomes the base
+//! color. Without locks the first block gets a random color which is then used as the base.
+//!
+//! Palettes are slices of `ColorBlock` of any length; generators address blocks by their
+//! logical position (0, 1, 2, ...) so layouts with many rows spread evenly.
+
+use palette::{FromColor, Oklch, Srgb};
+use rand::{Rng, RngCore, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::color::ColorBlock;
+use crate::gamut;
+
+/// The color theories a palette can be generated from
+#[derive(Copy, Clone, Debug, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum ColorTheories {
+    Analogous,
+    Complementary,
+    Triad,
+    Tetrad,
+    Hexad,
+    Monochrome,
+    Shadows,
+    Lights,
+    Neutrals,
+}
+
+impl ColorTheories {
+    /// Identifier used on the command line, e.g. `triad`
+    pub fn key(self) -> &'static str {
+        match self {
+            ColorTheories::Analogous => "analogous",
+            ColorTheories::Complementary => "complementary",
+            ColorTheories::Triad => "triad",
+            ColorTheories::Tetrad => "tetrad",
+            ColorTheories::Hexad => "hexad",
+            ColorTheories::Monochrome => "monochrome",
+            ColorTheories::Shadows => "shadows",
+            ColorTheories::Lights => "lights",
+            ColorTheories::Neutrals => "neutrals",
+        }
+    }
+
+    /// The generator implementing this theory
+    pub fn generator(self) -> &'static dyn PaletteGenerator {
+        match self {
+            ColorTheories::Analogous => &Analogous {
+                jitter: Jitter::Medium,
+            },
+            ColorTheories::Complementary => &COMPLEMENTARY,
+            ColorTheories::Triad => &TRIAD,
+            ColorTheories::Tetrad => &TETRAD,
+            ColorTheories::Hexad => &HEXAD,
+            ColorTheories::Monochrome => &Monochrome {
+                scale: LightnessScale::Linear,
+                jitter: Jitter::Medium,
+            },
+            ColorTheories::Shadows => &Shades { to_light: false },
+            ColorTheories::Lights => &Shades { to_light: true },
+            ColorTheories::Neutrals => &Neutrals,
+        }
+    }
+
+    /// Like [`generator`](Self::generator), with the Monochrome `scale` and the `jitter`
+    /// of the theories that draw random offsets
+    pub fn configured(self, scale: LightnessScale, jitter: Jitter) -> Box<dyn PaletteGenerator> {
+        match self {
+            ColorTheories::Analogous => Box::new(Analogous { jitter }),
+            ColorTheories::Complementary => Box::new(HarmonySpec {
+                jitter,
+                ..COMPLEMENTARY
+            }),
+            ColorTheories::Triad => Box::new(HarmonySpec { jitter, ..TRIAD }),
+            ColorTheories::Tetrad => Box::new(HarmonySpec { jitter, ..TETRAD }),
+            ColorTheories::Hexad => Box::new(HarmonySpec { jitter, ..HEXAD }),
+            ColorTheories::Monochrome => Box::new(Monochrome { scale, jitter }),
+            ColorTheories::Shadows => Box::new(Shades { to_light: false }),
+            ColorTheories::Lights => Box::new(Shades { to_light: true }),
+            ColorTheories::Neutrals => Box::new(Neutrals),
+        }
+    }
+
+    /// Whether the theory orders the blocks into a sequential ramp, so the palette reads
+    /// as a colormap from the first block to the last
+    pub fn is_ramp(self) -> bool {
+        matches!(
+            self,
+            ColorTheories::Monochrome
+                | ColorTheories::Shadows
+                | ColorTheories::Lights
+                | ColorTheories::Neutrals
+        )
+    }
+}
+
+/// How far generated colors stray at random from a theory's exact harmony
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Jitter {
+    /// No random offsets: the harmony exactly as the theory defines it
+    None,
+    /// Half the offsets of [`Jitter::Medium`]
+    Low,
+    /// The offsets every theory was tuned with
+    #[default]
+    Medium,
+    /// Twice the offsets of [`Jitter::Medium`]
+    High,
+}
+
+impl Jitter {
+    /// Identifier used in the config file and message ids, e.g. `medium`
+    pub fn key(self) -> &'static str {
+        match self {
+            Jitter::None => "none",
+            Jitter::Low => "low",
+            Jitter::Medium => "medium",
+            Jitter::High => "high",
+        }
+    }
+
+    /// Factor every random hue, saturation and value offset is scaled by
+    pub fn factor(self) -> f32 {
+        match self {
+            Jitter::None => 0.0,
+            Jitter::Low => 0.5,
+            Jitter::Medium => 1.0,
+            Jitter::High => 2.0,
+        }
+    }
+}
+
+/// Something that can (re)color the unlocked blocks of a palette
+pub trait PaletteGenerator {
+    /// Draws every random number from `rng`, so a seeded `rng` gives a reproducible palette
+    fn generate(&self, color_blocks: &mut [ColorBlock], rng: &mut dyn RngCore);
+}
+
+/// Regenerate the unlocked blocks of `color_blocks` using `theory`
+pub fn generate(theory: ColorTheories, color_blocks: &mut [ColorBlock]) {
+    theory.generator().generate(color_blocks, &mut rand::rng());
+}
+
+/// Like [`generate`], but the same `seed`, theory and blocks always give the same palette
+pub fn generate_seeded(theory: ColorTheories, color_blocks: &mut [ColorBlock], seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    theory.generator().generate(color_blocks, &mut rng);
+}
+
+/// Nudge every unlocked block a random distance from its current color: up to `step`
+/// degrees of hue and `step` percentage points of saturation and value, either way
+pub fn random_walk(color_blocks: &mut [ColorBlock], step: f32, rng: &mut dyn RngCore) {
+    if step <= 0.0 {
+        return;
+    }
+
+    for block in color_blocks.iter_mut().filter(|block| !block.locked) {
+        let hue = block.hsv.hue.into_positive_degrees() + rng.random_range(-step..=step);
+        let sat = block.hsv.saturation + rng.random_range(-step..=step) / 100.0;
+        let val = block.hsv.value + rng.random_range(-step..=step) / 100.0;
+        block.change_color(
+            hue.rem_euclid(360.0),
+            sat.clamp(0.0, 1.0),
+            val.clamp(0.0, 1.0),
+        );
+    }
+}
+
+/// A fresh palette of `count` blocks generated with `theory`, no locks involved
+///
+/// ```
+/// use terminal_palette::generators::{generate_palette, ColorTheories};
+///
+/// let palette = generate_palette(ColorTheories::Triad, 6);
+/// assert_eq!(palette.len(), 6);
+/// println!("{}", palette[0].get_hex());
+/// ```
+pub fn generate_palette(theory: ColorTheories, count: usize) -> Vec<ColorBlock> {
+    let mut color_blocks: Vec<ColorBlock> = (0..count)
+        .map(|i| ColorBlock::new(i + 1, 0.0, 0.0, 0.0))
+        .collect();
+
+    generate(theory, &mut color_blocks);
+    color_blocks
+}
+
+fn locked_blocks(color_blocks: &[ColorBlock]) -> Vec<ColorBlock> {
+    color_blocks
+        .iter()
+        .filter(|block| block.locked)
+        .cloned()
+        .collect()
+}
+
+/// (array position, logical position, locked) for every block
+fn logical_positions(color_blocks: &[ColorBlock]) -> Vec<(usize, usize, bool)> {
+    color_blocks
+        .iter()
+        .enumerate()
+        .map(|(pos, block)| (pos, pos, block.locked))
+        .collect()
+}
+
+/// Base hue, saturation and value to build around: the average of the locked blocks,
+/// or a fresh random color on the first block when nothing is locked.
+/// `default_sat`/`default_val` are only used when there is no first block to randomize.
+fn anchor_color(
+    color_blocks: &mut [ColorBlock],
+    locked_blocks: &[ColorBlock],
+    default_sat: f32,
+    default_val: f32,
+    rng: &mut dyn RngCore,
+) -> (f32, f32, f32) {
+    if !locked_blocks.is_empty() {
+        return (
+            ColorBlock::get_avg_hue(locked_blocks),
+            ColorBlock::get_avg_saturation(locked_blocks),
+            ColorBlock::get_avg_value(locked_blocks),
+        );
+    }
+
+    // Generate initial random color for first block
+    match color_blocks.first_mut() {
+        Some(color_block) => {
+            color_block.generate_random_color_with(rng);
+            (
+                color_block.hsv.hue.into_degrees(),
+                color_block.hsv.saturation,
+                color_block.hsv.value,
+            )
+        }
+        None => (0.0, default_sat, default_val),
+    }
+}
+
+/// Data-driven hue harmony: blocks are dealt round-robin into one group per hue offset,
+/// and blocks sharing a group spread out in saturation/value around the base color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HarmonySpec {
+    /// Hue of each group relative to the base hue, in degrees
+    pub hue_offsets: &'static [f32],
+    /// Maximum random hue jitter in degrees (exclusive)
+    pub rand_rate: i32,
+    /// Saturation/value used when there is neither a lock nor a block to randomize
+    pub default_sat: f32,
+    pub default_val: f32,
+    /// Spread of saturation/value inside a group, as (with locked blocks, without)
+    pub sat_variation: (f32, f32),
+    pub val_variation: (f32, f32),
+    /// Scales the random hue jitter
+    pub jitter: Jitter,
+}
+
+/// The base hue and its opposite, varying saturation/value within each group
+pub const COMPLEMENTARY: HarmonySpec = HarmonySpec {
+    hue_offsets: &[0.0, 180.0],
+    rand_rate: 4,
+    default_sat: 0.70,
+    default_val: 0.65,
+    sat_variation: (0.12, 0.18),
+    val_variation: (0.15, 0.22),
+    jitter: Jitter::Medium,
+};
+
+/// Three hue groups 120° apart, varying saturation/value within each group
+pub const TRIAD: HarmonySpec = HarmonySpec {
+    hue_offsets: &[0.0, 120.0, 240.0],
+    rand_rate: 4,
+    default_sat: 0.72,
+    default_val: 0.68,
+    sat_variation: (0.12, 0.18),
+    val_variation: (0.15, 0.22),
+    jitter: Jitter::Medium,
+};
+
+/// Four hue groups 90° apart, varying saturation/value within each group
+pub const TETRAD: HarmonySpec = HarmonySpec {
+    hue_offsets: &[0.0, 90.0, 180.0, 270.0],
+    rand_rate: 4,
+    default_sat: 0.68,
+    default_val: 0.63,
+    sat_variation: (0.12, 0.16),
+    val_variation: (0.15, 0.20),
+    jitter: Jitter::Medium,
+};
+
+/// Six hue groups 60° apart, varying saturation/value within each group
+pub const HEXAD: HarmonySpec = HarmonySpec {
+    hue_offsets: &[0.0, 60.0, 120.0, 180.0, 240.0, 300.0],
+    rand_rate: 4,
+    default_sat: 0.65,
+    default_val: 0.60,
+    sat_variation: (0.10, 0.14),
+    val_variation: (0.12, 0.18),
+    jitter: Jitter::Medium,
+};
+
+impl PaletteGenerator for HarmonySpec {
+    fn generate(&self, color_blocks: &mut [ColorBlock], rng: &mut dyn RngCore) {
+        let locked_blocks = locked_blocks(color_blocks);
+        let (base_hue, base_sat, base_val) = anchor_color(
+            color_blocks,
+            &locked_blocks,
+            self.default_sat,
+            self.default_val,
+            rng,
+        );
+
+        let logical_positions = logical_positions(color_blocks);
+        if logical_positions.is_empty() {
+            return;
+        }
+
+        let base_colors = self.hue_offsets.len();
+        let colors_per_group = logical_positions.len().div_ceil(base_colors);
+
+        // Moderate variation when a locked color exists, more when there is none
+        let (sat_variation_range, val_variation_range) = if !locked_blocks.is_empty() {
+            (self.sat_variation.0, self.val_variation.0)
+        } else {
+            (self.sat_variation.1, self.val_variation.1)
+        };
+
+        for (array_pos, logical_pos, is_locked) in logical_positions {
+            if is_locked {
+                continue; // Skip locked blocks
+            }
+
+            if let Some(color_block) = color_blocks.get_mut(array_pos) {
+                let randomness =
+                    rng.random_range(-self.rand_rate..self.rand_rate) as f32 * self.jitter.factor();
+
+                // Which base color group the block falls in, and its rank inside that group
+                let color_group = logical_pos % base_colors;
+                let variation_index = logical_pos / base_colors;
+
+                let group_base_hue = (base_hue + self.hue_offsets[color_group]) % 360.0;
+                let new_hue = (group_base_hue + randomness) % 360.0;
+
+                // 0.0 to 1.0 across the group
+                let variation_factor = if colors_per_group > 1 {
+                    (variation_index as f32) / (colors_per_group - 1) as f32
+                } else {
+                    0.5
+                };
+
+                // Create variation: center around base, spread based on variation_index
+                let sat_offset = (variation_factor - 0.5) * sat_variation_range * 2.0;
+                let val_offset = (variation_factor - 0.5) * val_variation_range * 2.0;
+
+                let new_sat = (base_sat + sat_offset).clamp(0.0, 1.0);
+                let new_val = (base_val + val_offset).clamp(0.0, 1.0);
+
+                color_block.change_color(new_hue, new_sat, new_val);
+            }
+        }
+    }
+}
+
+/// Neighbouring hues spread in 10° steps around the locked (or middle) block
+pub struct Analogous {
+    pub jitter: Jitter,
+}
+
+/// One hue across the blocks: saturation and value spread evenly for
+/// [`LightnessScale::Linear`], a design system's lightness steps otherwise
+pub struct Monochrome {
+    pub scale: LightnessScale,
+    /// Scales the small random hue shifts of the linear ramp
+    pub jitter: Jitter,
+}
+
+/// Lightness steps a monochrome ramp follows
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LightnessScale {
+    /// Saturation and value spread evenly, dark to light
+    #[default]
+    Linear,
+    /// Tailwind CSS's 50 to 950, light to dark
+    Tailwind,
+    /// Material Design's tones 10 to 99, dark to light
+    Material,
+}
+
+impl LightnessScale {
+    /// Identifier used in the config file and on the command line, e.g. `tailwind`
+    pub fn key(self) -> &'static str {
+        match self {
+            LightnessScale::Linear => "linear",
+            LightnessScale::Tailwind => "tailwind",
+            LightnessScale::Material => "material",
+        }
+    }
+
+    /// OKLCH lightness of every step, `None` for [`LightnessScale::Linear`]
+    pub fn steps(self) -> Option<&'static [f32]> {
+        match self {
+            LightnessScale::Linear => None,
+            // Lightness of Tailwind's red, which its other chromatic palettes stay close to
+            LightnessScale::Tailwind => Some(&[
+                0.971, 0.936, 0.885, 0.808, 0.704, 0.637, 0.577, 0.505, 0.444, 0.396, 0.258,
+            ]),
+            // Tones are CIELAB L*; for a neutral that is (L* + 16) / 116 in OKLCH
+            LightnessScale::Material => Some(&[
+                0.224, 0.310, 0.397, 0.483, 0.569, 0.655, 0.741, 0.828, 0.914, 0.957, 0.991,
+            ]),
+        }
+    }
+}
+
+/// A single-hue ramp through the locked (or first) block, towards white when `to_light`
+/// is set and towards black otherwise
+pub struct Shades {
+    pub to_light: bool,
+}
+
+/// A single-hue ramp desaturating away from the locked (or first) block
+pub struct Neutrals;
+
+impl PaletteGenerator for Analogous {
+    fn generate(&self, color_blocks: &mut [ColorBlock], rng: &mut dyn RngCore) {
+        analogous(color_blocks, self.jitter, rng);
+    }
+}
+
+impl PaletteGenerator for Monochrome {
+    fn generate(&self, color_blocks: &mut [ColorBlock], rng: &mut dyn RngCore) {
+        match self.scale.steps() {
+            Some(steps) => scaled_monochrome(color_blocks, steps, rng),
+            None => monochrome(color_blocks, self.jitter, rng),
+        }
+    }
+}
+
+impl PaletteGenerator for Shades {
+    fn generate(&self, color_blocks: &mut [ColorBlock], rng: &mut dyn RngCore) {
+        shades(color_blocks, self.to_light, rng);
+    }
+}
+
+impl PaletteGenerator for Neutrals {
+    fn generate(&self, color_blocks: &mut [ColorBlock], rng: &mut dyn RngCore) {
+        neutrals(color_blocks, rng);
+    }
+}
+
+fn analogous(color_blocks: &mut [ColorBlock], jitter: Jitter, rng: &mut dyn RngCore) {
+    let locked_blocks = locked_blocks(color_blocks);
+    let rand_rate = 3; // Minimal randomness for cleaner analogous relationships
+    let (base_hue, base_sat, base_val) =
+        anchor_color(color_blocks, &locked_blocks, 0.65, 0.65, rng);
+
+    let logical_positions = logical_positions(color_blocks);
+    if logical_positions.is_empty() {
+        return;
+    }
+
+    let total_blocks = logical_positions.len();
+
+    // Best practice: analogous colors should stay within a reasonable range
+    // to maintain true analogous harmony while having noticeable differences
+    // Professional tools like palettegenerator.com distribute colors bidirectionally
+    // Use a fixed step size for consistent, noticeable differences between colors
+    let step_size = 10.0; // Fixed 10° step for clear, noticeable differences
+
+    // Find the locked block's logical position to use as center (if any)
+    let center_logical_pos = logical_positions
+        .iter()
+        .find(|(_, _, is_locked)| *is_locked)
+        .map(|(_, logical_pos, _)| *logical_pos)
+        .unwrap_or(total_blocks / 2); // Use middle if no locked block
+
+    for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
+        if *is_locked {
+            continue; // Skip locked blocks
+        }
+
+        if let Some(color_block) = color_blocks.get_mut(*array_pos) {
+            let randomness = rng.random_range(-rand_rate..rand_rate) as f32 * jitter.factor();
+
+            // Distribute colors bidirectionally around base hue
+            // Colors before center go negative, colors after go positive
+            let offset = if *logical_pos < center_logical_pos {
+                // Before center: negative offset
+                let diff = (center_logical_pos - *logical_pos) as f32;
+                -(diff * step_size)
+            } else if *logical_pos > center_logical_pos {
+                // After center: positive offset
+                let diff = (*logical_pos - center_logical_pos) as f32;
+                diff * step_size
+            } else {
+                // At center (shouldn't happen for unlocked, but safety)
+                0.0
+            };
+            let new_hue = ((base_hue + offset + randomness) % 360.0 + 360.0) % 360.0;
+
+            // Vary saturation and value very slightly for visual interest while maintaining harmony
+            // Analogous colors should stay very close to the base color's characteristics
+            // Use locked blocks' saturation/value as base when available
+            let sat_variation = if !locked_blocks.is_empty() {
+                0.05 // Very small variation when locked color exists (±5%)
+            } else {
+                0.10 // Slightly more variation when no locked color (±10%)
+            };
+            let val_variation = if !locked_blocks.is_empty() {
+                0.05 // Very small variation when locked color exists (±5%)
+            } else {
+                0.10 // Slightly more variation when no locked color (±10%)
+            };
+
+            let new_sat = (base_sat
+                + rng.random_range(-sat_variation..sat_variation) as f32 / 100.0 * jitter.factor())
+            .clamp(0.0, 1.0);
+            let new_val = (base_val
+                + rng.random_range(-val_variation..val_variation) as f32 / 100.0 * jitter.factor())
+            .clamp(0.0, 1.0);
+
+            color_block.change_color(new_hue, new_sat, new_val);
+        }
+    }
+}
+
+fn monochrome(color_blocks: &mut [ColorBlock], jitter: Jitter, rng: &mut dyn RngCore) {
+    let locked_blocks = locked_blocks(color_blocks);
+    let mut base_hue: f32 = 0.0;
+    let hue_variation = 3.0; // Minimal hue variation for true monochrome (±3 degrees)
+    let rand_rate = 2; // Very low randomness for hue to maintain monochromatic integrity
+
+    if !locked_blocks.is_empty() {
+        base_hue = ColorBlock::get_avg_hue(&locked_blocks);
+    } else {
+        // Generate initial random color for first block
+        if let Some(color_block) = color_blocks.first_mut() {
+            color_block.generate_random_color_with(rng);
+            base_hue = color_block.hsv.hue.into_degrees();
+        }
+    }
+
+    let logical_positions = logical_positions(color_blocks);
+    if logical_positions.is_empty() {
+        return;
+    }
+
+    let total_blocks = logical_positions.len();
+
+    // Get anchor saturation and value from locked blocks or first block
+    let (anchor_sat, anchor_val) = if !locked_blocks.is_empty() {
+        if let Some(anchor_block) = locked_blocks.first() {
+            let (_, sat, val) = anchor_block.get_hsv_values();
+            (sat, val)
+        } else {
+            (0.6, 0.6) // Default fallback
+        }
+    } else {
+        if let Some(color_block) = color_blocks.first() {
+            let (_, sat, val) = color_block.get_hsv_values();
+            (sat, val)
+        } else {
+            (0.6, 0.6) // Default fallback
+        }
+    };
+
+    // For monochrome, we create variations in both saturation and brightness
+    // This creates tints (lighter), tones (muted), and shades (darker)
+    // Saturation range: from low (0.1) to high (0.9)
+    // Brightness range: from low (0.2) to high (0.9)
+
+    let sat_range_start = 0.1;
+    let sat_range_end = 0.9;
+    let val_range_start = 0.2;
+    let val_range_end = 0.9;
+
+    // Calculate step sizes for even distribution
+    let sat_step = if total_blocks > 1 {
+        (sat_range_end - sat_range_start) / (total_blocks - 1) as f32
+    } else {
+        0.0
+    };
+
+    let val_step = if total_blocks > 1 {
+        (val_range_end - val_range_start) / (total_blocks - 1) as f32
+    } else {
+        0.0
+    };
+
+    // Apply monochrome progression to all unlocked blocks
+    for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
+        if *is_locked {
+            continue; // Skip locked blocks
+        }
+
+        if let Some(color_block) = color_blocks.get_mut(*array_pos) {
+            // Keep hue constant with minimal variation for true monochrome
+            let hue_randomness = rng.random_range(-rand_rate..rand_rate) as f32 * jitter.factor();
+            let new_hue = (base_hue + hue_randomness * hue_variation / 10.0) % 360.0;
+
+            // Vary saturation across the range for visual interest
+            // Create a smooth progression that doesn't necessarily follow anchor
+            let new_sat = if locked_blocks.is_empty() {
+                // No locked blocks: distribute evenly across range
+                sat_range_start + (sat_step * *logical_pos as f32)
+            } else {
+                // With locked blocks: use anchor saturation as reference but still vary
+                // Create variation around anchor while maintaining smooth progression
+                let base_sat_progress = sat_range_start + (sat_step * *logical_pos as f32);
+                // Blend with anchor saturation for smoother transitions
+                (base_sat_progress * 0.7 + anchor_sat * 0.3).clamp(sat_range_start, sat_range_end)
+            };
+
+            // Vary brightness across the range
+            // Alternate between lighter and darker for more interesting palette
+            let new_val = if locked_blocks.is_empty() {
+                // No locked blocks: distribute evenly across range
+                val_range_start + (val_step * *logical_pos as f32)
+            } else {
+                // With locked blocks: use anchor value as reference but still vary
+                let base_val_progress = val_range_start + (val_step * *logical_pos as f32);
+                // Blend with anchor value for smoother transitions
+                (base_val_progress * 0.7 + anchor_val * 0.3).clamp(val_range_start, val_range_end)
+            };
+
+            color_block.change_color(new_hue, new_sat, new_val);
+        }
+    }
+}
+
+/// Put block `n` of the ramp on step `n` of `steps`, at the anchor's hue and as much of its
+/// OKLCH chroma as fits. Fewer blocks than steps skip steps evenly, more interpolate.
+fn scaled_monochrome(color_blocks: &mut [ColorBlock], steps: &[f32], rng: &mut dyn RngCore) {
+    let locked_blocks = locked_blocks(color_blocks);
+    let (hue, sat, val) = anchor_color(color_blocks, &locked_blocks, 0.6, 0.6, rng);
+    let anchor = ColorBlock::new(0, hue, sat, val);
+    let (r, g, b) = anchor.get_rgb_values();
+    let anchor = Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>());
+
+    let total_blocks = color_blocks.len();
+    for (array_pos, logical_pos, is_locked) in logical_positions(color_blocks) {
+        if is_locked {
+            continue;
+        }
+        let position = match total_blocks {
+            1 => (steps.len() / 2) as f32,
+            _ => logical_pos as f32 * (steps.len() - 1) as f32 / (total_blocks - 1) as f32,
+        };
+        let lightness = if total_blocks <= steps.len() {
+            steps[position.round() as usize]
+        } else {
+            let (below, above) = (position.floor() as usize, position.ceil() as usize);
+            let t = position - below as f32;
+            steps[below] + (steps[above] - steps[below]) * t
+        };
+
+        let color = gamut::fit(Oklch {
+            l: lightness,
+            ..anchor
+        });
+        color_blocks[array_pos].set_oklch(color);
+    }
+}
+
+fn shades(color_blocks: &mut [ColorBlock], to_light: bool, rng: &mut dyn RngCore) {
+    // Full range: 0.0 (black) to 1.0 (white) - no constraints
+    let black = 0.0;
+    let white = 1.0;
+
+    // Get base hue from locked blocks or generate
+    let locked_blocks = locked_blocks(color_blocks);
+    let base_hue: f32;
+
+    if !locked_blocks.is_empty() {
+        base_hue = ColorBlock::get_avg_hue(&locked_blocks);
+    } else {
+        // Generate initial random color for first block if no locks
+        if let Some(color_block) = color_blocks.first_mut() {
+            color_block.generate_random_color_with(rng);
+            base_hue = color_block.hsv.hue.into_degrees();
+        } else {
+            return; // No blocks available
+        }
+    }
+
+    // Collect all existing blocks with their array positions, values, saturations, and lock status
+    // Then map them to logical positions (0, 1, 2, ...) for even distribution
+    let mut block_info: Vec<(usize, f32, f32, bool)> = Vec::new();
+    for (i, block) in color_blocks.iter().enumerate() {
+        block_info.push((i, block.hsv.value, block.hsv.saturation, block.locked));
+    }
+
+    if block_info.is_empty() {
+        return;
+    }
+
+    let total_blocks = block_info.len();
+
+    // Map array positions to logical positions (0, 1, 2, ..., total_blocks-1)
+    // This ensures even distribution regardless of gaps in the array
+    let mut logical_positions: Vec<(usize, usize, f32, f32, bool)> = Vec::new();
+    for (logical_pos, (array_pos, val, sat, is_locked)) in block_info.iter().enumerate() {
+        logical_positions.push((*array_pos, logical_pos, *val, *sat, *is_locked));
+    }
+
+    // Find locked blocks and use the first one as anchor
+    let locked_info: Vec<(usize, usize, f32, f32)> = logical_positions
+        .iter()
+        .filter_map(|(array_pos, logical_pos, val, sat, is_locked)| {
+            if *is_locked {
+                Some((*array_pos, *logical_pos, *val, *sat))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Determine anchor (first locked block, or first block if none)
+    let (_anchor_array_pos, anchor_logical_pos, anchor_val, anchor_sat) =
+        if let Some((_array_pos, logical_pos, val, sat)) = locked_info.first() {
+            (*_array_pos, *logical_pos, *val, *sat)
+        } else {
+            // No locked blocks - use first block as anchor
+            let (array_pos, logical_pos, val, sat, _) = logical_positions[0];
+            (array_pos, logical_pos, val, sat)
+        };
+
+    // Calculate dynamic step size based on total block count
+    // More blocks = smaller step (smoother transition)
+    // Fewer blocks = larger step (bigger jumps)
+
+    // Calculate how many blocks are after the anchor (including the anchor itself)
+    let blocks_after_anchor = total_blocks - anchor_logical_pos;
+
+    // Calculate progression from anchor to target
+    // For Lights: anchor -> one step below white (evenly incremented)
+    // For Shadows: anchor -> one step above black (evenly incremented)
+    let step_from_anchor = if to_light {
+        // Lights: target is one step below white
+        // Calculate step size: (white - anchor_val) divided by number of blocks after anchor
+        // This ensures even increments and last block is one step below white
+        if blocks_after_anchor > 0 {
+            (white - anchor_val) / blocks_after_anchor as f32
+        } else {
+            0.0
+        }
+    } else {
+        // Shadows: target is one step above black
+        // Calculate step size: anchor_val divided by number of blocks after anchor
+        // This ensures even increments and last block is one step above black
+        if blocks_after_anchor > 0 {
+            anchor_val / blocks_after_anchor as f32
+        } else {
+            0.0
+        }
+    };
+
+    // Calculate how many blocks are before the anchor
+    let blocks_before_anchor = anchor_logical_pos;
+
+    // Calculate step size from start to anchor (if there are blocks before)
+    let step_to_anchor = if blocks_before_anchor > 0 {
+        if to_light {
+            // For lights: start is black, anchor is somewhere above
+            (anchor_val - black) / blocks_before_anchor as f32
+        } else {
+            // For shadows: start is white, anchor is somewhere below
+            (white - anchor_val) / blocks_before_anchor as f32
+        }
+    } else {
+        0.0
+    };
+
+    // For Lights mode: calculate desaturation step (from anchor saturation to 0.0)
+    // For Shadows mode: keep saturation constant (as requested - never change)
+    let sat_step_from_anchor = if to_light && blocks_after_anchor > 1 {
+        // Lights: desaturate from anchor_sat to 0.0 (white has no saturation)
+        anchor_sat / (blocks_after_anchor - 1) as f32
+    } else {
+        0.0
+    };
+
+    let sat_step_to_anchor = if to_light && blocks_before_anchor > 0 {
+        // Lights: before anchor, increase saturation from 0.0 to anchor_sat
+        anchor_sat / blocks_before_anchor as f32
+    } else {
+        0.0
+    };
+
+    // Apply progression to all unlocked blocks
+    for (array_pos, logical_pos, _current_val, _current_sat, is_locked) in logical_positions.iter()
+    {
+        if *is_locked {
+            continue; // Skip locked blocks
+        }
+
+        if let Some(color_block) = color_blocks.get_mut(*array_pos) {
+            // Calculate new value (brightness)
+            let new_val = if *logical_pos < anchor_logical_pos {
+                // Before anchor: progress from start toward anchor
+                if to_light {
+                    black + (step_to_anchor * *logical_pos as f32)
+                } else {
+                    white - (step_to_anchor * *logical_pos as f32)
+                }
+            } else if *logical_pos == anchor_logical_pos {
+                // At anchor: use anchor value (shouldn't happen for unlocked, but just in case)
+                anchor_val
+            } else {
+                // After anchor: progress from anchor toward target
+                let steps_after = (*logical_pos - anchor_logical_pos) as f32;
+                if to_light {
+                    anchor_val + (step_from_anchor * steps_after)
+                } else {
+                    anchor_val - (step_from_anchor * steps_after)
+                }
+            };
+
+            // Clamp value to valid range [0.0, 1.0]
+            let clamped_val = new_val.clamp(black, white);
+
+            // Calculate new saturation
+            let new_sat = if to_light {
+                // Lights mode: desaturate as we get lighter
+                if *logical_pos < anchor_logical_pos {
+                    // Before anchor: increase saturation toward anchor
+                    (sat_step_to_anchor * *logical_pos as f32).min(anchor_sat)
+                } else if *logical_pos == anchor_logical_pos {
+                    anchor_sat
+                } else {
+                    // After anchor: decrease saturation toward 0.0 (white)
+                    let steps_after = (*logical_pos - anchor_logical_pos) as f32;
+                    (anchor_sat - (sat_step_from_anchor * steps_after)).max(0.0)
+                }
+            } else {
+                // Shadows mode: keep saturation constant (never change)
+                anchor_sat
+            };
+
+            color_block.change_color(base_hue, new_sat, clamped_val);
+        }
+    }
+}
+
+fn neutrals(color_blocks: &mut [ColorBlock], rng: &mut dyn RngCore) {
+    // Get base hue and anchor color from locked blocks or generate
+    let locked_blocks = locked_blocks(color_blocks);
+    let base_hue: f32;
+    let anchor_sat: f32;
+    let anchor_val: f32;
+
+    if !locked_blocks.is_empty() {
+        base_hue = ColorBlock::get_avg_hue(&locked_blocks);
+        // Use the first locked block's saturation and value as anchor
+        if let Some(anchor_block) = locked_blocks.first() {
+            let (_, sat, val) = anchor_block.get_hsv_values();
+            anchor_sat = sat;
+            anchor_val = val;
+        } else {
+            return; // Should not happen, but safety check
+        }
+    } else {
+        // Generate initial random color for first block if no locks
+        if let Some(color_block) = color_blocks.first_mut() {
+            color_block.generate_random_color_with(rng);
+            let (h, s, v) = color_block.get_hsv_values();
+            base_hue = h;
+            anchor_sat = s;
+            anchor_val = v;
+        } else {
+            return; // No blocks available
+        }
+    }
+
+    let logical_positions = logical_positions(color_blocks);
+    if logical_positions.is_empty() {
+        return;
+    }
+
+    let total_blocks = logical_positions.len();
+
+    // Find locked blocks and use the first one as anchor
+    let locked_info: Vec<(usize, usize)> = logical_positions
+        .iter()
+        .filter_map(|(array_pos, logical_pos, is_locked)| {
+            if *is_locked {
+                Some((*array_pos, *logical_pos))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Determine anchor logical position (first locked block, or first block if none)
+    let anchor_logical_pos = if let Some((_, logical_pos)) = locked_info.first() {
+        *logical_pos
+    } else {
+        // No locked blocks - use first block as anchor
+        logical_positions[0].1
+    };
+
+    // Calculate desaturation progression
+    // We'll create a smooth transition from anchor saturation to 0 (fully desaturated)
+    // The anchor maintains its saturation, and other blocks desaturate progressively
+
+    // Calculate how many blocks are after the anchor (including anchor)
+    let blocks_after_anchor = total_blocks - anchor_logical_pos;
+
+    // Calculate how many blocks are before the anchor
+    let blocks_before_anchor = anchor_logical_pos;
+
+    // Desaturation step: from anchor_sat to 0.0
+    // Blocks before anchor: increase saturation from 0.0 to anchor_sat
+    // Anchor: keep anchor_sat
+    // Blocks after anchor: decrease saturation from anchor_sat to 0.0
+    let sat_step_to_anchor = if blocks_before_anchor > 0 {
+        anchor_sat / blocks_before_anchor as f32
+    } else {
+        0.0
+    };
+
+    let sat_step_from_anchor = if blocks_after_anchor > 1 {
+        anchor_sat / (blocks_after_anchor - 1) as f32
+    } else {
+        0.0
+    };
+
+    // Apply neutral progression to all unlocked blocks
+    for (array_pos, logical_pos, is_locked) in logical_positions.iter() {
+        if *is_locked {
+            continue; // Skip locked blocks
+        }
+
+        if let Some(color_block) = color_blocks.get_mut(*array_pos) {
+            // Calculate new saturation (desaturation progression)
+            let new_sat = if *logical_pos < anchor_logical_pos {
+                // Before anchor: increase saturation from 0.0 toward anchor
+                (sat_step_to_anchor * *logical_pos as f32).min(anchor_sat)
+            } else if *logical_pos == anchor_logical_pos {
+                // At anchor: use anchor saturation (shouldn't happen for unlocked, but safety)
+                anchor_sat
+            } else {
+                // After anchor: decrease saturation from anchor toward 0.0
+                let steps_after = (*logical_pos - anchor_logical_pos) as f32;
+                (anchor_sat - (sat_step_from_anchor * steps_after)).max(0.0)
+            };
+
+            // For neutrals, we keep the value relatively stable but add slight variation
+            // for visual depth. This creates a more interesting neutral palette.
+            // Value variation: ±5% from anchor value
+            let value_variation = 0.05;
+            let value_range =
+                (anchor_val - value_variation).max(0.0)..=(anchor_val + value_variation).min(1.0);
+
+            // Distribute value slightly across blocks for subtle depth
+            let value_progress = if total_blocks > 1 {
+                (*logical_pos as f32) / ((total_blocks - 1) as f32)
+            } else {
+                0.0
+            };
+
+            // Create a subtle value curve: slightly darker in middle, lighter at edges
+            // This creates a more natural neutral palette
+            let value_offset = (value_progress - 0.5) * 2.0; // -1.0 to 1.0
+            let value_adjustment = value_offset * value_variation * 0.5; // Reduced variation
+            let new_val =
+                (anchor_val + value_adjustment).clamp(*value_range.start(), *value_range.end());
+
+            color_block.change_color(base_hue, new_sat, new_val);
+        }
+    }
+}
@@ -0,0 +1,469 @@
+//! Named gradients: ordered color stops blended in a chosen space.
+//!
+//! Blocks are the discrete colors of a palette; a [`Gradient`] runs smoothly through some
+//! of them, for backgrounds, charts and heat maps. Where the stops sit and which space the
+//! colors blend in both shape the ramp: OKLab keeps the middle of blue to yellow from
+//! turning muddy, OKLCH walks around the hue wheel instead of through gray.
+//!
+//! ```
+//! use terminal_palette::gradient::{Gradient, Space};
+//!
+//! let colors = [String::from("#1D3557"), String::from("#E9C46A")];
+//! let gradient = Gradient::from_colors("dusk", &colors, Space::Oklab);
+//! assert_eq!(gradient.sample(0.0), "#1D3557");
+//! assert_eq!(
+//!     gradient.css(),
+//!     "linear-gradient(in oklab to right, #1D3557 0%, #E9C46A 100%)"
+//! );
+//! ```
+//!
+//! [`Gradient::from_css`] reads gradients back from CSS, such as ones copied off the web:
+//!
+//! ```
+//! use terminal_palette::gradient::{Gradient, Space};
+//!
+//! let css = "background: linear-gradient(45deg, rgba(131, 58, 180, 1), #FD1D1D 50%, #FCB045);";
+//! let gradient = Gradient::from_css("instagram", css).unwrap();
+//! assert_eq!(gradient.space, Space::Srgb);
+//! let positions: Vec<f32> = gradient.stops.iter().map(|stop| stop.position).collect();
+//! assert_eq!(positions, [0.0, 0.5, 1.0]);
+//! assert_eq!(gradient.stops[0].hex, "#833AB4");
+//! ```
+
+use std::{error::Error, fmt};
+
+use palette::{Clamp, FromColor, LinSrgb, Mix, Oklab, Oklch, Srgb};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::color::hex2rgb;
+use crate::convert::{self, ColorError};
+
+/// Samples between two stops when a format can only blend in sRGB, enough that the
+/// straight sRGB pieces follow the curve of the other spaces
+const SEGMENT_SAMPLES: usize = 8;
+
+/// Color space the colors between two stops are mixed in
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Space {
+    /// Perceptually even steps, the usual choice
+    #[default]
+    Oklab,
+    /// Like OKLab, but going around the hue wheel, which keeps the middle saturated
+    Oklch,
+    /// Plain sRGB channels, what browsers and most tools do by default
+    Srgb,
+    /// Linear light, like light from two sources mixing
+    LinearRgb,
+}
+
+impl Space {
+    /// Identifier used in `.palette` files and translations, e.g. `linear-rgb`
+    pub fn key(self) -> &'static str {
+        match self {
+            Space::Oklab => "oklab",
+            Space::Oklch => "oklch",
+            Space::Srgb => "srgb",
+            Space::LinearRgb => "linear-rgb",
+        }
+    }
+
+    /// Name of the space in a CSS `in <space>` interpolation hint
+    fn css(self) -> &'static str {
+        match self {
+            Space::Oklab => "oklab",
+            Space::Oklch => "oklch",
+            Space::Srgb => "srgb",
+            Space::LinearRgb => "srgb-linear",
+        }
+    }
+
+    /// `a` and `b` (hex) mixed in this space: `ratio` 0 is all `a`, 1 is all `b`
+    pub fn mix(self, a: &str, b: &str, ratio: f32) -> String {
+        let (a, b) = (srgb(a), srgb(b));
+        let ratio = ratio.clamp(0.0, 1.0);
+        let mixed = match self {
+            Space::Oklab => Srgb::from_color(Oklab::from_color(a).mix(Oklab::from_color(b), ratio)),
+            Space::Oklch => Srgb::from_color(Oklch::from_color(a).mix(Oklch::from_color(b), ratio)),
+            Space::Srgb => a.mix(b, ratio),
+            Space::LinearRgb => {
+                Srgb::from_linear(LinSrgb::from_color(a).mix(LinSrgb::from_color(b), ratio))
+            }
+        };
+        let rgb: Srgb<u8> = mixed.clamp().into_format();
+        convert::rgb_to_hex(rgb.red, rgb.green, rgb.blue)
+    }
+}
+
+/// Why [`Gradient::from_css`] rejected its input
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssError {
+    /// No `linear-gradient(` in the text
+    NotAGradient,
+    /// `linear-gradient(` without its closing parenthesis
+    Unclosed,
+    /// Interpolation space named after `in` other than the ones of [`Space`]
+    UnknownSpace(String),
+    /// A stop position other than a percentage, as typed
+    InvalidPosition(String),
+    /// The stop as typed and what is wrong with its color
+    InvalidColor(String, ColorError),
+    /// Number of color stops given; a gradient needs 2
+    TooFewStops(usize),
+}
+
+impl fmt::Display for CssError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CssError::NotAGradient => write!(f, "no linear-gradient() found"),
+            CssError::Unclosed => write!(f, "linear-gradient( is missing its closing \")\""),
+            CssError::UnknownSpace(space) => write!(
+                f,
+                "unknown interpolation space {space:?}, expected oklab, oklch, srgb or srgb-linear"
+            ),
+            CssError::InvalidPosition(position) => {
+                write!(f, "stop position {position:?} is not a percentage")
+            }
+            CssError::InvalidColor(stop, e) => write!(f, "{stop:?}: {e}"),
+            CssError::TooFewStops(count) => {
+                write!(f, "a gradient takes at least 2 colors, got {count}")
+            }
+        }
+    }
+}
+
+impl Error for CssError {}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stop {
+    /// `#RRGGBB`
+    pub hex: String,
+    /// Where along the gradient the color is reached, 0 at the start to 1 at the end
+    pub position: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Gradient {
+    pub name: String,
+    #[serde(default)]
+    pub space: Space,
+    /// Ordered by position
+    pub stops: Vec<Stop>,
+}
+
+impl Gradient {
+    /// A gradient through `colors` in order, spaced evenly from start to end
+    pub fn from_colors(name: &str, colors: &[String], space: Space) -> Self {
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        Self {
+            name: name.to_string(),
+            space,
+            stops: colors
+                .iter()
+                .enumerate()
+                .map(|(idx, hex)| Stop {
+                    hex: hex.to_uppercase(),
+                    position: idx as f32 / last,
+                })
+                .collect(),
+        }
+    }
+
+    /// The gradient in CSS `linear-gradient(...)` or `repeating-linear-gradient(...)`
+    /// somewhere in `css`, e.g. a whole `background:` declaration.
+    ///
+    /// Stops without a position are spread evenly between their neighbors and stops
+    /// placed before an earlier one move up to it, as browsers do; a stop with two
+    /// positions becomes two stops. Colors take any notation [`convert::parse_color`]
+    /// reads, and their alpha is dropped. The direction and color hints are dropped as
+    /// well, since a [`Gradient`] has neither. Without an `in <space>` hint the colors
+    /// blend in [`Space::Srgb`], the CSS default.
+    pub fn from_css(name: &str, css: &str) -> Result<Self, CssError> {
+        // Pasted CSS may break the stops over several lines
+        let css: String = css
+            .chars()
+            .map(|c| if c.is_whitespace() { ' ' } else { c })
+            .collect();
+        let lower = css.to_ascii_lowercase();
+        let start = lower
+            .find("linear-gradient(")
+            .ok_or(CssError::NotAGradient)?
+            + "linear-gradient(".len();
+        let args = split_top_level(&css[start..], ',').ok_or(CssError::Unclosed)?;
+
+        let mut space = Space::Srgb;
+        let mut stops: Vec<(String, Option<f32>)> = Vec::new();
+        for (idx, arg) in args.iter().enumerate() {
+            let words = split_top_level(&format!("{arg})"), ' ').unwrap_or_default();
+            let lower = arg.to_ascii_lowercase();
+            if idx == 0 && is_line(&lower) {
+                if let Some(at) = words
+                    .iter()
+                    .position(|word| word.eq_ignore_ascii_case("in"))
+                {
+                    let name = words.get(at + 1).map_or("", |word| word.as_str());
+                    space = css_space(name)?;
+                }
+                continue;
+            }
+
+            let positions = words
+                .iter()
+                .rev()
+                .take_while(|word| word.starts_with(|c: char| c.is_ascii_digit() || c == '.'))
+                .count();
+            let (color, positions) = words.split_at(words.len() - positions);
+            if color.is_empty() {
+                // A color hint, moving the middle between two stops
+                continue;
+            }
+            let color = color.join(" ");
+            let (r, g, b) = convert::parse_color(&opaque(&color))
+                .map_err(|e| CssError::InvalidColor(color.clone(), e))?;
+            let hex = convert::rgb_to_hex(r, g, b);
+            match positions {
+                [] => stops.push((hex, None)),
+                positions => {
+                    for position in positions {
+                        stops.push((hex.clone(), Some(css_position(position)?)));
+                    }
+                }
+            }
+        }
+        if stops.len() < 2 {
+            return Err(CssError::TooFewStops(stops.len()));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            space,
+            stops: fix_up_positions(stops),
+        })
+    }
+
+    /// The color at `position` (0 to 1): a stop's own color before the first and after
+    /// the last stop, mixed in [`Gradient::space`] between two. Black without stops.
+    pub fn sample(&self, position: f32) -> String {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return String::from("#000000");
+        };
+        if position <= first.position {
+            return first.hex.clone();
+        }
+        let Some(idx) = self.stops.iter().position(|stop| stop.position >= position) else {
+            return last.hex.clone();
+        };
+        let (from, to) = (&self.stops[idx - 1], &self.stops[idx]);
+        let span = to.position - from.position;
+        let ratio = if span > 0.0 {
+            (position - from.position) / span
+        } else {
+            1.0
+        };
+        self.space.mix(&from.hex, &to.hex, ratio)
+    }
+
+    /// CSS `linear-gradient` running left to right. Spaces other than sRGB are named in an
+    /// `in <space>` hint, which browsers without it ignore for plain sRGB.
+    pub fn css(&self) -> String {
+        let hint = match self.space {
+            Space::Srgb => String::from("to right"),
+            space => format!("in {} to right", space.css()),
+        };
+        let stops: Vec<String> = self
+            .stops
+            .iter()
+            .map(|stop| format!("{} {}%", stop.hex, percent(stop.position)))
+            .collect();
+        format!("linear-gradient({hint}, {})", stops.join(", "))
+    }
+
+    /// Stops for formats that blend in sRGB only: the stops themselves for
+    /// [`Space::Srgb`], with samples between them for the other spaces so the result
+    /// looks the same
+    pub fn srgb_stops(&self) -> Vec<Stop> {
+        if self.space == Space::Srgb {
+            return self.stops.clone();
+        }
+        let mut stops: Vec<Stop> = Vec::new();
+        for pair in self.stops.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            stops.extend((0..SEGMENT_SAMPLES).map(|step| {
+                let ratio = step as f32 / SEGMENT_SAMPLES as f32;
+                Stop {
+                    hex: self.space.mix(&from.hex, &to.hex, ratio),
+                    position: from.position + (to.position - from.position) * ratio,
+                }
+            }));
+        }
+        stops.extend(self.stops.last().cloned());
+        stops
+    }
+
+    /// The gradient as a GIMP `.ggr` file, one linear RGB segment between each pair of
+    /// [`Gradient::srgb_stops`]
+    pub fn ggr(&self) -> String {
+        let stops = self.srgb_stops();
+        let segments: Vec<&[Stop]> = stops.windows(2).collect();
+        let mut out = format!(
+            "GIMP Gradient\nName: {}\n{}\n",
+            self.name.replace('\n', " "),
+            segments.len()
+        );
+        for pair in segments {
+            let (left, right) = (&pair[0], &pair[1]);
+            let middle = (left.position + right.position) / 2.0;
+            // Blend type 0 (linear), coloring type 0 (RGB), both ends fixed colors
+            out.push_str(&format!(
+                "{:.6} {middle:.6} {:.6} {} 1.000000 {} 1.000000 0 0 0 0\n",
+                left.position,
+                right.position,
+                ggr_rgb(&left.hex),
+                ggr_rgb(&right.hex)
+            ));
+        }
+        out
+    }
+}
+
+/// `text` up to the parenthesis closing the one before it, split at `separator` outside
+/// nested parentheses, each part trimmed; `None` without the closing parenthesis
+fn split_top_level(text: &str, separator: char) -> Option<Vec<String>> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut depth = 0;
+    for c in text.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                parts.push(part.trim().to_string());
+                return Some(parts.into_iter().filter(|part| !part.is_empty()).collect());
+            }
+            ')' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                parts.push(std::mem::take(&mut part).trim().to_string());
+                continue;
+            }
+            _ => {}
+        }
+        part.push(c);
+    }
+    None
+}
+
+/// Whether the first argument of a gradient is its direction or interpolation space
+/// rather than a color stop
+fn is_line(arg: &str) -> bool {
+    arg.starts_with("to ")
+        || arg.starts_with("in ")
+        || arg.contains(" in ")
+        || ["deg", "turn", "rad", "grad"]
+            .iter()
+            .any(|unit| arg.split_whitespace().next().unwrap_or("").ends_with(unit))
+}
+
+fn css_space(name: &str) -> Result<Space, CssError> {
+    match name.to_ascii_lowercase().as_str() {
+        "oklab" => Ok(Space::Oklab),
+        "oklch" => Ok(Space::Oklch),
+        "srgb" => Ok(Space::Srgb),
+        "srgb-linear" => Ok(Space::LinearRgb),
+        _ => Err(CssError::UnknownSpace(name.to_string())),
+    }
+}
+
+/// A stop position from 0 to 1; bare numbers only for 0, like CSS
+fn css_position(text: &str) -> Result<f32, CssError> {
+    let invalid = || CssError::InvalidPosition(text.to_string());
+    match text.strip_suffix('%') {
+        Some(percent) => percent
+            .parse::<f32>()
+            .map(|p| p / 100.0)
+            .map_err(|_| invalid()),
+        None if text.parse::<f32>() == Ok(0.0) => Ok(0.0),
+        None => Err(invalid()),
+    }
+}
+
+/// `color` without its alpha: `rgba()` and `hsla()` as `rgb()` and `hsl()` without the
+/// fourth component, `/ alpha` left out, `#rrggbbaa` and `#rgba` cut short
+fn opaque(color: &str) -> String {
+    let lower = color.trim().to_ascii_lowercase();
+    if let Some(digits) = lower.strip_prefix('#')
+        && matches!(digits.len(), 4 | 8)
+    {
+        return format!("#{}", &digits[..digits.len() / 4 * 3]);
+    }
+    let Some((name, args)) = lower.split_once('(') else {
+        return lower;
+    };
+    let name = name.trim().trim_end_matches('a');
+    let args = args.trim_end_matches(')');
+    let args = match args.split_once('/') {
+        Some((channels, _)) => channels.to_string(),
+        None if args.matches(',').count() == 3 => args
+            .rsplit_once(',')
+            .map_or(args, |(channels, _)| channels)
+            .to_string(),
+        None => args.to_string(),
+    };
+    format!("{name}({})", args.trim())
+}
+
+/// Positions for every stop the way CSS places them: the first at 0 and the last at 1
+/// unless given, none before an earlier one, and runs of missing ones spread evenly
+fn fix_up_positions(stops: Vec<(String, Option<f32>)>) -> Vec<Stop> {
+    let last = stops.len() - 1;
+    let mut positions: Vec<Option<f32>> = stops.iter().map(|(_, position)| *position).collect();
+    positions[0] = positions[0].or(Some(0.0));
+    positions[last] = positions[last].or(Some(1.0));
+
+    let mut highest = 0.0_f32;
+    for position in positions.iter_mut().flatten() {
+        highest = highest.max(*position);
+        *position = highest;
+    }
+
+    let mut idx = 0;
+    while idx < last {
+        let next = (idx + 1..=last)
+            .find(|&next| positions[next].is_some())
+            .unwrap_or(last);
+        let (from, to) = (
+            positions[idx].unwrap_or(0.0),
+            positions[next].unwrap_or(1.0),
+        );
+        for (step, position) in positions[idx + 1..next].iter_mut().enumerate() {
+            let ratio = (step + 1) as f32 / (next - idx) as f32;
+            *position = Some(from + (to - from) * ratio);
+        }
+        idx = next;
+    }
+
+    stops
+        .into_iter()
+        .zip(positions)
+        .map(|((hex, _), position)| Stop {
+            hex,
+            position: position.unwrap_or(0.0).clamp(0.0, 1.0),
+        })
+        .collect()
+}
+
+/// `position` as a percentage with at most one decimal, e.g. `33.3`
+pub fn percent(position: f32) -> f32 {
+    (position * 1000.0).round() / 10.0
+}
+
+fn ggr_rgb(hex: &str) -> String {
+    let (r, g, b) = hex2rgb(hex);
+    [r, g, b]
+        .map(|channel| format!("{:.6}", f32::from(channel) / 255.0))
+        .join(" ")
+}
+
+fn srgb(hex: &str) -> Srgb {
+    let (r, g, b) = hex2rgb(hex);
+    Srgb::new(r, g, b).into_format()
+}
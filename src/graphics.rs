@@ -0,0 +1,300 @@
+//! Blocks drawn as raster swatches with rounded corners, in terminals that show images
+//! through the kitty graphics protocol or sixel. Everywhere else they stay colored cells.
+//!
+//! Terminals don't answer reliably whether they show images, so the protocol is told by
+//! what they put in the environment: kitty, WezTerm and Ghostty speak kitty's, foot,
+//! mlterm, Contour and iTerm2 sixel. `graphics` in the config picks one by hand or turns
+//! images `off`. Inside tmux and screen, which pass neither on as they are, blocks stay
+//! cells unless a protocol is picked, and so they do in terminals that keep the size of
+//! their cells in pixels to themselves.
+
+use std::{
+    env,
+    io::{self, Write},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use crossterm::{cursor::MoveTo, queue, terminal};
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
+
+/// Longest side of the images sent to kitty, which scales them to their cells; swatches
+/// are one color, more pixels would only make every frame slower
+const KITTY_MAX_SIDE: u32 = 64;
+
+/// Base64 bytes per chunk of a kitty image, the most the protocol takes
+const KITTY_CHUNK: usize = 4096;
+
+/// Which images blocks are drawn with, the `graphics` config key
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Whatever the terminal is known to show, see [`detect`]
+    #[default]
+    Auto,
+    Kitty,
+    Sixel,
+    /// Colored cells only
+    Off,
+}
+
+impl Mode {
+    /// The protocol to draw with, `None` for cells
+    pub fn protocol(self) -> Option<Protocol> {
+        match self {
+            Mode::Auto => detect(),
+            Mode::Kitty => Some(Protocol::Kitty),
+            Mode::Sixel => Some(Protocol::Sixel),
+            Mode::Off => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    /// Kitty's graphics protocol, which scales images to the cells they are placed on
+    Kitty,
+    /// Sixel, drawn pixel for pixel over the cells
+    Sixel,
+}
+
+/// A swatch to draw: the cells it covers and its color
+#[derive(Clone, Debug, PartialEq)]
+pub struct Raster {
+    pub area: Rect,
+    pub color: (u8, u8, u8),
+}
+
+/// The protocol of the terminal running the app, `None` when it shows no images
+pub fn detect() -> Option<Protocol> {
+    let var = |name| env::var(name).unwrap_or_default();
+    from_env(
+        &var("TERM"),
+        &var("TERM_PROGRAM"),
+        env::var_os("KITTY_WINDOW_ID").is_some(),
+    )
+}
+
+/// The protocol of a terminal by its `$TERM`, `$TERM_PROGRAM` and whether it set
+/// `$KITTY_WINDOW_ID`
+pub fn from_env(term: &str, term_program: &str, kitty_window: bool) -> Option<Protocol> {
+    if term.starts_with("screen") || term.starts_with("tmux") {
+        return None;
+    }
+    if kitty_window
+        || matches!(term, "xterm-kitty" | "xterm-ghostty")
+        || matches!(term_program, "WezTerm" | "ghostty")
+    {
+        return Some(Protocol::Kitty);
+    }
+    if term.starts_with("foot")
+        || term.starts_with("mlterm")
+        || term == "contour"
+        || matches!(term_program, "iTerm.app" | "contour")
+    {
+        return Some(Protocol::Sixel);
+    }
+    None
+}
+
+/// Pixels row by row, as RGBA
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+/// A `width` by `height` swatch of `color` with corners rounded by a sixth of its shorter
+/// side. Edge pixels are as opaque as the swatch covers them, so kitty blends them in.
+pub fn rounded_swatch((r, g, b): (u8, u8, u8), width: u32, height: u32) -> Image {
+    let radius = width.min(height) as f32 / 6.0;
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            // Distance past the corner circle's center, zero away from the corners
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let dx = (radius - px).max(px - (width as f32 - radius)).max(0.0);
+            let dy = (radius - py).max(py - (height as f32 - radius)).max(0.0);
+            let coverage = (radius - dx.hypot(dy) + 0.5).clamp(0.0, 1.0);
+            pixels.push([r, g, b, (coverage * 255.0).round() as u8]);
+        }
+    }
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// Kitty's sequences putting `image` on the `columns` by `rows` cells from the cursor,
+/// in chunks, without moving the cursor
+pub fn kitty(image: &Image, columns: u16, rows: u16) -> String {
+    let bytes: Vec<u8> = image.pixels.iter().flatten().copied().collect();
+    let data = STANDARD.encode(bytes);
+    let chunks: Vec<&str> = data
+        .as_bytes()
+        .chunks(KITTY_CHUNK)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+
+    let mut out = String::new();
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(idx + 1 < chunks.len());
+        match idx {
+            0 => out.push_str(&format!(
+                "\x1b_Ga=T,f=32,s={},v={},c={columns},r={rows},C=1,q=2,m={more};{chunk}\x1b\\",
+                image.width, image.height
+            )),
+            _ => out.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\")),
+        }
+    }
+    out
+}
+
+/// Kitty's sequence taking every image off the screen
+pub const KITTY_CLEAR: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+
+/// `image` as sixel, leaving pixels less than half opaque transparent. Every distinct
+/// color takes a register, so images are meant to have few of them.
+pub fn sixel(image: &Image) -> String {
+    let mut colors: Vec<[u8; 3]> = Vec::new();
+    let registers: Vec<Option<usize>> = image
+        .pixels
+        .iter()
+        .map(|&[r, g, b, a]| {
+            if a < 128 {
+                return None;
+            }
+            match colors.iter().position(|&color| color == [r, g, b]) {
+                Some(idx) => Some(idx),
+                None => {
+                    colors.push([r, g, b]);
+                    Some(colors.len() - 1)
+                }
+            }
+        })
+        .collect();
+
+    // Transparent background, square pixels, then the registers in percent
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", image.width, image.height);
+    for (idx, [r, g, b]) in colors.iter().enumerate() {
+        let percent = |channel: &u8| u32::from(*channel) * 100 / 255;
+        out.push_str(&format!(
+            "#{idx};2;{};{};{}",
+            percent(r),
+            percent(g),
+            percent(b)
+        ));
+    }
+
+    let (width, height) = (image.width as usize, image.height as usize);
+    for band in (0..height).step_by(6) {
+        for register in 0..colors.len() {
+            let sixels: Vec<u8> = (0..width)
+                .map(|x| {
+                    (0..6)
+                        .filter(|row| band + row < height)
+                        .filter(|row| registers[(band + row) * width + x] == Some(register))
+                        .fold(0, |bits, row| bits | 1 << row)
+                })
+                .collect();
+            if sixels.iter().all(|&bits| bits == 0) {
+                continue;
+            }
+            out.push_str(&format!("#{register}"));
+            push_runs(&mut out, &sixels);
+            // Back to the start of the band for the next color
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Sixels as characters, runs of more than three as `!count`
+fn push_runs(out: &mut String, sixels: &[u8]) {
+    let mut idx = 0;
+    while idx < sixels.len() {
+        let run = sixels[idx..]
+            .iter()
+            .take_while(|&&bits| bits == sixels[idx])
+            .count();
+        let symbol = char::from(63 + sixels[idx]);
+        match run {
+            1..=3 => out.extend(std::iter::repeat_n(symbol, run)),
+            _ => out.push_str(&format!("!{run}{symbol}")),
+        }
+        idx += run;
+    }
+}
+
+/// Draw `rasters` over the screen with `protocol`, taking kitty's earlier ones away first.
+/// Sixel has nothing to take away: whatever is drawn over its cells replaces it.
+pub fn draw(protocol: Protocol, rasters: &[Raster]) -> io::Result<()> {
+    let mut out = screen();
+    if protocol == Protocol::Kitty {
+        write!(out, "{KITTY_CLEAR}")?;
+    }
+    if rasters.is_empty() {
+        return out.flush();
+    }
+
+    let Some(cell) = cell_size() else {
+        return out.flush();
+    };
+
+    for raster in rasters {
+        let (width, height) = (
+            u32::from(raster.area.width) * cell.0,
+            u32::from(raster.area.height) * cell.1,
+        );
+        queue!(out, MoveTo(raster.area.x, raster.area.y))?;
+        match protocol {
+            Protocol::Kitty => {
+                let scale = (KITTY_MAX_SIDE as f32 / width.max(height) as f32).min(1.0);
+                let image = rounded_swatch(
+                    raster.color,
+                    ((width as f32 * scale).round() as u32).max(1),
+                    ((height as f32 * scale).round() as u32).max(1),
+                );
+                write!(
+                    out,
+                    "{}",
+                    kitty(&image, raster.area.width, raster.area.height)
+                )?;
+            }
+            Protocol::Sixel => {
+                write!(
+                    out,
+                    "{}",
+                    sixel(&rounded_swatch(raster.color, width, height))
+                )?;
+            }
+        }
+    }
+    out.flush()
+}
+
+/// Width and height of a cell in pixels, `None` when the terminal doesn't tell, which
+/// leaves no telling how big to draw
+pub fn cell_size() -> Option<(u32, u32)> {
+    let size = terminal::window_size().ok()?;
+    if size.width == 0 || size.height == 0 || size.columns == 0 || size.rows == 0 {
+        return None;
+    }
+    Some((
+        u32::from(size.width / size.columns),
+        u32::from(size.height / size.rows),
+    ))
+}
+
+/// Where images are drawn; tests must not draw on the terminal running them
+fn screen() -> Box<dyn Write> {
+    if cfg!(test) {
+        Box::new(io::sink())
+    } else {
+        Box::new(io::stdout())
+    }
+}
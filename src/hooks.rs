@@ -0,0 +1,160 @@
+//! Shell commands run after a palette is applied to the terminal or exported, so status
+//! bars, window managers and editors can follow along.
+//!
+//! Every command runs through `sh -c` with the palette in its environment:
+//!
+//! - `TERMINAL_PALETTE_EVENT`: `apply` or `export`
+//! - `TERMINAL_PALETTE_NAME`: the palette's name
+//! - `TERMINAL_PALETTE_COLORS`: every hex code, separated by spaces
+//! - `TERMINAL_PALETTE_COLOR0`, `TERMINAL_PALETTE_COLOR1`, ...: one hex code each
+//! - `TERMINAL_PALETTE_BACKGROUND`, `TERMINAL_PALETTE_PRIMARY`, ...: assigned roles only
+//! - `TERMINAL_PALETTE_EXPORT`: the file written, for exports to a file
+//! - `TERMINAL_PALETTE_JSON`: a JSON file holding all of the above, removed once every
+//!   command has finished
+//!
+//! Commands run one after the other without a terminal; what they print is dropped unless
+//! they fail.
+
+use std::{
+    env, fs, io,
+    path::PathBuf,
+    process::{self, Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use serde_json::{Value, json};
+use terminal_palette::export::ExportPalette;
+
+use crate::config::Hooks;
+
+/// Prefix of every variable handed to hooks
+const PREFIX: &str = "TERMINAL_PALETTE_";
+
+/// Tells apart the JSON files of hooks running at the same time
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// What happened to the palette
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// Set as the terminal's ANSI colors
+    Apply,
+    /// Exported, to the file if it went to one
+    Export(Option<PathBuf>),
+}
+
+impl Event {
+    fn key(&self) -> &'static str {
+        match self {
+            Event::Apply => "apply",
+            Event::Export(_) => "export",
+        }
+    }
+
+    /// The file exported to, if the export went to one
+    fn exported(&self) -> Option<String> {
+        match self {
+            Event::Export(Some(path)) => Some(path.display().to_string()),
+            _ => None,
+        }
+    }
+
+    /// The configured commands run for this event
+    pub fn commands<'a>(&self, hooks: &'a Hooks) -> &'a [String] {
+        match self {
+            Event::Apply => &hooks.apply,
+            Event::Export(_) => &hooks.export,
+        }
+    }
+}
+
+/// Run the commands configured for `event`, returning how many ran. Every command runs
+/// even when one before it fails; the error names the first failure.
+pub fn run(hooks: &Hooks, event: &Event, palette: &ExportPalette) -> io::Result<usize> {
+    let commands = event.commands(hooks);
+    if commands.is_empty() {
+        return Ok(0);
+    }
+
+    let hexes: Vec<&str> = palette.colors.iter().map(|c| c.hex.as_str()).collect();
+    let mut vars = vec![
+        (format!("{PREFIX}EVENT"), event.key().to_string()),
+        (format!("{PREFIX}NAME"), palette.name.clone()),
+        (format!("{PREFIX}COLORS"), hexes.join(" ")),
+    ];
+    for (idx, hex) in hexes.iter().enumerate() {
+        vars.push((format!("{PREFIX}COLOR{idx}"), hex.to_string()));
+    }
+    for color in &palette.colors {
+        if let Some(role) = color.role {
+            let name = format!("{PREFIX}{}", role.key().to_uppercase());
+            vars.push((name, color.hex.clone()));
+        }
+    }
+    if let Some(path) = event.exported() {
+        vars.push((format!("{PREFIX}EXPORT"), path));
+    }
+
+    let json_path = env::temp_dir().join(format!(
+        "terminal-palette-hook-{}-{}.json",
+        process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let document = palette_json(event, palette);
+    fs::write(&json_path, serde_json::to_string_pretty(&document)?)?;
+    vars.push((format!("{PREFIX}JSON"), json_path.display().to_string()));
+
+    let mut failures = Vec::new();
+    for command in commands {
+        if let Err(e) = run_command(command, &vars) {
+            failures.push(e);
+        }
+    }
+    let _ = fs::remove_file(&json_path);
+
+    match failures.first() {
+        None => Ok(commands.len()),
+        Some(first) => Err(io::Error::other(format!(
+            "{} of {} hooks failed, first: {first}",
+            failures.len(),
+            commands.len()
+        ))),
+    }
+}
+
+/// What `TERMINAL_PALETTE_JSON` holds: the event, the palette's name, its colors with
+/// their label and role, and the file exported to
+pub fn palette_json(event: &Event, palette: &ExportPalette) -> Value {
+    let colors: Vec<_> = palette
+        .colors
+        .iter()
+        .map(|c| json!({ "hex": c.hex, "label": c.label, "role": c.role.map(|r| r.key()) }))
+        .collect();
+    json!({
+        "event": event.key(),
+        "name": palette.name,
+        "colors": colors,
+        "export": event.exported(),
+    })
+}
+
+fn run_command(command: &str, vars: &[(String, String)]) -> io::Result<()> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(vars.iter().map(|(name, value)| (name, value)))
+        .stdin(Stdio::null())
+        .output()?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    // The last line of stderr usually says what went wrong
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => Err(io::Error::other(format!("{command}: {}", line.trim()))),
+        None => Err(io::Error::other(format!(
+            "{command} exited with {}",
+            output.status
+        ))),
+    }
+}
@@ -0,0 +1,107 @@
+//! Localized UI strings, looked up by Fluent message id with [`t!`](crate::t).
+//!
+//! English ships inside the binary (`i18n/en.ftl`). Other locales are read from
+//! `<config dir>/locales/<locale>.ftl`; messages they lack fall back to English.
+
+use std::{env, fs, sync::OnceLock};
+
+use fluent_bundle::{FluentArgs, FluentResource, concurrent::FluentBundle};
+use unic_langid::LanguageIdentifier;
+
+use crate::config;
+
+const ENGLISH: &str = include_str!("../i18n/en.ftl");
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+struct Localizer {
+    /// The chosen locale, `None` when that is English
+    bundle: Option<FluentBundle<FluentResource>>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+/// Pick the UI language: `locale` (from the config) or `$LANG`, else English.
+/// Only the first call has an effect.
+pub fn init(locale: Option<&str>) {
+    let requested = locale
+        .map(str::to_string)
+        .or_else(|| env::var("LANG").ok())
+        .and_then(|lang| {
+            // `de_DE.UTF-8` -> `de-DE`
+            let lang = lang.split('.').next().unwrap_or_default().replace('_', "-");
+            lang.parse::<LanguageIdentifier>().ok()
+        });
+
+    let bundle = requested
+        .filter(|id| id.language.as_str() != "en")
+        .and_then(|id| {
+            // Prefer `pt-BR.ftl`, then `pt.ftl`
+            [id.to_string(), id.language.to_string()]
+                .iter()
+                .find_map(|name| {
+                    fs::read_to_string(
+                        config::config_dir()
+                            .join("locales")
+                            .join(format!("{name}.ftl")),
+                    )
+                    .ok()
+                })
+                .map(|source| bundle(id, source))
+        });
+
+    let _ = LOCALIZER.set(Localizer {
+        bundle,
+        fallback: english(),
+    });
+}
+
+/// The message `id` formatted with `args`, in the active locale
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let localizer = LOCALIZER.get_or_init(|| Localizer {
+        bundle: None,
+        fallback: english(),
+    });
+
+    localizer
+        .bundle
+        .iter()
+        .chain([&localizer.fallback])
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .into_owned(),
+            )
+        })
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn english() -> FluentBundle<FluentResource> {
+    bundle("en".parse().unwrap(), ENGLISH.to_string())
+}
+
+fn bundle(locale: LanguageIdentifier, source: String) -> FluentBundle<FluentResource> {
+    // A translation with syntax errors still contributes the messages that parsed
+    let resource = FluentResource::try_new(source).unwrap_or_else(|(resource, _)| resource);
+
+    let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+    // Unicode isolation marks show up as garbage in many terminals
+    bundle.set_use_isolating(false);
+    let _ = bundle.add_resource(resource);
+    bundle
+}
+
+/// Look up a UI string: `t!("hint-quit")`, `t!("msg-copied", hex = hex)`
+#[macro_export]
+macro_rules! t {
+    ($id:literal) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:literal, $($key:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($key), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
@@ -0,0 +1,101 @@
+//! How a palette looks lit by something other than daylight.
+//!
+//! Screens show colors against the D65 white of daylight, but a printed or painted palette
+//! takes on the color of whatever light falls on it. [`adapt`] moves a color from D65 to
+//! another [`Illuminant`] with the Bradford chromatic adaptation transform, which gives the
+//! cast a daylight-balanced photo of the print would show.
+//!
+//! ```
+//! use terminal_palette::color::ColorBlock;
+//! use terminal_palette::illuminant::{self, Illuminant};
+//!
+//! let white = ColorBlock::new(1, 0.0, 0.0, 1.0);
+//! assert_eq!(illuminant::adapt(&white, Illuminant::D65).get_hex(), "#FFFFFF");
+//!
+//! // Warm indoor light yellows white paper
+//! let (r, g, b) = illuminant::adapt(&white, Illuminant::A).get_rgb_values();
+//! assert!(r > b && g > b);
+//! ```
+
+use palette::{FromColor, Srgb, Xyz};
+use strum_macros::EnumIter;
+
+use crate::color::ColorBlock;
+use crate::convert;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum Illuminant {
+    /// Noon daylight, the white screens are made for; colors stay as they are
+    D65,
+    /// Horizon daylight, the standard light for judging print
+    D50,
+    /// Incandescent bulbs, warm indoor light at 2856 K
+    A,
+    /// Cool white fluorescent tubes of offices, 4230 K
+    F2,
+    /// Overcast north sky, bluer than noon
+    D75,
+}
+
+impl Illuminant {
+    /// Id for translations and the command line
+    pub fn key(self) -> &'static str {
+        match self {
+            Illuminant::D65 => "d65",
+            Illuminant::D50 => "d50",
+            Illuminant::A => "a",
+            Illuminant::F2 => "f2",
+            Illuminant::D75 => "d75",
+        }
+    }
+
+    /// The white point in CIE XYZ, scaled to Y = 1 (2° observer)
+    pub fn white_point(self) -> [f32; 3] {
+        match self {
+            Illuminant::D65 => [0.950_47, 1.0, 1.088_83],
+            Illuminant::D50 => [0.964_22, 1.0, 0.825_21],
+            Illuminant::A => [1.098_5, 1.0, 0.355_85],
+            Illuminant::F2 => [0.991_87, 1.0, 0.673_95],
+            Illuminant::D75 => [0.949_72, 1.0, 1.226_38],
+        }
+    }
+}
+
+/// XYZ to the cone-like responses the Bradford transform scales
+const BRADFORD: [[f32; 3]; 3] = [
+    [0.895_1, 0.266_4, -0.161_4],
+    [-0.750_2, 1.713_5, 0.036_7],
+    [0.038_9, -0.068_5, 1.029_6],
+];
+/// The inverse of [`BRADFORD`]
+const BRADFORD_INVERSE: [[f32; 3]; 3] = [
+    [0.986_992_9, -0.147_054_3, 0.159_962_7],
+    [0.432_305_3, 0.518_360_3, 0.049_291_2],
+    [-0.008_528_5, 0.040_042_8, 0.968_486_7],
+];
+
+/// `block` as it would look under `illuminant`, clipped to sRGB. Everything but the color
+/// is kept, so the result renders in place of the block.
+pub fn adapt(block: &ColorBlock, illuminant: Illuminant) -> ColorBlock {
+    let mut adapted = block.clone();
+    if illuminant == Illuminant::D65 {
+        return adapted;
+    }
+
+    let (r, g, b) = block.get_rgb_values();
+    let xyz: Xyz = Xyz::from_color(Srgb::new(r, g, b).into_format::<f32>());
+    let source = multiply(&BRADFORD, Illuminant::D65.white_point());
+    let target = multiply(&BRADFORD, illuminant.white_point());
+    let cone = multiply(&BRADFORD, [xyz.x, xyz.y, xyz.z]);
+    let scaled = [0, 1, 2].map(|i| cone[i] * target[i] / source[i]);
+    let [x, y, z] = multiply(&BRADFORD_INVERSE, scaled);
+
+    let rgb: Srgb<u8> = Srgb::from_color(Xyz::new(x, y, z)).into_format();
+    let (hue, sat, val) = convert::rgb_to_hsv(rgb.red, rgb.green, rgb.blue);
+    adapted.change_color(hue, sat, val);
+    adapted
+}
+
+fn multiply(matrix: &[[f32; 3]; 3], vector: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row[0] * vector[0] + row[1] * vector[1] + row[2] * vector[2])
+}
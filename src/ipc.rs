@@ -0,0 +1,185 @@
+//! A Unix socket other programs drive the app through, while the TUI runs with `--listen`
+//! or without any UI with `--daemon`.
+//!
+//! Requests and replies are single lines of text. A reply starts with `ok` or `error`,
+//! followed by what the status bar would say or, when it has nothing to say, the
+//! palette's hex codes:
+//!
+//! ```text
+//! > get
+//! < ok #E07A5F #3D405B #81B29A
+//! > generate
+//! < ok #D9805B #D9C35B #A6D95B
+//! > palette #1E1E2E #CDD6F4 rgb(243 139 168)
+//! < ok #1E1E2E #CDD6F4 #F38BA8
+//! > theory nonsense
+//! < error Can't use nonsense here
+//! ```
+//!
+//! Besides `get`, `palette <colors>` and `apply`, every `:` command works, see
+//! [`crate::command`].
+
+use std::{
+    env,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::{
+    fs,
+    os::unix::net::{UnixListener, UnixStream},
+};
+
+/// How long [`send`] waits for a reply
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where instances listen unless told otherwise: `$XDG_RUNTIME_DIR/terminal-palette.sock`,
+/// else a per-user socket in the temp directory
+pub fn socket_path() -> PathBuf {
+    match env::var("XDG_RUNTIME_DIR") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir).join("terminal-palette.sock"),
+        _ => {
+            let user = env::var("USER").unwrap_or_default();
+            env::temp_dir().join(format!("terminal-palette-{user}.sock"))
+        }
+    }
+}
+
+/// One request line and where its reply goes
+pub struct Request {
+    pub line: String,
+    writer: Box<dyn Write + Send>,
+    /// Lets the connection read its next line once the reply is out
+    answered: Sender<()>,
+}
+
+impl Request {
+    /// Write `text` to the client before returning, so a reply to `quit` is out before
+    /// the app exits
+    pub fn reply(mut self, text: String) {
+        // The client may have hung up in the meantime
+        let _ = writeln!(self.writer, "{text}").and_then(|()| self.writer.flush());
+        let _ = self.answered.send(());
+    }
+}
+
+/// Accepts connections on a socket in the background and queues their requests for the
+/// app, which answers them between frames
+pub struct Server {
+    path: PathBuf,
+    receiver: Receiver<Request>,
+}
+
+impl Server {
+    /// Listen on `path`. A socket left behind by an instance that is gone is replaced;
+    /// one still answering is an error.
+    #[cfg(unix)]
+    pub fn bind(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("another instance listens on {}", path.display()),
+                ));
+            }
+            fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                thread::spawn(move || serve_connection(stream, sender));
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            receiver,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn bind(_path: &Path) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "sockets are only supported on Unix",
+        ))
+    }
+
+    /// Requests that arrived, waiting up to `timeout` for the first one
+    pub fn requests(&self, timeout: Duration) -> Vec<Request> {
+        let first = if timeout.is_zero() {
+            self.receiver.try_recv().ok()
+        } else {
+            self.receiver.recv_timeout(timeout).ok()
+        };
+        first.into_iter().chain(self.receiver.try_iter()).collect()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Hand every line of `stream` to the app, which writes back its reply, one at a time
+/// until the client hangs up or the app is gone
+#[cfg(unix)]
+fn serve_connection(stream: UnixStream, sender: Sender<Request>) -> io::Result<()> {
+    for line in BufReader::new(stream.try_clone()?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (answered, answer) = mpsc::channel();
+        let request = Request {
+            line,
+            writer: Box::new(stream.try_clone()?),
+            answered,
+        };
+        if sender.send(request).is_err() || answer.recv().is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Send one request to the instance listening on `path` and return its reply
+#[cfg(unix)]
+pub fn send(path: &Path, request: &str) -> io::Result<String> {
+    let mut stream = UnixStream::connect(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("nothing listens on {}: {e}", path.display()),
+        )
+    })?;
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    writeln!(stream, "{}", request.trim())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.is_empty() {
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the instance closed the connection without replying",
+        ))
+    } else {
+        Ok(reply.trim_end().to_string())
+    }
+}
+
+#[cfg(not(unix))]
+pub fn send(_path: &Path, _request: &str) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "sockets are only supported on Unix",
+    ))
+}
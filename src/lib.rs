@@ -0,0 +1,68 @@
+//! Palette generation behind the `terminal-palette` TUI, usable on its own.
+//!
+//! - [`ansi`] lays out and generates terminal color schemes (16 ANSI colors and friends)
+//! - [`balance`] weighs a palette's warm hues against its cool ones
+//! - [`blend`] mixes colors and whole palettes in OKLab
+//! - [`collections`] ships named colors to browse, CSS and more
+//! - [`color`] holds the [`ColorBlock`](color::ColorBlock) model
+//! - [`convert`] converts between hex, RGB, HSV and HSL with defined rounding and clamping
+//! - [`daylight`] tells day from night by the clock or by the sun, for scheduled palettes
+//! - [`export`] writes palettes in built-in formats such as CSS
+//! - [`extract`] takes palettes from the frames of GIF stills and animations
+//! - [`gamut`] brings colors from wider spaces into sRGB, keeping their hue
+//! - [`generators`] implements every color theory offered in the app behind the
+//!   [`PaletteGenerator`](generators::PaletteGenerator) trait; hue harmonies are plain
+//!   [`HarmonySpec`](generators::HarmonySpec) data
+//! - [`gradient`] blends named gradients through chosen stops, for CSS, SVG and GIMP
+//! - [`illuminant`] shows colors under warm indoor, fluorescent and other light
+//! - [`lint`] finds hard-coded colors in source files and the palette colors nearest them
+//! - [`night_light`] shows colors through the blue-cutting filters of screens at night
+//! - [`palette_file`] writes projects one color per line, for clean diffs in version control
+//! - [`palette_json`] reads and writes palettes as JSON in a fixed shape, for other tools
+//! - [`pairing`] suggests colors that pair with another at a readable contrast
+//! - [`library`] and [`project`] read and write saved palettes and `.tpal` projects
+//! - [`plugins`] runs Lua scripts providing extra theories and export formats
+//! - [`roles`] names what each color is for (background, text, primary, ...)
+//! - [`states`] derives hover, active, focus and disabled variants of a UI color
+//! - [`swatches`] looks colors up in user-supplied swatch books
+//!
+//! ```
+//! use terminal_palette::color::ColorBlock;
+//! use terminal_palette::generators::{self, ColorTheories};
+//!
+//! // Keep a brand color and build a triad around it
+//! let mut brand = ColorBlock::new(1, 12.0, 0.58, 0.88);
+//! brand.locked = true;
+//!
+//! let mut blocks = vec![brand];
+//! blocks.extend((2..=4).map(|id| ColorBlock::new(id, 0.0, 0.0, 0.0)));
+//!
+//! generators::generate(ColorTheories::Triad, &mut blocks);
+//! let hexes: Vec<String> = blocks.iter().map(|b| b.get_hex()).collect();
+//! assert_eq!(hexes.len(), 4);
+//! ```
+
+pub mod ansi;
+pub mod balance;
+pub mod blend;
+pub mod collections;
+pub mod color;
+pub mod convert;
+pub mod daylight;
+pub mod export;
+pub mod extract;
+pub mod gamut;
+pub mod generators;
+pub mod gradient;
+pub mod illuminant;
+pub mod library;
+pub mod lint;
+pub mod night_light;
+pub mod pairing;
+pub mod palette_file;
+pub mod palette_json;
+pub mod plugins;
+pub mod project;
+pub mod roles;
+pub mod states;
+pub mod swatches;
@@ -0,0 +1,608 @@
+//! The saved palette library: palettes, favorites, backups and recents under the data dir.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::{Freeze, HEX_CHARS};
+use crate::convert;
+use crate::generators::ColorTheories;
+use crate::palette_json;
+use crate::roles::Role;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedColor {
+    pub hex: String,
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen: Option<Freeze>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<Role>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPalette {
+    pub name: String,
+    pub theory: ColorTheories,
+    pub colors: Vec<SavedColor>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub starred: bool,
+}
+
+/// Individually starred colors, kept outside of any palette
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Favorites {
+    pub colors: Vec<String>,
+}
+
+impl Favorites {
+    /// Star `hex` if it isn't yet, unstar it otherwise. Returns whether it is starred now.
+    pub fn toggle_color(&mut self, hex: &str) -> bool {
+        if let Some(pos) = self.colors.iter().position(|c| c.eq_ignore_ascii_case(hex)) {
+            self.colors.remove(pos);
+            false
+        } else {
+            self.colors.push(hex.to_uppercase());
+            true
+        }
+    }
+}
+
+/// Root directory for everything the app persists.
+/// Honors `XDG_DATA_HOME`, falling back to `~/.local/share/terminal-palette`.
+pub fn data_dir() -> PathBuf {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var_os("HOME").unwrap_or_default();
+            PathBuf::from(home).join(".local").join("share")
+        }
+    };
+
+    base.join("terminal-palette")
+}
+
+pub fn palettes_dir() -> PathBuf {
+    data_dir().join("palettes")
+}
+
+/// Turn a user supplied palette name into a safe file stem
+pub fn slugify(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    slug.trim_matches('-').to_string()
+}
+
+pub fn palette_path(name: &str) -> PathBuf {
+    palettes_dir().join(format!("{}.json", slugify(name)))
+}
+
+pub fn favorites_path() -> PathBuf {
+    data_dir().join("favorites.json")
+}
+
+/// Starred colors, empty when nothing has been starred yet
+pub fn load_favorites() -> io::Result<Favorites> {
+    let path = favorites_path();
+    if !path.exists() {
+        return Ok(Favorites::default());
+    }
+
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn save_favorites(favorites: &Favorites) -> io::Result<()> {
+    fs::create_dir_all(data_dir())?;
+    let json = serde_json::to_string_pretty(favorites).map_err(io::Error::other)?;
+    fs::write(favorites_path(), json)
+}
+
+pub fn save_palette(palette: &SavedPalette) -> io::Result<PathBuf> {
+    if slugify(&palette.name).is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "palette name is empty",
+        ));
+    }
+
+    fs::create_dir_all(palettes_dir())?;
+
+    let path = palette_path(&palette.name);
+
+    // Tag and star edits don't count as a new version, only color or theory changes do
+    if let Ok(previous) = load_palette(&path)
+        && (previous.colors != palette.colors || previous.theory != palette.theory)
+    {
+        backup_palette(&path, &palette.name)?;
+    }
+
+    let json = serde_json::to_string_pretty(palette).map_err(io::Error::other)?;
+    fs::write(&path, json)?;
+
+    Ok(path)
+}
+
+/// How many earlier versions are kept per saved palette
+pub const BACKUP_LIMIT: usize = 5;
+
+pub fn backups_dir(name: &str) -> PathBuf {
+    data_dir().join("backups").join(slugify(name))
+}
+
+/// Earlier versions of a saved palette, newest first
+pub fn list_backups(name: &str) -> io::Result<Vec<PathBuf>> {
+    let dir = backups_dir(name);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+
+    // File stems are millisecond timestamps, so name order is age order
+    backups.sort();
+    backups.reverse();
+    Ok(backups)
+}
+
+/// Copy the file at `path` into the backups of `name`, dropping the oldest beyond `BACKUP_LIMIT`
+fn backup_palette(path: &Path, name: &str) -> io::Result<()> {
+    let dir = backups_dir(name);
+    fs::create_dir_all(&dir)?;
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    fs::copy(path, dir.join(format!("{millis:016}.json")))?;
+
+    for old in list_backups(name)?.into_iter().skip(BACKUP_LIMIT) {
+        fs::remove_file(old)?;
+    }
+
+    Ok(())
+}
+
+/// Swap the saved palette `name` with its newest backup.
+/// The replaced version becomes a backup itself, so restoring twice undoes the restore.
+pub fn restore_previous_version(name: &str) -> io::Result<SavedPalette> {
+    let Some(newest) = list_backups(name)?.into_iter().next() else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no earlier version saved",
+        ));
+    };
+
+    let mut restored = load_palette(&newest)?;
+    fs::remove_file(&newest)?;
+
+    // Keep the library identity (name, tags, star) of the current entry
+    if let Ok(current) = load_palette(&palette_path(name)) {
+        restored.name = current.name;
+        restored.tags = current.tags;
+        restored.starred = current.starred;
+    }
+
+    save_palette(&restored)?;
+    Ok(restored)
+}
+
+pub fn load_palette(path: &Path) -> io::Result<SavedPalette> {
+    let json = fs::read_to_string(path)?;
+    // Exported palette JSON may leave out what saved palettes always have
+    let palette: SavedPalette = match serde_json::from_str(&json) {
+        Ok(palette) => palette,
+        Err(e) => {
+            palette_json::parse(&json).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, e))?
+        }
+    };
+
+    if let Some(bad) = palette.colors.iter().find(|c| !is_valid_hex(&c.hex)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid color {:?}", bad.hex),
+        ));
+    }
+
+    Ok(palette)
+}
+
+/// Paths of the `.json` files in the palettes directory
+fn palette_files() -> io::Result<Vec<PathBuf>> {
+    let dir = palettes_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect())
+}
+
+/// All palettes in the library, sorted by name. Unreadable files are skipped.
+pub fn list_palettes() -> io::Result<Vec<SavedPalette>> {
+    let mut palettes: Vec<SavedPalette> = palette_files()?
+        .iter()
+        .filter_map(|path| load_palette(path).ok())
+        .collect();
+
+    palettes.sort_by_key(|p| p.name.to_lowercase());
+    Ok(palettes)
+}
+
+pub fn is_valid_hex(hex: &str) -> bool {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    hex.len() == 6 && hex.chars().all(|c| HEX_CHARS.contains(&c))
+}
+
+/// Largest CIE76 distance at which a saved color still counts as "close" to a searched hex
+pub const COLOR_MATCH_DISTANCE: f32 = 15.0;
+
+/// Indices of `palettes` matching `query`, best match first.
+/// A hex query (`#e07a5f` or `e07a5f`) matches palettes containing a perceptually close color,
+/// anything else is fuzzy-matched against names and tags.
+pub fn search_palettes(palettes: &[SavedPalette], query: &str) -> Vec<usize> {
+    let query = query.trim();
+    if query.is_empty() {
+        return (0..palettes.len()).collect();
+    }
+
+    let mut scored: Vec<(usize, i32)> = if is_valid_hex(query) {
+        let target = parse_hex(query);
+        palettes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, palette)| {
+                palette
+                    .colors
+                    .iter()
+                    .map(|color| convert::delta_e(target, parse_hex(&color.hex)))
+                    .filter(|distance| *distance <= COLOR_MATCH_DISTANCE)
+                    .min_by(|a, b| a.total_cmp(b))
+                    .map(|distance| (idx, -(distance * 100.0) as i32))
+            })
+            .collect()
+    } else {
+        palettes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, palette)| {
+                std::iter::once(&palette.name)
+                    .chain(palette.tags.iter())
+                    .filter_map(|text| fuzzy_score(query, text))
+                    .max()
+                    .map(|score| (idx, score))
+            })
+            .collect()
+    };
+
+    scored.sort_by_key(|(_, score)| -score);
+    scored.into_iter().map(|(idx, _)| idx).collect()
+}
+
+/// Case-insensitive subsequence match, rewarding consecutive and word-start hits.
+/// Returns `None` when `needle` is not a subsequence of `haystack`.
+pub fn fuzzy_score(needle: &str, haystack: &str) -> Option<i32> {
+    let haystack: Vec<char> = haystack.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last_match: Option<usize> = None;
+
+    for n in needle.to_lowercase().chars() {
+        let found = haystack[pos..].iter().position(|h| *h == n)? + pos;
+
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !haystack[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        last_match = Some(found);
+        pos = found + 1;
+    }
+
+    Some(score - haystack.len() as i32 / 8)
+}
+
+/// Tags typed as a comma separated list, trimmed and de-duplicated
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(|t| t.trim().to_lowercase()) {
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn parse_hex(hex: &str) -> (u8, u8, u8) {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+    (channel(0), channel(2), channel(4))
+}
+
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// The whole library in one file, for moving it between machines
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryBundle {
+    pub version: u32,
+    pub palettes: Vec<SavedPalette>,
+    #[serde(default)]
+    pub favorites: Favorites,
+}
+
+/// What to do when an imported palette has the same name as a different local one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictStrategy {
+    /// Leave the local palette alone
+    Keep,
+    /// Overwrite the local palette with the imported one
+    Replace,
+    /// Import under a free name like "sunset (2)"
+    Rename,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub added: usize,
+    pub replaced: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+    pub favorites_added: usize,
+}
+
+pub fn export_library(path: &Path) -> io::Result<usize> {
+    export_library_with_progress(path, |_, _| {})
+}
+
+/// [`export_library`], calling `on_progress(done, total)` after each palette is read
+pub fn export_library_with_progress(
+    path: &Path,
+    mut on_progress: impl FnMut(usize, usize),
+) -> io::Result<usize> {
+    let files = palette_files()?;
+    let mut palettes = Vec::with_capacity(files.len());
+    for (i, file) in files.iter().enumerate() {
+        if let Ok(palette) = load_palette(file) {
+            palettes.push(palette);
+        }
+        on_progress(i + 1, files.len());
+    }
+    palettes.sort_by_key(|p| p.name.to_lowercase());
+
+    let bundle = LibraryBundle {
+        version: BUNDLE_VERSION,
+        palettes,
+        favorites: load_favorites()?,
+    };
+
+    let json = serde_json::to_string_pretty(&bundle).map_err(io::Error::other)?;
+    fs::write(path, json)?;
+
+    Ok(bundle.palettes.len())
+}
+
+/// Merge a bundle into the local library. Palettes identical to a local one are skipped,
+/// name clashes with different contents are resolved with `strategy`.
+pub fn import_library(path: &Path, strategy: ConflictStrategy) -> io::Result<ImportReport> {
+    let json = fs::read_to_string(path)?;
+    let mut bundle: LibraryBundle =
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    if bundle.version > BUNDLE_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "bundle version {} is newer than supported ({BUNDLE_VERSION})",
+                bundle.version
+            ),
+        ));
+    }
+
+    // Bundles may be written by hand, so accept any notation and store it as hex
+    for color in bundle.palettes.iter_mut().flat_map(|p| p.colors.iter_mut()) {
+        color.hex = normalize_color(&color.hex)?;
+    }
+
+    let local = list_palettes()?;
+    let mut report = ImportReport::default();
+
+    for mut palette in bundle.palettes {
+        let existing = load_palette(&palette_path(&palette.name)).ok();
+
+        match existing {
+            None => {
+                save_palette(&palette)?;
+                report.added += 1;
+            }
+            Some(existing) if existing == palette => report.skipped += 1,
+            Some(_) => match strategy {
+                ConflictStrategy::Keep => report.skipped += 1,
+                ConflictStrategy::Replace => {
+                    save_palette(&palette)?;
+                    report.replaced += 1;
+                }
+                // A previous rename import already brought these colors in
+                ConflictStrategy::Rename if local.iter().any(|p| p.colors == palette.colors) => {
+                    report.skipped += 1
+                }
+                ConflictStrategy::Rename => {
+                    palette.name = free_name(&palette.name);
+                    save_palette(&palette)?;
+                    report.renamed += 1;
+                }
+            },
+        }
+    }
+
+    let mut favorites = load_favorites()?;
+    for hex in bundle.favorites.colors {
+        if let Ok(hex) = normalize_color(&hex)
+            && !favorites
+                .colors
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(&hex))
+        {
+            favorites.colors.push(hex);
+            report.favorites_added += 1;
+        }
+    }
+    save_favorites(&favorites)?;
+
+    Ok(report)
+}
+
+/// Any notation [`convert::parse_color`] accepts as `#RRGGBB`
+pub fn normalize_color(color: &str) -> io::Result<String> {
+    let (r, g, b) = convert::parse_color(color).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid color {color:?}: {e}"),
+        )
+    })?;
+    Ok(convert::rgb_to_hex(r, g, b))
+}
+
+/// First "name (n)" that isn't taken in the library yet
+fn free_name(name: &str) -> String {
+    (2..)
+        .map(|n| format!("{name} ({n})"))
+        .find(|candidate| !palette_path(candidate).exists())
+        .unwrap()
+}
+
+/// How many entries the Ctrl+R quick switcher remembers
+pub const RECENT_LIMIT: usize = 10;
+
+/// A recently opened or saved palette/project, with its colors for the preview swatches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub colors: Vec<String>,
+}
+
+pub fn recent_path() -> PathBuf {
+    data_dir().join("recent.json")
+}
+
+/// Recently used palettes, most recent first
+pub fn load_recent() -> io::Result<Vec<RecentEntry>> {
+    let path = recent_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Move `entry` to the front of the recent list, dropping older duplicates of the same file
+pub fn remember_recent(mut entry: RecentEntry) -> io::Result<()> {
+    entry.path = fs::canonicalize(&entry.path).unwrap_or(entry.path);
+
+    let mut recent = load_recent().unwrap_or_default();
+    recent.retain(|r| r.path != entry.path);
+    recent.insert(0, entry);
+    recent.truncate(RECENT_LIMIT);
+
+    fs::create_dir_all(data_dir())?;
+    let json = serde_json::to_string_pretty(&recent).map_err(io::Error::other)?;
+    fs::write(recent_path(), json)
+}
+
+/// How many colors the color history keeps
+pub const COLOR_HISTORY_LIMIT: usize = 500;
+/// Half-width in degrees of the range a single-hue history search covers
+pub const HUE_SEARCH_SPREAD: f32 = 15.0;
+
+pub fn color_history_path() -> PathBuf {
+    data_dir().join("color-history.json")
+}
+
+/// Every color locked or edited by hand, across sessions, most recent first
+pub fn load_color_history() -> io::Result<Vec<String>> {
+    let path = color_history_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Move `hex` to the front of the color history, dropping the oldest past the limit
+pub fn remember_color(hex: &str) -> io::Result<()> {
+    let hex = hex.to_uppercase();
+    let mut history = load_color_history().unwrap_or_default();
+    history.retain(|c| *c != hex);
+    history.insert(0, hex);
+    history.truncate(COLOR_HISTORY_LIMIT);
+
+    fs::create_dir_all(data_dir())?;
+    let json = serde_json::to_string_pretty(&history).map_err(io::Error::other)?;
+    fs::write(color_history_path(), json)
+}
+
+/// Indices of `colors` whose hue is in the range `query` gives, in their order.
+/// `200-240` is a range (`340-20` wraps through red), a single hue covers
+/// ±[`HUE_SEARCH_SPREAD`]; grays have no hue and only show for an empty query.
+pub fn search_colors_by_hue(colors: &[String], query: &str) -> Vec<usize> {
+    let query = query.trim();
+    if query.is_empty() {
+        return (0..colors.len()).collect();
+    }
+
+    let bounds = match query.split_once('-') {
+        Some((from, to)) => from.trim().parse().ok().zip(to.trim().parse().ok()),
+        None => query
+            .parse::<f32>()
+            .ok()
+            .map(|hue| (hue - HUE_SEARCH_SPREAD, hue + HUE_SEARCH_SPREAD)),
+    };
+    let Some((from, to)) = bounds else {
+        return Vec::new();
+    };
+    let (from, to): (f32, f32) = (from.rem_euclid(360.0), to.rem_euclid(360.0));
+
+    colors
+        .iter()
+        .enumerate()
+        .filter(|(_, hex)| {
+            let (r, g, b) = parse_hex(hex);
+            let (hue, sat, _) = convert::rgb_to_hsv(r, g, b);
+            let in_range = if from <= to {
+                (from..=to).contains(&hue)
+            } else {
+                hue >= from || hue <= to
+            };
+            sat > 0.0 && in_range
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
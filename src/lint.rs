@@ -0,0 +1,282 @@
+//! Colors written into source files, checked against a palette: every hex code and color
+//! function [`convert::find_colors`] finds, with the nearest palette color, so hard-coded
+//! colors that drifted off the palette can be reported.
+//!
+//! ```
+//! use terminal_palette::lint;
+//!
+//! let palette = [(224, 122, 95), (61, 64, 91)];
+//! let css = ".a { color: #E07A5F }\n.b { color: #3e405b; background: #FF0000 }\n";
+//! let off: Vec<_> = lint::literals(css, &palette)
+//!     .into_iter()
+//!     .filter(|literal| literal.delta_e > 1.0)
+//!     .collect();
+//! assert_eq!(off.len(), 1);
+//! assert_eq!((off[0].line, off[0].column, off[0].text.as_str()), (2, 34, "#FF0000"));
+//! ```
+//!
+//! [`rewrite`] goes a step further and puts the palette's variables in place of the hex
+//! codes that match, and [`patch`] turns that into a diff to review.
+
+use std::{
+    ffi::OsStr,
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::convert;
+
+/// Directories [`files`] doesn't go into besides hidden ones: dependencies and build output
+pub const SKIPPED_DIRS: [&str; 4] = ["node_modules", "target", "dist", "vendor"];
+
+/// Lines of context around every change in a [`patch`]
+const PATCH_CONTEXT: usize = 3;
+
+/// Style sheet languages [`rewrite`] knows how to refer to a variable in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Syntax {
+    /// `var(--name)`
+    Css,
+    /// `$name`, for both `.scss` and `.sass`
+    Scss,
+}
+
+impl Syntax {
+    /// The syntax of a file by its extension, `None` for anything but a style sheet
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.extension().and_then(OsStr::to_str)? {
+            "css" => Some(Syntax::Css),
+            "scss" | "sass" => Some(Syntax::Scss),
+            _ => None,
+        }
+    }
+
+    /// How a use of the variable `name` is written
+    pub fn reference(self, name: &str) -> String {
+        match self {
+            Syntax::Css => format!("var(--{name})"),
+            Syntax::Scss => format!("${name}"),
+        }
+    }
+
+    /// Whether a declaration, read from its start up to a value, declares a variable,
+    /// which is where hex codes belong
+    fn declares(self, declaration: &str) -> bool {
+        let declaration = declaration.trim_start();
+        match self {
+            Syntax::Css => declaration.starts_with("--"),
+            Syntax::Scss => declaration.starts_with('$') || declaration.starts_with("--"),
+        }
+    }
+}
+
+/// A color written in a text
+#[derive(Clone, Debug, PartialEq)]
+pub struct Literal {
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    /// Where in the text it is, in bytes
+    pub span: Range<usize>,
+    /// As written, like `#fff` or `rgb(0 0 0)`
+    pub text: String,
+    pub color: (u8, u8, u8),
+    /// Index of the palette color nearest to it
+    pub nearest: usize,
+    /// How far it is from that color, see [`convert::delta_e`]
+    pub delta_e: f32,
+}
+
+/// Every color written in `text`, each with the nearest of the `palette` colors. Returns
+/// nothing for an empty palette.
+pub fn literals(text: &str, palette: &[(u8, u8, u8)]) -> Vec<Literal> {
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut scanned = 0;
+    let mut found = Vec::new();
+    for (span, color) in convert::find_colors(text) {
+        for (idx, _) in text[scanned..span.start].match_indices('\n') {
+            line += 1;
+            line_start = scanned + idx + 1;
+        }
+        scanned = span.start;
+
+        let Some((nearest, delta_e)) = palette
+            .iter()
+            .map(|&candidate| convert::delta_e(color, candidate))
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            return Vec::new();
+        };
+        found.push(Literal {
+            line,
+            column: text[line_start..span.start].chars().count() + 1,
+            text: text[span.clone()].to_string(),
+            span,
+            color,
+            nearest,
+            delta_e,
+        });
+    }
+    found
+}
+
+/// `text` with every hex code at most `tolerance` (ΔE) from a `palette` color replaced by
+/// that color's variable from `names`, and how many were replaced. Color functions stay as
+/// they are, and so do the values of variables, or the palette's own definitions would
+/// end up referring to themselves.
+///
+/// ```
+/// use terminal_palette::lint::{self, Syntax};
+///
+/// let palette = [(224, 122, 95), (61, 64, 91)];
+/// let names = [String::from("accent"), String::from("ink")];
+/// let css = ":root { --accent: #E07A5F; }\n.a { color: #e07a5f; border-color: #3E405B; }\n";
+/// let (rewritten, replaced) = lint::rewrite(css, &palette, &names, Syntax::Css, 1.0);
+/// assert_eq!(replaced, 2);
+/// assert_eq!(
+///     rewritten,
+///     ":root { --accent: #E07A5F; }\n.a { color: var(--accent); border-color: var(--ink); }\n"
+/// );
+/// ```
+pub fn rewrite(
+    text: &str,
+    palette: &[(u8, u8, u8)],
+    names: &[String],
+    syntax: Syntax,
+    tolerance: f32,
+) -> (String, usize) {
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut replaced = 0;
+    for literal in literals(text, palette) {
+        let declaration_start = text[..literal.span.start]
+            .rfind([';', '{', '}', '\n'])
+            .map_or(0, |idx| idx + 1);
+        if !literal.text.starts_with('#')
+            || literal.delta_e > tolerance
+            || syntax.declares(&text[declaration_start..literal.span.start])
+        {
+            continue;
+        }
+        let Some(name) = names.get(literal.nearest) else {
+            continue;
+        };
+        out.push_str(&text[copied..literal.span.start]);
+        out.push_str(&syntax.reference(name));
+        copied = literal.span.end;
+        replaced += 1;
+    }
+    out.push_str(&text[copied..]);
+    (out, replaced)
+}
+
+/// A unified diff from `old` to `new` for the file at `path`, as `git apply` and `patch -p1`
+/// read it; empty when they are the same. Lines are compared one to one, so both texts need
+/// the same number of lines, as [`rewrite`] keeps them.
+///
+/// ```
+/// use terminal_palette::lint;
+///
+/// let diff = lint::patch("a.css", "a {\n  color: #FFF;\n}\n", "a {\n  color: white;\n}\n");
+/// assert_eq!(
+///     diff,
+///     "--- a/a.css\n+++ b/a.css\n@@ -1,3 +1,3 @@\n a {\n-  color: #FFF;\n+  color: white;\n }\n"
+/// );
+/// ```
+pub fn patch(path: &str, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+    debug_assert_eq!(old.len(), new.len(), "patch compares lines one to one");
+
+    // Changed lines with their context, merged where the context touches
+    let mut hunks: Vec<Range<usize>> = Vec::new();
+    for idx in (0..old.len()).filter(|&idx| old[idx] != new[idx]) {
+        let start = idx.saturating_sub(PATCH_CONTEXT);
+        let end = (idx + PATCH_CONTEXT + 1).min(old.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => hunks.push(start..end),
+        }
+    }
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("--- a/{path}\n+++ b/{path}\n");
+    for hunk in hunks {
+        out.push_str(&format!(
+            "@@ -{0},{1} +{0},{1} @@\n",
+            hunk.start + 1,
+            hunk.len()
+        ));
+        let mut idx = hunk.start;
+        while idx < hunk.end {
+            if old[idx] == new[idx] {
+                push_patch_line(&mut out, ' ', old[idx]);
+                idx += 1;
+                continue;
+            }
+            let run_end = (idx..hunk.end)
+                .find(|&end| old[end] == new[end])
+                .unwrap_or(hunk.end);
+            for line in &old[idx..run_end] {
+                push_patch_line(&mut out, '-', line);
+            }
+            for line in &new[idx..run_end] {
+                push_patch_line(&mut out, '+', line);
+            }
+            idx = run_end;
+        }
+    }
+    out
+}
+
+fn push_patch_line(out: &mut String, marker: char, line: &str) {
+    out.push(marker);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// `root` itself if it is a file, else every file below it in path order, leaving out
+/// hidden files and directories and [`SKIPPED_DIRS`]
+pub fn files(root: &Path) -> io::Result<Vec<PathBuf>> {
+    if root.is_file() {
+        return Ok(vec![root.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() && !SKIPPED_DIRS.contains(&name.as_ref()) {
+                dirs.push(entry.path());
+            } else if file_type.is_file() {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The text of `path`, or `None` for binary files, which have no colors to read
+pub fn read_text(path: &Path) -> io::Result<Option<String>> {
+    let bytes = fs::read(path)?;
+    match String::from_utf8(bytes) {
+        Ok(text) if !text.contains('\0') => Ok(Some(text)),
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,33 @@
+//! Optional `--log-file` logging. The TUI owns stdout, so logs only ever go to a file.
+
+use std::{
+    fs::{self, OpenOptions},
+    io,
+    path::Path,
+    sync::Mutex,
+};
+
+use tracing_subscriber::EnvFilter;
+
+/// Used unless `RUST_LOG` says otherwise
+const DEFAULT_FILTER: &str = "terminal_palette=debug";
+
+/// Append logs to `path` for the rest of the process
+pub fn init(path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_env_filter(filter)
+        .try_init()
+        .map_err(io::Error::other)
+}
@@ -1,9 +1,46 @@
-use std::io;
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use clap::Parser;
+use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
+    execute,
+};
+use tracing::{error, info};
+
+mod action;
+mod announce;
 mod app;
+mod appearance;
+mod cli;
+mod clipboard;
+mod command;
+mod config;
+mod editor;
+mod graphics;
+mod hooks;
+mod i18n;
+mod ipc;
+mod logging;
+mod osc;
+mod tasks;
+mod transition;
 mod widgets;
 
+use strum::IntoEnumIterator;
+use terminal_palette::color::hex2rgb;
+use terminal_palette::export::{self, ExportColor, ExportPalette, Format};
+use terminal_palette::extract::{self, PixelCounts};
+use terminal_palette::{ansi, convert, library, lint, palette_file, plugins, project};
+
 use crate::app::App;
+use crate::cli::{Cli, Command, LibraryCommand, PluginCommand};
+use crate::config::Config;
+use crate::hooks::Event;
 
 #[macro_export]
 macro_rules! margin {
@@ -16,8 +53,453 @@ macro_rules! margin {
 }
 
 fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+
+    if let Some(path) = &cli.log_file {
+        logging::init(path)?;
+    }
+    info!(version = env!("CARGO_PKG_VERSION"), "starting");
+
+    if !cli.check.is_empty() {
+        return check_files(&cli.check);
+    }
+
+    // No config file yet: ask the setup questions, which write one
+    let first_run = !config::config_path().exists();
+    let config = config::load()?;
+    i18n::init(config.locale.as_deref());
+
+    let socket = cli.socket.unwrap_or_else(ipc::socket_path);
+    if let Some(command) = cli.command {
+        return run_command(command, &config, &socket)
+            .inspect_err(|e| error!("command failed: {e}"));
+    }
+
+    let mut app = App::new(config);
+
+    // `terminal-palette project.tpal` opens a project,
+    // `terminal-palette my-palette.json` a saved palette
+    if let Some(path) = cli.file {
+        let opened = if project::is_project_path(&path) {
+            app.open_project(&path)
+        } else {
+            app.load_palette_file(&path)
+        };
+        opened.inspect_err(|e| error!("could not open {}: {e}", path.display()))?;
+    } else if app.config.follow_system_appearance {
+        match appearance::detect() {
+            Some(appearance) => app.switch_appearance(appearance),
+            None => info!("no system appearance to follow"),
+        }
+    }
+
+    if cli.daemon || cli.listen {
+        app.ipc = Some(ipc::Server::bind(&socket)?);
+        info!(socket = %socket.display(), "listening");
+    }
+    if cli.slideshow {
+        app.start_slideshow();
+    } else if first_run && !cli.daemon {
+        app.start_setup();
+    }
+    if cli.daemon {
+        return app.serve();
+    }
+
+    // Images are sized in pixels, which some terminals keep to themselves
+    app.graphics = app
+        .config
+        .graphics
+        .protocol()
+        .filter(|_| graphics::cell_size().is_some());
     let mut terminal = ratatui::init();
-    let app_result = App::default().run(&mut terminal);
+    // Pasted text arrives as one event instead of a burst of key presses
+    execute!(io::stdout(), EnableBracketedPaste)?;
+    let app_result = app.run(&mut terminal);
+    let _ = execute!(io::stdout(), DisableBracketedPaste);
     ratatui::restore();
-    app_result
+    app_result.inspect_err(|e| error!("terminal error: {e}"))
+}
+
+fn run_command(command: Command, config: &Config, socket: &Path) -> io::Result<()> {
+    match command {
+        Command::Color { color } => {
+            let (r, g, b) = convert::parse_color(&color).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("{color:?}: {e}"))
+            })?;
+            let (h, s, l) = convert::rgb_to_hsl(r, g, b);
+            println!(
+                "{}  rgb({r}, {g}, {b})  hsl({:.0}, {:.0}%, {:.0}%)",
+                convert::rgb_to_hex(r, g, b),
+                h,
+                s * 100.0,
+                l * 100.0
+            );
+        }
+        Command::Library(LibraryCommand::Export { path }) => {
+            let count = library::export_library(&path)?;
+            println!("Exported {count} palettes to {}", path.display());
+        }
+        Command::Library(LibraryCommand::Import { path, on_conflict }) => {
+            let report = library::import_library(&path, on_conflict.into())?;
+            println!(
+                "Imported {} new, {} replaced, {} renamed, {} skipped palettes and {} favorite colors",
+                report.added,
+                report.replaced,
+                report.renamed,
+                report.skipped,
+                report.favorites_added
+            );
+        }
+        Command::Plugin(PluginCommand::List) => {
+            let (plugins, failed) = plugins::load_plugins();
+            for plugin in &plugins {
+                let mut hooks = Vec::new();
+                if plugin.generates() {
+                    hooks.push("theory");
+                }
+                if plugin.exports() {
+                    hooks.push("export");
+                }
+                println!(
+                    "{} ({})  {}",
+                    plugin.name,
+                    hooks.join(", "),
+                    plugin.path.display()
+                );
+            }
+            for (path, e) in &failed {
+                eprintln!("{}: {e}", path.display());
+            }
+            if plugins.is_empty() && failed.is_empty() {
+                println!("No plugins in {}", plugins::plugins_dir().display());
+            }
+        }
+        Command::Plugin(PluginCommand::Export {
+            plugin,
+            path,
+            output,
+        }) => {
+            let (plugins, _) = plugins::load_plugins();
+            let plugin = plugins
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(&plugin))
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("no plugin named {plugin:?}"),
+                    )
+                })?;
+
+            let palette = load_export_palette(&path)?;
+            let colors: Vec<String> = palette.colors.iter().map(|c| c.hex.clone()).collect();
+            let labels: Vec<String> = palette.colors.iter().map(|c| c.label.clone()).collect();
+            let roles: Vec<_> = palette.colors.iter().map(|c| c.role).collect();
+
+            let text = plugin.export(&palette.name, &colors, &labels, &roles)?;
+            write_output(output.as_deref(), text.as_bytes())?;
+            hooks::run(&config.hooks, &Event::Export(output), &palette)?;
+        }
+        Command::Export {
+            format,
+            path,
+            output,
+            size,
+        } => {
+            let palette = load_export_palette(&path)?;
+            let mut options = config.export.clone();
+            if let Some((width, height)) = size {
+                (options.png_width, options.png_height) = (width, height);
+            }
+            let contents = export::export_bytes(format.into(), &palette, &options)?;
+            write_output(output.as_deref(), &contents)?;
+            hooks::run(&config.hooks, &Event::Export(output), &palette)?;
+        }
+        Command::Extract { source, at, colors } => {
+            let counted = picture_colors(&source, at)?;
+            for ((r, g, b), share) in extract::palette(&counted, colors) {
+                println!("{}  {share:.1}%", convert::rgb_to_hex(r, g, b));
+            }
+        }
+        Command::Lint {
+            path,
+            palette,
+            tolerance,
+        } => lint(&path, &palette, tolerance)?,
+        Command::Rewrite {
+            path,
+            palette,
+            tolerance,
+            output,
+        } => rewrite(config, &path, &palette, tolerance, output.as_deref())?,
+        Command::Send { request } => {
+            let reply = ipc::send(socket, &request.join(" "))?;
+            println!("{reply}");
+            if reply.starts_with("error") {
+                return Err(io::Error::other("the request failed"));
+            }
+        }
+        Command::Theme { wallpaper, apply } => theme(config, &wallpaper, apply)?,
+    }
+
+    Ok(())
+}
+
+/// The colors of a GIF file or URL, see [`extract::frame_colors`]
+fn picture_colors(source: &str, at: Duration) -> io::Result<PixelCounts> {
+    if extract::is_url(source) {
+        extract::frame_colors_of(&extract::download(source, |_, _| ())?, at, source)
+    } else {
+        extract::frame_colors(Path::new(source), at)
+    }
+}
+
+/// `theme`: an ANSI scheme around the colors of `wallpaper`, written to every
+/// `theme_exports` file, or printed without any
+fn theme(config: &Config, wallpaper: &str, apply: bool) -> io::Result<()> {
+    let counted = picture_colors(wallpaper, Duration::ZERO)?;
+    let colors: Vec<_> = extract::palette(&counted, osc::ANSI_COLORS)
+        .into_iter()
+        .map(|(color, _)| color)
+        .collect();
+    let scheme = ansi::from_colors(&colors, config.ansi_dim_variants);
+
+    let name = wallpaper
+        .rsplit('/')
+        .next()
+        .and_then(|file| Path::new(file).file_stem())
+        .map_or_else(
+            || String::from("wallpaper"),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+    let palette = ExportPalette {
+        name,
+        theory: None,
+        colors: scheme
+            .iter()
+            .map(|block| ExportColor {
+                label: block.label.clone(),
+                ..ExportColor::new(&block.get_hex())
+            })
+            .collect(),
+        gradients: Vec::new(),
+    };
+
+    if apply {
+        let rgb: Vec<_> = scheme.iter().map(|block| block.get_rgb_values()).collect();
+        osc::apply_palette(&rgb)?;
+        osc::broadcast(&rgb, &config.apply_targets)?;
+        if config.announce {
+            announce::announce(&palette)?;
+        }
+        hooks::run(&config.hooks, &Event::Apply, &palette)?;
+    }
+    if config.theme_exports.is_empty() {
+        for color in &palette.colors {
+            println!("{:<15} {}", color.label, color.hex);
+        }
+        eprintln!(
+            "No theme_exports in {}, nothing written",
+            config::config_path().display()
+        );
+        return Ok(());
+    }
+
+    let (plugins, _) = plugins::load_plugins();
+    let mut failed = 0;
+    for target in &config.theme_exports {
+        let path = config::config_dir().join(&target.path);
+        let contents = match Format::iter().find(|format| format.key() == target.format) {
+            Some(format) => export::export_bytes(format, &palette, &config.export),
+            None => match plugins
+                .iter()
+                .find(|plugin| plugin.exports() && plugin.name.eq_ignore_ascii_case(&target.format))
+            {
+                Some(plugin) => {
+                    let hexes: Vec<String> = palette.colors.iter().map(|c| c.hex.clone()).collect();
+                    let labels: Vec<String> =
+                        palette.colors.iter().map(|c| c.label.clone()).collect();
+                    plugin
+                        .export(&palette.name, &hexes, &labels, &vec![None; hexes.len()])
+                        .map(String::into_bytes)
+                }
+                None => Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no format or plugin named {:?}", target.format),
+                )),
+            },
+        };
+        let written = contents.and_then(|contents| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, contents)
+        });
+        let event = Event::Export(Some(path.clone()));
+        let written = written.inspect(|()| println!("Wrote {}", path.display()));
+        match written.and_then(|()| hooks::run(&config.hooks, &event, &palette)) {
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                failed += 1;
+            }
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(io::Error::other(format!(
+            "{failed} of {} theme exports failed",
+            config.theme_exports.len()
+        ))),
+    }
+}
+
+/// `lint`: print every color below `root` further than `tolerance` from the palette in
+/// `palette_path`, and fail if there is any
+fn lint(root: &Path, palette_path: &Path, tolerance: f32) -> io::Result<()> {
+    let palette = load_export_palette(palette_path)?;
+    let colors = palette_rgb(&palette);
+
+    let files = lint::files(root)?;
+    let (mut found, mut in_files) = (0, 0);
+    for path in &files {
+        let Some(text) = lint::read_text(path)? else {
+            continue;
+        };
+        let off: Vec<_> = lint::literals(&text, &colors)
+            .into_iter()
+            .filter(|literal| literal.delta_e > tolerance)
+            .collect();
+        for literal in &off {
+            println!(
+                "{}:{}:{}: {} is not in the palette, nearest is {} (ΔE {:.1})",
+                path.display(),
+                literal.line,
+                literal.column,
+                literal.text,
+                palette.colors[literal.nearest].hex,
+                literal.delta_e
+            );
+        }
+        found += off.len();
+        in_files += usize::from(!off.is_empty());
+    }
+
+    match found {
+        0 => {
+            eprintln!("No colors off the palette in {} files", files.len());
+            Ok(())
+        }
+        found => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{found} colors off the palette in {in_files} files"),
+        )),
+    }
+}
+
+/// `rewrite`: a patch putting the palette's variables, named as the CSS and SCSS exports
+/// name them, in place of the hex codes in the style sheets below `root`
+fn rewrite(
+    config: &Config,
+    root: &Path,
+    palette_path: &Path,
+    tolerance: f32,
+    output: Option<&Path>,
+) -> io::Result<()> {
+    let palette = load_export_palette(palette_path)?;
+    let colors = palette_rgb(&palette);
+    let css_names = export::variable_names(&palette);
+    let scss_names = export::scss_names(&palette, &config.export.scss_prefix);
+
+    let mut patch = String::new();
+    let (mut replaced, mut in_files) = (0, 0);
+    for path in lint::files(root)? {
+        let Some(syntax) = lint::Syntax::of(&path) else {
+            continue;
+        };
+        let Some(text) = lint::read_text(&path)? else {
+            continue;
+        };
+        let names = match syntax {
+            lint::Syntax::Css => &css_names,
+            lint::Syntax::Scss => &scss_names,
+        };
+        let (rewritten, count) = lint::rewrite(&text, &colors, names, syntax, tolerance);
+        if count == 0 {
+            continue;
+        }
+        // `./src/a.css` would read as a path starting with a `.` directory to `git apply`
+        let name = path.strip_prefix(".").unwrap_or(&path);
+        patch.push_str(&lint::patch(&name.display().to_string(), &text, &rewritten));
+        replaced += count;
+        in_files += 1;
+    }
+
+    write_output(output, patch.as_bytes())?;
+    eprintln!("{replaced} colors replaced in {in_files} files");
+    Ok(())
+}
+
+/// `--check`: print every problem in `paths` to stderr, one per line, and fail if there
+/// were any
+fn check_files(paths: &[PathBuf]) -> io::Result<()> {
+    let mut failed = 0;
+    for path in paths {
+        let is_palette_file = path
+            .extension()
+            .is_some_and(|ext| ext == palette_file::EXTENSION);
+        let problems: Vec<String> = match fs::read_to_string(path) {
+            Ok(text) if is_palette_file => palette_file::check(&text)
+                .err()
+                .unwrap_or_default()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            Ok(_) => load_export_palette(path)
+                .err()
+                .map(|e| e.to_string())
+                .into_iter()
+                .collect(),
+            Err(e) => vec![e.to_string()],
+        };
+        for problem in &problems {
+            eprintln!("{}: {problem}", path.display());
+        }
+        failed += usize::from(!problems.is_empty());
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{failed} of {} files failed the check", paths.len()),
+        )),
+    }
+}
+
+/// A `.tpal` project or a saved palette, ready for an exporter
+fn load_export_palette(path: &Path) -> io::Result<ExportPalette> {
+    if project::is_project_path(path) {
+        Ok(ExportPalette::from(&project::load_project(path)?))
+    } else {
+        Ok(ExportPalette::from(&library::load_palette(path)?))
+    }
+}
+
+/// The palette's colors as RGB, whether or not its hex codes start with `#`
+fn palette_rgb(palette: &ExportPalette) -> Vec<(u8, u8, u8)> {
+    palette
+        .colors
+        .iter()
+        .map(|c| hex2rgb(c.hex.trim_start_matches('#')))
+        .collect()
+}
+
+/// Write to `output`, or to stdout without one
+fn write_output(output: Option<&Path>, contents: &[u8]) -> io::Result<()> {
+    match output {
+        Some(output) => fs::write(output, contents),
+        None => io::stdout().write_all(contents),
+    }
 }
@@ -0,0 +1,67 @@
+//! How a palette looks through a night-light filter.
+//!
+//! Night Shift, Night Light, f.lux and redshift cut the blue of a screen late at night by
+//! lowering its color temperature, which is exactly when many terminal schemes are read.
+//! [`filter`] scales each channel by the color of a black body at the chosen temperature
+//! relative to the 6500 K screens are made for, the way those tools scale the gamma ramps.
+//!
+//! ```
+//! use terminal_palette::color::ColorBlock;
+//! use terminal_palette::night_light;
+//!
+//! let white = ColorBlock::new(1, 0.0, 0.0, 1.0);
+//! let (r, g, b) = night_light::filter(&white, 3400).get_rgb_values();
+//! assert_eq!(r, 255);
+//! assert!(g < r && b < g);
+//! ```
+
+use crate::color::ColorBlock;
+use crate::convert;
+
+/// Temperatures (kelvin) the filters commonly offer, mildest first
+pub const TEMPERATURES: [u32; 3] = [4500, 3400, 2700];
+/// White point of sRGB screens, left as it is
+pub const DAYLIGHT: u32 = 6500;
+
+/// Factor each of red, green and blue is scaled by at `kelvin`, 1 for all three at
+/// [`DAYLIGHT`]
+pub fn channel_scales(kelvin: u32) -> [f32; 3] {
+    let color = black_body(kelvin);
+    let daylight = black_body(DAYLIGHT);
+    [0, 1, 2].map(|i| (color[i] / daylight[i]).min(1.0))
+}
+
+/// `block` as it shows through a filter at `kelvin`. Everything but the color is kept, so
+/// the result renders in place of the block.
+pub fn filter(block: &ColorBlock, kelvin: u32) -> ColorBlock {
+    let mut filtered = block.clone();
+    let (r, g, b) = block.get_rgb_values();
+    let [red, green, blue] = channel_scales(kelvin);
+    let scale = |channel: u8, factor: f32| (f32::from(channel) * factor).round() as u8;
+    let (r, g, b) = (scale(r, red), scale(g, green), scale(b, blue));
+    let (hue, sat, val) = convert::rgb_to_hsv(r, g, b);
+    filtered.change_color(hue, sat, val);
+    filtered
+}
+
+/// Approximate color of a black body at `kelvin` as 0-255 channels, after Tanner Helland's
+/// fit to the CIE 1964 data, good from 1000 K to 40000 K
+fn black_body(kelvin: u32) -> [f32; 3] {
+    let t = kelvin.clamp(1000, 40000) as f32 / 100.0;
+    let (red, green) = if t <= 66.0 {
+        (255.0, 99.470_8 * t.ln() - 161.119_57)
+    } else {
+        (
+            329.698_73 * (t - 60.0).powf(-0.133_204_76),
+            288.122_17 * (t - 60.0).powf(-0.075_514_85),
+        )
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+    [red, green, blue].map(|channel: f32| channel.clamp(0.0, 255.0))
+}
@@ -0,0 +1,108 @@
+//! Recoloring the terminal itself with OSC escape sequences, for the slideshow.
+//!
+//! Terminals that don't understand them ignore them, so this never fails in a visible way.
+//! Other terminals are recolored by writing the same sequences to their tty, see
+//! [`broadcast`].
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// The ANSI palette has 16 entries; blocks past that are left out
+pub const ANSI_COLORS: usize = 16;
+
+/// Gives a terminal its own palette back (OSC 104)
+const RESET: &str = "\x1b]104\x07";
+
+/// Set ANSI colors `0..` to `colors` (OSC 4)
+pub fn apply_palette(colors: &[(u8, u8, u8)]) -> io::Result<()> {
+    let mut stdout = terminal();
+    write!(stdout, "{}", palette_sequence(colors))?;
+    stdout.flush()
+}
+
+/// Give the terminal its own palette back (OSC 104)
+pub fn reset_palette() -> io::Result<()> {
+    let mut stdout = terminal();
+    write!(stdout, "{RESET}")?;
+    stdout.flush()
+}
+
+/// Where the app's own terminal is recolored; tests must not recolor the one running them
+fn terminal() -> Box<dyn Write> {
+    if cfg!(test) {
+        Box::new(io::sink())
+    } else {
+        Box::new(io::stdout())
+    }
+}
+
+fn palette_sequence(colors: &[(u8, u8, u8)]) -> String {
+    colors
+        .iter()
+        .take(ANSI_COLORS)
+        .enumerate()
+        .map(|(idx, (r, g, b))| format!("\x1b]4;{idx};rgb:{r:02x}/{g:02x}/{b:02x}\x07"))
+        .collect()
+}
+
+/// [`apply_palette`] to other terminals: ttys like `/dev/pts/3` or pipes read by something
+/// passing the sequences on. A `*` file name stands for everything in its directory, so
+/// `/dev/pts/*` reaches every terminal the user may write to, skipping the others. Returns
+/// how many were recolored; the error names the first listed one that couldn't be.
+pub fn broadcast(colors: &[(u8, u8, u8)], targets: &[PathBuf]) -> io::Result<usize> {
+    write_to_all(&palette_sequence(colors), targets)
+}
+
+/// [`reset_palette`] of every terminal [`broadcast`] recolored
+pub fn reset_broadcast(targets: &[PathBuf]) -> io::Result<usize> {
+    write_to_all(RESET, targets)
+}
+
+fn write_to_all(sequence: &str, targets: &[PathBuf]) -> io::Result<usize> {
+    let mut written = 0;
+    let mut failures = Vec::new();
+    for target in targets {
+        let (paths, listed) = match (target.file_name(), target.parent()) {
+            (Some(name), Some(dir)) if name == "*" => (entries(dir), false),
+            _ => (vec![target.clone()], true),
+        };
+        for path in paths {
+            let result = OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .and_then(|mut target| target.write_all(sequence.as_bytes()));
+            match result {
+                Ok(()) => written += 1,
+                Err(e) if listed => failures.push(format!("{}: {e}", path.display())),
+                // Terminals of other users, or ones closing right now
+                Err(_) => {}
+            }
+        }
+    }
+
+    match failures.first() {
+        None => Ok(written),
+        Some(first) => Err(io::Error::other(format!(
+            "{} of {} terminals failed, first: {first}",
+            failures.len(),
+            written + failures.len()
+        ))),
+    }
+}
+
+/// Everything in `dir` but the `ptmx` multiplexer, which isn't a terminal
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().is_some_and(|name| name != "ptmx"))
+        .collect();
+    paths.sort();
+    paths
+}
@@ -0,0 +1,125 @@
+//! Colors that pair with one color of a palette at a readable contrast.
+//!
+//! [`suggest`] looks at every other block as a foreground (or background) for the chosen
+//! one. Blocks already meeting [`AA`] are suggested as they are; blocks short of [`AA`] or
+//! [`AAA`] are also offered lightened or darkened just enough to reach it, keeping their
+//! hue. Suggestions are ordered by how well their hue sits with the chosen color.
+
+use crate::color::ColorBlock;
+
+/// WCAG AA minimum contrast for body text
+pub const AA: f32 = 4.5;
+/// WCAG AAA minimum contrast for body text
+pub const AAA: f32 = 7.0;
+
+/// Hue differences (degrees) that read as harmonious: the same hue, analogous, triad,
+/// split-complementary and complementary
+const HARMONIC_ANGLES: [f32; 5] = [0.0, 30.0, 120.0, 150.0, 180.0];
+/// Below this saturation a color is a neutral, which goes with any hue
+const NEUTRAL_SATURATION: f32 = 0.1;
+
+#[derive(Clone, Debug)]
+pub struct Suggestion {
+    pub color: ColorBlock,
+    /// Index of the block the color comes from
+    pub source: usize,
+    /// Whether the source was lightened or darkened to reach the contrast
+    pub adjusted: bool,
+    /// Contrast ratio against the chosen color
+    pub ratio: f32,
+    /// Degrees off the nearest harmonious hue difference; 0 is best
+    pub harmony: f32,
+}
+
+/// Pairings for the block at `selected`, best harmony first, higher contrast first among
+/// equals. Empty if `selected` is out of range.
+pub fn suggest(color_blocks: &[ColorBlock], selected: usize) -> Vec<Suggestion> {
+    let Some(base) = color_blocks.get(selected) else {
+        return Vec::new();
+    };
+
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for (source, block) in color_blocks.iter().enumerate() {
+        if source == selected {
+            continue;
+        }
+        let harmony = harmony(base, block);
+        let ratio = block.contrast_ratio(base);
+        if ratio >= AA {
+            suggestions.push(Suggestion {
+                color: block.clone(),
+                source,
+                adjusted: false,
+                ratio,
+                harmony,
+            });
+        }
+
+        for target in [AA, AAA].into_iter().filter(|&target| ratio < target) {
+            let Some(color) = adjust(block, base, target) else {
+                continue;
+            };
+            if suggestions
+                .iter()
+                .any(|other| other.color.get_hex() == color.get_hex())
+            {
+                continue;
+            }
+            suggestions.push(Suggestion {
+                ratio: color.contrast_ratio(base),
+                color,
+                source,
+                adjusted: true,
+                harmony,
+            });
+        }
+    }
+
+    suggestions.sort_by(|a, b| {
+        a.harmony
+            .total_cmp(&b.harmony)
+            .then(b.ratio.total_cmp(&a.ratio))
+    });
+    suggestions
+}
+
+/// `block` if it already reaches `min` contrast against `base`, else its nearest variant
+/// that does
+pub fn readable(block: &ColorBlock, base: &ColorBlock, min: f32) -> Option<ColorBlock> {
+    if block.contrast_ratio(base) >= min {
+        Some(block.clone())
+    } else {
+        adjust(block, base, min)
+    }
+}
+
+/// `block` pushed to `min` contrast against `base` in whichever direction changes it least,
+/// or `None` if neither direction gets there
+fn adjust(block: &ColorBlock, base: &ColorBlock, min: f32) -> Option<ColorBlock> {
+    let distance = |color: &ColorBlock| {
+        (color.hsv.value - block.hsv.value).abs()
+            + (color.hsv.saturation - block.hsv.saturation).abs()
+    };
+
+    [true, false]
+        .into_iter()
+        .filter_map(|lighten| {
+            let mut color = block.clone();
+            color.push_contrast(base, min, lighten).then_some(color)
+        })
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+/// How far the hue difference of `a` and `b` is from [`HARMONIC_ANGLES`]
+fn harmony(a: &ColorBlock, b: &ColorBlock) -> f32 {
+    if a.hsv.saturation < NEUTRAL_SATURATION || b.hsv.saturation < NEUTRAL_SATURATION {
+        return 0.0;
+    }
+    let difference =
+        (b.hsv.hue.into_positive_degrees() - a.hsv.hue.into_positive_degrees()).rem_euclid(360.0);
+    let difference = difference.min(360.0 - difference);
+    HARMONIC_ANGLES
+        .iter()
+        .map(|angle| (difference - angle).abs())
+        .fold(f32::INFINITY, f32::min)
+}
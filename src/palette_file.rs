@@ -0,0 +1,378 @@
+//! `.palette` files, a line-oriented form of a project for version control.
+//!
+//! Every color takes one line, in palette order, with its attributes always in the same
+//! order, so changing a color changes exactly its line in a diff. A header names the palette
+//! and its theory. Timestamps and the generation history are left out, so saving an
+//! unchanged palette writes the same bytes again.
+//!
+//! ```text
+//! terminal-palette 1
+//! name "sunset"
+//! theory triad
+//! #E07A5F locked role=primary width=2 label="terra cotta"
+//! #3D405B freeze=hue note="body text only"
+//! #81B29A
+//! gradient "dusk" oklab #3D405B@0 #E07A5F@100
+//! ```
+//!
+//! A color line starts with its hex code, followed by any of `locked`, `freeze=hue` or
+//! `freeze=lightness`, `role=<role>`, `width=<n>`, `oklch=<l>,<c>,<h>` for colors outside
+//! sRGB, `label="..."` and `note="..."`, in that order. Gradients follow the colors, one
+//! per line: name, [`Space`] and stops at percentages. Blank lines are ignored.
+//! [`check`] tells whether a file parses and is written the way [`write`] writes it, for CI
+//! hooks.
+//!
+//! ```
+//! use terminal_palette::palette_file;
+//!
+//! let text = "terminal-palette 1\nname \"sunset\"\ntheory triad\n#E07A5F locked\n#3D405B\n";
+//! let project = palette_file::parse(text).unwrap();
+//! assert!(project.blocks[0].locked);
+//! assert_eq!(palette_file::write(&project), text);
+//! assert!(palette_file::check(text).is_ok());
+//!
+//! let errors = palette_file::check("terminal-palette 1\nname \"x\"\ntheory triad\n#12345\n");
+//! assert_eq!(errors.unwrap_err()[0].to_string(), "line 4: invalid color \"#12345\"");
+//! ```
+
+use std::fmt;
+
+use strum::IntoEnumIterator;
+
+use crate::color::{Freeze, hex2rgb};
+use crate::convert;
+use crate::generators::ColorTheories;
+use crate::gradient::{self, Gradient, Space, Stop};
+use crate::library::is_valid_hex;
+use crate::project::{PROJECT_VERSION, Project, ProjectBlock, ProjectMetadata};
+use crate::roles::Role;
+
+pub const EXTENSION: &str = "palette";
+/// First line of every file; the number is the format version
+const MAGIC: &str = "terminal-palette";
+const VERSION: u32 = 1;
+
+/// What is wrong with a line, numbered from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// The file for `project`: header, then one line per block
+pub fn write(project: &Project) -> String {
+    let mut out = format!(
+        "{MAGIC} {VERSION}\nname {}\ntheory {}\n",
+        quote(&project.name),
+        project.theory.key()
+    );
+    for block in &project.blocks {
+        let mut line = block.hex.to_uppercase();
+        if block.locked {
+            line.push_str(" locked");
+        }
+        if let Some(channel) = block.frozen {
+            line.push_str(&format!(" freeze={}", channel.key()));
+        }
+        if let Some(role) = block.role {
+            line.push_str(&format!(" role={}", role.key()));
+        }
+        if let Some(width) = block.width {
+            line.push_str(&format!(" width={width}"));
+        }
+        if let Some([l, c, h]) = block.oklch {
+            line.push_str(&format!(" oklch={l:.4},{c:.4},{h:.2}"));
+        }
+        if !block.label.is_empty() {
+            line.push_str(&format!(" label={}", quote(&block.label)));
+        }
+        if !block.note.is_empty() {
+            line.push_str(&format!(" note={}", quote(&block.note)));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    for ramp in &project.gradients {
+        let stops: Vec<String> = ramp
+            .stops
+            .iter()
+            .map(|stop| format!("{}@{}", stop.hex, gradient::percent(stop.position)))
+            .collect();
+        out.push_str(&format!(
+            "gradient {} {} {}\n",
+            quote(&ramp.name),
+            ramp.space.key(),
+            stops.join(" ")
+        ));
+    }
+    out
+}
+
+/// The project `text` describes, or every line that is wrong with it. The format keeps
+/// neither metadata nor history, so the project gets fresh metadata and an empty history. HSV is worked out from
+/// the hex codes.
+pub fn parse(text: &str) -> Result<Project, Vec<LineError>> {
+    let mut errors = Vec::new();
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    match lines.next() {
+        Some((_, line)) if line == format!("{MAGIC} {VERSION}") => {}
+        found => {
+            return Err(vec![LineError {
+                line: found.map_or(1, |(line, _)| line),
+                message: format!("expected \"{MAGIC} {VERSION}\" first"),
+            }]);
+        }
+    }
+
+    let (mut name, mut theory) = (None, None);
+    let (mut blocks, mut gradients) = (Vec::new(), Vec::new());
+    for (number, line) in lines {
+        let result = match line.split_once(' ').unwrap_or((line, "")) {
+            ("name", value) => unquote(value.trim()).map(|value| name = Some(value)),
+            ("theory", key) => ColorTheories::iter()
+                .find(|theory| theory.key() == key.trim())
+                .map(|found| theory = Some(found))
+                .ok_or_else(|| format!("unknown theory {:?}", key.trim())),
+            ("gradient", _) => parse_gradient(line).map(|g| gradients.push(g)),
+            (first, _) if first.starts_with('#') => parse_block(line).map(|b| blocks.push(b)),
+            (first, _) => Err(format!("expected a color or a header line, not {first:?}")),
+        };
+        if let Err(message) = result {
+            errors.push(LineError {
+                line: number,
+                message,
+            });
+        }
+    }
+
+    let last_line = text.lines().count().max(1);
+    for (missing, found) in [("name", name.is_some()), ("theory", theory.is_some())] {
+        if !found {
+            errors.push(LineError {
+                line: last_line,
+                message: format!("no {missing} line"),
+            });
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(Project {
+        version: PROJECT_VERSION,
+        name: name.unwrap_or_default(),
+        theory: theory.unwrap_or(ColorTheories::Analogous),
+        selected: 0,
+        blocks,
+        metadata: ProjectMetadata::new(),
+        history: Vec::new(),
+        gradients,
+    })
+}
+
+/// Whether `text` parses and is written exactly as [`write`] would write it
+pub fn check(text: &str) -> Result<(), Vec<LineError>> {
+    let canonical = write(&parse(text)?);
+    if canonical == text {
+        return Ok(());
+    }
+
+    let (line, expected) = canonical
+        .lines()
+        .zip(text.lines())
+        .enumerate()
+        .find(|(_, (expected, found))| expected != found)
+        .map(|(idx, (expected, _))| (idx + 1, expected))
+        .unwrap_or_else(|| {
+            let common = canonical.lines().count().min(text.lines().count());
+            (common + 1, canonical.lines().nth(common).unwrap_or(""))
+        });
+    Err(vec![LineError {
+        line,
+        message: match expected {
+            "" => String::from("expected the file to end here, with a newline"),
+            expected => format!("expected {expected:?}"),
+        },
+    }])
+}
+
+fn parse_block(line: &str) -> Result<ProjectBlock, String> {
+    let mut tokens = tokenize(line)?.into_iter();
+    let hex = tokens.next().unwrap_or_default().to_uppercase();
+    if !is_valid_hex(&hex) {
+        return Err(format!("invalid color {hex:?}"));
+    }
+    let (r, g, b) = hex2rgb(hex.trim_start_matches('#'));
+    let (h, s, v) = convert::rgb_to_hsv(r, g, b);
+    let mut block = ProjectBlock {
+        hex,
+        hsv: [h, s, v],
+        locked: false,
+        frozen: None,
+        label: String::new(),
+        note: String::new(),
+        width: None,
+        role: None,
+        oklch: None,
+    };
+
+    for token in tokens {
+        let (key, value) = token.split_once('=').unwrap_or((&token, ""));
+        match key {
+            "locked" if value.is_empty() => block.locked = true,
+            "freeze" => {
+                block.frozen = Some(match value {
+                    "hue" => Freeze::Hue,
+                    "lightness" => Freeze::Lightness,
+                    _ => return Err(format!("unknown freeze {value:?}")),
+                })
+            }
+            "role" => {
+                block.role = Some(
+                    Role::iter()
+                        .find(|role| role.key() == value)
+                        .ok_or_else(|| format!("unknown role {value:?}"))?,
+                )
+            }
+            "width" => {
+                block.width = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|&width| width > 0)
+                        .ok_or_else(|| format!("invalid width {value:?}"))?,
+                )
+            }
+            "oklch" => {
+                let channels: Vec<f32> = value
+                    .split(',')
+                    .map(|channel| channel.parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| format!("invalid oklch {value:?}"))?;
+                let [l, c, h] = channels[..] else {
+                    return Err(format!("oklch takes 3 numbers, not {value:?}"));
+                };
+                block.oklch = Some([l, c, h]);
+            }
+            "label" => block.label = unquote(value)?,
+            "note" => block.note = unquote(value)?,
+            _ => return Err(format!("unknown attribute {token:?}")),
+        }
+    }
+    Ok(block)
+}
+
+fn parse_gradient(line: &str) -> Result<Gradient, String> {
+    let tokens = tokenize(line)?;
+    let [_, name, space, stops @ ..] = &tokens[..] else {
+        return Err(String::from("a gradient needs a name, a space and stops"));
+    };
+    let space = Space::iter()
+        .find(|candidate| candidate.key() == space)
+        .ok_or_else(|| format!("unknown space {space:?}"))?;
+    let stops = stops
+        .iter()
+        .map(|stop| {
+            let (hex, percent) = stop
+                .split_once('@')
+                .ok_or_else(|| format!("expected a stop like #RRGGBB@50, not {stop:?}"))?;
+            let percent: f32 = percent
+                .parse()
+                .ok()
+                .filter(|percent| (0.0..=100.0).contains(percent))
+                .ok_or_else(|| format!("invalid stop position {percent:?}"))?;
+            if !is_valid_hex(hex) {
+                return Err(format!("invalid color {hex:?}"));
+            }
+            Ok(Stop {
+                hex: hex.to_uppercase(),
+                position: percent / 100.0,
+            })
+        })
+        .collect::<Result<Vec<Stop>, String>>()?;
+    if stops.len() < 2 {
+        return Err(String::from("a gradient needs at least 2 stops"));
+    }
+    if stops
+        .windows(2)
+        .any(|pair| pair[1].position < pair[0].position)
+    {
+        return Err(String::from("stops must be in order of position"));
+    }
+    Ok(Gradient {
+        name: unquote(name)?,
+        space,
+        stops,
+    })
+}
+
+/// Split at spaces outside double quotes
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let (mut quoted, mut escaped) = (false, false);
+    for c in line.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ' ' | '\t' if !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        token.push(c);
+    }
+    if quoted {
+        return Err(String::from("unclosed quote"));
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+fn unquote(value: &str) -> Result<String, String> {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .filter(|_| value.len() >= 2)
+        .ok_or_else(|| format!("expected a quoted string, not {value:?}"))?;
+    let mut text = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some(escaped @ ('\\' | '"')) => text.push(escaped),
+                other => return Err(format!("invalid escape \\{}", other.unwrap_or(' '))),
+            },
+            (c, false) => text.push(c),
+        }
+    }
+    Ok(text)
+}
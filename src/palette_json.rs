@@ -0,0 +1,142 @@
+//! Palettes as JSON for other tools and scripts, in a shape that stays put whatever the app
+//! keeps internally. [`write`] produces it and [`parse`] reads it back.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "name": "sunset",
+//!   "theory": "Triad",
+//!   "colors": [
+//!     { "hex": "#E07A5F", "hsv": [12.6, 0.5759, 0.8784], "locked": true },
+//!     { "hex": "#3D405B", "hsv": [234.0, 0.3297, 0.3569], "locked": false }
+//!   ]
+//! }
+//! ```
+//!
+//! `hsv` holds the hue in degrees and the saturation and value from 0 to 1. When reading,
+//! `hex` wins and `hsv` only counts for colors without one, so scripts can write either.
+//! Everything but the colors may be left out: `version` then reads as 1, `name` as empty,
+//! `theory` (also `null` for palettes made elsewhere) as `Analogous` and `locked` as false.
+//!
+//! ```
+//! use terminal_palette::palette_json;
+//!
+//! let palette = palette_json::parse(r##"{ "colors": [{ "hsv": [0, 1, 1] }, { "hex": "#fff" }] }"##)
+//!     .unwrap();
+//! assert_eq!(palette.colors[0].hex, "#FF0000");
+//! assert_eq!(palette.colors[1].hex, "#FFFFFF");
+//! ```
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::convert;
+use crate::export::ExportPalette;
+use crate::generators::ColorTheories;
+use crate::library::{SavedColor, SavedPalette};
+
+/// Version of the shape; readers refuse newer ones
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Document {
+    #[serde(default = "first_version")]
+    version: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    theory: Option<ColorTheories>,
+    colors: Vec<Color>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Color {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hsv: Option<[f32; 3]>,
+    #[serde(default)]
+    locked: bool,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+/// The palette as pretty-printed JSON
+pub fn write(palette: &ExportPalette) -> String {
+    let colors = palette
+        .colors
+        .iter()
+        .map(|color| {
+            let (r, g, b) = convert::parse_hex(&color.hex).unwrap_or_default();
+            let (h, s, v) = convert::rgb_to_hsv(r, g, b);
+            Color {
+                hex: Some(color.hex.clone()),
+                hsv: Some([round(h, 1), round(s, 4), round(v, 4)]),
+                locked: color.locked,
+            }
+        })
+        .collect();
+    let document = Document {
+        version: VERSION,
+        name: palette.name.clone(),
+        theory: palette.theory,
+        colors,
+    };
+    let mut out = serde_json::to_string_pretty(&document).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// A palette from its JSON, ready to open or save in the library
+pub fn parse(text: &str) -> io::Result<SavedPalette> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let document: Document =
+        serde_json::from_str(text).map_err(|e| invalid(format!("invalid palette JSON: {e}")))?;
+    if document.version > VERSION {
+        return Err(invalid(format!(
+            "palette JSON version {} is newer than this app reads ({VERSION})",
+            document.version
+        )));
+    }
+
+    let colors = document
+        .colors
+        .iter()
+        .enumerate()
+        .map(|(idx, color)| {
+            let (r, g, b) = match (&color.hex, color.hsv) {
+                (Some(hex), _) => convert::parse_hex(hex)
+                    .map_err(|e| invalid(format!("color {}: {hex:?}: {e}", idx + 1)))?,
+                (None, Some([h, s, v])) => convert::hsv_to_rgb(h, s, v),
+                (None, None) => {
+                    return Err(invalid(format!(
+                        "color {} has neither hex nor hsv",
+                        idx + 1
+                    )));
+                }
+            };
+            Ok(SavedColor {
+                hex: convert::rgb_to_hex(r, g, b),
+                locked: color.locked,
+                frozen: None,
+                role: None,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+
+    Ok(SavedPalette {
+        name: document.name,
+        theory: document.theory.unwrap_or(ColorTheories::Analogous),
+        colors,
+        tags: Vec::new(),
+        starred: false,
+    })
+}
+
+fn round(value: f32, places: i32) -> f32 {
+    let scale = 10f32.powi(places);
+    (value * scale).round() / scale
+}
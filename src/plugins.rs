@@ -0,0 +1,245 @@
+//! Lua plugins adding color theories and export formats.
+//!
+//! Every `*.lua` file in [`plugins_dir`] is a plugin. It returns a table with a `name` and
+//! at least one of two hooks:
+//!
+//! ```lua
+//! return {
+//!   name = "Golden angle",
+//!
+//!   -- `locked` lists the locked blocks as { index = 1-based position, hex = "#RRGGBB" },
+//!   -- `count` is the palette size. Return one hex per block; locked blocks keep their
+//!   -- color whatever is returned for them, missing entries leave blocks unchanged.
+//!   generate = function(locked, count)
+//!     local colors = {}
+//!     for i = 1, count do colors[i] = "#336699" end
+//!     return colors
+//!   end,
+//!
+//!   -- `palette` is { name = "...", colors = { "#RRGGBB", ... }, labels = { "...", ... },
+//!   -- roles = { background = "#RRGGBB", primary = "#RRGGBB", ... } } with assigned roles only
+//!   extension = "txt",
+//!   export = function(palette)
+//!     return table.concat(palette.colors, "\n")
+//!   end,
+//! }
+//! ```
+//!
+//! Scripts run in a sandbox with only the `string`, `table`, `math` and `utf8` libraries.
+//!
+//! Builds without the `plugins` feature still find the scripts, but every one of them fails
+//! to load with [`io::ErrorKind::Unsupported`].
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "plugins")]
+use mlua::{Function, Lua, LuaOptions, StdLib, Table};
+
+use crate::library;
+#[cfg(feature = "plugins")]
+use crate::library::normalize_color;
+use crate::roles::Role;
+
+pub const PLUGIN_EXTENSION: &str = "lua";
+
+pub fn plugins_dir() -> PathBuf {
+    library::data_dir().join("plugins")
+}
+
+/// A loaded plugin script
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+    /// File extension for exported text, without the dot
+    pub extension: String,
+    #[cfg(feature = "plugins")]
+    lua: Lua,
+}
+
+#[cfg(feature = "plugins")]
+impl Plugin {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let source = fs::read_to_string(path)?;
+        let lua = Lua::new_with(
+            StdLib::STRING | StdLib::TABLE | StdLib::MATH | StdLib::UTF8,
+            LuaOptions::default(),
+        )
+        .map_err(lua_error)?;
+
+        let (name, extension) = {
+            let table: Table = lua
+                .load(&source)
+                .set_name(path.to_string_lossy())
+                .eval()
+                .map_err(lua_error)?;
+
+            let name: Option<String> = table.get("name").map_err(lua_error)?;
+            let extension: Option<String> = table.get("extension").map_err(lua_error)?;
+            lua.set_named_registry_value(PLUGIN_KEY, table)
+                .map_err(lua_error)?;
+            (name, extension)
+        };
+
+        let fallback_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let plugin = Self {
+            name: name.unwrap_or(fallback_name),
+            path: path.to_path_buf(),
+            extension: extension.unwrap_or_else(|| String::from("txt")),
+            lua,
+        };
+
+        if !plugin.generates() && !plugin.exports() {
+            return Err(invalid("plugin defines neither `generate` nor `export`"));
+        }
+
+        Ok(plugin)
+    }
+
+    /// Whether the plugin provides a color theory
+    pub fn generates(&self) -> bool {
+        self.hook("generate").is_some()
+    }
+
+    /// Whether the plugin provides an export format
+    pub fn exports(&self) -> bool {
+        self.hook("export").is_some()
+    }
+
+    /// Run the `generate` hook. `locked` holds (0-based position, hex) of every locked block.
+    pub fn generate(&self, locked: &[(usize, String)], count: usize) -> io::Result<Vec<String>> {
+        let hook = self
+            .hook("generate")
+            .ok_or_else(|| invalid("plugin has no `generate` hook"))?;
+
+        let locked_table = self.lua.create_table().map_err(lua_error)?;
+        for (i, (pos, hex)) in locked.iter().enumerate() {
+            let entry = self.lua.create_table().map_err(lua_error)?;
+            entry.set("index", pos + 1).map_err(lua_error)?;
+            entry.set("hex", hex.as_str()).map_err(lua_error)?;
+            locked_table.set(i + 1, entry).map_err(lua_error)?;
+        }
+
+        let colors: Vec<String> = hook.call((locked_table, count)).map_err(lua_error)?;
+
+        colors
+            .iter()
+            .map(|color| normalize_color(color))
+            .collect::<io::Result<_>>()
+            .map_err(|e| invalid(&format!("generate returned {e}")))
+    }
+
+    /// Run the `export` hook on a palette; `roles` runs parallel to `colors`
+    pub fn export(
+        &self,
+        name: &str,
+        colors: &[String],
+        labels: &[String],
+        roles: &[Option<Role>],
+    ) -> io::Result<String> {
+        let hook = self
+            .hook("export")
+            .ok_or_else(|| invalid("plugin has no `export` hook"))?;
+
+        let palette = self.lua.create_table().map_err(lua_error)?;
+        palette.set("name", name).map_err(lua_error)?;
+        palette.set("colors", colors.to_vec()).map_err(lua_error)?;
+        palette.set("labels", labels.to_vec()).map_err(lua_error)?;
+
+        let roles_table = self.lua.create_table().map_err(lua_error)?;
+        for (color, role) in colors.iter().zip(roles) {
+            if let Some(role) = role {
+                roles_table
+                    .set(role.key(), color.as_str())
+                    .map_err(lua_error)?;
+            }
+        }
+        palette.set("roles", roles_table).map_err(lua_error)?;
+
+        hook.call(palette).map_err(lua_error)
+    }
+
+    fn hook(&self, name: &str) -> Option<Function<'_>> {
+        let table: Table = self.lua.named_registry_value(PLUGIN_KEY).ok()?;
+        table.get(name).ok()
+    }
+}
+
+/// Stand-ins for builds without Lua: nothing loads, so the hooks are never reached
+#[cfg(not(feature = "plugins"))]
+impl Plugin {
+    pub fn load(_path: &Path) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "built without plugin support",
+        ))
+    }
+
+    pub fn generates(&self) -> bool {
+        false
+    }
+
+    pub fn exports(&self) -> bool {
+        false
+    }
+
+    pub fn generate(&self, _locked: &[(usize, String)], _count: usize) -> io::Result<Vec<String>> {
+        Err(invalid("plugin has no `generate` hook"))
+    }
+
+    pub fn export(
+        &self,
+        _name: &str,
+        _colors: &[String],
+        _labels: &[String],
+        _roles: &[Option<Role>],
+    ) -> io::Result<String> {
+        Err(invalid("plugin has no `export` hook"))
+    }
+}
+
+/// Every plugin in [`plugins_dir`] sorted by name, plus the scripts that failed to load
+pub fn load_plugins() -> (Vec<Plugin>, Vec<(PathBuf, io::Error)>) {
+    let mut plugins = Vec::new();
+    let mut failed = Vec::new();
+
+    let Ok(entries) = fs::read_dir(plugins_dir()) else {
+        return (plugins, failed);
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == PLUGIN_EXTENSION))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match Plugin::load(&path) {
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => failed.push((path, e)),
+        }
+    }
+
+    plugins.sort_by_key(|p| p.name.to_lowercase());
+    (plugins, failed)
+}
+
+/// Registry slot holding the table a plugin script returned
+#[cfg(feature = "plugins")]
+const PLUGIN_KEY: &str = "terminal_palette_plugin";
+
+#[cfg(feature = "plugins")]
+fn lua_error(e: mlua::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,151 @@
+//! `.tpal` project files, and their line-oriented [`.palette`](crate::palette_file) form.
+
+use std::{
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::Freeze;
+use crate::generators::ColorTheories;
+use crate::gradient::Gradient;
+use crate::library::is_valid_hex;
+use crate::palette_file;
+use crate::roles::Role;
+
+pub const PROJECT_EXTENSION: &str = "tpal";
+pub const PROJECT_VERSION: u32 = 1;
+
+/// A `.tpal` project: everything needed to reopen a working session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub version: u32,
+    pub name: String,
+    pub theory: ColorTheories,
+    pub selected: usize,
+    pub blocks: Vec<ProjectBlock>,
+    pub metadata: ProjectMetadata,
+    /// Earlier palettes of this project as hex lists, oldest first
+    #[serde(default)]
+    pub history: Vec<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gradients: Vec<Gradient>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectBlock {
+    pub hex: String,
+    /// Exact HSV (degrees, 0-1, 0-1) so reopening doesn't drift through hex rounding
+    pub hsv: [f32; 3],
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen: Option<Freeze>,
+    #[serde(default)]
+    pub label: String,
+    /// Free-text usage note, written as a comment by the code exports
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    /// Share of its row in the main view relative to the other blocks; `None` for the
+    /// usual 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<Role>,
+    /// OKLCH (lightness, chroma, hue in degrees) asked for when it lies outside sRGB;
+    /// `hex` and `hsv` hold it clipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oklch: Option<[f32; 3]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectMetadata {
+    /// Unix timestamps in seconds
+    pub created: u64,
+    pub modified: u64,
+    pub app_version: String,
+}
+
+impl ProjectMetadata {
+    pub fn new() -> Self {
+        let now = unix_now();
+        Self {
+            created: now,
+            modified: now,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+impl Default for ProjectMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn is_project_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == PROJECT_EXTENSION || ext == palette_file::EXTENSION)
+}
+
+fn is_palette_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == palette_file::EXTENSION)
+}
+
+pub fn load_project(path: &Path) -> io::Result<Project> {
+    let json = fs::read_to_string(path)?;
+    if is_palette_file(path) {
+        return palette_file::parse(&json).map_err(|errors| {
+            let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            io::Error::new(io::ErrorKind::InvalidData, errors.join("; "))
+        });
+    }
+    let project: Project =
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    if project.version > PROJECT_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "project version {} is newer than supported ({PROJECT_VERSION})",
+                project.version
+            ),
+        ));
+    }
+
+    if let Some(bad) = project.blocks.iter().find(|b| !is_valid_hex(&b.hex)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid color {:?}", bad.hex),
+        ));
+    }
+
+    Ok(project)
+}
+
+/// Write `project` to `path`, stamping its modification time. `.palette` paths get the
+/// line-oriented form, which leaves the metadata out.
+pub fn save_project(project: &mut Project, path: &Path) -> io::Result<()> {
+    project.metadata.modified = unix_now();
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    if is_palette_file(path) {
+        return fs::write(path, palette_file::write(project));
+    }
+    let json = serde_json::to_string_pretty(project).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
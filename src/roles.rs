@@ -0,0 +1,285 @@
+//! Semantic roles of palette colors.
+//!
+//! A role says what a color is for in an interface, so exporters and previews can put each
+//! color in the right slot instead of guessing from its position or lightness. Every role is
+//! held by at most one block of a palette.
+//!
+//! [`validate`] checks the assigned roles against each other: readable text, status colors
+//! in the hues people expect, and a surface that sits on the right side of the background.
+//! [`fill_missing`] colors the roles nobody assigned so they pass those checks.
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::color::ColorBlock;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Background,
+    Surface,
+    Text,
+    Primary,
+    Secondary,
+    Error,
+    Warning,
+    Success,
+}
+
+impl Role {
+    /// Stable lowercase name, as used in saved files and handed to export plugins
+    pub fn key(self) -> &'static str {
+        match self {
+            Role::Background => "background",
+            Role::Surface => "surface",
+            Role::Text => "text",
+            Role::Primary => "primary",
+            Role::Secondary => "secondary",
+            Role::Error => "error",
+            Role::Warning => "warning",
+            Role::Success => "success",
+        }
+    }
+}
+
+/// Give `role` to the block at `index`, taking it from whichever block held it before.
+/// `None` clears the block's role.
+pub fn assign(color_blocks: &mut [ColorBlock], index: usize, role: Option<Role>) {
+    if let Some(role) = role {
+        for block in color_blocks.iter_mut().filter(|b| b.role == Some(role)) {
+            block.role = None;
+        }
+    }
+    if let Some(block) = color_blocks.get_mut(index) {
+        block.role = role;
+    }
+}
+
+/// The block holding `role`, if any
+pub fn find(color_blocks: &[ColorBlock], role: Role) -> Option<&ColorBlock> {
+    color_blocks.iter().find(|block| block.role == Some(role))
+}
+
+/// WCAG AA minimum for body text
+pub const MIN_TEXT_CONTRAST: f32 = 4.5;
+/// Below this saturation a status color reads as gray whatever its hue
+pub const MIN_STATUS_SATURATION: f32 = 0.25;
+
+/// A role-specific rule the palette breaks
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// Text on the background or surface is below [`MIN_TEXT_CONTRAST`]
+    LowContrast { on: Role, ratio: f32 },
+    /// A status color's hue (degrees) is outside [`expected_hues`], or it's too gray to tell
+    UnexpectedHue { role: Role, hue: f32 },
+    /// The surface should be lighter than a dark background and darker than a light one
+    SurfaceInverted { dark_background: bool },
+}
+
+impl Violation {
+    /// The role to fix, to jump to the offending block
+    pub fn role(&self) -> Role {
+        match self {
+            Violation::LowContrast { .. } => Role::Text,
+            Violation::UnexpectedHue { role, .. } => *role,
+            Violation::SurfaceInverted { .. } => Role::Surface,
+        }
+    }
+}
+
+/// Hue range (from, to) in degrees readers expect for a status role; `from > to` wraps
+/// through red at 0°
+pub fn expected_hues(role: Role) -> Option<(f32, f32)> {
+    match role {
+        Role::Error => Some((340.0, 20.0)),
+        Role::Warning => Some((25.0, 65.0)),
+        Role::Success => Some((75.0, 170.0)),
+        _ => None,
+    }
+}
+
+/// Every rule broken by the roles assigned in `color_blocks`; rules involving a role no
+/// block holds are skipped
+pub fn validate(color_blocks: &[ColorBlock]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let background = find(color_blocks, Role::Background);
+    let surface = find(color_blocks, Role::Surface);
+
+    if let Some(text) = find(color_blocks, Role::Text) {
+        for (on, block) in [(Role::Background, background), (Role::Surface, surface)] {
+            if let Some(block) = block {
+                let ratio = text.contrast_ratio(block);
+                if ratio < MIN_TEXT_CONTRAST {
+                    violations.push(Violation::LowContrast { on, ratio });
+                }
+            }
+        }
+    }
+
+    if let (Some(background), Some(surface)) = (background, surface) {
+        let dark_background = background.get_relative_luminance() < 0.5;
+        let surface_lighter =
+            surface.get_relative_luminance() > background.get_relative_luminance();
+        if surface_lighter != dark_background {
+            violations.push(Violation::SurfaceInverted { dark_background });
+        }
+    }
+
+    for role in [Role::Error, Role::Warning, Role::Success] {
+        let (Some(block), Some((from, to))) = (find(color_blocks, role), expected_hues(role))
+        else {
+            continue;
+        };
+        let hue = block.hsv.hue.into_positive_degrees();
+        let in_range = if from <= to {
+            (from..=to).contains(&hue)
+        } else {
+            hue >= from || hue <= to
+        };
+        if !in_range || block.hsv.saturation < MIN_STATUS_SATURATION {
+            violations.push(Violation::UnexpectedHue { role, hue });
+        }
+    }
+
+    violations
+}
+
+/// WCAG minimum for non-text UI (buttons, icons, status markers) against the background
+pub const MIN_UI_CONTRAST: f32 = 3.0;
+
+/// Order roles are handed out in when there are fewer free blocks than missing roles
+const FILL_ORDER: [Role; 8] = [
+    Role::Background,
+    Role::Text,
+    Role::Primary,
+    Role::Surface,
+    Role::Secondary,
+    Role::Error,
+    Role::Warning,
+    Role::Success,
+];
+
+/// Give every role no block holds yet to a free block (unlocked, without a role) and color
+/// it to fit the assigned ones: the background and surface follow the primary's hue, text
+/// meets [`MIN_TEXT_CONTRAST`] on both, and the accents share the primary's saturation and
+/// value at a split-complementary or status hue, pushed to [`MIN_UI_CONTRAST`].
+///
+/// Returns the roles filled and the roles left out for lack of free blocks.
+pub fn fill_missing(
+    color_blocks: &mut [ColorBlock],
+    rng: &mut dyn RngCore,
+) -> (Vec<Role>, Vec<Role>) {
+    let mut free = color_blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block)| !block.locked && block.role.is_none())
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    let (mut filled, mut left_out) = (Vec::new(), Vec::new());
+    for role in FILL_ORDER {
+        if find(color_blocks, role).is_some() {
+            continue;
+        }
+        match free.next() {
+            Some(idx) => {
+                color_blocks[idx].role = Some(role);
+                filled.push(role);
+            }
+            None => left_out.push(role),
+        }
+    }
+
+    // Anchor everything on the primary, or on whatever the user did assign
+    let anchor = [
+        Role::Primary,
+        Role::Secondary,
+        Role::Background,
+        Role::Surface,
+    ]
+    .into_iter()
+    .filter(|role| !filled.contains(role))
+    .find_map(|role| find(color_blocks, role))
+    .map(|block| block.hsv.hue.into_positive_degrees())
+    .unwrap_or_else(|| rng.random_range(0.0..360.0));
+    // Keep a light theme light: only a given text or background decides
+    let dark = match (
+        filled.contains(&Role::Background),
+        find(color_blocks, Role::Background),
+        find(color_blocks, Role::Text),
+    ) {
+        (false, Some(background), _) => background.get_relative_luminance() < 0.5,
+        (true, _, Some(text)) if !filled.contains(&Role::Text) => {
+            text.get_relative_luminance() >= 0.5
+        }
+        _ => true,
+    };
+
+    // Each color depends only on the ones before it
+    let color_order = [
+        Role::Background,
+        Role::Surface,
+        Role::Primary,
+        Role::Secondary,
+        Role::Text,
+        Role::Error,
+        Role::Warning,
+        Role::Success,
+    ];
+    for role in color_order.into_iter().filter(|role| filled.contains(role)) {
+        let (hue, sat, val) = match role {
+            Role::Background => (anchor, 0.25, if dark { 0.12 } else { 0.97 }),
+            Role::Surface => {
+                let (hue, sat, val) = hsv_of(color_blocks, Role::Background);
+                (hue, sat, if dark { val + 0.08 } else { val - 0.06 })
+            }
+            Role::Text => (anchor, 0.08, if dark { 0.93 } else { 0.15 }),
+            Role::Primary => (anchor, 0.6, if dark { 0.85 } else { 0.6 }),
+            Role::Secondary => {
+                let (hue, sat, val) = hsv_of(color_blocks, Role::Primary);
+                ((hue + 150.0) % 360.0, sat, val)
+            }
+            Role::Error | Role::Warning | Role::Success => {
+                let (_, sat, val) = hsv_of(color_blocks, Role::Primary);
+                let hue = match role {
+                    Role::Error => 5.0,
+                    Role::Warning => 42.0,
+                    _ => 130.0,
+                };
+                (hue, sat.max(0.55), val.max(0.6))
+            }
+        };
+
+        let idx = color_blocks
+            .iter()
+            .position(|block| block.role == Some(role))
+            .expect("role was just assigned");
+        color_blocks[idx].change_color(hue, sat.clamp(0.0, 1.0), val.clamp(0.0, 1.0));
+
+        let min = match role {
+            Role::Background | Role::Surface => continue,
+            Role::Text => MIN_TEXT_CONTRAST,
+            _ => MIN_UI_CONTRAST,
+        };
+        for against in [Role::Background, Role::Surface] {
+            if let Some(against) = find(color_blocks, against).cloned() {
+                color_blocks[idx].push_contrast(&against, min, dark);
+            }
+        }
+    }
+
+    (filled, left_out)
+}
+
+/// HSV of the block holding `role`, or a mid gray if none does
+fn hsv_of(color_blocks: &[ColorBlock], role: Role) -> (f32, f32, f32) {
+    find(color_blocks, role).map_or((0.0, 0.0, 0.5), |block| {
+        (
+            block.hsv.hue.into_positive_degrees(),
+            block.hsv.saturation,
+            block.hsv.value,
+        )
+    })
+}
@@ -0,0 +1,89 @@
+//! Interaction-state variants of a UI color: hover, active, focus and disabled.
+//!
+//! Hover, active and focus move the base color's OKLCH lightness by their offset, keeping
+//! its hue and as much of its chroma as fits in sRGB. They move away from the nearer end, so a dark button lightens and a
+//! light one darkens. Disabled is the base at partial opacity over the background, which is
+//! how a faded control actually looks.
+//!
+//! ```
+//! use terminal_palette::states::{self, State, StateOffsets};
+//!
+//! let variants = states::variants("#3D405B", "#FFFFFF", &StateOffsets::default());
+//! assert_eq!(variants.len(), 4);
+//! assert_eq!(variants[0].0, State::Hover);
+//! ```
+
+use palette::{FromColor, Oklch, Srgb};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::{blend, convert, gamut};
+
+/// Above this OKLCH lightness a base color counts as light and its variants darken
+const LIGHT: f32 = 0.6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum State {
+    Hover,
+    Active,
+    Focus,
+    Disabled,
+}
+
+impl State {
+    /// Identifier used in message ids and labels, e.g. `hover`
+    pub fn key(self) -> &'static str {
+        match self {
+            State::Hover => "hover",
+            State::Active => "active",
+            State::Focus => "focus",
+            State::Disabled => "disabled",
+        }
+    }
+}
+
+/// How far each state moves from the base; every field is optional in the config file
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateOffsets {
+    /// OKLCH lightness (0-1) moved for hover
+    pub hover: f32,
+    /// OKLCH lightness moved for active (pressed)
+    pub active: f32,
+    /// OKLCH lightness moved for focus
+    pub focus: f32,
+    /// Opacity of the disabled color over the background
+    pub disabled_alpha: f32,
+}
+
+impl Default for StateOffsets {
+    fn default() -> Self {
+        Self {
+            hover: 0.06,
+            active: 0.12,
+            focus: 0.18,
+            disabled_alpha: 0.38,
+        }
+    }
+}
+
+/// Every [`State`] of `base` (hex) in order, `background` being what disabled fades into
+pub fn variants(base: &str, background: &str, offsets: &StateOffsets) -> Vec<(State, String)> {
+    let (r, g, b) = convert::parse_hex(base).unwrap_or((0, 0, 0));
+    let oklch = Oklch::from_color(Srgb::new(r, g, b).into_format::<f32>());
+    let direction = if oklch.l > LIGHT { -1.0 } else { 1.0 };
+    let shifted = |offset: f32| {
+        let l = (oklch.l + direction * offset).clamp(0.0, 1.0);
+        blend::to_hex(Srgb::from_color(gamut::fit(Oklch { l, ..oklch })))
+    };
+
+    vec![
+        (State::Hover, shifted(offsets.hover)),
+        (State::Active, shifted(offsets.active)),
+        (State::Focus, shifted(offsets.focus)),
+        (
+            State::Disabled,
+            blend::composite(base, offsets.disabled_alpha, background),
+        ),
+    ]
+}
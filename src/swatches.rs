@@ -0,0 +1,118 @@
+//! Swatch books: named reference colors, such as a brand's or a print vendor's, to find the
+//! closest match of a palette color in.
+//!
+//! A book is a TOML file with a `name` and one `[[swatch]]` table per color, holding its
+//! `name` and either `hex = "#RRGGBB"` or `lab = [L, a, b]` (CIELAB, D65 white point).
+//! Matches are ranked by CIEDE2000 color difference, so "nearest" is what an eye would pick.
+//!
+//! ```
+//! use terminal_palette::color::ColorBlock;
+//! use terminal_palette::swatches;
+//!
+//! let book = swatches::parse(r##"
+//!     name = "Brand"
+//!
+//!     [[swatch]]
+//!     name = "Signal Red"
+//!     hex = "#D7263D"
+//!
+//!     [[swatch]]
+//!     name = "Deep Sea"
+//!     lab = [28.0, 5.0, -35.0]
+//! "##).unwrap();
+//!
+//! let red = ColorBlock::new(1, 352.0, 0.8, 0.8);
+//! let (swatch, _) = book.nearest(&red).unwrap();
+//! assert_eq!(swatch.name, "Signal Red");
+//! ```
+
+use std::{fs, io, path::Path};
+
+use palette::{FromColor, Lab, Srgb, color_difference::Ciede2000};
+use serde::Deserialize;
+
+use crate::color::ColorBlock;
+use crate::convert;
+
+#[derive(Clone, Debug)]
+pub struct Swatch {
+    pub name: String,
+    pub lab: Lab,
+}
+
+#[derive(Clone, Debug)]
+pub struct SwatchBook {
+    pub name: String,
+    pub swatches: Vec<Swatch>,
+}
+
+impl SwatchBook {
+    /// The swatch closest to `block` and its CIEDE2000 difference; `None` for an empty book
+    pub fn nearest(&self, block: &ColorBlock) -> Option<(&Swatch, f32)> {
+        let (r, g, b) = block.get_rgb_values();
+        let lab = Lab::from_color(Srgb::new(r, g, b).into_format::<f32>());
+
+        self.swatches
+            .iter()
+            .map(|swatch| (swatch, swatch.lab.difference(lab)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+#[derive(Deserialize)]
+struct BookFile {
+    name: String,
+    #[serde(default, rename = "swatch")]
+    swatches: Vec<SwatchEntry>,
+}
+
+#[derive(Deserialize)]
+struct SwatchEntry {
+    name: String,
+    hex: Option<String>,
+    lab: Option<[f32; 3]>,
+}
+
+/// Read a swatch book from TOML text, see the module docs for the format
+pub fn parse(text: &str) -> io::Result<SwatchBook> {
+    let file: BookFile =
+        toml::from_str(text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let swatches = file
+        .swatches
+        .into_iter()
+        .map(|entry| {
+            let lab = match (&entry.hex, entry.lab) {
+                (Some(hex), None) => {
+                    let (r, g, b) = convert::parse_hex(hex).map_err(|e| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("swatch {:?}: {e}", entry.name),
+                        )
+                    })?;
+                    Lab::from_color(Srgb::new(r, g, b).into_format::<f32>())
+                }
+                (None, Some([l, a, b])) => Lab::new(l, a, b),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("swatch {:?} needs either hex or lab", entry.name),
+                    ));
+                }
+            };
+            Ok(Swatch {
+                name: entry.name,
+                lab,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+
+    Ok(SwatchBook {
+        name: file.name,
+        swatches,
+    })
+}
+
+pub fn load(path: &Path) -> io::Result<SwatchBook> {
+    parse(&fs::read_to_string(path)?)
+}
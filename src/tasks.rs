@@ -0,0 +1,120 @@
+//! Background jobs (exports, fetches, image work) that must not block the UI loop.
+//!
+//! Every job runs on its own thread and reports back over a channel; the app drains the
+//! channel between frames and mirrors progress in the status bar.
+
+use std::{
+    io,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use terminal_palette::extract::PixelCounts;
+
+/// How often the UI loop wakes up to collect task messages while a job is running
+pub const TASK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskMessage {
+    Progress {
+        label: String,
+        done: usize,
+        total: usize,
+    },
+    /// Final outcome: what the job made on success, the error text otherwise
+    Finished {
+        label: String,
+        result: Result<TaskOutput, String>,
+    },
+}
+
+/// What a job hands back to the app
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskOutput {
+    /// A status line saying what was done
+    Summary(String),
+    /// The colors of a picture for the blocks, see [`terminal_palette::extract`]
+    Picture(PixelCounts),
+}
+
+impl From<String> for TaskOutput {
+    fn from(summary: String) -> Self {
+        TaskOutput::Summary(summary)
+    }
+}
+
+/// Handed to a job so it can report how far along it is
+pub struct Progress {
+    label: String,
+    sender: Sender<TaskMessage>,
+}
+
+impl Progress {
+    pub fn report(&self, done: usize, total: usize) {
+        // The receiver only disappears when the app is shutting down
+        let _ = self.sender.send(TaskMessage::Progress {
+            label: self.label.clone(),
+            done,
+            total,
+        });
+    }
+}
+
+pub struct TaskRunner {
+    sender: Sender<TaskMessage>,
+    receiver: Receiver<TaskMessage>,
+    running: usize,
+}
+
+impl TaskRunner {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            running: 0,
+        }
+    }
+
+    /// Run `job` on a worker thread; its `Ok` value becomes the completion message
+    pub fn spawn<F>(&mut self, label: &str, job: F)
+    where
+        F: FnOnce(&Progress) -> io::Result<TaskOutput> + Send + 'static,
+    {
+        let progress = Progress {
+            label: label.to_string(),
+            sender: self.sender.clone(),
+        };
+        self.running += 1;
+
+        thread::spawn(move || {
+            let result = job(&progress).map_err(|e| e.to_string());
+            let _ = progress.sender.send(TaskMessage::Finished {
+                label: progress.label.clone(),
+                result,
+            });
+        });
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.running > 0
+    }
+
+    /// Every message received since the last call, without blocking
+    pub fn drain(&mut self) -> Vec<TaskMessage> {
+        let messages: Vec<TaskMessage> = self.receiver.try_iter().collect();
+        let finished = messages
+            .iter()
+            .filter(|m| matches!(m, TaskMessage::Finished { .. }))
+            .count();
+        self.running = self.running.saturating_sub(finished);
+        messages
+    }
+}
+
+impl Default for TaskRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
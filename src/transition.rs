@@ -0,0 +1,58 @@
+//! Short fades from the previous palette to a freshly generated one.
+//!
+//! Colors are interpolated in OKLab so the midpoint of a fade looks like a midpoint, instead
+//! of detouring through unrelated hues as HSV interpolation would.
+
+use std::time::{Duration, Instant};
+
+use palette::{FromColor, Hsv, Mix, Oklab};
+
+use terminal_palette::color::ColorBlock;
+
+pub const TRANSITION_DURATION: Duration = Duration::from_millis(200);
+
+/// How often the screen is redrawn while a transition runs
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+#[derive(Debug, Clone)]
+pub struct Transition {
+    from: Vec<Hsv>,
+    started: Instant,
+}
+
+impl Transition {
+    /// Start fading away from the colors `from` currently shows
+    pub fn new(from: &[ColorBlock]) -> Self {
+        Self {
+            from: from.iter().map(|block| block.hsv).collect(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.started.elapsed() >= TRANSITION_DURATION
+    }
+
+    /// `to` as it should look right now. Blocks added since the start show their final color.
+    pub fn blend(&self, to: &[ColorBlock]) -> Vec<ColorBlock> {
+        let progress = self.started.elapsed().as_secs_f32() / TRANSITION_DURATION.as_secs_f32();
+        let t = ease(progress.clamp(0.0, 1.0));
+
+        to.iter()
+            .enumerate()
+            .map(|(pos, block)| {
+                let mut block = block.clone();
+                if let Some(&from) = self.from.get(pos) {
+                    let mixed = Oklab::from_color(from).mix(Oklab::from_color(block.hsv), t);
+                    block.hsv = Hsv::from_color(mixed);
+                }
+                block
+            })
+            .collect()
+    }
+}
+
+/// Smoothstep: starts and ends gently
+fn ease(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
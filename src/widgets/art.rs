@@ -0,0 +1,109 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    widgets::{Block, Borders, Widget},
+};
+
+use terminal_palette::color::ColorBlock;
+
+use crate::t;
+
+/// A lakeside landscape at sunset, one character per pixel. Digits are tones from `0`
+/// (darkest) to `9` (lightest), drawn two pixels per cell with half blocks.
+const ART: &[&str] = &[
+    "1111111111111111111111111111111111111111111111111111111111111111",
+    "1111111111111111111111111111111111111111111111111111111111111111",
+    "2222222222222222222222222222222222222222222222222222222222222222",
+    "2222222222222222222222222222222222222222288888882222222222222222",
+    "2222222222222222222222222222222222222222889999988222222222222222",
+    "3333333333333333333333333333333333333338899999998833333333333333",
+    "3333333333333333333333333333333333333388999999999883333333333333",
+    "3333333333333333333333333333333333333389999999999983333333333333",
+    "4444444444444444444444444444444444444489999999999984444444444444",
+    "4444444444444444444444444444444444444489999999999984444444444444",
+    "4444444444444444444444444444444444444489999999999984444444444444",
+    "5555555555555555555555555555544444444449999999999985555555555555",
+    "5555555111555511155555555555444444444444999999999885111555555555",
+    "5555555111555511155555555554444444444444499999998855111555555555",
+    "4666666111666611166666666644444444444444449999988666111666666666",
+    "4666661111166111116622224444444444444444444888886661111166666666",
+    "4466661111166111112222222224444444444444444466666661111166666666",
+    "4447771111174111112222222222244444444444444444444441111144777777",
+    "4444711111111111111222222222222244444444444444444411111114444744",
+    "4444411111111111111222222222222222444444444444444411111114444444",
+    "4444411111111111111222222222222222222222222222224411111114444444",
+    "4444111111111111111122222222222222222222222222222111111111444444",
+    "2244442202222220222222222222222222222222222222222222202444444422",
+    "2222222202222220222222222222222222222222222222222222202222222222",
+    "6666656666666666566666666665666666666656666666666566666666665666",
+    "6656666666666566666666665666666666656666666666566666666665666666",
+    "6666666666566666666665666666666656666666666566666666665666666666",
+    "3333333533333333335333333333353333333333533333333335333333333353",
+    "3333533333333335333333333353333333333533333333335333333333353333",
+    "3533333333335333333333353333333333533333333335333333333353333333",
+    "3333333335333333333353333333333533333333335333333333353333333333",
+    "3333335333333333353333333333533333333335333333333353333333333533",
+];
+/// The lightest tone in [`ART`]
+const MAX_TONE: usize = 9;
+
+/// The bundled [`ART`] recolored by the palette, for an impression of the mood it sets
+/// beyond flat swatches. Tones map onto the blocks ordered by lightness, so the scene keeps
+/// its shading whatever order the blocks are in.
+pub struct ArtPreview {
+    /// One color per tone, darkest first
+    tones: Vec<Color>,
+}
+
+impl ArtPreview {
+    pub fn new(color_blocks: &[ColorBlock]) -> Self {
+        let mut by_lightness: Vec<_> = color_blocks.iter().collect();
+        by_lightness.sort_by(|a, b| {
+            a.get_relative_luminance()
+                .total_cmp(&b.get_relative_luminance())
+        });
+
+        let tones = (0..=MAX_TONE)
+            .map(|tone| {
+                let last = by_lightness.len().saturating_sub(1);
+                match by_lightness.get((tone * last + MAX_TONE / 2) / MAX_TONE) {
+                    Some(block) => {
+                        let (r, g, b) = block.get_rgb_values();
+                        Color::Rgb(r, g, b)
+                    }
+                    None => Color::Reset,
+                }
+            })
+            .collect();
+        Self { tones }
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Color {
+        let tone = ART[y].as_bytes()[x].saturating_sub(b'0') as usize;
+        self.tones[tone.min(MAX_TONE)]
+    }
+}
+
+impl Widget for &ArtPreview {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(format!(" {} ", t!("title-art-preview")))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let width = ART[0].len().min(inner.width as usize);
+        let height = (ART.len() / 2).min(inner.height as usize);
+        let left = inner.x + (inner.width - width as u16) / 2;
+        let top = inner.y + (inner.height - height as u16) / 2;
+        for row in 0..height {
+            for x in 0..width {
+                buf[(left + x as u16, top + row as u16)]
+                    .set_symbol("▀")
+                    .set_fg(self.pixel(x, row * 2))
+                    .set_bg(self.pixel(x, row * 2 + 1));
+            }
+        }
+    }
+}
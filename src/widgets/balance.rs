@@ -0,0 +1,83 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Paragraph, Widget},
+};
+
+use terminal_palette::balance::{Balance, HUE_BINS, Lean};
+use terminal_palette::color::ColorBlock;
+
+use crate::t;
+
+/// Degrees of the wheel each histogram bar covers
+const BIN_DEGREES: usize = 360 / HUE_BINS;
+
+/// The warm, cool and neutral shares of a palette over a histogram of its hues, each bar
+/// colored by the middle of its slice of the wheel
+pub struct BalancePane<'a> {
+    balance: &'a Balance,
+}
+
+impl<'a> BalancePane<'a> {
+    pub fn new(balance: &'a Balance) -> Self {
+        Self { balance }
+    }
+}
+
+impl Widget for BalancePane<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [summary_area, chart_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+
+        let lean = match self.balance.lean() {
+            Lean::Warm => t!("balance-leans-warm"),
+            Lean::Cool => t!("balance-leans-cool"),
+            Lean::Even => t!("balance-even"),
+            Lean::NoHues => t!("balance-no-hues"),
+        };
+        Paragraph::new(vec![
+            Line::from(format!(
+                " {}",
+                t!(
+                    "balance-summary",
+                    warm = self.balance.warm.round() as u32,
+                    cool = self.balance.cool.round() as u32,
+                    neutral = self.balance.neutral.round() as u32
+                )
+            ))
+            .bold(),
+            Line::from(format!(" {lean}")),
+        ])
+        .render(summary_area, buf);
+
+        let bar_gap = 1;
+        let bar_width = (chart_area
+            .width
+            .saturating_sub(bar_gap * (HUE_BINS as u16 - 1))
+            / HUE_BINS as u16)
+            .clamp(1, 6);
+        let bars: Vec<Bar> = self
+            .balance
+            .histogram
+            .iter()
+            .enumerate()
+            .map(|(bin, &count)| {
+                let start = bin * BIN_DEGREES;
+                let middle = (start + BIN_DEGREES / 2) as f32;
+                let (r, g, b) = ColorBlock::new(0, middle, 0.8, 0.9).get_rgb_values();
+                Bar::default()
+                    .value(u64::from(count))
+                    .text_value(String::new())
+                    .label(Line::from(format!("{start}°")))
+                    .style(Style::new().fg(Color::Rgb(r, g, b)))
+            })
+            .collect();
+        BarChart::default()
+            .bar_width(bar_width)
+            .bar_gap(bar_gap)
+            .data(BarGroup::default().bars(&bars))
+            .render(chart_area, buf);
+    }
+}
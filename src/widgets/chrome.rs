@@ -0,0 +1,76 @@
+//! The app's own colors taken from the roles of the palette being edited.
+//!
+//! Widgets draw their chrome in the terminal's default colors; [`Chrome::apply`] then
+//! recolors whatever kept them, so every page and popup follows the roles without knowing
+//! about them. Colors a widget picked on purpose, such as the swatches, stay as they are.
+
+use ratatui::{buffer::Buffer, style::Color};
+
+use terminal_palette::color::ColorBlock;
+use terminal_palette::roles::{self, Role};
+
+use crate::widgets::status_bar;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Chrome {
+    /// Behind pages and popups
+    pub background: Option<Color>,
+    /// Behind the status bar
+    pub surface: Option<Color>,
+    pub text: Option<Color>,
+    /// Borders and the highlighted row of lists
+    pub accent: Option<Color>,
+}
+
+impl Chrome {
+    /// Colors of the blocks holding the background, surface, text and primary roles;
+    /// roles no block holds leave their part of the chrome alone
+    pub fn from_roles(color_blocks: &[ColorBlock]) -> Self {
+        let color = |role| {
+            roles::find(color_blocks, role).map(|block| {
+                let (r, g, b) = block.get_rgb_values();
+                Color::Rgb(r, g, b)
+            })
+        };
+        Self {
+            background: color(Role::Background),
+            surface: color(Role::Surface),
+            text: color(Role::Text),
+            accent: color(Role::Primary),
+        }
+    }
+
+    pub fn apply(&self, buf: &mut Buffer) {
+        for cell in buf.content.iter_mut() {
+            let background = match cell.bg {
+                Color::Reset => self.background,
+                status_bar::BACKGROUND => self.surface,
+                _ => None,
+            };
+            if let Some(color) = background {
+                cell.bg = color;
+            }
+
+            if cell.fg != Color::Reset {
+                continue;
+            }
+            let foreground = if is_chrome_symbol(cell.symbol()) {
+                self.accent
+            } else {
+                self.text
+            };
+            if let Some(color) = foreground {
+                cell.fg = color;
+            }
+        }
+    }
+}
+
+/// Box-drawing borders and the `>` marking the highlighted list row
+fn is_chrome_symbol(symbol: &str) -> bool {
+    symbol == ">"
+        || symbol
+            .chars()
+            .next()
+            .is_some_and(|c| ('\u{2500}'..='\u{257F}').contains(&c))
+}
@@ -0,0 +1,249 @@
+use palette::{FromColor, Mix, Oklab, Srgb};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use terminal_palette::ansi;
+use terminal_palette::color::ColorBlock;
+use terminal_palette::roles::{self, Role};
+
+use crate::t;
+
+/// What a piece of the sample snippet is, as far as a highlighter cares
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Plain,
+    Keyword,
+    Function,
+    Type,
+    Str,
+    Number,
+    Comment,
+    /// Things meant to stand out as failures: `Err`, `panic!`
+    Failure,
+}
+
+use Token::*;
+
+const SNIPPET: &[&[(Token, &str)]] = &[
+    &[(Comment, "// Parse a port, falling back to the default")],
+    &[
+        (Keyword, "fn "),
+        (Function, "port"),
+        (Plain, "(value: "),
+        (Keyword, "&"),
+        (Type, "str"),
+        (Plain, ") -> "),
+        (Type, "Result"),
+        (Plain, "<"),
+        (Type, "u16"),
+        (Plain, ", "),
+        (Type, "String"),
+        (Plain, "> {"),
+    ],
+    &[
+        (Plain, "    "),
+        (Keyword, "if "),
+        (Plain, "value."),
+        (Function, "is_empty"),
+        (Plain, "() {"),
+    ],
+    &[
+        (Plain, "        "),
+        (Keyword, "return "),
+        (Type, "Ok"),
+        (Plain, "("),
+        (Number, "8080"),
+        (Plain, ");"),
+    ],
+    &[(Plain, "    }")],
+    &[
+        (Plain, "    value."),
+        (Function, "parse"),
+        (Plain, "()."),
+        (Function, "map_err"),
+        (Plain, "(|e| "),
+        (Function, "format!"),
+        (Plain, "("),
+        (Str, "\"bad port {value:?}: {e}\""),
+        (Plain, "))"),
+    ],
+    &[(Plain, "}")],
+    &[],
+    &[
+        (Keyword, "match "),
+        (Function, "port"),
+        (Plain, "("),
+        (Str, "\"80a\""),
+        (Plain, ") {"),
+    ],
+    &[
+        (Plain, "    "),
+        (Type, "Ok"),
+        (Plain, "(port) => "),
+        (Function, "println!"),
+        (Plain, "("),
+        (Str, "\"listening on {port}\""),
+        (Plain, "),"),
+    ],
+    &[
+        (Plain, "    "),
+        (Failure, "Err"),
+        (Plain, "(e) => "),
+        (Failure, "panic!"),
+        (Plain, "("),
+        (Str, "\"{e}\""),
+        (Plain, "),"),
+    ],
+    &[(Plain, "}")],
+];
+
+/// A code snippet highlighted with the palette, to judge it as an editor theme before
+/// exporting it. Colors come from the blocks' roles, see [`CodePreview::new`].
+pub struct CodePreview {
+    background: Color,
+    gutter: Color,
+    text: Color,
+    keyword: Color,
+    function: Color,
+    constant: Color,
+    string: Color,
+    comment: Color,
+    failure: Color,
+}
+
+impl CodePreview {
+    /// Assigned roles win. Unassigned ones come from the slots of an ANSI scheme, or else
+    /// background and text are the darkest and lightest blocks and the accents take the
+    /// remaining blocks in order.
+    pub fn new(color_blocks: &[ColorBlock]) -> Self {
+        let role = |role: Role| {
+            roles::find(color_blocks, role)
+                .or_else(|| fallback(color_blocks, role))
+                .map_or(Color::Reset, rgb)
+        };
+
+        let background = role(Role::Background);
+        let text = role(Role::Text);
+        let comment = if ansi::is_scheme(color_blocks) {
+            rgb(&color_blocks[ansi::BRIGHT_OFFSET])
+        } else {
+            mix(text, background)
+        };
+
+        Self {
+            background,
+            gutter: role(Role::Surface),
+            text,
+            keyword: role(Role::Primary),
+            function: role(Role::Secondary),
+            constant: role(Role::Warning),
+            string: role(Role::Success),
+            comment,
+            failure: role(Role::Error),
+        }
+    }
+
+    fn color(&self, token: Token) -> Color {
+        match token {
+            Plain => self.text,
+            Keyword => self.keyword,
+            Function => self.function,
+            Type | Number => self.constant,
+            Str => self.string,
+            Comment => self.comment,
+            Failure => self.failure,
+        }
+    }
+}
+
+impl Widget for &CodePreview {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(format!(" {} ", t!("title-code-preview")))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let lines: Vec<Line> = SNIPPET
+            .iter()
+            .enumerate()
+            .map(|(i, tokens)| {
+                let mut spans = vec![Span::styled(
+                    format!(" {:>2} ", i + 1),
+                    Style::new().fg(self.color(Comment)).bg(self.gutter),
+                )];
+                spans.push(Span::raw(" "));
+                spans.extend(
+                    tokens
+                        .iter()
+                        .map(|(token, text)| Span::styled(*text, self.color(*token))),
+                );
+                Line::from(spans)
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .style(Style::new().bg(self.background))
+            .render(inner, buf);
+    }
+}
+
+/// The block standing in for `role` when no block holds it
+fn fallback(color_blocks: &[ColorBlock], role: Role) -> Option<&ColorBlock> {
+    if ansi::is_scheme(color_blocks) {
+        let slot = match role {
+            Role::Background => ansi::BACKGROUND,
+            Role::Text => ansi::FOREGROUND,
+            Role::Surface => 0,
+            Role::Error => 1,
+            Role::Success => 2,
+            Role::Warning => 3,
+            Role::Secondary => 4,
+            Role::Primary => 5,
+        };
+        return color_blocks.get(slot);
+    }
+
+    let by_luminance = |a: &&ColorBlock, b: &&ColorBlock| {
+        a.get_relative_luminance()
+            .total_cmp(&b.get_relative_luminance())
+    };
+    let darkest = color_blocks.iter().min_by(by_luminance)?;
+    let lightest = color_blocks.iter().max_by(by_luminance)?;
+    let accents: Vec<&ColorBlock> = color_blocks
+        .iter()
+        .filter(|block| !std::ptr::eq(*block, darkest) && !std::ptr::eq(*block, lightest))
+        .collect();
+    let accent = |n: usize| accents.get(n % accents.len().max(1)).copied();
+
+    match role {
+        Role::Background => Some(darkest),
+        Role::Text => Some(lightest),
+        Role::Surface => Some(darkest),
+        Role::Primary => accent(0),
+        Role::Secondary => accent(1),
+        Role::Success => accent(2),
+        Role::Warning => accent(3),
+        Role::Error => accent(4),
+    }
+}
+
+fn rgb(block: &ColorBlock) -> Color {
+    let (r, g, b) = block.get_rgb_values();
+    Color::Rgb(r, g, b)
+}
+
+/// Halfway between two colors in OKLab
+fn mix(a: Color, b: Color) -> Color {
+    let oklab = |color: Color| match color {
+        Color::Rgb(r, g, b) => Oklab::from_color(Srgb::new(r, g, b).into_format::<f32>()),
+        _ => Oklab::new(0.5, 0.0, 0.0),
+    };
+    let mixed: Srgb<u8> = Srgb::from_color(oklab(a).mix(oklab(b), 0.5)).into_format();
+    Color::Rgb(mixed.red, mixed.green, mixed.blue)
+}
@@ -1,154 +1,123 @@
-use rand::Rng;
-
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Stylize},
+    style::{Color, Style, Stylize},
     symbols::border,
     text::Line,
     widgets::{Block, Borders, Padding, Paragraph, Widget},
 };
 
-use palette::{FromColor, Hsv, RgbHue, Srgb};
-
-pub fn hex2rgb(hex: &str) -> (u8, u8, u8) {
-    let mut hex_owned = hex.to_string();
-    hex_owned.push_str("000000");
-    let padded = &hex_owned[..6];
+use terminal_palette::color::{ColorBlock, Freeze};
+use terminal_palette::roles::Role;
+use terminal_palette::swatches::SwatchBook;
 
-    let r = u8::from_str_radix(&padded[0..2], 16).unwrap();
-    let g = u8::from_str_radix(&padded[2..4], 16).unwrap();
-    let b = u8::from_str_radix(&padded[4..6], 16).unwrap();
+use crate::graphics::Raster;
+use crate::{i18n, margin, t};
 
-    (r, g, b)
+/// Translated name of `role`
+pub fn role_name(role: Role) -> String {
+    i18n::translate(&format!("role-{}", role.key()), None)
 }
 
-pub fn rgb2hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
-    let r = r as f32 / 255.0;
-    let g = g as f32 / 255.0;
-    let b = b as f32 / 255.0;
-
-    let max = r.max(g).max(b);
-    let min = r.min(g).min(b);
-    let delta = max - min;
-
-    // Hue
-    let h = if delta == 0.0 {
-        0.0
-    } else if max == r {
-        60.0 * (((g - b) / delta) % 6.0)
-    } else if max == g {
-        60.0 * (((b - r) / delta) + 2.0)
-    } else {
-        60.0 * (((r - g) / delta) + 4.0)
-    };
-
-    let h = if h < 0.0 { h + 360.0 } else { h };
+/// Lock indicator of a block that keeps only `channel`
+pub fn lock_label(channel: Freeze) -> String {
+    i18n::translate(&format!("block-frozen-{}", channel.key()), None)
+}
 
-    // Saturation
-    let s = if max == 0.0 { 0.0 } else { delta / max };
+/// Blocks narrower than this drop the words of their indicator and the HSV and RGB lines
+const NARROW_WIDTH: u16 = 22;
 
-    // Value
-    let v = max;
+/// Lock state glyphs, which tell blocks apart where words don't fit and colors don't help
+const GLYPH_LOCKED: &str = "●";
+const GLYPH_UNLOCKED: &str = "○";
+const GLYPH_STARRED: &str = "★";
+const GLYPH_NOTE: &str = "✎";
 
-    (h, s, v)
+fn freeze_glyph(channel: Freeze) -> &'static str {
+    match channel {
+        Freeze::Hue => "◐",
+        Freeze::Lightness => "◑",
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct ColorBlock {
-    pub block_id: usize,
-
-    pub hsv: Hsv,
-
+/// Renders a single `ColorBlock` with its lock indicator
+pub struct ColorBlockWidget<'a> {
+    pub block: &'a ColorBlock,
     pub selected: bool,
-    pub locked: bool,
+    /// Nearest swatch of the loaded swatch book and its CIEDE2000 difference
+    pub swatch: Option<(&'a str, f32)>,
+    /// Whether the color is starred in the favorites
+    pub starred: bool,
+    /// Whether an image draws the color in [`ColorBlockWidget::swatch_area`], leaving the
+    /// cells the terminal's own colors
+    pub raster: bool,
 }
 
-impl ColorBlock {
-    pub fn new(block_id: usize, hue: f32, sat: f32, val: f32) -> Self {
-        let hue = RgbHue::from_degrees(hue);
-        let hsv: Hsv = Hsv::new(hue, sat, val);
-
+impl<'a> ColorBlockWidget<'a> {
+    pub fn new(block: &'a ColorBlock, selected: bool) -> Self {
         Self {
-            block_id: block_id,
-
-            hsv: hsv,
-
-            selected: false,
-            locked: false,
+            block,
+            selected,
+            swatch: None,
+            starred: false,
+            raster: false,
         }
     }
 
-    pub fn generate_random_color(&mut self) {
-        let mut rng = rand::rng();
-        let hue = rng.random_range(0..360);
-        let sat = rng.random_range(50..90); // MIGHT GONNA EDIT THESE LATER
-        let val = rng.random_range(50..90);
-
-        self.change_color(hue as f32, sat as f32 / 100.0, val as f32 / 100.0);
-    }
-
-    pub fn change_color(&mut self, hue: f32, sat: f32, val: f32) {
-        let new_hue = RgbHue::from_degrees(hue);
-        let hsv: Hsv = Hsv::new(new_hue, sat, val);
-
-        self.hsv = hsv;
-    }
-
-    pub fn get_rgb_values(&self) -> (u8, u8, u8) {
-        let rgb: Srgb<f32> = Srgb::from_color(self.hsv);
-
-        let red = (rgb.red * 255.0).round() as u8;
-        let green = (rgb.green * 255.0).round() as u8;
-        let blue = (rgb.blue * 255.0).round() as u8;
-
-        return (red, green, blue);
-    }
-
-    pub fn get_hsv_values(&self) -> (f32, f32, f32) {
-        let hue: f32 = self.hsv.hue.into_raw_degrees();
-        let saturation: f32 = self.hsv.saturation;
-        let value: f32 = self.hsv.value;
-
-        return (hue, saturation, value);
+    pub fn raster(mut self, raster: bool) -> Self {
+        self.raster = raster;
+        self
     }
 
-    pub fn get_hex(&self) -> String {
-        let (r, g, b) = self.get_rgb_values();
-        format!("#{r:02X}{g:02X}{b:02X}")
+    /// The border of the block below its indicator: selection wins, otherwise full locks
+    /// get a thick one and partial locks a rounded one
+    fn border(&self) -> Option<border::Set> {
+        match (self.selected, self.block.locked, self.block.frozen) {
+            (true, _, _) => Some(border::DOUBLE),
+            (false, true, _) => Some(border::THICK),
+            (false, false, Some(_)) => Some(border::ROUNDED),
+            (false, false, None) => None,
+        }
     }
 
-    /// Calculate relative luminance using WCAG formula
-    /// Returns a value between 0.0 (black) and 1.0 (white)
-    pub fn get_relative_luminance(&self) -> f32 {
-        let (r, g, b) = self.get_rgb_values();
-
-        // Convert to linear RGB values (0.0 to 1.0)
-        let r_linear = if r as f32 / 255.0 <= 0.03928 {
-            (r as f32 / 255.0) / 12.92
-        } else {
-            ((r as f32 / 255.0 + 0.055) / 1.055).powf(2.4)
+    /// Rows of the block above its text, inside its border, where a raster swatch goes:
+    /// `None` when the body has no room for one
+    pub fn swatch_area(&self, area: Rect) -> Option<Rect> {
+        let body = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
         };
-
-        let g_linear = if g as f32 / 255.0 <= 0.03928 {
-            (g as f32 / 255.0) / 12.92
-        } else {
-            ((g as f32 / 255.0 + 0.055) / 1.055).powf(2.4)
+        let (inner, text_row) = match self.border() {
+            Some(_) => (
+                body.inner(margin!(1, 1)),
+                (body.height / 2).saturating_sub(1),
+            ),
+            None => (body, body.height / 2),
         };
-
-        let b_linear = if b as f32 / 255.0 <= 0.03928 {
-            (b as f32 / 255.0) / 12.92
-        } else {
-            ((b as f32 / 255.0 + 0.055) / 1.055).powf(2.4)
+        // A column of room on either side and a row above the text
+        let swatch = Rect {
+            x: inner.x + 1,
+            y: inner.y,
+            width: inner.width.saturating_sub(2),
+            height: text_row.saturating_sub(1),
         };
+        (swatch.width >= 2 && swatch.height >= 1).then_some(swatch)
+    }
+
+    pub fn swatch(mut self, swatch: Option<(&'a str, f32)>) -> Self {
+        self.swatch = swatch;
+        self
+    }
 
-        // Calculate relative luminance
-        0.2126 * r_linear + 0.7152 * g_linear + 0.0722 * b_linear
+    pub fn starred(mut self, starred: bool) -> Self {
+        self.starred = starred;
+        self
     }
 
     /// Get appropriate text color (black or white) based on background luminance
     pub fn get_text_color(&self) -> Color {
-        let luminance = self.get_relative_luminance();
+        let luminance = self.block.get_relative_luminance();
         // Use 0.5 as threshold - above is light (use dark text), below is dark (use light text)
         if luminance > 0.5 {
             Color::Rgb(0, 0, 0) // Black text for light backgrounds
@@ -156,144 +125,302 @@ impl ColorBlock {
             Color::Rgb(255, 255, 255) // White text for dark backgrounds
         }
     }
+}
+
+impl Widget for &ColorBlockWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let whole = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(1), Constraint::Fill(1)])
+            .split(area);
 
-    pub fn get_avg_hue(blocks: &Vec<Option<ColorBlock>>) -> f32 {
-        let mut hue_as_deg: f32 = 0.0;
+        let narrow = area.width < NARROW_WIDTH;
+        let (hue, saturation, value) = self.block.get_hsv_values();
+        let (red, green, blue) = self.block.get_rgb_values();
 
-        for block in blocks.iter() {
-            let block = block.unwrap();
+        // Blocks drawn as an image keep the terminal's colors around it
+        let card = self.raster && self.swatch_area(area).is_some();
+        let (color, text_color) = if card {
+            (Color::Reset, Color::Reset)
+        } else {
+            (Color::Rgb(red, green, blue), self.get_text_color())
+        };
 
-            hue_as_deg += block.hsv.hue.into_degrees() as f32;
+        let (lock_indicator_color, lock_glyph, lock_indicator_label) =
+            match (self.block.locked, self.block.frozen) {
+                (true, _) => (Color::Rgb(139, 0, 0), GLYPH_LOCKED, t!("block-locked")),
+                (false, Some(channel)) => (
+                    Color::Rgb(153, 102, 0),
+                    freeze_glyph(channel),
+                    lock_label(channel),
+                ),
+                (false, None) => (Color::Rgb(2, 48, 32), GLYPH_UNLOCKED, t!("block-unlocked")),
+            };
+        let mut glyphs = vec![lock_glyph];
+        if self.starred {
+            glyphs.push(GLYPH_STARRED);
+        }
+        if !self.block.note.is_empty() {
+            glyphs.push(GLYPH_NOTE);
         }
+        let glyphs = glyphs.join(" ");
+        let indicator = format!("{glyphs} {lock_indicator_label}");
+        let indicator = if Line::from(indicator.as_str()).width() <= area.width as usize {
+            indicator
+        } else {
+            glyphs
+        };
 
-        //return
-        hue_as_deg / blocks.len() as f32
-    }
+        let lock_indicator_block = Block::default()
+            .borders(Borders::NONE)
+            .bg(lock_indicator_color);
 
-    pub fn get_avg_saturation(blocks: &Vec<Option<ColorBlock>>) -> f32 {
-        let mut total_sat: f32 = 0.0;
+        // Lock borders take the text color so they show on any block
+        let border = self.border().map(|set| {
+            if self.selected {
+                (set, Style::default())
+            } else {
+                (set, Style::default().fg(text_color))
+            }
+        });
+
+        let block = match border {
+            Some((set, style)) => Block::default()
+                .borders(Borders::ALL)
+                .border_set(set)
+                .border_style(style)
+                .padding(Padding::new(
+                    0,
+                    0,
+                    (whole[1].height / 2).saturating_sub(1),
+                    0,
+                )),
+            None => Block::default()
+                .borders(Borders::NONE)
+                .padding(Padding::new(0, 0, whole[1].height / 2, 0)),
+        }
+        .bg(color);
 
-        for block in blocks.iter() {
-            let block = block.unwrap();
-            total_sat += block.hsv.saturation;
+        let mut lines = Vec::new();
+        if !self.block.label.is_empty() {
+            lines.push(Line::from(self.block.label.as_str()).fg(text_color).bold());
+        }
+        if let Some(role) = self.block.role {
+            lines.push(Line::from(role_name(role)).fg(text_color).italic());
         }
 
-        total_sat / blocks.len() as f32
-    }
+        // Out-of-gamut colors are shown clipped, flag them until fitted
+        let hex = match self.block.out_of_gamut {
+            Some(_) => format!("⚠ {}", self.block.get_hex()),
+            None => self.block.get_hex(),
+        };
+        if !narrow {
+            lines.extend([
+                Line::from(format!("HSV: {hue}, {:.2}, {:.2}", saturation, value)).fg(text_color),
+                Line::from(format!("RGB: {red}, {green}, {blue}")).fg(text_color),
+            ]);
+        }
+        lines.push(Line::from(hex).fg(text_color));
+        if let Some((name, delta_e)) = self.swatch.filter(|_| !narrow) {
+            lines.push(Line::from(format!("≈ {name} (ΔE {delta_e:.1})")).fg(text_color));
+        }
+        lines.push(Line::from(""));
 
-    pub fn get_avg_value(blocks: &Vec<Option<ColorBlock>>) -> f32 {
-        let mut total_val: f32 = 0.0;
+        Paragraph::new(lines)
+            .block(block)
+            .alignment(Alignment::Center)
+            .render(whole[1], buf);
 
-        for block in blocks.iter() {
-            let block = block.unwrap();
-            total_val += block.hsv.value;
-        }
+        Paragraph::new(Line::from(indicator))
+            .block(lock_indicator_block)
+            .alignment(Alignment::Center)
+            .render(whole[0], buf);
+    }
+}
 
-        total_val / blocks.len() as f32
+/// Number of blocks per row; palettes larger than 9 wrap into rows of 8
+pub fn grid_columns(block_count: usize) -> usize {
+    if block_count <= 9 {
+        block_count.max(1)
+    } else {
+        8
     }
 }
 
-impl Widget for ColorBlock {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let whole = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Length(1), Constraint::Fill(1)])
-            .split(area);
+/// Blocks laid out in rows of [`grid_columns`], for moving between them by row and column
+/// while they keep their order in the palette
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Grid {
+    /// Number of blocks
+    pub len: usize,
+    /// Blocks per row, the last row may hold fewer
+    pub columns: usize,
+}
 
-        let mut padding = Padding::new(0, 0, whole[1].height / 2, 0);
-        let selected_padding = Padding::new(0, 0, whole[1].height / 2 - 1, 0);
+impl Grid {
+    /// The grid `len` blocks are drawn in
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            columns: grid_columns(len),
+        }
+    }
 
-        let (hue, saturation, value) = self.get_hsv_values();
-        let (red, green, blue) = self.get_rgb_values();
+    /// Number of rows, counting a last one that isn't full
+    pub fn rows(self) -> usize {
+        self.len.div_ceil(self.columns)
+    }
 
-        let color = Color::Rgb(red, green, blue);
-        let text_color = self.get_text_color();
+    /// Row and column of the block at `index`
+    pub fn cell(self, index: usize) -> (usize, usize) {
+        (index / self.columns, index % self.columns)
+    }
+
+    /// The block at `column` of `row`, or the row's last when it is shorter; `None` past
+    /// the last row
+    pub fn index(self, row: usize, column: usize) -> Option<usize> {
+        let start = row * self.columns;
+        (start < self.len).then(|| (start + column.min(self.columns - 1)).min(self.len - 1))
+    }
+}
 
-        if self.selected {
-            padding = selected_padding;
+pub struct MainContent<'a> {
+    pub color_blocks: &'a [ColorBlock],
+    pub selected_block_id: usize,
+    pub swatch_book: Option<&'a SwatchBook>,
+    /// Hex codes of the starred colors
+    pub starred: &'a [String],
+    /// Whether the blocks' colors are drawn as images, see [`MainContent::rasters`]
+    pub raster: bool,
+}
+
+impl<'a> MainContent<'a> {
+    pub fn new(color_blocks: &'a [ColorBlock], selected_block_id: usize) -> Self {
+        Self {
+            color_blocks,
+            selected_block_id,
+            swatch_book: None,
+            starred: &[],
+            raster: false,
         }
+    }
+
+    /// Leave the blocks' colors to images drawn where [`MainContent::rasters`] says
+    pub fn raster(mut self, raster: bool) -> Self {
+        self.raster = raster;
+        self
+    }
 
-        let mut lock_indicator_color: Color = Color::Rgb(2, 48, 32);
+    /// Show each block's nearest swatch from `book`
+    pub fn swatch_book(mut self, book: Option<&'a SwatchBook>) -> Self {
+        self.swatch_book = book;
+        self
+    }
 
-        let mut lock_indicator_label = String::from("UNLOCKED");
+    /// Mark the blocks whose color is among `starred`
+    pub fn starred(mut self, starred: &'a [String]) -> Self {
+        self.starred = starred;
+        self
+    }
 
-        if self.locked {
-            lock_indicator_color = Color::Rgb(139, 0, 0);
-            lock_indicator_label = String::from("LOCKED");
-        }
+    /// The area of every block, in order
+    fn block_areas(&self, area: Rect) -> Vec<Rect> {
+        let columns = grid_columns(self.color_blocks.len());
+        let rows = self.color_blocks.chunks(columns);
 
-        let lock_indicator_block = Block::default()
-            .borders(Borders::NONE)
-            .bg(lock_indicator_color);
+        let row_areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Fill(1); rows.len()])
+            .split(area);
 
-        let mut block = Block::default()
-            .borders(Borders::NONE)
-            .padding(padding)
-            .bg(color);
+        rows.zip(row_areas.iter())
+            .flat_map(|(row, row_area)| {
+                // Every row keeps the full column count so blocks line up, unless widened
+                let constraints = row.iter().map(|block| Constraint::Fill(block.width)).chain(
+                    std::iter::repeat_n(Constraint::Fill(1), columns - row.len()),
+                );
+                let layout = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(constraints)
+                    .split(*row_area);
+                layout[..row.len()].to_vec()
+            })
+            .collect()
+    }
 
-        let selected_block = Block::default()
-            .borders(Borders::ALL)
-            .border_set(border::DOUBLE)
-            .padding(padding)
-            .bg(color);
+    fn widget(&self, idx: usize) -> ColorBlockWidget<'a> {
+        let block = &self.color_blocks[idx];
+        let swatch = self
+            .swatch_book
+            .and_then(|book| book.nearest(block))
+            .map(|(swatch, delta_e)| (swatch.name.as_str(), delta_e));
+        let hex = block.get_hex();
+        let starred = self.starred.iter().any(|c| c.eq_ignore_ascii_case(&hex));
+        ColorBlockWidget::new(block, idx == self.selected_block_id)
+            .swatch(swatch)
+            .starred(starred)
+            .raster(self.raster)
+    }
 
-        if self.selected {
-            block = selected_block;
-        }
+    /// The swatches to draw as images over the blocks rendered in `area`, one per block
+    /// with room for one
+    pub fn rasters(&self, area: Rect) -> Vec<Raster> {
+        self.block_areas(area)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, block_area)| {
+                let area = self.widget(idx).swatch_area(block_area)?;
+                let color = self.color_blocks[idx].get_rgb_values();
+                Some(Raster { area, color })
+            })
+            .collect()
+    }
+}
 
-        Paragraph::new(vec![
-            Line::from(format!("HSV: {hue}, {:.2}, {:.2}", saturation, value)).fg(text_color),
-            Line::from(format!("RGB: {red}, {green}, {blue}")).fg(text_color),
-            Line::from(self.get_hex()).fg(text_color),
-            Line::from(""),
-        ])
-        .block(block)
-        .alignment(Alignment::Center)
-        .render(whole[1], buf);
-
-        Paragraph::new(Line::from(lock_indicator_label))
-            .block(lock_indicator_block)
-            .alignment(Alignment::Center)
-            .render(whole[0], buf);
+impl Widget for &mut MainContent<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for (idx, block_area) in self.block_areas(area).into_iter().enumerate() {
+            self.widget(idx).render(block_area, buf);
+        }
     }
 }
 
-pub struct MainContent {
-    pub color_blocks: [Option<ColorBlock>; 9],
-    pub selected_block_id: usize,
+/// A palette as one row of plain swatches with their hex, for comparing against the main view
+pub struct PaletteStrip<'a> {
+    pub color_blocks: &'a [ColorBlock],
+    pub title: String,
 }
 
-impl MainContent {
-    pub fn new(color_blocks: [Option<ColorBlock>; 9], selected_block_id: usize) -> Self {
+impl<'a> PaletteStrip<'a> {
+    pub fn new(color_blocks: &'a [ColorBlock], title: String) -> Self {
         Self {
-            color_blocks: color_blocks,
-            selected_block_id: selected_block_id,
+            color_blocks,
+            title,
         }
     }
 }
 
-impl Widget for &mut MainContent {
+impl Widget for &PaletteStrip<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let block_count = self.color_blocks.iter().filter(|b| b.is_some()).count();
-
-        let constraints: Vec<Constraint> = vec![Constraint::Fill(1); block_count];
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
 
-        let layout = Layout::default()
+        let cells = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(constraints)
-            .split(area);
-
-        for (idx, block) in self
-            .color_blocks
-            .iter_mut()
-            .filter_map(|maybe| maybe.as_mut())
-            .enumerate()
-        {
-            // Mark selection
-            block.selected = idx == self.selected_block_id;
-
-            // Render into its packed layout slot
-            block.render(layout[idx], buf);
+            .constraints(vec![Constraint::Fill(1); self.color_blocks.len()])
+            .split(inner);
+
+        for (color_block, cell) in self.color_blocks.iter().zip(cells.iter()) {
+            let (r, g, b) = color_block.get_rgb_values();
+            let text_color = ColorBlockWidget::new(color_block, false).get_text_color();
+            let padding = Padding::new(0, 0, cell.height.saturating_sub(1) / 2, 0);
+            Paragraph::new(Line::from(color_block.get_hex()).fg(text_color))
+                .block(Block::default().padding(padding).bg(Color::Rgb(r, g, b)))
+                .alignment(Alignment::Center)
+                .render(*cell, buf);
         }
     }
 }
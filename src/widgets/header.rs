@@ -11,7 +11,7 @@ pub struct Header {
 
 impl Header {
     pub fn new(title: &'static str) -> Self {
-        Self { title: title }
+        Self { title }
     }
 }
 
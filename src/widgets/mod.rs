@@ -1,3 +1,8 @@
+pub mod art;
+pub mod balance;
+pub mod chrome;
+pub mod code;
 pub mod content;
 pub mod header;
+pub mod preview;
 pub mod status_bar;
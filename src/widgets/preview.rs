@@ -0,0 +1,255 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols::Marker,
+    text::Line,
+    widgets::{
+        Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, GraphType, Padding,
+        Paragraph, Widget,
+    },
+};
+
+use terminal_palette::color::ColorBlock;
+use terminal_palette::export::{self, Usage};
+
+use crate::{i18n, t};
+
+/// Bar groups in the sample bar chart
+const BAR_GROUPS: [&str; 3] = ["Q1", "Q2", "Q3"];
+/// Points per series in the sample line chart
+const LINE_POINTS: usize = 24;
+
+/// Sample charts colored by the palette, one series per block, to judge a palette in the
+/// data-viz context it's meant for. The data is made up but fixed, so redraws don't jitter.
+pub struct ChartPreview<'a> {
+    pub color_blocks: &'a [ColorBlock],
+    /// Also shade a heatmap through the blocks in order, for palettes that are a ramp
+    pub heatmap: bool,
+}
+
+impl<'a> ChartPreview<'a> {
+    pub fn new(color_blocks: &'a [ColorBlock]) -> Self {
+        Self {
+            color_blocks,
+            heatmap: false,
+        }
+    }
+
+    pub fn heatmap(mut self, heatmap: bool) -> Self {
+        self.heatmap = heatmap;
+        self
+    }
+
+    fn colors(&self) -> Vec<Color> {
+        self.color_blocks
+            .iter()
+            .map(|block| {
+                let (r, g, b) = block.get_rgb_values();
+                Color::Rgb(r, g, b)
+            })
+            .collect()
+    }
+
+    fn render_bars(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(format!(" {} ", t!("title-bar-chart")))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let colors = self.colors();
+        let series = colors.len().max(1) as u16;
+        let group_gap = 2;
+        let group_width = inner
+            .width
+            .saturating_sub(group_gap * (BAR_GROUPS.len() as u16 - 1))
+            / BAR_GROUPS.len() as u16;
+        let bar_gap = u16::from(group_width >= series * 2);
+        let bar_width = (group_width.saturating_sub(bar_gap * (series - 1)) / series).clamp(1, 6);
+
+        let mut chart = BarChart::default()
+            .bar_width(bar_width)
+            .bar_gap(bar_gap)
+            .group_gap(group_gap)
+            .max(100);
+        for (group, label) in BAR_GROUPS.iter().enumerate() {
+            let bars: Vec<Bar> = colors
+                .iter()
+                .enumerate()
+                .map(|(series, color)| {
+                    Bar::default()
+                        .value(sample_value(series, group))
+                        .text_value(String::new())
+                        .style(Style::new().fg(*color))
+                })
+                .collect();
+            chart = chart.data(BarGroup::default().label((*label).into()).bars(&bars));
+        }
+        chart.render(inner, buf);
+    }
+
+    fn render_lines(&self, area: Rect, buf: &mut Buffer) {
+        let points: Vec<Vec<(f64, f64)>> = (0..self.color_blocks.len())
+            .map(|series| {
+                (0..LINE_POINTS)
+                    .map(|x| (x as f64, sample_wave(series, x)))
+                    .collect()
+            })
+            .collect();
+
+        let datasets: Vec<Dataset> = points
+            .iter()
+            .zip(self.colors())
+            .map(|(data, color)| {
+                Dataset::default()
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::new().fg(color))
+                    .data(data)
+            })
+            .collect();
+
+        Chart::new(datasets)
+            .block(
+                Block::default()
+                    .title(format!(" {} ", t!("title-line-chart")))
+                    .borders(Borders::ALL),
+            )
+            .x_axis(Axis::default().bounds([0.0, (LINE_POINTS - 1) as f64]))
+            .y_axis(Axis::default().bounds([0.0, 100.0]))
+            .render(area, buf);
+    }
+
+    /// A smooth field quantized to the blocks, first block lowest. Every step of the ramp
+    /// becomes a contour band, so uneven steps show up as bands of uneven contrast.
+    fn render_heatmap(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(format!(" {} ", t!("title-heatmap")))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let colors = self.colors();
+        if colors.is_empty() || inner.is_empty() {
+            return;
+        }
+        let color_at = |x: u16, y: u16| {
+            let u = f64::from(x) / f64::from(inner.width.max(2) - 1);
+            let v = f64::from(y) / f64::from(inner.height * 2 - 1);
+            let step = (sample_field(u, v) * colors.len() as f64) as usize;
+            colors[step.min(colors.len() - 1)]
+        };
+
+        // Upper half blocks give each cell two rows of the field
+        for y in 0..inner.height {
+            for x in 0..inner.width {
+                buf[(inner.x + x, inner.y + y)]
+                    .set_char('▀')
+                    .set_fg(color_at(x, y * 2))
+                    .set_bg(color_at(x, y * 2 + 1));
+            }
+        }
+    }
+}
+
+impl Widget for &ChartPreview<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = if self.heatmap {
+            let [charts_area, heatmap_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+            self.render_heatmap(heatmap_area, buf);
+            charts_area
+        } else {
+            area
+        };
+        let [bars_area, lines_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+
+        self.render_bars(bars_area, buf);
+        self.render_lines(lines_area, buf);
+    }
+}
+
+/// Made-up bar height in `10..100`, different per series and group
+fn sample_value(series: usize, group: usize) -> u64 {
+    10 + ((series * 37 + group * 53 + series * group * 11) % 90) as u64
+}
+
+/// Made-up smooth series in `0..100`, phase-shifted per series so the lines cross
+fn sample_wave(series: usize, x: usize) -> f64 {
+    let phase = series as f64 * 0.9;
+    let offset = 20.0 + (series * 23 % 60) as f64;
+    (offset + 18.0 * (x as f64 * 0.45 + phase).sin()).clamp(0.0, 100.0)
+}
+
+/// Made-up smooth field in `0..=1` over the unit square: a slope rising to the right with a
+/// peak on it, so every level of the ramp appears both as a straight and as a curved band
+fn sample_field(u: f64, v: f64) -> f64 {
+    let peak = (-((u - 0.6).powi(2) + (v - 0.45).powi(2)) / 0.05).exp();
+    (1.1 * u - 0.05 + 0.3 * peak).clamp(0.0, 1.0)
+}
+
+/// The palette as bands as wide as each color's share of a design, dominant first, see
+/// [`export::proportions`]
+pub struct ProportionPreview<'a> {
+    pub color_blocks: &'a [ColorBlock],
+}
+
+impl<'a> ProportionPreview<'a> {
+    pub fn new(color_blocks: &'a [ColorBlock]) -> Self {
+        Self { color_blocks }
+    }
+}
+
+impl Widget for &ProportionPreview<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(format!(" {} ", t!("title-proportions")))
+            .borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let widths: Vec<u16> = self.color_blocks.iter().map(|block| block.width).collect();
+        let mut bands: Vec<_> = self
+            .color_blocks
+            .iter()
+            .zip(export::proportions(&widths))
+            .collect();
+        bands.sort_by_key(|(_, (usage, _))| *usage as u8);
+
+        // Tenths of a percent keep small accents from rounding away
+        let areas = Layout::horizontal(
+            bands
+                .iter()
+                .map(|(_, (_, percent))| Constraint::Fill((percent * 10.0).round() as u16)),
+        )
+        .split(inner);
+        for (band, (color_block, (usage, percent))) in areas.iter().zip(&bands) {
+            let (r, g, b) = color_block.get_rgb_values();
+            let text = if color_block.get_relative_luminance() > 0.5 {
+                Color::Rgb(0, 0, 0)
+            } else {
+                Color::Rgb(255, 255, 255)
+            };
+            let lines = vec![
+                Line::from(usage_name(*usage)).bold(),
+                Line::from(format!("{percent:.0}%")),
+                Line::from(color_block.get_hex()),
+            ];
+            Paragraph::new(lines)
+                .centered()
+                .fg(text)
+                .block(
+                    Block::default()
+                        .padding(Padding::top(band.height.saturating_sub(3) / 2))
+                        .bg(Color::Rgb(r, g, b)),
+                )
+                .render(*band, buf);
+        }
+    }
+}
+
+fn usage_name(usage: Usage) -> String {
+    i18n::translate(&format!("usage-{}", usage.key()), None)
+}
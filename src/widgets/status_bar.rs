@@ -1,86 +1,325 @@
+use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
-    layout::Alignment,
+    layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Stylize},
     text::{Line, Span},
     widgets::{Block, Padding, Paragraph, Widget},
 };
 
-use crate::app::CurrentPage;
+use terminal_palette::color::ColorBlock;
+use terminal_palette::pairing::{self, AA};
+use terminal_palette::roles::{self, Role};
+
+use crate::action::bindings;
+use crate::app::{CurrentPage, LibraryInput};
+use crate::widgets::content::{lock_label, role_name};
+use crate::{i18n, t};
 
 #[derive(Debug)]
-pub struct StatusBar {
+pub struct StatusBar<'a> {
     pub current_page: CurrentPage,
+    pub message: &'a str,
+    /// Right-aligned segment: active theory, RNG seed
+    pub theory: String,
+    pub seed: Option<u64>,
+    /// Block whose details take the row above the hints, with its place in the palette
+    /// (0-based) and the palette's length
+    pub selected: Option<&'a ColorBlock>,
+    pub position: (usize, usize),
+    /// Name of the selected block's nearest swatch, for blocks without a label
+    pub swatch: Option<&'a str>,
+    /// Focused text field of the library popup, which switches its keymap
+    pub input: LibraryInput,
+    /// Which page of hints to show when they don't fit; wraps around
+    pub hint_page: usize,
+    /// Whether a macro is being recorded
+    pub recording: bool,
+    /// Name of the light the palette is simulated under, unless it's daylight
+    pub lighting: Option<String>,
+    /// Temperature (kelvin) of the night-light filter the palette is shown through
+    pub night_light: Option<u32>,
+    /// Color of the key hints and the theory, taken from the palette
+    pub accent: Color,
 }
 
-impl StatusBar {
-    pub fn new(current_page: CurrentPage) -> Self {
-        Self { current_page }
+impl<'a> StatusBar<'a> {
+    pub fn new(current_page: CurrentPage, message: &'a str) -> Self {
+        Self {
+            current_page,
+            message,
+            theory: String::new(),
+            seed: None,
+            selected: None,
+            position: (0, 0),
+            swatch: None,
+            input: LibraryInput::None,
+            hint_page: 0,
+            recording: false,
+            lighting: None,
+            night_light: None,
+            accent: Color::Cyan,
+        }
+    }
+
+    pub fn hints(mut self, input: LibraryInput, hint_page: usize) -> Self {
+        self.input = input;
+        self.hint_page = hint_page;
+        self
+    }
+
+    pub fn recording(mut self, recording: bool) -> Self {
+        self.recording = recording;
+        self
+    }
+
+    pub fn lighting(mut self, lighting: Option<String>) -> Self {
+        self.lighting = lighting;
+        self
+    }
+
+    pub fn night_light(mut self, night_light: Option<u32>) -> Self {
+        self.night_light = night_light;
+        self
+    }
+
+    /// Take the accent from the block with the primary role, or the first block without
+    /// one, lightened or darkened as little as needed to read on the bar
+    pub fn accent(mut self, color_blocks: &[ColorBlock]) -> Self {
+        // The bar's background, taken to be #000000
+        let background = ColorBlock::new(0, 0.0, 0.0, 0.0);
+        let primary = roles::find(color_blocks, Role::Primary).or(color_blocks.first());
+        if let Some(color) = primary.and_then(|block| pairing::readable(block, &background, AA)) {
+            let (r, g, b) = color.get_rgb_values();
+            self.accent = Color::Rgb(r, g, b);
+        }
+        self
+    }
+
+    pub fn info(mut self, theory: String, seed: Option<u64>) -> Self {
+        self.theory = theory;
+        self.seed = seed;
+        self
+    }
+
+    /// Show the details of `selected`, the block at `index` of `count`
+    pub fn details(
+        mut self,
+        selected: Option<&'a ColorBlock>,
+        index: usize,
+        count: usize,
+        swatch: Option<&'a str>,
+    ) -> Self {
+        self.selected = selected;
+        self.position = (index, count);
+        self.swatch = swatch;
+        self
+    }
+
+    /// The selected block's place, hex, name, role and lock
+    fn get_details(&self) -> Option<Line<'_>> {
+        let block = self.selected?;
+        let (index, count) = self.position;
+        let mut spans = vec![
+            Span::styled(
+                format!(
+                    " {}  ",
+                    t!("status-block", index = index + 1, count = count)
+                ),
+                Color::DarkGray,
+            ),
+            Span::raw(block.get_hex()).add_modifier(Modifier::BOLD),
+        ];
+        let name = match (block.label.as_str(), self.swatch) {
+            ("", Some(swatch)) => Some(format!("≈ {swatch}")),
+            ("", None) => None,
+            (label, _) => Some(label.to_string()),
+        };
+        if let Some(name) = name {
+            spans.push(Span::raw(format!("  {name}")));
+        }
+        if let Some(role) = block.role {
+            spans.push(Span::styled(format!("  {}", role_name(role)), self.accent));
+        }
+        let lock = match (block.locked, block.frozen) {
+            (true, _) => t!("block-locked"),
+            (false, Some(channel)) => lock_label(channel),
+            (false, None) => t!("block-unlocked"),
+        };
+        spans.push(Span::styled(format!("  {lock}"), Color::DarkGray));
+        Some(Line::from(spans))
     }
 
-    fn get_hints(&self) -> Vec<Span<'_>> {
-        match self.current_page {
-            CurrentPage::Main => vec![
-                Span::styled("[q]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Quit  "),
-                Span::styled("[←]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::styled("[→]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Move  "),
-                Span::styled("[a]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Add  "),
-                Span::styled("[d]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Delete  "),
-                Span::styled("[x]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Theory  "),
-                Span::styled("[z]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Edit  "),
-                Span::styled("[l]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Lock  "),
-                Span::styled("[c]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Copy  "),
-                Span::styled("[Space]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Generate"),
-            ],
-            CurrentPage::TheorySelector => vec![
-                Span::styled("[x]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::styled("[q]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::styled("[Esc]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Close  "),
-                Span::styled("[←]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" First  "),
-                Span::styled("[→]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Last  "),
-                Span::styled("[↑]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::styled("[↓]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Move  "),
-                Span::styled("[Enter]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::styled("[Space]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Apply"),
-            ],
-            CurrentPage::EditColor => vec![
-                Span::styled("[z]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::styled("[q]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Cancel  "),
-                Span::styled("[Backspace]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Delete  "),
-                Span::styled("[Ctrl+Backspace]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Clear  "),
-                Span::styled("[Enter]", Color::Cyan).add_modifier(Modifier::BOLD),
-                Span::raw(" Apply"),
-            ],
+    fn get_info(&self) -> Line<'_> {
+        let mut spans = Vec::new();
+        if self.recording {
+            spans.push(Span::styled(
+                format!("{}  ", t!("status-recording")),
+                Color::Red,
+            ));
+        }
+        if let Some(lighting) = &self.lighting {
+            spans.push(Span::styled(
+                format!("{}  ", t!("status-lighting", light = lighting.as_str())),
+                Color::Yellow,
+            ));
+        }
+        if let Some(kelvin) = self.night_light {
+            spans.push(Span::styled(
+                format!("{}  ", t!("status-night-light", kelvin = kelvin)),
+                Color::Yellow,
+            ));
+        }
+        spans.push(Span::styled(self.theory.as_str(), self.accent));
+        if let Some(seed) = self.seed {
+            spans.push(Span::styled(
+                format!("  {}", t!("status-seed", seed = format!("{seed:x}"))),
+                Color::DarkGray,
+            ));
         }
+        spans.push(Span::raw(" "));
+        Line::from(spans)
+    }
+
+    /// Every hint of the page's keymap as (keys, label), in keymap order
+    fn get_hints(&self) -> Vec<(String, String)> {
+        let mut hints: Vec<(&str, String)> = Vec::new();
+        for binding in bindings(self.current_page, self.input) {
+            if binding.hint.is_empty() {
+                continue;
+            }
+            let keys: String = binding
+                .keys
+                .iter()
+                .map(|&(code, modifiers)| format!("[{}]", key_label(code, modifiers)))
+                .collect();
+            match hints.last_mut() {
+                Some((hint, joined)) if *hint == binding.hint => joined.push_str(&keys),
+                _ => hints.push((binding.hint, keys)),
+            }
+        }
+
+        hints
+            .into_iter()
+            .map(|(hint, keys)| (keys, i18n::translate(hint, None)))
+            .collect()
+    }
+
+    /// The hints split into pages that fit `width`, each page ending in the "more" indicator
+    /// when there is more than one
+    fn hint_pages(&self, width: usize) -> Vec<Line<'static>> {
+        let hints = self.get_hints();
+        let hint_width =
+            |(keys, label): &(String, String)| text_width(keys) + 1 + text_width(label);
+        let total: usize = hints
+            .iter()
+            .map(|hint| hint_width(hint) + HINT_GAP.len())
+            .sum();
+
+        let more = format!("{}{}", HINT_GAP, t!("hint-more"));
+        let available = if total <= width + HINT_GAP.len() {
+            usize::MAX
+        } else {
+            width.saturating_sub(text_width(&more))
+        };
+
+        let mut pages: Vec<Vec<(String, String)>> = vec![Vec::new()];
+        let mut used = 0;
+        for hint in hints {
+            let needed = hint_width(&hint);
+            if used > 0 && used + HINT_GAP.len() + needed > available {
+                pages.push(Vec::new());
+                used = 0;
+            }
+            used += if used == 0 {
+                needed
+            } else {
+                HINT_GAP.len() + needed
+            };
+            pages.last_mut().unwrap().push(hint);
+        }
+
+        let paged = pages.len() > 1;
+        pages
+            .into_iter()
+            .map(|page| {
+                let mut spans = Vec::new();
+                for (i, (keys, label)) in page.into_iter().enumerate() {
+                    if i > 0 {
+                        spans.push(Span::raw(HINT_GAP));
+                    }
+                    spans.push(Span::styled(keys, self.accent).add_modifier(Modifier::BOLD));
+                    spans.push(Span::raw(format!(" {label}")));
+                }
+                if paged {
+                    spans.push(Span::styled(more.clone(), Color::DarkGray));
+                }
+                Line::from(spans)
+            })
+            .collect()
     }
 }
 
-impl Widget for &StatusBar {
-    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
+const HINT_GAP: &str = "  ";
+
+/// Behind the status bar
+pub const BACKGROUND: Color = Color::Black;
+
+/// Columns `text` takes in the terminal
+fn text_width(text: &str) -> usize {
+    Span::raw(text).width()
+}
+
+/// How a key is written in hints: `q`, `Ctrl+s`, `Space`, `↑`
+fn key_label(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let key = match code {
+        KeyCode::Char(' ') => String::from("Space"),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Left => String::from("←"),
+        KeyCode::Right => String::from("→"),
+        KeyCode::Up => String::from("↑"),
+        KeyCode::Down => String::from("↓"),
+        other => other.to_string(),
+    };
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        format!("Ctrl+{key}")
+    } else if modifiers.contains(KeyModifiers::ALT) {
+        format!("Alt+{key}")
+    } else {
+        key
+    }
+}
+
+impl Widget for &StatusBar<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer) {
         let block = Block::default()
-            .bg(Color::Black)
+            .bg(BACKGROUND)
             .padding(Padding::new(0, 0, 1, 1));
 
-        let hints = self.get_hints();
-        Paragraph::new(Line::from(hints))
+        let info = self.get_info();
+        let [hints_area, info_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(info.width() as u16)])
+                .areas(area);
+
+        // A pending message takes the place of the hints until the next key press
+        let line = if self.message.is_empty() {
+            let mut pages = self.hint_pages(hints_area.width as usize);
+            pages.swap_remove(self.hint_page % pages.len())
+        } else {
+            Line::from(Span::styled(self.message, Color::Yellow))
+        };
+
+        Paragraph::new(line)
             .alignment(Alignment::Center)
+            .block(block.clone())
+            .render(hints_area, buf);
+        Paragraph::new(info)
+            .alignment(Alignment::Right)
             .block(block)
-            .render(area, buf);
+            .render(info_area, buf);
+
+        // In the padding row above the hints, following the selection as it moves
+        if let Some(details) = self.get_details() {
+            Paragraph::new(details).render(Rect { height: 1, ..area }, buf);
+        }
     }
 }
@@ -0,0 +1,385 @@
+//! Property tests for the public conversion API.
+
+use proptest::prelude::*;
+
+use palette::Oklch;
+use strum::IntoEnumIterator;
+use terminal_palette::convert::{
+    ColorError, ColorFormat, HexError, delta_e, hsl_to_rgb, hsv_to_rgb, most_distinct, parse_color,
+    parse_color_exact, parse_hex, rgb_to_hex, rgb_to_hsl, rgb_to_hsv, scan_colors, wrap_hue,
+};
+use terminal_palette::export::{ExportColor, ExportPalette};
+use terminal_palette::gamut;
+use terminal_palette::generators::ColorTheories;
+use terminal_palette::gradient::{self, CssError, Gradient, Space};
+use terminal_palette::lint;
+use terminal_palette::palette_json;
+
+fn close(a: (u8, u8, u8), b: (u8, u8, u8)) -> bool {
+    a.0.abs_diff(b.0) <= 1 && a.1.abs_diff(b.1) <= 1 && a.2.abs_diff(b.2) <= 1
+}
+
+proptest! {
+    #[test]
+    fn hex_round_trips(r: u8, g: u8, b: u8) {
+        let hex = rgb_to_hex(r, g, b);
+        prop_assert_eq!(parse_hex(&hex), Ok((r, g, b)));
+        prop_assert_eq!(parse_hex(&hex.to_lowercase()), Ok((r, g, b)));
+        prop_assert_eq!(parse_hex(hex.trim_start_matches('#')), Ok((r, g, b)));
+    }
+
+    #[test]
+    fn hsv_round_trips(r: u8, g: u8, b: u8) {
+        let (h, s, v) = rgb_to_hsv(r, g, b);
+        prop_assert!((0.0..360.0).contains(&h));
+        prop_assert!((0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&v));
+        prop_assert_eq!(hsv_to_rgb(h, s, v), (r, g, b));
+    }
+
+    #[test]
+    fn hsl_round_trips(r: u8, g: u8, b: u8) {
+        let (h, s, l) = rgb_to_hsl(r, g, b);
+        prop_assert!((0.0..360.0).contains(&h));
+        prop_assert!((0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&l));
+        let back = hsl_to_rgb(h, s, l);
+        prop_assert!(close(back, (r, g, b)), "{:?} != {:?}", back, (r, g, b));
+    }
+
+    #[test]
+    fn hue_wraps(h in -1000.0f32..1000.0, s in 0.0f32..=1.0, v in 0.0f32..=1.0) {
+        let wrapped = wrap_hue(h);
+        prop_assert!((0.0..360.0).contains(&wrapped));
+        prop_assert!(close(hsv_to_rgb(h, s, v), hsv_to_rgb(h + 360.0, s, v)));
+    }
+
+    #[test]
+    fn out_of_range_components_clamp(h in 0.0f32..360.0, over in 1.0f32..100.0) {
+        prop_assert_eq!(hsv_to_rgb(h, over, over), hsv_to_rgb(h, 1.0, 1.0));
+        prop_assert_eq!(hsv_to_rgb(h, -over, -over), hsv_to_rgb(h, 0.0, 0.0));
+        prop_assert_eq!(hsl_to_rgb(h, over, -over), hsl_to_rgb(h, 1.0, 0.0));
+    }
+
+    #[test]
+    fn any_floats_are_accepted(h: f32, s: f32, v: f32) {
+        // Must not panic, whatever the input (NaN and infinities included)
+        hsv_to_rgb(h, s, v);
+        hsl_to_rgb(h, s, v);
+    }
+
+    #[test]
+    fn parse_color_reads_every_notation(r: u8, g: u8, b: u8) {
+        let hex = rgb_to_hex(r, g, b);
+        prop_assert_eq!(parse_color(&hex), Ok((r, g, b)));
+        prop_assert_eq!(parse_color(&hex.replace('#', "0x")), Ok((r, g, b)));
+        prop_assert_eq!(parse_color(&format!("rgb({r}, {g}, {b})")), Ok((r, g, b)));
+        prop_assert_eq!(parse_color(&format!("RGB({r} {g} {b})")), Ok((r, g, b)));
+
+        let (h, s, l) = rgb_to_hsl(r, g, b);
+        let hsl = format!("hsl({h}deg, {}%, {}%)", s * 100.0, l * 100.0);
+        let back = parse_color(&hsl).unwrap();
+        prop_assert!(close(back, (r, g, b)), "{:?} != {:?}", back, (r, g, b));
+    }
+
+    #[test]
+    fn formats_read_back(r: u8, g: u8, b: u8) {
+        // HSL is left out: whole degrees and percent lose a little
+        for format in [ColorFormat::Hex, ColorFormat::Rgb] {
+            prop_assert_eq!(parse_color(&format.format(r, g, b)), Ok((r, g, b)));
+        }
+        // Four decimals of OKLCH can land a dark channel one step off
+        let oklch = parse_color(&ColorFormat::Oklch.format(r, g, b)).unwrap();
+        prop_assert!(close(oklch, (r, g, b)), "{:?} != {:?}", oklch, (r, g, b));
+    }
+
+    #[test]
+    fn shorthand_doubles_digits(r in 0u8..16, g in 0u8..16, b in 0u8..16) {
+        let short = format!("#{r:x}{g:x}{b:x}");
+        let long = format!("#{r:x}{r:x}{g:x}{g:x}{b:x}{b:x}");
+        prop_assert_eq!(parse_hex(&short), parse_hex(&long));
+    }
+
+    #[test]
+    fn partial_hex_is_rejected(digits in "[0-9a-fA-F]{1,12}") {
+        prop_assume!(digits.len() != 3 && digits.len() != 6);
+        prop_assert_eq!(parse_hex(&digits), Err(HexError::InvalidLength(digits.len())));
+    }
+
+    #[test]
+    fn non_hex_characters_are_rejected(prefix in "[0-9a-f]{0,3}", bad in "[g-zG-Z!@_-]") {
+        let input = format!("{prefix}{bad}00");
+        prop_assert!(matches!(parse_hex(&input), Err(HexError::InvalidDigit(_))));
+    }
+}
+
+#[test]
+fn empty_hex_is_rejected() {
+    assert_eq!(parse_hex(""), Err(HexError::Empty));
+    assert_eq!(parse_hex(" # "), Err(HexError::Empty));
+}
+
+#[test]
+fn parse_color_accepts_names() {
+    assert_eq!(parse_color("tomato"), Ok((255, 99, 71)));
+    assert_eq!(parse_color(" Light Sea-Green "), Ok((32, 178, 170)));
+    assert_eq!(parse_color("#abc"), Ok((170, 187, 204)));
+    assert_eq!(parse_color("abc"), Ok((170, 187, 204)));
+    assert_eq!(parse_color("rgb(100%, 0%, 50%)"), Ok((255, 0, 128)));
+}
+
+#[test]
+fn parse_color_errors_are_precise() {
+    let error = |input: &str| parse_color(input).unwrap_err();
+
+    assert_eq!(error("  "), ColorError::Empty);
+    assert_eq!(error("e07a"), ColorError::Hex(HexError::InvalidLength(4)));
+    assert_eq!(error("0xe0z"), ColorError::Hex(HexError::InvalidDigit('z')));
+    assert_eq!(error("blurple"), ColorError::UnknownName("blurple".into()));
+    assert_eq!(
+        error("cmyk(0, 0, 0)"),
+        ColorError::UnknownFunction("cmyk".into())
+    );
+    assert_eq!(error("rgb(1, 2, 3"), ColorError::Unclosed("rgb".into()));
+    assert_eq!(
+        error("rgb(1, 2)"),
+        ColorError::ComponentCount("rgb".into(), 2)
+    );
+    assert_eq!(
+        error("hsl(x, 2%, 3%)"),
+        ColorError::InvalidNumber("x".into())
+    );
+    assert_eq!(
+        error("hsl(10, 120%, 50%)").to_string(),
+        "saturation 120% is outside 0%-100%"
+    );
+}
+
+#[test]
+fn parse_color_reads_oklch_and_lab() {
+    assert!(close(
+        parse_color("oklch(62.8% 0.2577 29.23)").unwrap(),
+        (255, 0, 0)
+    ));
+    assert!(close(
+        parse_color("lab(53.24 80.09 67.2)").unwrap(),
+        (255, 0, 0)
+    ));
+    assert_eq!(
+        parse_color("oklch(1.5 0.1 30)").unwrap_err().to_string(),
+        "lightness 1.5 is outside 0-1"
+    );
+
+    // P3's pure green lies well outside sRGB
+    let p3_green = parse_color_exact("color(display-p3 0 100% 0)").unwrap();
+    assert!(p3_green.out_of_gamut().is_some());
+    assert_eq!(
+        parse_color("color(srgb 0 1 0)").unwrap_err(),
+        ColorError::UnknownColorSpace("srgb".into())
+    );
+
+    let vivid = parse_color_exact("oklch(0.45 0.35 264)").unwrap();
+    assert!(vivid.out_of_gamut().is_some());
+    assert_eq!(parse_color_exact("#3D405B").unwrap().out_of_gamut(), None);
+}
+
+proptest! {
+    #[test]
+    fn fit_lands_in_gamut_keeping_lightness_and_hue(
+        l in 0.0f32..=1.0,
+        chroma in 0.0f32..0.5,
+        hue in 0.0f32..360.0,
+    ) {
+        let color = Oklch::new(l, chroma, hue);
+        let fitted = gamut::fit(color);
+        prop_assert!(gamut::in_gamut(fitted));
+        prop_assert!(fitted.chroma <= color.chroma);
+        prop_assert_eq!(fitted.l, color.l);
+        prop_assert_eq!(fitted.hue, color.hue);
+    }
+}
+
+proptest! {
+    #[test]
+    fn display_p3_round_trips(r in 0.0f32..=1.0, g in 0.0f32..=1.0, b in 0.0f32..=1.0) {
+        let color = gamut::from_display_p3(r, g, b);
+        prop_assert!(gamut::in_display_p3(color));
+        let (r2, g2, b2) = gamut::to_display_p3(color);
+        prop_assert!((r - r2).abs() < 1e-3 && (g - g2).abs() < 1e-3 && (b - b2).abs() < 1e-3);
+    }
+}
+
+proptest! {
+    #[test]
+    fn gradient_css_reads_back(
+        colors in prop::collection::vec(any::<(u8, u8, u8)>(), 2..6),
+        space in prop::sample::select(Space::iter().collect::<Vec<_>>()),
+    ) {
+        let hexes: Vec<String> = colors.iter().map(|&(r, g, b)| rgb_to_hex(r, g, b)).collect();
+        let mut gradient = Gradient::from_colors("ramp", &hexes, space);
+        for stop in &mut gradient.stops {
+            stop.position = gradient::percent(stop.position) / 100.0;
+        }
+        prop_assert_eq!(Gradient::from_css("ramp", &gradient.css()), Ok(gradient));
+    }
+}
+
+#[test]
+fn gradient_css_places_stops_like_browsers() {
+    let css = "linear-gradient(to bottom,\n  hsla(0, 0%, 100%, 0.8),\n  #0000 20% 40%,\n  60%,\n  rgb(255 0 0 / 50%) 10%,\n  blue)";
+    let gradient = Gradient::from_css("", css).unwrap();
+    let stops: Vec<(&str, f32)> = gradient
+        .stops
+        .iter()
+        .map(|stop| (stop.hex.as_str(), stop.position))
+        .collect();
+    assert_eq!(
+        stops,
+        [
+            ("#FFFFFF", 0.0),
+            ("#000000", 0.2),
+            ("#000000", 0.4),
+            // Placed before the stop ahead of it, so moved up to it
+            ("#FF0000", 0.4),
+            ("#0000FF", 1.0),
+        ]
+    );
+
+    assert_eq!(
+        Gradient::from_css("", "linear-gradient(red 10px, blue)"),
+        Err(CssError::InvalidPosition(String::from("10px")))
+    );
+    assert_eq!(
+        Gradient::from_css("", "linear-gradient(in hsl, red, blue)"),
+        Err(CssError::UnknownSpace(String::from("hsl")))
+    );
+    assert_eq!(
+        Gradient::from_css("", "linear-gradient(red, blue"),
+        Err(CssError::Unclosed)
+    );
+    assert_eq!(
+        Gradient::from_css("", "#FF0000"),
+        Err(CssError::NotAGradient)
+    );
+}
+
+proptest! {
+    #[test]
+    fn scanning_finds_every_color_once(colors in prop::collection::vec(any::<(u8, u8, u8)>(), 0..20)) {
+        let css: String = colors
+            .iter()
+            .enumerate()
+            .map(|(idx, &(r, g, b))| match idx % 2 {
+                0 => format!(".c{idx} {{ color: {}; }}\n", rgb_to_hex(r, g, b)),
+                _ => format!(".c{idx} {{ fill: rgb({r}, {g}, {b}) }}\n"),
+            })
+            .collect();
+        let mut distinct = colors.clone();
+        let mut seen = Vec::new();
+        distinct.retain(|color| !seen.contains(color) && { seen.push(*color); true });
+        prop_assert_eq!(scan_colors(&css), distinct);
+    }
+
+    #[test]
+    fn linting_points_at_each_color_as_written(
+        colors in prop::collection::vec(any::<(u8, u8, u8)>(), 1..20),
+    ) {
+        let css: String = colors
+            .iter()
+            .enumerate()
+            .map(|(idx, &(r, g, b))| match idx % 2 {
+                0 => format!("  --c{idx}: {};\n", rgb_to_hex(r, g, b).to_lowercase()),
+                _ => format!("  --c{idx}: rgb({r} {g} {b});\n"),
+            })
+            .collect();
+        let literals = lint::literals(&css, &colors[..1]);
+        prop_assert_eq!(literals.len(), colors.len());
+        for (idx, literal) in literals.iter().enumerate() {
+            prop_assert_eq!(literal.line, idx + 1);
+            prop_assert_eq!(literal.column, format!("  --c{idx}: ").len() + 1);
+            prop_assert_eq!(&css[literal.span.clone()], literal.text.as_str());
+            prop_assert_eq!(parse_color(&literal.text), Ok(colors[idx]));
+            prop_assert_eq!(literal.delta_e == 0.0, colors[idx] == colors[0]);
+        }
+    }
+
+    #[test]
+    fn rewriting_replaces_exact_hex_codes_line_for_line(
+        colors in prop::collection::vec(any::<(u8, u8, u8)>(), 1..20),
+    ) {
+        let css: String = colors
+            .iter()
+            .enumerate()
+            .map(|(idx, &(r, g, b))| format!(".c{idx} {{ color: {}; }}\n", rgb_to_hex(r, g, b)))
+            .collect();
+        let names = vec![String::from("first")];
+        let (rewritten, replaced) =
+            lint::rewrite(&css, &colors[..1], &names, lint::Syntax::Css, 0.0);
+        let same = colors.iter().filter(|&&color| color == colors[0]).count();
+        prop_assert_eq!(replaced, same);
+        prop_assert_eq!(rewritten.lines().count(), colors.len());
+        prop_assert_eq!(rewritten.matches("var(--first)").count(), same);
+
+        let patch = lint::patch("c.css", &css, &rewritten);
+        prop_assert_eq!(patch.lines().filter(|line| line.starts_with("+.")).count(), same);
+    }
+
+    #[test]
+    fn palette_json_reads_back_what_it_writes(
+        colors in prop::collection::vec((any::<(u8, u8, u8)>(), any::<bool>()), 1..20),
+        theory in prop::sample::select(ColorTheories::iter().collect::<Vec<_>>()),
+    ) {
+        let palette = ExportPalette {
+            name: String::from("round trip"),
+            theory: Some(theory),
+            colors: colors
+                .iter()
+                .map(|&((r, g, b), locked)| ExportColor {
+                    locked,
+                    ..ExportColor::new(&rgb_to_hex(r, g, b))
+                })
+                .collect(),
+            gradients: Vec::new(),
+        };
+        let read = palette_json::parse(&palette_json::write(&palette)).unwrap();
+        prop_assert_eq!(&read.name, "round trip");
+        prop_assert_eq!(read.theory, theory);
+        for (color, &((r, g, b), locked)) in read.colors.iter().zip(&colors) {
+            prop_assert_eq!(&color.hex, &rgb_to_hex(r, g, b));
+            prop_assert_eq!(color.locked, locked);
+        }
+
+        // The hsv alone gets the same colors back
+        let json = palette_json::write(&palette);
+        let mut document: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for color in document["colors"].as_array_mut().unwrap() {
+            color.as_object_mut().unwrap().remove("hex");
+        }
+        let read = palette_json::parse(&document.to_string()).unwrap();
+        for (color, &((r, g, b), _)) in read.colors.iter().zip(&colors) {
+            let (r2, g2, b2) = parse_hex(&color.hex).unwrap();
+            prop_assert!(close((r, g, b), (r2, g2, b2)), "{} vs {}", color.hex, rgb_to_hex(r, g, b));
+        }
+    }
+
+    #[test]
+    fn most_distinct_keeps_order_and_spreads_out(
+        colors in prop::collection::vec(any::<(u8, u8, u8)>(), 1..40),
+        count in 1usize..10,
+    ) {
+        let picks = most_distinct(&colors, count);
+        prop_assert_eq!(picks.len(), count.min(colors.len()));
+        let mut rest = colors.iter();
+        prop_assert!(picks.iter().all(|pick| rest.any(|color| color == pick)));
+
+        // Greedy max-min is within half of the best spread, so no color left out can be
+        // twice as far from every pick as the closest two picks are from each other
+        let spread = picks
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, &a)| picks[idx + 1..].iter().map(move |&b| delta_e(a, b)))
+            .fold(f32::INFINITY, f32::min);
+        for &color in &colors {
+            let nearest = picks.iter().map(|&pick| delta_e(pick, color)).fold(f32::INFINITY, f32::min);
+            prop_assert!(nearest <= spread + 1e-3, "{color:?} is {nearest} from the picks, spread {spread}");
+        }
+    }
+}